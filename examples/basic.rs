@@ -1,6 +1,6 @@
-use teacup::run;
+use teacup::Teacup;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    run().await
+    Teacup::builder().run().await
 }
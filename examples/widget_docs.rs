@@ -0,0 +1,28 @@
+// writes screenshots of the built in widgets to target/widget-docs
+use teacup::{Button, DocState, Font, Text, WidgetDoc, WidgetGallery};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    teacup::ConsoleLogger::init()?;
+    let font = Font::system_default().ok_or_else(|| anyhow::anyhow!("no system font found"))?;
+
+    let mut gallery = WidgetGallery::new();
+    gallery
+        .register(WidgetDoc::new("Button", {
+            let font = font.clone();
+            move |theme| {
+                let label = Text::new("Save", font.clone(), 14.0, theme.on_primary);
+                Button::new(label, theme)
+            }
+        }))
+        .register(
+            WidgetDoc::new("Text", move |theme| {
+                Text::new("The quick brown fox", font.clone(), 14.0, theme.text)
+            })
+            .states(&[DocState::Idle]),
+        );
+
+    let written = gallery.generate("target/widget-docs").await?;
+    println!("wrote {} screenshots", written.len());
+    Ok(())
+}
@@ -0,0 +1,388 @@
+use std::{
+    f32::consts::TAU,
+    sync::{Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use tinycolors::srgb;
+
+use crate::{builder::Node, layout::Primative, theme::mix};
+
+// longest step the integrator takes at once. long frames are split up so a
+// hitch doesn't make a stiff spring explode
+const MAX_STEP: f32 = 1.0 / 240.0;
+
+// within this distance and speed of the target the spring snaps and stops
+const REST_DISTANCE: f32 = 0.001;
+const REST_SPEED: f32 = 0.01;
+
+// a damped spring pulling a value towards a target. defaults are close to
+// critically damped, so it settles quickly without bouncing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+    value: f32,
+    velocity: f32,
+    target: f32,
+}
+
+impl Spring {
+    pub fn new(value: f32) -> Self {
+        Self {
+            stiffness: 300.0,
+            damping: 34.0,
+            mass: 1.0,
+            value,
+            velocity: 0.0,
+            target: value,
+        }
+    }
+
+    // how much it overshoots. 1 is critically damped, lower values bounce
+    pub fn with_damping_ratio(mut self, ratio: f32) -> Self {
+        self.damping = ratio * 2.0 * (self.stiffness * self.mass).sqrt();
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    // flings towards `target` starting at `velocity`, in value units per second
+    pub fn fling(&mut self, target: f32, velocity: f32) {
+        self.target = target;
+        self.velocity = velocity;
+    }
+
+    // jumps to `value` and stops there
+    pub fn snap(&mut self, value: f32) {
+        self.value = value;
+        self.target = value;
+        self.velocity = 0.0;
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.value == self.target && self.velocity == 0.0
+    }
+
+    pub fn step(&mut self, dt: Duration) {
+        let mut remaining = dt.as_secs_f32();
+        while remaining > 0.0 && !self.is_settled() {
+            let dt = remaining.min(MAX_STEP);
+            remaining -= dt;
+
+            let force = -self.stiffness * (self.value - self.target) - self.damping * self.velocity;
+            self.velocity += force / self.mass * dt;
+            self.value += self.velocity * dt;
+
+            if (self.value - self.target).abs() < REST_DISTANCE && self.velocity.abs() < REST_SPEED
+            {
+                self.snap(self.target);
+            }
+        }
+    }
+}
+
+// the animations below are functions of the frame clock rather than of how
+// many frames ran, so they look the same at any frame rate and a stalled
+// window picks up where the clock says it should be
+
+// an on/off cycle, like a text caret. it starts on, and `restart` begins a
+// fresh on phase so a caret stays solid while typing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Blink {
+    pub period: Duration,
+    since: Instant,
+}
+
+impl Blink {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            since: Instant::now(),
+        }
+    }
+
+    pub fn restart(&mut self, now: Instant) {
+        self.since = now;
+    }
+
+    pub fn is_on(&self, now: Instant) -> bool {
+        let period = self.period.as_secs_f32();
+        if period <= 0.0 {
+            return true;
+        }
+        now.saturating_duration_since(self.since).as_secs_f32() % period < period / 2.0
+    }
+}
+
+// eases from one value to another over `duration`, like a hover highlight
+// fading in. retargeting part way starts from wherever it currently is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fade {
+    pub duration: Duration,
+    pub easing: Easing,
+    from: f32,
+    to: f32,
+    start: Option<Instant>,
+}
+
+impl Fade {
+    pub fn new(duration: Duration, value: f32) -> Self {
+        Self {
+            duration,
+            easing: Easing::default(),
+            from: value,
+            to: value,
+            start: None,
+        }
+    }
+
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn value(&self, now: Instant) -> f32 {
+        let Some(start) = self.start else {
+            return self.to;
+        };
+        let t = self.easing.apply(progress(start, now, self.duration));
+        self.from + (self.to - self.from) * t
+    }
+
+    pub fn set_target(&mut self, target: f32, now: Instant) {
+        if target == self.to {
+            return;
+        }
+        self.from = self.value(now);
+        self.to = target;
+        self.start = Some(now);
+    }
+
+    pub fn snap(&mut self, value: f32) {
+        self.from = value;
+        self.to = value;
+        self.start = None;
+    }
+
+    pub fn is_settled(&self, now: Instant) -> bool {
+        self.start
+            .is_none_or(|start| now.saturating_duration_since(start) >= self.duration)
+    }
+}
+
+// a smooth 0 to 1 and back cycle, e.g. for pulsing a highlight
+pub fn pulse(elapsed: Duration, period: Duration) -> f32 {
+    let period = period.as_secs_f32();
+    if period <= 0.0 {
+        return 0.0;
+    }
+    0.5 - 0.5 * (elapsed.as_secs_f32() / period * TAU).cos()
+}
+
+// fraction of `duration` that has passed since `start`, from 0 to 1
+fn progress(start: Instant, now: Instant, duration: Duration) -> f32 {
+    let duration = duration.as_secs_f32();
+    if duration > 0.0 {
+        (now.saturating_duration_since(start).as_secs_f32() / duration).min(1.0)
+    } else {
+        1.0
+    }
+}
+
+// maps linear progress from 0 to 1 onto how far along the animated value is
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+    // a css style curve through (0, 0), the two control points and (1, 1)
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::CubicBezier(x1, y1, x2, y2) => {
+                let curve = |a: f32, b: f32, s: f32| {
+                    3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s * s * (1.0 - s) + s * s * s
+                };
+                let slope = |a: f32, b: f32, s: f32| {
+                    3.0 * a * (1.0 - s).powi(2)
+                        + 6.0 * (b - a) * s * (1.0 - s)
+                        + 3.0 * (1.0 - b) * s * s
+                };
+                // newton's method for the curve parameter that lands on x = t
+                let mut s = t;
+                for _ in 0..8 {
+                    let error = curve(x1, x2, s) - t;
+                    let dx = slope(x1, x2, s);
+                    if error.abs() < 1e-5 || dx.abs() < 1e-6 {
+                        break;
+                    }
+                    s = (s - error / dx).clamp(0.0, 1.0);
+                }
+                curve(y1, y2, s)
+            }
+        }
+    }
+}
+
+// a property an Animator can move, holding the value to end on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Property {
+    // pins the size so layout lays the rest of the tree out around the
+    // animated value
+    Width(i32),
+    Height(i32),
+    // shifts the laid out position, taking the subtree with it
+    Translation((i32, i32)),
+    Color(srgb),
+    Opacity(f32),
+}
+
+impl Property {
+    fn same_kind(&self, other: &Property) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    fn read(&self, prim: &dyn Primative) -> Property {
+        match self {
+            Property::Width(_) => Property::Width(prim.get_width()),
+            Property::Height(_) => Property::Height(prim.get_height()),
+            Property::Translation(_) => Property::Translation(prim.translation()),
+            Property::Color(to) => Property::Color(prim.color().unwrap_or(*to)),
+            Property::Opacity(_) => Property::Opacity(prim.opacity()),
+        }
+    }
+
+    fn write(&self, prim: &mut dyn Primative) {
+        match *self {
+            Property::Width(width) => {
+                prim.set_min_width(width);
+                prim.set_max_width(Some(width));
+            }
+            Property::Height(height) => {
+                prim.set_min_height(height);
+                prim.set_max_height(Some(height));
+            }
+            Property::Translation(translation) => prim.set_translation(translation),
+            Property::Color(color) => prim.set_color(color),
+            Property::Opacity(opacity) => prim.set_opacity(opacity),
+        }
+    }
+
+    fn lerp(&self, to: &Property, t: f32) -> Property {
+        let lerp = |a: i32, b: i32| a + ((b - a) as f32 * t).round() as i32;
+        match (*self, *to) {
+            (Property::Width(a), Property::Width(b)) => Property::Width(lerp(a, b)),
+            (Property::Height(a), Property::Height(b)) => Property::Height(lerp(a, b)),
+            (Property::Translation(a), Property::Translation(b)) => {
+                Property::Translation((lerp(a.0, b.0), lerp(a.1, b.1)))
+            }
+            (Property::Color(a), Property::Color(b)) => Property::Color(mix(a, b, t)),
+            (Property::Opacity(a), Property::Opacity(b)) => Property::Opacity(a + (b - a) * t),
+            _ => *to,
+        }
+    }
+}
+
+struct Tween {
+    node: Weak<Mutex<dyn Primative>>,
+    from: Option<Property>,
+    to: Property,
+    duration: Duration,
+    easing: Easing,
+    // set on the first tick, so time spent before the next frame isn't skipped
+    start: Option<Instant>,
+}
+
+// tweens properties of primitives in the tree. the event loop ticks it once a
+// frame before layout, so size changes flow through the layout passes and
+// siblings move smoothly out of the way
+#[derive(Default)]
+pub struct Animator {
+    tweens: Vec<Tween>,
+}
+
+impl Animator {
+    // starts from the current value. an animation already running on the same
+    // property of `node` is replaced, continuing from wherever it got to
+    pub fn animate(&mut self, node: &Node, to: Property, duration: Duration, easing: Easing) {
+        let node = std::sync::Arc::downgrade(node);
+        self.tweens
+            .retain(|tween| !(Weak::ptr_eq(&tween.node, &node) && tween.to.same_kind(&to)));
+        self.tweens.push(Tween {
+            node,
+            from: None,
+            to,
+            duration,
+            easing,
+            start: None,
+        });
+    }
+
+    // stops animating `node`, leaving it where it is
+    pub fn cancel(&mut self, node: &Node) {
+        let node = std::sync::Arc::downgrade(node);
+        self.tweens
+            .retain(|tween| !Weak::ptr_eq(&tween.node, &node));
+    }
+
+    pub fn is_animating(&self, node: &Node) -> bool {
+        let node = std::sync::Arc::downgrade(node);
+        self.tweens
+            .iter()
+            .any(|tween| Weak::ptr_eq(&tween.node, &node))
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.tweens.is_empty()
+    }
+
+    // writes every property for `now`, dropping animations that finished or
+    // whose primitive left the tree
+    pub fn tick(&mut self, now: Instant) {
+        self.tweens.retain_mut(|tween| {
+            let Some(node) = tween.node.upgrade() else {
+                return false;
+            };
+            let Ok(mut prim) = node.lock() else {
+                return false;
+            };
+            let start = *tween.start.get_or_insert(now);
+            let from = *tween.from.get_or_insert_with(|| tween.to.read(&*prim));
+            let t = progress(start, now, tween.duration);
+            from.lerp(&tween.to, tween.easing.apply(t))
+                .write(&mut *prim);
+            t < 1.0
+        });
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    context::{Announcement, UiContext},
+    event::Event,
+    input::InputSettings,
+    layout::UI,
+    renderer::{plugin::RenderPlugin, settings::RenderSettings, shaders::CustomShader},
+    theme::Theme,
+    window::WindowOptions,
+};
+
+type BuildFn = Box<dyn FnMut(&mut UiContext) -> UI + Send>;
+type EventCallback = Box<dyn FnMut(&mut UI, &Event) + Send>;
+type FrameCallback = Box<dyn FnMut(&mut UI) + Send>;
+type AnnounceCallback = Box<dyn FnMut(&Announcement) + Send>;
+
+// an application built on teacup. `build` creates the ui once the window and
+// renderer exist; teacup owns the event loop and calls back into the app
+pub struct App {
+    pub options: WindowOptions,
+    pub theme: Theme,
+    pub(crate) build: BuildFn,
+    pub(crate) on_event: Option<EventCallback>,
+    pub(crate) on_frame: Option<FrameCallback>,
+    pub(crate) on_announce: Option<AnnounceCallback>,
+    pub(crate) shaders: Vec<(String, CustomShader)>,
+    pub(crate) plugins: Vec<(String, Box<dyn RenderPlugin>)>,
+}
+
+impl App {
+    pub fn new(build: impl FnMut(&mut UiContext) -> UI + Send + 'static) -> Self {
+        Self {
+            options: WindowOptions::default(),
+            theme: Theme::default(),
+            build: Box::new(build),
+            on_event: None,
+            on_frame: None,
+            on_announce: None,
+            shaders: Vec::new(),
+            plugins: Vec::new(),
+        }
+    }
+
+    pub fn with_options(mut self, options: WindowOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // handed to `build` through `UiContext::theme`
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    // sees every window event before teacup handles it
+    pub fn on_event(mut self, f: impl FnMut(&mut UI, &Event) + Send + 'static) -> Self {
+        self.on_event = Some(Box::new(f));
+        self
+    }
+
+    // runs once per frame, right before layout and drawing
+    pub fn on_frame(mut self, f: impl FnMut(&mut UI) + Send + 'static) -> Self {
+        self.on_frame = Some(Box::new(f));
+        self
+    }
+
+    // the accessibility backend. gets everything passed to
+    // `UiContext::announce`, once per frame in the order it was announced
+    pub fn on_announce(mut self, f: impl FnMut(&Announcement) + Send + 'static) -> Self {
+        self.on_announce = Some(Box::new(f));
+        self
+    }
+
+    // compiled when the renderer starts. elements pick it with `.shader(name)`
+    pub fn with_shader(mut self, name: &str, shader: CustomShader) -> Self {
+        self.shaders.retain(|(n, _)| n != name);
+        self.shaders.push((name.to_string(), shader));
+        self
+    }
+
+    // set up once the renderer exists. `PluginView::new(name)` places it in
+    // the tree
+    pub fn with_plugin(mut self, name: &str, plugin: impl RenderPlugin + 'static) -> Self {
+        self.plugins.retain(|(n, _)| n != name);
+        self.plugins.push((name.to_string(), Box::new(plugin)));
+        self
+    }
+
+    pub async fn run(self) -> anyhow::Result<()> {
+        crate::run_app(self).await
+    }
+}
+
+// where a teacup app starts:
+//
+//     Teacup::builder()
+//         .window(WindowOptions { title: "notes".into(), ..Default::default() })
+//         .render(RenderSettings { max_frame_rate: Some(30.0), ..Default::default() })
+//         .theme(Theme::light())
+//         .app(App::new(build).on_frame(step))
+//         .run()
+//         .await
+//
+// anything left out keeps its default, and without an app the demo ui runs.
+// settings given here win over the same ones already on the app
+pub struct Teacup;
+
+impl Teacup {
+    pub fn builder() -> TeacupBuilder {
+        TeacupBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct TeacupBuilder {
+    window: Option<WindowOptions>,
+    render: Option<RenderSettings>,
+    input: Option<InputSettings>,
+    theme: Option<Theme>,
+    app: Option<App>,
+}
+
+impl TeacupBuilder {
+    // everything about the window. its `render` and `input` are replaced by
+    // the ones given on their own, in whichever order
+    pub fn window(mut self, options: WindowOptions) -> Self {
+        self.window = Some(options);
+        self
+    }
+
+    pub fn render(mut self, settings: RenderSettings) -> Self {
+        self.render = Some(settings);
+        self
+    }
+
+    // overrides for the os's double click and scroll settings
+    pub fn input(mut self, settings: InputSettings) -> Self {
+        self.input = Some(settings);
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    pub fn app(mut self, app: App) -> Self {
+        self.app = Some(app);
+        self
+    }
+
+    // the app with everything else applied, without starting it
+    pub fn build(self) -> App {
+        let mut app = self.app.unwrap_or_else(|| App::new(crate::build_ui));
+        if let Some(options) = self.window {
+            app.options = options;
+        }
+        if let Some(render) = self.render {
+            app.options.render = render;
+        }
+        if let Some(input) = self.input {
+            app.options.input = input;
+        }
+        if let Some(theme) = self.theme {
+            app.theme = theme;
+        }
+        app
+    }
+
+    // opens the window and runs until it closes
+    pub async fn run(self) -> anyhow::Result<()> {
+        self.build().run().await
+    }
+}
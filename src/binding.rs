@@ -0,0 +1,554 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard, PoisonError, Weak},
+};
+
+use crate::{builder::Node, layout::Primative};
+
+// a piece of app state the ui can be bound to. clones share the value, so the
+// app keeps one and hands the others to bindings, callbacks or other threads.
+// every change bumps a version the bindings compare against once per frame
+pub struct Signal<T> {
+    inner: Arc<Mutex<SignalState<T>>>,
+}
+
+struct SignalState<T> {
+    value: T,
+    version: u64,
+}
+
+impl<T> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SignalState { value, version: 0 })),
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        let mut state = self.lock();
+        state.value = value;
+        state.version += 1;
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut state = self.lock();
+        f(&mut state.value);
+        state.version += 1;
+    }
+
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.lock().value)
+    }
+
+    pub fn version(&self) -> u64 {
+        self.lock().version
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SignalState<T>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Clone> Signal<T> {
+    pub fn get(&self) -> T {
+        self.lock().value.clone()
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for Signal<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Signal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("Signal")
+            .field("value", &state.value)
+            .field("version", &state.version)
+            .finish()
+    }
+}
+
+type BindingApply<T> = Box<dyn FnMut(&T, &mut dyn Primative) + Send>;
+
+// copies a signal's value onto one node whenever it changes, see `UI::bind`.
+// the node is held weakly, so the binding goes away with it
+pub struct Binding<T> {
+    signal: Signal<T>,
+    node: Weak<Mutex<dyn Primative>>,
+    apply: BindingApply<T>,
+    // the version last applied, none until the first frame
+    seen: Option<u64>,
+}
+
+impl<T> Binding<T> {
+    // `apply` runs with the signal locked, so it mustn't set that same signal
+    pub fn new(
+        signal: &Signal<T>,
+        node: &Node,
+        apply: impl FnMut(&T, &mut dyn Primative) + Send + 'static,
+    ) -> Self {
+        Self {
+            signal: signal.clone(),
+            node: Arc::downgrade(node),
+            apply: Box::new(apply),
+            seen: None,
+        }
+    }
+}
+
+// a binding with its value type erased, so the ui can keep them in one list
+pub(crate) trait SyncBinding: Send {
+    fn is_stale(&self) -> bool;
+
+    // applies a changed value. none once the node is gone, otherwise whether
+    // the tree needs a full layout because the node doesn't track dirtiness
+    fn sync(&mut self) -> Option<bool>;
+}
+
+impl<T: Send> SyncBinding for Binding<T> {
+    fn is_stale(&self) -> bool {
+        self.seen != Some(self.signal.version())
+    }
+
+    fn sync(&mut self) -> Option<bool> {
+        let node = self.node.upgrade()?;
+        let state = self.signal.lock();
+        if self.seen == Some(state.version) {
+            return Some(false);
+        }
+        self.seen = Some(state.version);
+        let Ok(mut prim) = node.lock() else {
+            return Some(false);
+        };
+        (self.apply)(&state.value, prim.deref_mut());
+        prim.mark_dirty();
+        Some(!prim.is_dirty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionChange {
+    Insert(usize),
+    Remove(usize),
+    Move { from: usize, to: usize },
+    Update(usize),
+    Reset,
+}
+
+#[derive(Debug, Default)]
+pub struct ObservableVec<T> {
+    items: Vec<T>,
+    changes: Vec<CollectionChange>,
+}
+
+impl<T> ObservableVec<T> {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        self.changes
+            .push(CollectionChange::Insert(self.items.len() - 1));
+    }
+
+    pub fn insert(&mut self, index: usize, item: T) {
+        self.items.insert(index, item);
+        self.changes.push(CollectionChange::Insert(index));
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let item = self.items.remove(index);
+        self.changes.push(CollectionChange::Remove(index));
+        item
+    }
+
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        self.changes.push(CollectionChange::Move { from, to });
+    }
+
+    pub fn update(&mut self, index: usize, f: impl FnOnce(&mut T)) {
+        f(&mut self.items[index]);
+        self.changes.push(CollectionChange::Update(index));
+    }
+
+    pub fn set(&mut self, index: usize, item: T) {
+        self.items[index] = item;
+        self.changes.push(CollectionChange::Update(index));
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.changes.push(CollectionChange::Reset);
+    }
+
+    pub fn replace_all(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.changes.push(CollectionChange::Reset);
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    // what the next `take_changes` returns, for anything that has to follow
+    // the same changes, like a SelectionModel
+    pub fn pending_changes(&self) -> &[CollectionChange] {
+        &self.changes
+    }
+
+    pub fn take_changes(&mut self) -> Vec<CollectionChange> {
+        std::mem::take(&mut self.changes)
+    }
+}
+
+impl<T> Deref for ObservableVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<T> From<Vec<T>> for ObservableVec<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self {
+            items,
+            changes: vec![CollectionChange::Reset],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    Insert,
+    Remove,
+    Move,
+}
+
+pub struct ItemTransition {
+    pub kind: TransitionKind,
+    pub element: Arc<Mutex<dyn Primative>>,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl ItemTransition {
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).min(1.0)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+// a child while the pending changes are replayed
+enum Slot {
+    Existing {
+        element: Arc<Mutex<dyn Primative>>,
+        updated: bool,
+    },
+    // inserted since the last sync. built once every change is in, from
+    // whatever ended up at its index
+    Pending,
+}
+
+type ElementFactory<T> = Box<dyn Fn(&T) -> Arc<Mutex<dyn Primative>> + Send>;
+type ElementUpdater<T> = Box<dyn Fn(&T, &mut dyn Primative) + Send>;
+
+pub struct ListBinding<T> {
+    factory: ElementFactory<T>,
+    updater: Option<ElementUpdater<T>>,
+    pub transition_duration: f32,
+    transitions: Vec<ItemTransition>,
+}
+
+impl<T> ListBinding<T> {
+    pub fn new(factory: impl Fn(&T) -> Arc<Mutex<dyn Primative>> + Send + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+            updater: None,
+            transition_duration: 0.2,
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn with_updater(
+        mut self,
+        updater: impl Fn(&T, &mut dyn Primative) + Send + 'static,
+    ) -> Self {
+        self.updater = Some(Box::new(updater));
+        self
+    }
+
+    // applies pending changes from `source` to `children` in place. returns true
+    // if the structure of `children` changed and the parent needs a relayout.
+    // the changes are replayed on the children first and new elements only
+    // built at the end, since the items they were recorded for may have moved
+    // or gone by now. a reset, or changes that don't fit the children, rebuild
+    // them all
+    pub fn sync(
+        &mut self,
+        source: &mut ObservableVec<T>,
+        children: &mut Vec<Arc<Mutex<dyn Primative>>>,
+    ) -> bool {
+        let changes = source.take_changes();
+        if changes.is_empty() {
+            return false;
+        }
+        let mut slots: Vec<Slot> = children
+            .drain(..)
+            .map(|element| Slot::Existing {
+                element,
+                updated: false,
+            })
+            .collect();
+        let mut relayout = false;
+        let mut rebuild = false;
+        for change in changes {
+            match change {
+                CollectionChange::Insert(i) if i <= slots.len() => {
+                    slots.insert(i, Slot::Pending);
+                    relayout = true;
+                }
+                CollectionChange::Remove(i) if i < slots.len() => {
+                    // one that was never built just goes
+                    if let Slot::Existing { element, .. } = slots.remove(i) {
+                        self.start_transition(TransitionKind::Remove, element);
+                    }
+                    relayout = true;
+                }
+                CollectionChange::Move { from, to } if from < slots.len() && to < slots.len() => {
+                    let slot = slots.remove(from);
+                    if let Slot::Existing { element, .. } = &slot {
+                        self.start_transition(TransitionKind::Move, element.clone());
+                    }
+                    slots.insert(to, slot);
+                    relayout = true;
+                }
+                CollectionChange::Update(i) if i < slots.len() => {
+                    if let Slot::Existing { updated, .. } = &mut slots[i] {
+                        *updated = true;
+                    }
+                }
+                _ => {
+                    rebuild = true;
+                    break;
+                }
+            }
+        }
+
+        if rebuild || slots.len() != source.len() {
+            children.extend(source.iter().map(|item| (self.factory)(item)));
+            self.transitions.clear();
+            return true;
+        }
+        for (slot, item) in slots.into_iter().zip(source.iter()) {
+            let element = match slot {
+                Slot::Pending => {
+                    let element = (self.factory)(item);
+                    self.start_transition(TransitionKind::Insert, element.clone());
+                    element
+                }
+                Slot::Existing {
+                    element,
+                    updated: true,
+                } => match &self.updater {
+                    Some(updater) => {
+                        if let Ok(mut prim) = element.lock() {
+                            updater(item, prim.deref_mut());
+                        }
+                        element
+                    }
+                    None => {
+                        relayout = true;
+                        (self.factory)(item)
+                    }
+                },
+                Slot::Existing { element, .. } => element,
+            };
+            children.push(element);
+        }
+        relayout
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        for transition in &mut self.transitions {
+            transition.elapsed += dt;
+        }
+        self.transitions.retain(|t| !t.is_finished());
+    }
+
+    pub fn transitions(&self) -> &[ItemTransition] {
+        &self.transitions
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.transitions.is_empty()
+    }
+
+    fn start_transition(&mut self, kind: TransitionKind, element: Arc<Mutex<dyn Primative>>) {
+        self.transitions
+            .retain(|t| !Arc::ptr_eq(&t.element, &element));
+        self.transitions.push(ItemTransition {
+            kind,
+            element,
+            elapsed: 0.0,
+            duration: self.transition_duration,
+        });
+    }
+}
+
+// a ListBinding and the collection it follows, with the item type erased so a
+// widget like ListView can hold one
+pub(crate) trait BoundList: Send {
+    fn has_changes(&self) -> bool;
+    fn pending_changes(&self) -> Vec<CollectionChange>;
+    fn sync(&mut self, children: &mut Vec<Node>) -> bool;
+    // moves an item in the source, so the children follow on the next sync
+    fn move_item(&mut self, from: usize, to: usize);
+    fn tick(&mut self, dt: f32);
+    fn transitions(&self) -> &[ItemTransition];
+}
+
+pub(crate) struct Bound<T> {
+    source: Arc<Mutex<ObservableVec<T>>>,
+    binding: ListBinding<T>,
+}
+
+impl<T> Bound<T> {
+    // the first sync builds every child from the source, whatever was there
+    pub(crate) fn new(source: Arc<Mutex<ObservableVec<T>>>, binding: ListBinding<T>) -> Self {
+        source
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .changes
+            .push(CollectionChange::Reset);
+        Self { source, binding }
+    }
+
+    fn source(&self) -> MutexGuard<'_, ObservableVec<T>> {
+        self.source.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Send> BoundList for Bound<T> {
+    fn has_changes(&self) -> bool {
+        self.source().has_changes()
+    }
+
+    fn pending_changes(&self) -> Vec<CollectionChange> {
+        self.source().pending_changes().to_vec()
+    }
+
+    fn sync(&mut self, children: &mut Vec<Node>) -> bool {
+        let mut source = self.source.lock().unwrap_or_else(PoisonError::into_inner);
+        self.binding.sync(&mut source, children)
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        self.source().move_item(from, to);
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.binding.tick(dt);
+    }
+
+    fn transitions(&self) -> &[ItemTransition] {
+        self.binding.transitions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{ListBinding, ObservableVec};
+    use crate::layout::{Primative, Rectangle};
+
+    // each row is as wide as its item, so the rows say what they were built
+    // from
+    fn rows() -> ListBinding<i32> {
+        ListBinding::new(|item: &i32| {
+            Arc::new(Mutex::new(Rectangle {
+                width: *item,
+                ..Default::default()
+            })) as Arc<Mutex<dyn Primative>>
+        })
+    }
+
+    fn widths(children: &[Arc<Mutex<dyn Primative>>]) -> Vec<i32> {
+        children
+            .iter()
+            .map(|child| child.lock().unwrap().get_width())
+            .collect()
+    }
+
+    #[test]
+    fn push_then_remove_before_sync() {
+        let mut source = ObservableVec::from(vec![1, 2]);
+        let mut children = Vec::new();
+        let mut binding = rows();
+        binding.sync(&mut source, &mut children);
+
+        source.push(3);
+        source.remove(2);
+        source.push(4);
+        source.remove(0);
+        assert!(binding.sync(&mut source, &mut children));
+        assert_eq!(widths(&children), vec![2, 4]);
+    }
+
+    #[test]
+    fn insert_then_truncate_before_sync() {
+        let mut source = ObservableVec::from(vec![1]);
+        let mut children = Vec::new();
+        let mut binding = rows();
+        binding.sync(&mut source, &mut children);
+
+        source.insert(0, 5);
+        source.push(6);
+        source.remove(2);
+        source.remove(1);
+        binding.sync(&mut source, &mut children);
+        assert_eq!(widths(&children), vec![5]);
+    }
+
+    #[test]
+    fn updates_apply_to_the_final_item() {
+        let mut source = ObservableVec::from(vec![1, 2, 3]);
+        let mut children = Vec::new();
+        let mut binding = rows();
+        binding.sync(&mut source, &mut children);
+
+        source.set(2, 7);
+        source.move_item(2, 0);
+        binding.sync(&mut source, &mut children);
+        assert_eq!(widths(&children), vec![7, 1, 2]);
+    }
+}
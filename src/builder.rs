@@ -0,0 +1,325 @@
+use std::{
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::EventContext,
+    fill::Fill,
+    floating::{Anchor, Positioning},
+    layout::{
+        AlignCross, AlignMain, Container, LayoutMode, Primative, Rectangle, ScrollContainer,
+        Sizing, SizingMode,
+    },
+    reconcile::NodeId,
+};
+
+pub type Node = Arc<Mutex<dyn Primative>>;
+
+// anything that can be a child in the tree. lets builders take rectangles,
+// widgets and other builders without the caller wrapping them
+pub trait IntoNode {
+    fn into_node(self) -> Node;
+}
+
+impl<T: Primative + 'static> IntoNode for T {
+    fn into_node(self) -> Node {
+        Arc::new(Mutex::new(self))
+    }
+}
+
+impl IntoNode for Node {
+    fn into_node(self) -> Node {
+        self
+    }
+}
+
+// fluent construction of a `Rectangle` (or `ScrollContainer`) and its children
+#[derive(Default)]
+pub struct ElementBuilder {
+    rect: Rectangle,
+    scrollable: bool,
+}
+
+// each takes its id from where it's called, so the same declaration matches
+// itself when the tree is rebuilt. see `UI::rebuild`
+#[track_caller]
+pub fn rect() -> ElementBuilder {
+    let mut builder = ElementBuilder::default();
+    builder.rect.id = Some(NodeId::caller());
+    builder
+}
+
+#[track_caller]
+pub fn row() -> ElementBuilder {
+    rect().layout(LayoutMode::LeftToRight)
+}
+
+#[track_caller]
+pub fn column() -> ElementBuilder {
+    rect().layout(LayoutMode::TopToBottom)
+}
+
+impl ElementBuilder {
+    pub fn layout(mut self, layout_mode: LayoutMode) -> Self {
+        self.rect.layout_mode = layout_mode;
+        self
+    }
+
+    // for nodes whose call site doesn't pin them down, like items of a list
+    // that can reorder. the key should be unique among its siblings
+    pub fn id(mut self, key: impl Hash) -> Self {
+        self.rect.id = Some(NodeId::new(key));
+        self
+    }
+
+    pub fn sizing(mut self, sizing: Sizing) -> Self {
+        self.rect.sizing = sizing;
+        self
+    }
+
+    pub fn width(mut self, width: SizingMode) -> Self {
+        self.rect.sizing.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: SizingMode) -> Self {
+        self.rect.sizing.height = height;
+        self
+    }
+
+    pub fn grow(self) -> Self {
+        self.sizing(Sizing::GROW)
+    }
+
+    pub fn fit(self) -> Self {
+        self.sizing(Sizing::FIT)
+    }
+
+    pub fn fixed(self, width: i32, height: i32) -> Self {
+        self.width(SizingMode::Fixed(width))
+            .height(SizingMode::Fixed(height))
+    }
+
+    pub fn min_width(mut self, width: i32) -> Self {
+        self.rect.min_width = width;
+        self
+    }
+
+    pub fn min_height(mut self, height: i32) -> Self {
+        self.rect.min_height = height;
+        self
+    }
+
+    pub fn max_width(mut self, width: i32) -> Self {
+        self.rect.max_width = Some(width);
+        self
+    }
+
+    pub fn max_height(mut self, height: i32) -> Self {
+        self.rect.max_height = Some(height);
+        self
+    }
+
+    pub fn padding(mut self, padding: i32) -> Self {
+        self.rect.padding = padding;
+        self
+    }
+
+    pub fn gap(mut self, child_gap: i32) -> Self {
+        self.rect.child_gap = child_gap;
+        self
+    }
+
+    // children flow onto a new line when they run out of room
+    pub fn wrap(mut self) -> Self {
+        self.rect.wrap = true;
+        self
+    }
+
+    pub fn align_main(mut self, align: AlignMain) -> Self {
+        self.rect.align_main = align;
+        self
+    }
+
+    pub fn align_cross(mut self, align: AlignCross) -> Self {
+        self.rect.align_cross = align;
+        self
+    }
+
+    // centers children on both axes
+    pub fn center(self) -> Self {
+        self.align_main(AlignMain::Center)
+            .align_cross(AlignCross::Center)
+    }
+
+    pub fn color(mut self, color: srgb) -> Self {
+        self.rect.fill = Fill::Solid(color);
+        self
+    }
+
+    // a color or a gradient, see `Fill`
+    pub fn fill(mut self, fill: impl Into<Fill>) -> Self {
+        self.rect.fill = fill.into();
+        self
+    }
+
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.rect.corner_radius = radius;
+        self
+    }
+
+    pub fn border(mut self, width: i32, color: srgb) -> Self {
+        self.rect.border_width = width;
+        self.rect.border_color = color;
+        self
+    }
+
+    // keeps children clear of the border
+    pub fn border_in_padding(mut self) -> Self {
+        self.rect.border_in_padding = true;
+        self
+    }
+
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.rect.opacity = opacity;
+        self
+    }
+
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.rect.brightness = Some(brightness);
+        self
+    }
+
+    // cuts children off at the edge, see `Rectangle::clip_children`
+    pub fn clip_children(mut self) -> Self {
+        self.rect.clip_children = true;
+        self
+    }
+
+    // sees events passing through in both the capture and bubble phases
+    pub fn on_event(mut self, f: impl FnMut(&mut EventContext) + Send + 'static) -> Self {
+        self.rect.event_handler = Some(Box::new(f));
+        self
+    }
+
+    // draws above siblings with a lower index
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.rect.z_index = z_index;
+        self
+    }
+
+    // takes this out of the parent's layout and puts it against `anchor`,
+    // moved by `offset`. see `Positioning::Floating`
+    pub fn floating(mut self, anchor: Anchor, offset: (i32, i32)) -> Self {
+        self.rect.positioning = Positioning::Floating { anchor, offset };
+        self
+    }
+
+    // draws the fill and border with a shader registered through
+    // App::with_shader
+    pub fn shader(mut self, name: &str) -> Self {
+        self.rect.shader = Some(name.to_string());
+        self
+    }
+
+    // clip children to the bounds and let them scroll
+    pub fn scrollable(mut self) -> Self {
+        self.scrollable = true;
+        self
+    }
+
+    pub fn child(mut self, child: impl IntoNode) -> Self {
+        self.rect.children.push(child.into_node());
+        self
+    }
+
+    pub fn children<T: IntoNode>(mut self, children: impl IntoIterator<Item = T>) -> Self {
+        self.rect
+            .children
+            .extend(children.into_iter().map(IntoNode::into_node));
+        self
+    }
+
+    pub fn build(self) -> Node {
+        self.into_node()
+    }
+
+    // for the root of a `UI`
+    pub fn into_container(self) -> Arc<Mutex<dyn Container>> {
+        if self.scrollable {
+            Arc::new(Mutex::new(ScrollContainer::new(self.rect)))
+        } else {
+            Arc::new(Mutex::new(self.rect))
+        }
+    }
+}
+
+impl IntoNode for ElementBuilder {
+    fn into_node(self) -> Node {
+        if self.scrollable {
+            ScrollContainer::new(self.rect).into_node()
+        } else {
+            self.rect.into_node()
+        }
+    }
+}
+
+// declarative tree construction on top of the builder. an element is one of
+// `rect`, `row` or `column`, optionally followed by `(key: value, flag)` args,
+// builder method calls, and a `{ child; child }` block. a parenthesized
+// expression is used as a child as is
+//
+//     ui! {
+//         row(padding: 16, gap: 16, grow) {
+//             rect(grow).color(RED);
+//             column(grow) { (label) }
+//         }
+//     }
+#[macro_export]
+macro_rules! ui {
+    (@args $builder:ident) => {};
+    (@args $builder:ident $key:ident : $value:expr , $($rest:tt)*) => {
+        $builder = $builder.$key($value);
+        $crate::ui!(@args $builder $($rest)*);
+    };
+    (@args $builder:ident $key:ident : $value:expr) => {
+        $builder = $builder.$key($value);
+    };
+    (@args $builder:ident $flag:ident , $($rest:tt)*) => {
+        $builder = $builder.$flag();
+        $crate::ui!(@args $builder $($rest)*);
+    };
+    (@args $builder:ident $flag:ident) => {
+        $builder = $builder.$flag();
+    };
+
+    (@children $builder:ident []) => {};
+    (@children $builder:ident [] ; $($rest:tt)*) => {
+        $crate::ui!(@children $builder [] $($rest)*);
+    };
+    (@children $builder:ident [$($current:tt)+] ; $($rest:tt)*) => {
+        $builder = $builder.child($crate::ui!($($current)+));
+        $crate::ui!(@children $builder [] $($rest)*);
+    };
+    (@children $builder:ident [$($current:tt)+]) => {
+        $builder = $builder.child($crate::ui!($($current)+));
+    };
+    (@children $builder:ident [$($current:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ui!(@children $builder [$($current)* $next] $($rest)*);
+    };
+
+    (($child:expr)) => {
+        $child
+    };
+    ($kind:ident $(($($arg:tt)*))? $(.$method:ident($($method_arg:expr),*))* $({$($child:tt)*})?) => {{
+        #[allow(unused_mut)]
+        let mut builder = $crate::builder::$kind();
+        $($crate::ui!(@args builder $($arg)*);)?
+        $(builder = builder.$method($($method_arg),*);)*
+        $($crate::ui!(@children builder [] $($child)*);)?
+        builder
+    }};
+}
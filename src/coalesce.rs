@@ -0,0 +1,183 @@
+use glfw::WindowEvent;
+
+// how an event is treated when a frame's worth of them is handled at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Priority {
+    // every one matters and they keep their order, like clicks and keys
+    Discrete,
+    // only where things ended up matters, like the pointer's position or the
+    // window's size. a run of them between discrete events collapses into one
+    // of each kind
+    Continuous,
+}
+
+pub(crate) fn priority(event: &WindowEvent) -> Priority {
+    match event {
+        WindowEvent::CursorPos(..)
+        | WindowEvent::Scroll(..)
+        | WindowEvent::Pos(..)
+        | WindowEvent::Size(..)
+        | WindowEvent::FramebufferSize(..)
+        | WindowEvent::ContentScale(..) => Priority::Continuous,
+        _ => Priority::Discrete,
+    }
+}
+
+// a window event as it comes out of `coalesce`. `precise` is whether a scroll
+// came from a trackpad rather than wheel notches, read off the deltas before
+// they were summed, since fractional deltas can add up to whole ones
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Coalesced {
+    pub event: WindowEvent,
+    pub precise: bool,
+}
+
+impl Coalesced {
+    fn new(event: WindowEvent) -> Self {
+        let precise = match event {
+            WindowEvent::Scroll(x, y) => x.fract() != 0.0 || y.fract() != 0.0,
+            _ => false,
+        };
+        Self { event, precise }
+    }
+
+    // folds `next` into this when they're the same kind of continuous event.
+    // scrolls add up, keeping wheel notches and trackpad deltas apart so the
+    // sum still reads as the device it came from
+    fn merge(&mut self, next: &Coalesced) -> bool {
+        match (&mut self.event, &next.event) {
+            (WindowEvent::Scroll(x, y), WindowEvent::Scroll(dx, dy)) => {
+                if self.precise != next.precise {
+                    return false;
+                }
+                *x += dx;
+                *y += dy;
+                true
+            }
+            (previous, event)
+                if std::mem::discriminant(previous) == std::mem::discriminant(event) =>
+            {
+                *self = next.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// what's left of a frame's events once the continuous ones are coalesced, so
+// a fast mouse over a heavy ui costs one hit test a frame instead of dozens.
+// discrete events keep their order relative to everything else, and a
+// collapsed event lands where the last of its kind was
+pub(crate) fn coalesce(events: impl IntoIterator<Item = WindowEvent>) -> Vec<Coalesced> {
+    let mut out: Vec<Coalesced> = Vec::new();
+    // where the current run of continuous events starts in `out`
+    let mut run = 0;
+    for event in events.into_iter().map(Coalesced::new) {
+        if priority(&event.event) == Priority::Discrete {
+            out.push(event);
+            run = out.len();
+            continue;
+        }
+        let earlier = out[run..].iter().rposition(|queued| {
+            std::mem::discriminant(&queued.event) == std::mem::discriminant(&event.event)
+        });
+        match earlier {
+            Some(index) => {
+                let mut merged = out.remove(run + index);
+                if !merged.merge(&event) {
+                    // a scroll from the other kind of device. the earlier one
+                    // stays where it was
+                    out.insert(run + index, merged);
+                    out.push(event);
+                    continue;
+                }
+                out.push(merged);
+            }
+            None => out.push(event),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use glfw::{Action, MouseButton, WindowEvent};
+
+    use super::{Coalesced, coalesce};
+
+    fn events(coalesced: Vec<Coalesced>) -> Vec<WindowEvent> {
+        coalesced.into_iter().map(|queued| queued.event).collect()
+    }
+
+    fn click() -> WindowEvent {
+        WindowEvent::MouseButton(
+            MouseButton::Button1,
+            Action::Press,
+            glfw::Modifiers::empty(),
+        )
+    }
+
+    #[test]
+    fn pointer_moves_collapse_between_discrete_events() {
+        let out = coalesce([
+            WindowEvent::CursorPos(1.0, 1.0),
+            WindowEvent::CursorPos(2.0, 2.0),
+            click(),
+            WindowEvent::CursorPos(3.0, 3.0),
+            WindowEvent::Size(10, 10),
+            WindowEvent::CursorPos(4.0, 4.0),
+        ]);
+        assert_eq!(
+            events(out),
+            [
+                WindowEvent::CursorPos(2.0, 2.0),
+                click(),
+                WindowEvent::Size(10, 10),
+                WindowEvent::CursorPos(4.0, 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrolls_add_up_and_stay_precise() {
+        let out = coalesce([
+            WindowEvent::Scroll(0.0, 0.5),
+            WindowEvent::Scroll(0.0, 0.5),
+            WindowEvent::Scroll(0.25, 0.0),
+        ]);
+        assert_eq!(
+            out,
+            [Coalesced {
+                event: WindowEvent::Scroll(0.25, 1.0),
+                precise: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn wheel_and_trackpad_scrolls_stay_apart() {
+        let out = coalesce([
+            WindowEvent::Scroll(0.0, 1.0),
+            WindowEvent::Scroll(0.0, 0.5),
+            WindowEvent::Scroll(0.0, 2.0),
+        ]);
+        assert_eq!(
+            out,
+            [
+                Coalesced {
+                    event: WindowEvent::Scroll(0.0, 1.0),
+                    precise: false,
+                },
+                Coalesced {
+                    event: WindowEvent::Scroll(0.0, 0.5),
+                    precise: true,
+                },
+                Coalesced {
+                    event: WindowEvent::Scroll(0.0, 2.0),
+                    precise: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::sync::{Arc, OnceLock};
+
+use tinycolors::srgb;
+
+use crate::{
+    renderer::post::{ColorLut, PostEffect},
+    theme::rgb,
+};
+
+// the dichromacies the debug simulation can show. each is the full strength
+// form, so milder color blindness looks somewhere in between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorVision {
+    // no long wavelength (red) cones
+    Protanopia,
+    // no medium wavelength (green) cones, the most common
+    Deuteranopia,
+    // no short wavelength (blue) cones
+    Tritanopia,
+}
+
+// the lut resolution. the simulation is linear, so this is plenty
+const LUT_SIZE: u32 = 33;
+
+static LUTS: [OnceLock<Arc<ColorLut>>; 3] = [const { OnceLock::new() }; 3];
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 3] = [
+        ColorVision::Protanopia,
+        ColorVision::Deuteranopia,
+        ColorVision::Tritanopia,
+    ];
+
+    // machado, oliveira and fernandes 2009, at severity 1. works on linear rgb
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorVision::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorVision::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorVision::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    // how `color` looks with this deficiency
+    pub fn simulate(self, color: srgb) -> srgb {
+        let [r, g, b] = self.simulate_encoded([color.r, color.g, color.b]);
+        rgb(r, g, b)
+    }
+
+    fn simulate_encoded(self, color: [f32; 3]) -> [f32; 3] {
+        let [r, g, b] = color.map(to_linear);
+        self.matrix()
+            .map(|row| to_encoded((row[0] * r + row[1] * g + row[2] * b).clamp(0.0, 1.0)))
+    }
+
+    // built once per deficiency, so the post processor keeps the same upload
+    pub fn lut(self) -> Arc<ColorLut> {
+        LUTS[self as usize]
+            .get_or_init(|| ColorLut::from_fn(LUT_SIZE, |c| self.simulate_encoded(c)))
+            .clone()
+    }
+
+    // the full screen pass `UI::color_vision` adds after the app's own effects
+    pub fn effect(self) -> PostEffect {
+        PostEffect::color_grade(self.lut())
+    }
+}
+
+// okabe and ito's palette, picked to stay apart under every common color
+// blindness. black comes first, so charts usually start from the second
+pub const OKABE_ITO: [srgb; 8] = [
+    srgb {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+    },
+    srgb {
+        r: 0.902,
+        g: 0.624,
+        b: 0.0,
+    },
+    srgb {
+        r: 0.337,
+        g: 0.706,
+        b: 0.914,
+    },
+    srgb {
+        r: 0.0,
+        g: 0.620,
+        b: 0.451,
+    },
+    srgb {
+        r: 0.941,
+        g: 0.894,
+        b: 0.259,
+    },
+    srgb {
+        r: 0.0,
+        g: 0.447,
+        b: 0.698,
+    },
+    srgb {
+        r: 0.835,
+        g: 0.369,
+        b: 0.0,
+    },
+    srgb {
+        r: 0.800,
+        g: 0.475,
+        b: 0.655,
+    },
+];
+
+// perceptual distance between two colors as seen by the viewer who can tell
+// them apart least, normal vision included. roughly 0 to 1, with anything
+// under 0.1 hard to separate in a thin line
+pub fn min_difference(a: srgb, b: srgb) -> f32 {
+    ColorVision::ALL
+        .iter()
+        .map(|vision| oklab_distance(vision.simulate(a), vision.simulate(b)))
+        .fold(oklab_distance(a, b), f32::min)
+}
+
+// `count` colors from `candidates`, each chosen to be as far as possible from
+// the ones before it for every kind of color vision. the first candidate is
+// always kept, so put the color a series should start with there
+pub fn pick_distinguishable(candidates: &[srgb], count: usize) -> Vec<srgb> {
+    let (first, rest) = match candidates.split_first() {
+        Some((first, rest)) if count > 0 => (vec![*first], rest.to_vec()),
+        _ => return Vec::new(),
+    };
+    extend_distinguishable(first, rest, count)
+}
+
+// colors for `count` chart series. up to seven are the okabe-ito colors after
+// black, past that more are picked from an even spread of the rgb cube,
+// though with that many some pairs will be close whatever the choice
+pub fn series_colors(count: usize) -> Vec<srgb> {
+    if count < OKABE_ITO.len() {
+        return OKABE_ITO[1..=count].to_vec();
+    }
+    let steps = [0.0, 0.25, 0.5, 0.75, 1.0];
+    let mut candidates = Vec::new();
+    for r in steps {
+        for g in steps {
+            for b in steps {
+                candidates.push(rgb(r, g, b));
+            }
+        }
+    }
+    extend_distinguishable(OKABE_ITO[1..].to_vec(), candidates, count)
+}
+
+fn extend_distinguishable(
+    mut picked: Vec<srgb>,
+    mut remaining: Vec<srgb>,
+    count: usize,
+) -> Vec<srgb> {
+    while picked.len() < count && !remaining.is_empty() {
+        let distance = |candidate: &srgb| {
+            picked
+                .iter()
+                .map(|color| min_difference(*color, *candidate))
+                .fold(f32::MAX, f32::min)
+        };
+        let (best, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(i, candidate)| (i, distance(candidate)))
+            .fold(
+                (0, f32::MIN),
+                |best, next| if next.1 > best.1 { next } else { best },
+            );
+        picked.push(remaining.swap_remove(best));
+    }
+    picked
+}
+
+fn to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_encoded(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn oklab(color: srgb) -> [f32; 3] {
+    let [r, g, b] = [color.r, color.g, color.b].map(to_linear);
+    let l = (0.4122215 * r + 0.5363325 * g + 0.0514460 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.107397 * b).cbrt();
+    let s = (0.0883025 * r + 0.2817188 * g + 0.6299787 * b).cbrt();
+    [
+        0.2104543 * l + 0.7936178 * m - 0.0040720 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.0259040 * l + 0.7827718 * m - 0.8086758 * s,
+    ]
+}
+
+fn oklab_distance(a: srgb, b: srgb) -> f32 {
+    let (a, b) = (oklab(a), oklab(b));
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
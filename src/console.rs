@@ -0,0 +1,496 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use log::{Level, Log, Metadata, Record};
+
+use crate::{
+    event::Event,
+    input::Key,
+    layout::{Primative, UI},
+    renderer::{
+        frame::Frame,
+        mesh_builder::{make_ss_rectangle, make_ss_rounded_ring},
+        text::Font,
+    },
+    stats::UiStats,
+    text,
+    theme::{Theme, rgb},
+};
+
+// how many lines of log output are kept around for the console
+const LOG_CAPACITY: usize = 500;
+const FONT_SIZE: f32 = 13.0;
+const PADDING: i32 = 12;
+// the key that opens and closes the console
+const TOGGLE: char = '`';
+const BUILTINS: [(&str, &str); 6] = [
+    ("help", "lists the commands"),
+    ("clear", "clears the console"),
+    ("layout", "prints the element tree with its computed layout"),
+    ("bounds", "toggles outlines around every element"),
+    (
+        "stats",
+        "toggles frame time and input latency in the corner",
+    ),
+    ("theme", "theme <dark|light>, switches the theme"),
+];
+
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+static LOG: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+// adds a line to what the console shows, dropping the oldest past
+// `LOG_CAPACITY`
+pub fn record(level: Level, target: &str, message: String) {
+    if let Ok(mut log) = LOG.lock() {
+        if log.len() == LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(LogLine {
+            level,
+            target: target.to_string(),
+            message,
+        });
+    }
+}
+
+pub fn recent_logs() -> Vec<LogLine> {
+    LOG.lock()
+        .map_or_else(|_| Vec::new(), |log| log.iter().cloned().collect())
+}
+
+fn clear_logs() {
+    if let Ok(mut log) = LOG.lock() {
+        log.clear();
+    }
+}
+
+// env_logger, configured from RUST_LOG as usual, with a copy of everything it
+// prints kept for the console. call `ConsoleLogger::init` in place of
+// `env_logger::init`
+pub struct ConsoleLogger {
+    inner: env_logger::Logger,
+}
+
+impl ConsoleLogger {
+    pub fn init() -> Result<(), log::SetLoggerError> {
+        let inner = env_logger::Builder::from_default_env().build();
+        let filter = inner.filter();
+        log::set_boxed_logger(Box::new(Self { inner }))?;
+        log::set_max_level(filter);
+        Ok(())
+    }
+}
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            self::record(record.level(), record.target(), record.args().to_string());
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+// the same for tracing. add it to a subscriber next to whatever else prints
+// the events
+pub struct ConsoleLayer;
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _cx: tracing_subscriber::layer::Context<'_, S>) {
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+        let level = match *event.metadata().level() {
+            tracing::Level::ERROR => Level::Error,
+            tracing::Level::WARN => Level::Warn,
+            tracing::Level::INFO => Level::Info,
+            tracing::Level::DEBUG => Level::Debug,
+            tracing::Level::TRACE => Level::Trace,
+        };
+        record(level, event.metadata().target(), message);
+    }
+}
+
+// the message first, then any other fields as name=value
+struct MessageVisitor<'a>(&'a mut String);
+
+impl tracing::field::Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{value:?}"));
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+// takes the rest of the line after the command name. returns what to print
+pub type ConsoleCommand = Box<dyn FnMut(&mut UI, &[&str]) -> String + Send>;
+pub type ThemeCallback = Box<dyn FnMut(&mut UI, &Theme) + Send>;
+
+pub(crate) enum ConsoleInput {
+    Ignored,
+    Consumed,
+    Run(String),
+}
+
+// an in-app console over the top of the window, opened with the backtick key.
+// it shows recent log output and runs commands typed into it, the built in
+// ones plus any the app registers. on by default in debug builds only
+pub struct DevConsole {
+    pub enabled: bool,
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    // where up and down have walked back to in `history`
+    history_index: Option<usize>,
+    commands: BTreeMap<String, (String, ConsoleCommand)>,
+    on_theme: Option<ThemeCallback>,
+    font: Option<Arc<Font>>,
+}
+
+impl Default for DevConsole {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(debug_assertions),
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_index: None,
+            commands: BTreeMap::new(),
+            on_theme: None,
+            font: None,
+        }
+    }
+}
+
+impl DevConsole {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        if open && !self.load_font() {
+            log::warn!("no system font found, the console can't open");
+            return;
+        }
+        self.open = open && self.enabled;
+    }
+
+    fn load_font(&mut self) -> bool {
+        if self.font.is_none() {
+            self.font = Font::system_default();
+        }
+        self.font.is_some()
+    }
+
+    // names the built in commands use are taken
+    pub fn register(
+        &mut self,
+        name: &str,
+        help: &str,
+        command: impl FnMut(&mut UI, &[&str]) -> String + Send + 'static,
+    ) {
+        if BUILTINS.iter().any(|(builtin, _)| *builtin == name) {
+            log::warn!("console command {name:?} is built in");
+            return;
+        }
+        self.commands
+            .insert(name.to_string(), (help.to_string(), Box::new(command)));
+    }
+
+    // what `theme` does. the tree keeps the colors it was built with, so apps
+    // that want the switch to show everywhere rebuild it from here. without
+    // one only the window background changes
+    pub fn on_theme(&mut self, callback: impl FnMut(&mut UI, &Theme) + Send + 'static) {
+        self.on_theme = Some(Box::new(callback));
+    }
+
+    fn help(&self) -> String {
+        let builtins = BUILTINS
+            .iter()
+            .map(|(name, help)| (name.to_string(), help.to_string()));
+        let registered = self
+            .commands
+            .iter()
+            .map(|(name, (help, _))| (name.clone(), help.clone()));
+        builtins
+            .chain(registered)
+            .map(|(name, help)| format!("{name}: {help}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub(crate) fn handle_event(&mut self, event: &Event) -> ConsoleInput {
+        if !self.enabled {
+            return ConsoleInput::Ignored;
+        }
+        if *event == Event::Text(TOGGLE) {
+            self.set_open(!self.open);
+            return ConsoleInput::Consumed;
+        }
+        if !self.open {
+            return ConsoleInput::Ignored;
+        }
+        match event {
+            Event::Text(c) if !c.is_control() => self.input.push(*c),
+            Event::KeyPressed { key, .. } => match key {
+                Key::Enter => {
+                    let line = std::mem::take(&mut self.input);
+                    self.history_index = None;
+                    if line.trim().is_empty() {
+                        return ConsoleInput::Consumed;
+                    }
+                    if self.history.last() != Some(&line) {
+                        self.history.push(line.clone());
+                    }
+                    return ConsoleInput::Run(line);
+                }
+                Key::Backspace => {
+                    self.input.pop();
+                }
+                Key::Escape => self.open = false,
+                Key::Up if !self.history.is_empty() => {
+                    let index = self
+                        .history_index
+                        .map_or(self.history.len() - 1, |index| index.saturating_sub(1));
+                    self.history_index = Some(index);
+                    self.input = self.history[index].clone();
+                }
+                Key::Down => {
+                    let index = self.history_index.map(|index| index + 1);
+                    self.history_index = index.filter(|index| *index < self.history.len());
+                    self.input = self
+                        .history_index
+                        .map(|index| self.history[index].clone())
+                        .unwrap_or_default();
+                }
+                _ => {}
+            },
+            Event::Text(_) | Event::KeyReleased { .. } => {}
+            // the pointer still works on the page underneath
+            _ => return ConsoleInput::Ignored,
+        }
+        ConsoleInput::Consumed
+    }
+
+    // the top of the window, with the newest log lines just above the input
+    pub(crate) fn draw(
+        &self,
+        render_pass: &mut wgpu::RenderPass,
+        frame: &mut Frame,
+        size: (i32, i32),
+    ) {
+        let Some(font) = self.font.as_ref().filter(|_| self.open) else {
+            return;
+        };
+        let theme = Theme::dark();
+        let px = text::scaled(FONT_SIZE);
+        let line_height = (font.line_height(px).ceil() as i32).max(1);
+        let height = size.1 * 2 / 5;
+        make_ss_rectangle(0, 0, size.0, height, theme.background, size).draw(render_pass, frame);
+        make_ss_rectangle(0, height, size.0, 2, theme.border, size).draw(render_pass, frame);
+
+        let input_y = height - PADDING - line_height;
+        let prompt = format!("> {}_", self.input);
+        let x = PADDING as f32;
+        frame.draw_text(
+            render_pass,
+            font,
+            &prompt,
+            px,
+            (x, input_y as f32),
+            theme.accent,
+            size,
+        );
+
+        let rows = ((input_y - PADDING) / line_height).max(0) as usize;
+        let logs = recent_logs();
+        let shown = &logs[logs.len().saturating_sub(rows)..];
+        let top = input_y - shown.len() as i32 * line_height;
+        for (i, line) in shown.iter().enumerate() {
+            let color = match line.level {
+                Level::Error => theme.danger,
+                Level::Warn => theme.accent,
+                Level::Info => theme.text,
+                Level::Debug | Level::Trace => theme.inactive,
+            };
+            let text = if line.target == "console" {
+                line.message.clone()
+            } else {
+                format!("{:<5} {}: {}", line.level, line.target, line.message)
+            };
+            let y = top + i as i32 * line_height;
+            frame.draw_text(render_pass, font, &text, px, (x, y as f32), color, size);
+        }
+    }
+
+    // the `stats` overlay, `UiStats::summary` in the top right corner. stays up
+    // with the console closed
+    pub(crate) fn draw_stats(
+        &self,
+        stats: &UiStats,
+        render_pass: &mut wgpu::RenderPass,
+        frame: &mut Frame,
+        size: (i32, i32),
+    ) {
+        let Some(font) = &self.font else {
+            return;
+        };
+        let theme = Theme::dark();
+        let px = text::scaled(FONT_SIZE);
+        let line_height = (font.line_height(px).ceil() as i32).max(1);
+        let summary = stats.summary();
+        let lines: Vec<&str> = summary.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| font.measure(line, px).0.ceil() as i32)
+            .max()
+            .unwrap_or(0)
+            + 2 * PADDING;
+        let height = lines.len() as i32 * line_height + 2 * PADDING;
+        let x = size.0 - width;
+        make_ss_rectangle(x, 0, width, height, theme.background, size).draw(render_pass, frame);
+        for (i, line) in lines.iter().enumerate() {
+            let y = PADDING + i as i32 * line_height;
+            frame.draw_text(
+                render_pass,
+                font,
+                line,
+                px,
+                ((x + PADDING) as f32, y as f32),
+                theme.text,
+                size,
+            );
+        }
+    }
+}
+
+// runs one line typed into the console, echoing it and the output to the log
+pub(crate) fn run(ui: &mut UI, line: &str) {
+    record(Level::Info, "console", format!("> {line}"));
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((name, args)) = words.split_first() else {
+        return;
+    };
+    let output = match *name {
+        "help" => ui.console.help(),
+        "clear" => {
+            clear_logs();
+            String::new()
+        }
+        "layout" => dump_layout(ui),
+        "bounds" => {
+            ui.debug_bounds = !ui.debug_bounds;
+            format!("bounds {}", if ui.debug_bounds { "on" } else { "off" })
+        }
+        "stats" => {
+            ui.debug_stats = !ui.debug_stats;
+            ui.console.load_font();
+            format!(
+                "stats {}\n{}",
+                if ui.debug_stats { "on" } else { "off" },
+                ui.stats.summary()
+            )
+        }
+        "theme" => match args.first().copied() {
+            Some("dark") => set_theme(ui, &Theme::dark()),
+            Some("light") => set_theme(ui, &Theme::light()),
+            _ => "usage: theme <dark|light>".to_string(),
+        },
+        // taken out while it runs so it can have the whole ui
+        name => match ui.console.commands.remove(name) {
+            Some((help, mut command)) => {
+                let output = command(ui, args);
+                ui.console
+                    .commands
+                    .insert(name.to_string(), (help, command));
+                output
+            }
+            None => format!("unknown command {name:?}, try help"),
+        },
+    };
+    for line in output.lines() {
+        record(Level::Info, "console", line.to_string());
+    }
+}
+
+fn set_theme(ui: &mut UI, theme: &Theme) -> String {
+    ui.background_color = theme.background;
+    if let Some(mut on_theme) = ui.console.on_theme.take() {
+        on_theme(ui, theme);
+        ui.console.on_theme = Some(on_theme);
+    }
+    String::new()
+}
+
+// one line per element, indented by depth, with its type, position and size
+fn dump_layout(ui: &mut UI) -> String {
+    let mut output = String::new();
+    if let Ok(mut root) = ui.root_item.lock()
+        && let Some(root) = root.as_primative()
+    {
+        dump_node(root, 0, &mut output);
+    }
+    output
+}
+
+fn dump_node(prim: &dyn Primative, depth: usize, output: &mut String) {
+    let kind = prim.kind().rsplit("::").next().unwrap_or_default();
+    let (x, y) = prim.get_position();
+    let _ = writeln!(
+        output,
+        "{:indent$}{kind} ({x}, {y}) {}x{}",
+        "",
+        prim.get_width(),
+        prim.get_height(),
+        indent = depth * 2
+    );
+    for child in prim.children() {
+        if let Ok(child) = child.lock() {
+            dump_node(&*child, depth + 1, output);
+        }
+    }
+}
+
+// the `bounds` overlay, an outline around everything in the tree
+pub(crate) fn draw_bounds(
+    prim: &dyn Primative,
+    render_pass: &mut wgpu::RenderPass,
+    frame: &mut Frame,
+    size: (i32, i32),
+) {
+    let (x, y) = prim.get_position();
+    let color = rgb(1.0, 0.2, 0.8);
+    make_ss_rounded_ring(
+        x,
+        y,
+        prim.get_width(),
+        prim.get_height(),
+        0.0,
+        2,
+        color,
+        size,
+    )
+    .draw(render_pass, frame);
+    for child in prim.children() {
+        if let Ok(child) = child.lock() {
+            draw_bounds(&*child, render_pass, frame, size);
+        }
+    }
+}
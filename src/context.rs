@@ -0,0 +1,606 @@
+use std::{
+    sync::{
+        Mutex,
+        mpsc::{Receiver, TryRecvError},
+    },
+    time::{Duration, Instant},
+};
+
+use glfw::PWindow;
+
+use crate::{
+    idle::{self, IdleDeadline, IdleId, IdleQueue},
+    input::{self, InputSettings, Key, Modifiers},
+    platform::{Conventions, SystemSettings},
+    preferences::Preferences,
+    renderer::{
+        projection::Orientation,
+        settings::{PresentMode, RedrawMode},
+    },
+    text,
+    theme::Theme,
+    timers::{self, TimerId, Timers},
+};
+
+// the steps zoom moves through with the keyboard, like a browser's
+pub const ZOOM_LEVELS: [f32; 13] = [
+    0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0,
+];
+const UNZOOMED: usize = 5;
+const ZOOM_KEY: &str = "zoom";
+
+// the current frame's time, kept here like `text::text_scale` so widgets can
+// animate on the frame clock while laying out, which has no context to read
+// from
+static FRAME_NOW: Mutex<Option<Instant>> = Mutex::new(None);
+
+// the frame clock's `now`, or the real time before the first frame
+pub fn frame_now() -> Instant {
+    FRAME_NOW
+        .lock()
+        .ok()
+        .and_then(|now| *now)
+        .unwrap_or_else(Instant::now)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CursorMode {
+    #[default]
+    Normal,
+    Hidden,
+    // hidden and locked to the window. cursor positions keep counting past the
+    // window edges so drags never run out of room
+    Captured,
+}
+
+// how urgently a screen reader should speak an announcement, as in aria live
+// regions
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Politeness {
+    // waits for the user to go idle, for things like a load finishing
+    #[default]
+    Polite,
+    // interrupts whatever is being read, for errors and the like
+    Assertive,
+}
+
+// a message for screen reader users about something that changed without
+// focus moving, like a toast appearing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub text: String,
+    pub politeness: Politeness,
+}
+
+// time as seen by the ui. ticks once per frame so everything drawn in a frame
+// agrees on the current time
+#[derive(Debug, Clone, Copy)]
+pub struct FrameClock {
+    start: Instant,
+    now: Instant,
+    delta: Duration,
+    frame: u64,
+    suspended: bool,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            now,
+            delta: Duration::ZERO,
+            frame: 0,
+            suspended: false,
+        }
+    }
+}
+
+impl FrameClock {
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        self.delta = now - self.now;
+        self.now = now;
+        self.frame += 1;
+    }
+
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
+    // time since the clock started, as of the current frame
+    pub fn elapsed(&self) -> Duration {
+        self.now - self.start
+    }
+
+    // time between the previous frame and this one
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    // set while the window is in the background. idle animations like a
+    // blinking caret hold still so the event loop can sleep
+    pub fn animations_suspended(&self) -> bool {
+        self.suspended
+    }
+}
+
+// window level state that widgets can read and make requests through. requests
+// are queued and applied to the window once per frame
+#[derive(Debug, Default)]
+pub struct UiContext {
+    cursor_pos: (f64, f64),
+    cursor_mode: CursorMode,
+    raw_motion: bool,
+    restore_pos: Option<(f64, f64)>,
+    dirty: bool,
+    orientation: Orientation,
+    orientation_changed: bool,
+    adapter: Option<wgpu::AdapterInfo>,
+    clock: FrameClock,
+    conventions: Conventions,
+    theme: Theme,
+    // inverted so the default is focused
+    window_blurred: bool,
+    // steps away from 100% along `ZOOM_LEVELS`, so the default is unzoomed
+    zoom_step: i32,
+    zoom_changed: bool,
+    text_scale_override: Option<f32>,
+    text_scale_changed: bool,
+    input_settings: InputSettings,
+    // what the os settings were when last read, and a read still going
+    system_settings: SystemSettings,
+    system_settings_read: Option<Receiver<SystemSettings>>,
+    preferences: Preferences,
+    announcements: Vec<Announcement>,
+    present_mode: PresentMode,
+    present_mode_changed: bool,
+    max_frame_rate: Option<f32>,
+    redraw_mode: RedrawMode,
+    pub(crate) timers: Timers,
+    pub(crate) idle: IdleQueue,
+    #[cfg(feature = "renderdoc")]
+    capture_requested: bool,
+}
+
+impl UiContext {
+    pub fn cursor_pos(&self) -> (f64, f64) {
+        self.cursor_pos
+    }
+
+    pub fn cursor_moved(&mut self, pos: (f64, f64)) {
+        self.cursor_pos = pos;
+    }
+
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        if mode == CursorMode::Captured {
+            self.capture_cursor(false);
+            return;
+        }
+        if self.cursor_mode != mode {
+            self.cursor_mode = mode;
+            self.raw_motion = false;
+            self.dirty = true;
+        }
+    }
+
+    pub fn is_cursor_captured(&self) -> bool {
+        self.cursor_mode == CursorMode::Captured
+    }
+
+    // hides and locks the cursor until release_cursor, remembering where it was.
+    // raw motion skips os pointer acceleration where the platform supports it
+    pub fn capture_cursor(&mut self, raw_motion: bool) {
+        if self.is_cursor_captured() {
+            return;
+        }
+        self.restore_pos = Some(self.cursor_pos);
+        self.cursor_mode = CursorMode::Captured;
+        self.raw_motion = raw_motion;
+        self.dirty = true;
+    }
+
+    // shows the cursor again back where it was captured
+    pub fn release_cursor(&mut self) {
+        if !self.is_cursor_captured() {
+            return;
+        }
+        self.cursor_mode = CursorMode::Normal;
+        self.raw_motion = false;
+        self.dirty = true;
+    }
+
+    // the gpu the renderer ended up on, for about screens and bug reports
+    pub fn adapter(&self) -> Option<&wgpu::AdapterInfo> {
+        self.adapter.as_ref()
+    }
+
+    pub(crate) fn set_adapter(&mut self, adapter: wgpu::AdapterInfo) {
+        self.adapter = Some(adapter);
+    }
+
+    pub fn clock(&self) -> FrameClock {
+        self.clock
+    }
+
+    // whether the window has keyboard focus. a background window suspends idle
+    // animations, and widgets that only make sense while typing, like a text
+    // caret, stop drawing
+    pub fn window_focused(&self) -> bool {
+        !self.window_blurred
+    }
+
+    pub(crate) fn set_window_focused(&mut self, focused: bool) {
+        self.window_blurred = !focused;
+        self.clock.suspended = !focused;
+    }
+
+    // advances the clock and fires any timers that came due, called by the
+    // event loop at the start of each frame. returns whether any fired
+    pub(crate) fn tick(&mut self) -> bool {
+        self.clock.tick();
+        if let Ok(mut now) = FRAME_NOW.lock() {
+            *now = Some(self.clock.now());
+        }
+        timers::run_due(self, self.clock.now())
+    }
+
+    // runs `f` once, on the first frame after `delay` has passed
+    pub fn set_timeout(
+        &mut self,
+        delay: Duration,
+        f: impl FnMut(&mut UiContext) + Send + 'static,
+    ) -> TimerId {
+        self.timers.add(self.clock.now() + delay, None, Box::new(f))
+    }
+
+    // runs `f` every `period` until the timer is cleared
+    pub fn set_interval(
+        &mut self,
+        period: Duration,
+        f: impl FnMut(&mut UiContext) + Send + 'static,
+    ) -> TimerId {
+        self.timers
+            .add(self.clock.now() + period, Some(period), Box::new(f))
+    }
+
+    // returns false when the timer already fired or was cleared. callbacks may
+    // clear their own timer
+    pub fn clear_timer(&mut self, id: TimerId) -> bool {
+        self.timers.clear(id)
+    }
+
+    // runs `f` after frames that finish early, for up to `budget` at a time and
+    // never past when the next frame is due. for work nothing is waiting on,
+    // like warming caches or rasterizing glyphs ahead of time, kept off the
+    // frames that handle input. `f` returns true while it has more to do.
+    // changes it makes show up the next time the tree is laid out
+    pub fn on_idle(
+        &mut self,
+        budget: Duration,
+        f: impl FnMut(&mut UiContext, IdleDeadline) -> bool + Send + 'static,
+    ) -> IdleId {
+        self.idle.add(budget, Box::new(f))
+    }
+
+    // returns false when the work already finished or was cancelled.
+    // callbacks may cancel themselves
+    pub fn cancel_idle(&mut self, id: IdleId) -> bool {
+        self.idle.cancel(id)
+    }
+
+    pub fn has_idle_work(&self) -> bool {
+        !self.idle.is_empty()
+    }
+
+    // gives idle callbacks the time left until `frame_end`, called by the event
+    // loop once the frame is presented
+    pub(crate) fn run_idle(&mut self, frame_end: Instant) -> bool {
+        idle::run_idle(self, frame_end)
+    }
+
+    // when the event loop next has to wake up for a timer, if any are pending
+    pub fn next_timer_deadline(&self) -> Option<Instant> {
+        self.timers.next_deadline()
+    }
+
+    pub fn conventions(&self) -> &Conventions {
+        &self.conventions
+    }
+
+    // overrides the platform defaults, e.g. to preview another os's behavior
+    pub fn set_conventions(&mut self, conventions: Conventions) {
+        self.conventions = conventions;
+    }
+
+    // the app's colors, from `TeacupBuilder::theme`. widgets take it when
+    // they're built
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    // only reaches widgets built after it, so rebuild the tree to restyle
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // called by the platform layer when the display rotates
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        if self.orientation != orientation {
+            self.orientation = orientation;
+            self.orientation_changed = true;
+        }
+    }
+
+    // returns the new orientation once after each change
+    pub fn take_orientation_change(&mut self) -> Option<Orientation> {
+        std::mem::take(&mut self.orientation_changed).then_some(self.orientation)
+    }
+
+    // starts as `RenderSettings::present_mode`. a change reconfigures the
+    // surface next frame
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        if self.present_mode != mode {
+            self.present_mode = mode;
+            self.present_mode_changed = true;
+        }
+    }
+
+    pub(crate) fn take_present_mode_change(&mut self) -> Option<PresentMode> {
+        std::mem::take(&mut self.present_mode_changed).then_some(self.present_mode)
+    }
+
+    // records the next frame with renderdoc, from the start of rendering until
+    // the gpu is done with it, through wgpu's graphics debugger capture. only
+    // does anything when the app was launched from renderdoc or has it
+    // injected, and only with the wgpu backend. other backends drop the
+    // request with a warning
+    #[cfg(feature = "renderdoc")]
+    pub fn capture_frame(&mut self) {
+        self.capture_requested = true;
+    }
+
+    #[cfg(feature = "renderdoc")]
+    pub(crate) fn take_capture_request(&mut self) -> bool {
+        std::mem::take(&mut self.capture_requested)
+    }
+
+    // frames per second the event loop won't go over, if any
+    pub fn max_frame_rate(&self) -> Option<f32> {
+        self.max_frame_rate
+    }
+
+    pub fn set_max_frame_rate(&mut self, max_frame_rate: Option<f32>) {
+        self.max_frame_rate = max_frame_rate.filter(|rate| *rate > 0.0);
+    }
+
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.redraw_mode
+    }
+
+    pub fn set_redraw_mode(&mut self, mode: RedrawMode) {
+        self.redraw_mode = mode;
+    }
+
+    // how much larger than normal the user wants everything drawn. it
+    // multiplies every layout dimension and font size on top of the display's
+    // scale factor, and is kept in the preferences between runs
+    pub fn zoom(&self) -> f32 {
+        ZOOM_LEVELS[(UNZOOMED as i32 + self.zoom_step) as usize]
+    }
+
+    // snaps to the nearest of `ZOOM_LEVELS`
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let nearest = ZOOM_LEVELS
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| (*a - zoom).abs().total_cmp(&(*b - zoom).abs()))
+            .map_or(UNZOOMED, |(i, _)| i);
+        self.set_zoom_step(nearest as i32 - UNZOOMED as i32);
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.set_zoom_step(self.zoom_step + 1);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.set_zoom_step(self.zoom_step - 1);
+    }
+
+    pub fn reset_zoom(&mut self) {
+        self.set_zoom_step(0);
+    }
+
+    fn set_zoom_step(&mut self, step: i32) {
+        let step = step.clamp(
+            -(UNZOOMED as i32),
+            (ZOOM_LEVELS.len() - 1 - UNZOOMED) as i32,
+        );
+        if step != self.zoom_step {
+            self.zoom_step = step;
+            self.zoom_changed = true;
+            self.preferences.set(ZOOM_KEY, self.zoom());
+        }
+    }
+
+    // returns the new zoom once after each change
+    pub(crate) fn take_zoom_change(&mut self) -> Option<f32> {
+        std::mem::take(&mut self.zoom_changed).then(|| self.zoom())
+    }
+
+    // command or control with =, + or - zooms, and with 0 resets. returns
+    // whether the key was taken
+    pub(crate) fn handle_zoom_key(&mut self, key: Key, modifiers: Modifiers) -> bool {
+        if !modifiers.command() || modifiers.alt {
+            return false;
+        }
+        match key {
+            Key::Char('=' | '+') => self.zoom_in(),
+            Key::Char('-') => self.zoom_out(),
+            Key::Char('0') => self.reset_zoom(),
+            _ => return false,
+        }
+        true
+    }
+
+    // multiplier for font sizes from the os text size setting, separate from
+    // zoom. text widgets scale their font size by it, and the tree is measured
+    // and laid out again when it changes
+    pub fn text_scale(&self) -> f32 {
+        text::text_scale()
+    }
+
+    // overrides the os setting. none follows it again
+    pub fn set_text_scale(&mut self, scale: Option<f32>) {
+        self.text_scale_override = scale;
+        self.refresh_text_scale();
+    }
+
+    fn refresh_text_scale(&mut self) {
+        let scale = self
+            .text_scale_override
+            .or(self.system_settings.text_scale)
+            .unwrap_or(1.0);
+        if text::set_text_scale(scale) {
+            self.text_scale_changed = true;
+        }
+    }
+
+    // true once after each change
+    pub(crate) fn take_text_scale_change(&mut self) -> bool {
+        std::mem::take(&mut self.text_scale_changed)
+    }
+
+    // the overrides, not what's in effect. see `input::double_click_interval`
+    // and `input::wheel_lines` for that
+    pub fn input_settings(&self) -> InputSettings {
+        self.input_settings
+    }
+
+    pub fn set_input_settings(&mut self, settings: InputSettings) {
+        self.input_settings = settings;
+        self.refresh_input_settings();
+    }
+
+    fn refresh_input_settings(&mut self) {
+        let settings = self.input_settings;
+        input::set_double_click_interval(
+            settings
+                .double_click_interval
+                .or(self.system_settings.double_click_interval)
+                .unwrap_or(input::DOUBLE_CLICK_INTERVAL),
+        );
+        input::set_wheel_lines(
+            settings
+                .wheel_lines
+                .or(self.system_settings.wheel_lines)
+                .unwrap_or(input::WHEEL_LINES),
+        );
+    }
+
+    // starts reading the os settings again in the background, see
+    // `poll_system_settings`. done whenever the window gets focus back, since
+    // the user changes them in another app
+    pub(crate) fn reread_system_settings(&mut self) {
+        if self.system_settings_read.is_none() {
+            self.system_settings_read = Some(SystemSettings::read_in_background());
+        }
+    }
+
+    // applies the settings from a read that's finished, called once a frame
+    pub(crate) fn poll_system_settings(&mut self) {
+        let Some(read) = &self.system_settings_read else {
+            return;
+        };
+        match read.try_recv() {
+            Ok(settings) => self.apply_system_settings(settings),
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {}
+        }
+        self.system_settings_read = None;
+    }
+
+    // the text scale and input settings follow whatever isn't overridden
+    pub(crate) fn apply_system_settings(&mut self, settings: SystemSettings) {
+        self.system_settings = settings;
+        self.refresh_text_scale();
+        self.refresh_input_settings();
+    }
+
+    // queues `text` for the accessibility backend, see `App::on_announce`.
+    // sent once per frame, so an assertive announcement drops the polite ones
+    // still waiting and repeats of the same text go out once
+    pub fn announce(&mut self, text: impl Into<String>, politeness: Politeness) {
+        let announcement = Announcement {
+            text: text.into(),
+            politeness,
+        };
+        if politeness == Politeness::Assertive {
+            self.announcements
+                .retain(|pending| pending.politeness == Politeness::Assertive);
+        }
+        if !self.announcements.contains(&announcement) {
+            self.announcements.push(announcement);
+        }
+    }
+
+    pub(crate) fn take_announcements(&mut self) -> Vec<Announcement> {
+        std::mem::take(&mut self.announcements)
+    }
+
+    pub fn preferences(&self) -> &Preferences {
+        &self.preferences
+    }
+
+    pub fn preferences_mut(&mut self) -> &mut Preferences {
+        &mut self.preferences
+    }
+
+    // swaps in preferences loaded at startup and picks up the zoom saved in
+    // them
+    pub(crate) fn set_preferences(&mut self, preferences: Preferences) {
+        let zoom = preferences.get_parsed::<f32>(ZOOM_KEY);
+        self.preferences = preferences;
+        if let Some(zoom) = zoom {
+            self.set_zoom(zoom);
+        }
+    }
+
+    pub fn apply(&mut self, window: &mut PWindow) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+
+        window.set_cursor_mode(match self.cursor_mode {
+            CursorMode::Normal => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Captured => glfw::CursorMode::Disabled,
+        });
+        if window.glfw.supports_raw_motion() {
+            window.set_raw_mouse_motion(self.raw_motion);
+        }
+        if self.cursor_mode != CursorMode::Captured
+            && let Some((x, y)) = self.restore_pos.take()
+        {
+            window.set_cursor_pos(x, y);
+            self.cursor_pos = (x, y);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{builder::Node, event::Event, layout::Container};
+
+pub(crate) type EventHandler = Box<dyn FnMut(&mut EventContext) + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    // on the way down from the root, so containers see the event before
+    // their children
+    Capture,
+    // at the element the event was aimed at
+    Target,
+    // on the way back up, for events the children didn't stop
+    Bubble,
+    // to elements the pointer was over or last pressed on that the event
+    // missed, so they can drop their hover or focus. can't be stopped
+    Exit,
+}
+
+// what a handler asked the window to do with the cursor. applied to the
+// context once the event has been through the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CursorRequest {
+    Capture { raw_motion: bool },
+    Release,
+}
+
+// what a handler gets while an event travels through the tree
+pub struct EventContext<'a> {
+    pub event: &'a Event,
+    phase: Phase,
+    stopped: bool,
+    cursor: &'a mut Option<CursorRequest>,
+}
+
+impl EventContext<'_> {
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    // nothing further along the path sees the event
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    // see `UiContext::capture_cursor`, for drags that shouldn't stop at the
+    // edge of the screen
+    pub fn capture_cursor(&mut self, raw_motion: bool) {
+        *self.cursor = Some(CursorRequest::Capture { raw_motion });
+    }
+
+    pub fn release_cursor(&mut self) {
+        *self.cursor = Some(CursorRequest::Release);
+    }
+}
+
+// the elements under the pointer when it last moved and when it was last
+// pressed, below the root
+#[derive(Default)]
+pub(crate) struct PointerPaths {
+    hovered: Vec<Node>,
+    pressed: Vec<Node>,
+}
+
+impl PointerPaths {
+    // the remembered path `event` replaces, if it's one that does
+    fn for_event(&mut self, event: &Event) -> Option<&mut Vec<Node>> {
+        match event {
+            Event::PointerMoved { .. } | Event::PointerLeft => Some(&mut self.hovered),
+            Event::PointerPressed { .. } => Some(&mut self.pressed),
+            _ => None,
+        }
+    }
+
+    // the tooltip of the deepest hovered element that has one at `point`
+    pub(crate) fn tooltip(&self, point: (i32, i32)) -> Option<String> {
+        self.hovered
+            .iter()
+            .rev()
+            .find_map(|node| node.lock().ok()?.tooltip(point))
+    }
+}
+
+// a stop on the path from the root to the target
+enum Hop {
+    Root(Arc<Mutex<dyn Container>>),
+    Node(Node),
+}
+
+impl Hop {
+    fn deliver(&self, cx: &mut EventContext) {
+        match self {
+            Hop::Root(root) => {
+                if let Ok(mut root) = root.lock()
+                    && let Some(prim) = root.as_primative()
+                {
+                    prim.on_event(cx);
+                }
+            }
+            Hop::Node(node) => {
+                if let Ok(mut prim) = node.lock() {
+                    prim.on_event(cx);
+                }
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<Node> {
+        match self {
+            Hop::Root(root) => root
+                .lock()
+                .ok()
+                .and_then(|mut root| root.as_primative().map(|prim| prim.children().to_vec()))
+                .unwrap_or_default(),
+            Hop::Node(node) => node
+                .lock()
+                .map(|prim| prim.children().to_vec())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+// follows the topmost child under `point` down from `start`. without a point
+// the path is just `start`
+fn path_to(start: Hop, point: Option<(i32, i32)>) -> Vec<Hop> {
+    let mut path = vec![start];
+    let Some(point) = point else {
+        return path;
+    };
+    loop {
+        let mut children: Vec<(i32, Node)> = path[path.len() - 1]
+            .children()
+            .into_iter()
+            .map(|child| (child.lock().map_or(0, |prim| prim.z_index()), child))
+            .collect();
+        // drawn last is on top, so search from the end
+        children.sort_by_key(|(z_index, _)| *z_index);
+        let hit = children
+            .into_iter()
+            .rev()
+            .find(|(_, child)| child.lock().is_ok_and(|prim| prim.contains(point)));
+        match hit {
+            Some((_, child)) => path.push(Hop::Node(child)),
+            None => return path,
+        }
+    }
+}
+
+// sends `event` to whatever the pointer left behind, then down the path in
+// the capture phase, to the target, and back up in the bubble phase. returns
+// true when a handler stopped it. what handlers asked of the cursor is left
+// in `cursor`
+pub(crate) fn dispatch(
+    root: Arc<Mutex<dyn Container>>,
+    overlay: Option<Node>,
+    point: Option<(i32, i32)>,
+    event: &Event,
+    paths: &mut PointerPaths,
+    cursor: &mut Option<CursorRequest>,
+) -> bool {
+    let start = match overlay {
+        Some(node) => Hop::Node(node),
+        None => Hop::Root(root),
+    };
+    let path = path_to(start, point);
+    let Some((target, ancestors)) = path.split_last() else {
+        return false;
+    };
+
+    let mut cx = EventContext {
+        event,
+        phase: Phase::Exit,
+        stopped: false,
+        cursor,
+    };
+    if let Some(last) = paths.for_event(event) {
+        let current: Vec<Node> = match event {
+            Event::PointerLeft => Vec::new(),
+            _ => path
+                .iter()
+                .filter_map(|hop| match hop {
+                    Hop::Node(node) => Some(node.clone()),
+                    Hop::Root(_) => None,
+                })
+                .collect(),
+        };
+        for node in std::mem::replace(last, current.clone()) {
+            if !current.iter().any(|kept| Arc::ptr_eq(kept, &node)) {
+                Hop::Node(node).deliver(&mut cx);
+            }
+        }
+        cx.stopped = false;
+    }
+
+    cx.phase = Phase::Capture;
+    for hop in ancestors {
+        hop.deliver(&mut cx);
+        if cx.stopped {
+            return true;
+        }
+    }
+    cx.phase = Phase::Target;
+    target.deliver(&mut cx);
+    cx.phase = Phase::Bubble;
+    for hop in ancestors.iter().rev() {
+        if cx.stopped {
+            return true;
+        }
+        hop.deliver(&mut cx);
+    }
+    cx.stopped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::{
+        builder::IntoNode,
+        event::{Event, PointerButton},
+        input::Modifiers,
+        layout::{Rectangle, Sizing, SizingMode, UI},
+        theme::Theme,
+        widgets::button::Button,
+    };
+
+    // a 124x64 button, its label and padding, in the top left corner
+    fn ui_with_button(clicks: Arc<AtomicUsize>) -> UI {
+        let label = Rectangle {
+            sizing: Sizing {
+                width: SizingMode::Fixed(100),
+                height: SizingMode::Fixed(40),
+            },
+            ..Default::default()
+        };
+        let button = Button::new(label, &Theme::default()).on_click(move || {
+            clicks.fetch_add(1, Ordering::SeqCst);
+        });
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![button.into_node()],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+        ui
+    }
+
+    fn press(position: (i32, i32)) -> Event {
+        Event::PointerPressed {
+            button: PointerButton::Primary,
+            position,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn release(position: (i32, i32)) -> Event {
+        Event::PointerReleased {
+            button: PointerButton::Primary,
+            position,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    #[test]
+    fn clicking_a_button_fires_on_click() {
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let mut ui = ui_with_button(clicks.clone());
+        assert!(ui.dispatch_event(&press((50, 30))));
+        assert!(ui.dispatch_event(&release((50, 30))));
+        assert_eq!(clicks.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn releasing_off_the_button_cancels_the_click() {
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let mut ui = ui_with_button(clicks.clone());
+        ui.dispatch_event(&press((50, 30)));
+        ui.dispatch_event(&Event::PointerMoved {
+            position: (300, 300),
+        });
+        ui.dispatch_event(&release((300, 300)));
+        assert_eq!(clicks.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn pressing_elsewhere_is_not_handled() {
+        let clicks = Arc::new(AtomicUsize::new(0));
+        let mut ui = ui_with_button(clicks.clone());
+        assert!(!ui.dispatch_event(&press((300, 300))));
+        assert!(!ui.dispatch_event(&release((300, 300))));
+        assert_eq!(clicks.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use crate::input::{Key, Modifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerButton {
+    Primary,
+    Secondary,
+    Middle,
+    // extra mouse buttons, numbered from 4
+    Other(u8),
+}
+
+impl PointerButton {
+    fn from_glfw(button: glfw::MouseButton) -> Self {
+        match button {
+            glfw::MouseButton::Button1 => PointerButton::Primary,
+            glfw::MouseButton::Button2 => PointerButton::Secondary,
+            glfw::MouseButton::Button3 => PointerButton::Middle,
+            button => PointerButton::Other(button as u8 + 1),
+        }
+    }
+}
+
+// input and window events with the windowing backend translated away.
+// positions are in layout pixels, like everything else in the tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    PointerMoved {
+        position: (i32, i32),
+    },
+    PointerPressed {
+        button: PointerButton,
+        position: (i32, i32),
+        modifiers: Modifiers,
+    },
+    PointerReleased {
+        button: PointerButton,
+        position: (i32, i32),
+        modifiers: Modifiers,
+    },
+    PointerEntered,
+    PointerLeft,
+    // `repeat` is set for presses generated by holding the key down
+    KeyPressed {
+        key: Key,
+        modifiers: Modifiers,
+        repeat: bool,
+    },
+    KeyReleased {
+        key: Key,
+        modifiers: Modifiers,
+    },
+    // typed text, after the keyboard layout and any input method
+    Text(char),
+    // in lines, positive is up and to the right. `precise` deltas come from
+    // trackpads and the like, a fraction of a line at a time and including
+    // the os's momentum after the fingers lift, and shouldn't be smoothed
+    Scroll {
+        delta: (f64, f64),
+        precise: bool,
+    },
+    // window size in window pixels
+    Resized((i32, i32)),
+    // the window moved to a display with a different content scale, or the
+    // display's scale changed. carries the new `UI::scale_factor`
+    ScaleFactorChanged(f32),
+    Focused(bool),
+    CloseRequested,
+    FilesDropped(Vec<PathBuf>),
+}
+
+impl Event {
+    // keys teacup doesn't name come through as text only. `cursor` is the last
+    // known pointer position in window pixels, since button events carry none,
+    // and `layout_scale` is how many layout pixels make up a window pixel
+    pub(crate) fn from_glfw(
+        event: &glfw::WindowEvent,
+        cursor: (f64, f64),
+        layout_scale: f64,
+    ) -> Option<Event> {
+        let layout = |(x, y): (f64, f64)| {
+            (
+                (x * layout_scale).round() as i32,
+                (y * layout_scale).round() as i32,
+            )
+        };
+        Some(match event {
+            glfw::WindowEvent::CursorPos(x, y) => Event::PointerMoved {
+                position: layout((*x, *y)),
+            },
+            glfw::WindowEvent::MouseButton(button, action, modifiers) => {
+                let button = PointerButton::from_glfw(*button);
+                let position = layout(cursor);
+                let modifiers = Modifiers::from_glfw(*modifiers);
+                match action {
+                    glfw::Action::Release => Event::PointerReleased {
+                        button,
+                        position,
+                        modifiers,
+                    },
+                    _ => Event::PointerPressed {
+                        button,
+                        position,
+                        modifiers,
+                    },
+                }
+            }
+            glfw::WindowEvent::CursorEnter(true) => Event::PointerEntered,
+            glfw::WindowEvent::CursorEnter(false) => Event::PointerLeft,
+            glfw::WindowEvent::Key(key, _, action, modifiers) => {
+                let key = Key::from_glfw(*key)?;
+                let modifiers = Modifiers::from_glfw(*modifiers);
+                match action {
+                    glfw::Action::Release => Event::KeyReleased { key, modifiers },
+                    action => Event::KeyPressed {
+                        key,
+                        modifiers,
+                        repeat: *action == glfw::Action::Repeat,
+                    },
+                }
+            }
+            glfw::WindowEvent::Char(c) => Event::Text(*c),
+            // glfw doesn't say what did the scrolling, but wheels move in whole
+            // notches and trackpads almost never do
+            glfw::WindowEvent::Scroll(x, y) => Event::Scroll {
+                delta: (*x, *y),
+                precise: x.fract() != 0.0 || y.fract() != 0.0,
+            },
+            glfw::WindowEvent::Size(width, height) => Event::Resized((*width, *height)),
+            glfw::WindowEvent::ContentScale(scale, _) => Event::ScaleFactorChanged(*scale),
+            glfw::WindowEvent::Focus(focused) => Event::Focused(*focused),
+            glfw::WindowEvent::Close => Event::CloseRequested,
+            glfw::WindowEvent::FileDrop(paths) => Event::FilesDropped(paths.clone()),
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,680 @@
+use std::{collections::HashMap, fmt::Write as _, io::Cursor, path::Path, sync::Arc};
+
+use tinycolors::srgb;
+
+use crate::{
+    layout::{LAYOUT_SCALE, UI},
+    renderer::{
+        draw_list::{self, DrawCommand},
+        image::ImageData,
+        mesh_builder::Mesh,
+        text::Font,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Svg,
+    Pdf,
+}
+
+impl ExportFormat {
+    // from the file's extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "svg" => Some(ExportFormat::Svg),
+            "pdf" => Some(ExportFormat::Pdf),
+            _ => None,
+        }
+    }
+}
+
+// the ui as vector shapes, text and images, for saving or printing. plays back
+// `UI::draw_list`: shapes are the same triangles the gpu gets, text keeps its
+// font embedded so it stays selectable, and images are embedded at their full
+// size
+impl UI {
+    pub fn export_svg(&self) -> String {
+        write_svg(&Page::from_ui(self))
+    }
+
+    pub fn export_pdf(&self) -> Vec<u8> {
+        write_pdf(&Page::from_ui(self))
+    }
+
+    pub fn export(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let bytes = match ExportFormat::from_path(path) {
+            Some(ExportFormat::Svg) => self.export_svg().into_bytes(),
+            Some(ExportFormat::Pdf) => self.export_pdf(),
+            None => anyhow::bail!("can't tell what to export {} as", path.display()),
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+// one thing drawn, in layout pixels. colors are 0 to 1 srgb
+enum Item {
+    // triangles of one color
+    Fill {
+        triangles: Vec<[(f32, f32); 3]>,
+        color: [f32; 3],
+        alpha: f32,
+    },
+    Text {
+        font: Arc<Font>,
+        text: String,
+        px: f32,
+        origin: (f32, f32),
+        color: [f32; 3],
+    },
+    Image {
+        image: Arc<ImageData>,
+        position: (f32, f32),
+        extent: (f32, f32),
+        opacity: f32,
+    },
+    // items up to the matching `Unclip` are cut to this box
+    Clip {
+        position: (f32, f32),
+        extent: (f32, f32),
+    },
+    Unclip,
+}
+
+// everything drawn in paint order, over the background
+struct Page {
+    size: (i32, i32),
+    background: srgb,
+    background_alpha: f32,
+    items: Vec<Item>,
+}
+
+impl Page {
+    // the ui's draw list with transforms applied
+    fn from_ui(ui: &UI) -> Self {
+        let list = ui.draw_list();
+        let mut page = Self {
+            size: list.size,
+            background: ui.background_color,
+            background_alpha: ui.background_alpha,
+            items: Vec::new(),
+        };
+        let mut offsets = vec![(0.0f32, 0.0f32)];
+        for command in &list.lowered() {
+            let offset = offsets.last().copied().unwrap_or_default();
+            let moved = |(x, y): (i32, i32)| (x as f32 + offset.0, y as f32 + offset.1);
+            let item = match command {
+                DrawCommand::Mesh(mesh) => {
+                    page.fill(mesh, list.mesh_size(), offset);
+                    continue;
+                }
+                DrawCommand::Text {
+                    font,
+                    text,
+                    px,
+                    origin,
+                    color,
+                } => Item::Text {
+                    font: font.clone(),
+                    text: text.clone(),
+                    px: *px,
+                    origin: (origin.0 + offset.0, origin.1 + offset.1),
+                    color: color.to_srgb(),
+                },
+                DrawCommand::Image {
+                    image,
+                    position,
+                    extent,
+                    opacity,
+                    ..
+                } => Item::Image {
+                    image: image.clone(),
+                    position: moved(*position),
+                    extent: (extent.0 as f32, extent.1 as f32),
+                    opacity: *opacity,
+                },
+                DrawCommand::PushClip { position, extent } => Item::Clip {
+                    position: moved(*position),
+                    extent: (extent.0 as f32, extent.1 as f32),
+                },
+                DrawCommand::PopClip => Item::Unclip,
+                DrawCommand::PushTransform { offset: by } => {
+                    offsets.push((offset.0 + by.0, offset.1 + by.1));
+                    continue;
+                }
+                DrawCommand::PopTransform => {
+                    if offsets.len() > 1 {
+                        offsets.pop();
+                    }
+                    continue;
+                }
+                // gone once the list is lowered
+                DrawCommand::Pipeline(_)
+                | DrawCommand::ImageRegion { .. }
+                | DrawCommand::Skeletons(_)
+                | DrawCommand::Particles(_)
+                | DrawCommand::Plugin { .. } => continue,
+            };
+            page.items.push(item);
+        }
+        page
+    }
+
+    // runs of triangles with the same color become one item, so the shapes
+    // they make don't show seams where the triangles meet
+    fn fill(&mut self, mesh: &Mesh, mesh_size: (i32, i32), offset: (f32, f32)) {
+        for triangle in draw_list::triangles(mesh, mesh_size) {
+            let points = triangle.points.map(|(x, y)| (x + offset.0, y + offset.1));
+            if let Some(Item::Fill {
+                triangles,
+                color,
+                alpha,
+            }) = self.items.last_mut()
+                && draw_list::rgba8(*color, *alpha) == triangle.rgba8()
+            {
+                triangles.push(points);
+                continue;
+            }
+            self.items.push(Item::Fill {
+                triangles: vec![points],
+                color: triangle.color,
+                alpha: triangle.alpha,
+            });
+        }
+    }
+
+    // fonts in the order they're first used
+    fn fonts(&self) -> Vec<Arc<Font>> {
+        let mut fonts: Vec<Arc<Font>> = Vec::new();
+        for item in &self.items {
+            if let Item::Text { font, .. } = item
+                && !fonts.iter().any(|known| Arc::ptr_eq(known, font))
+            {
+                fonts.push(font.clone());
+            }
+        }
+        fonts
+    }
+}
+
+fn font_index(fonts: &[Arc<Font>], font: &Arc<Font>) -> usize {
+    fonts
+        .iter()
+        .position(|known| Arc::ptr_eq(known, font))
+        .unwrap_or(0)
+}
+
+fn to_hex(color: [f32; 3]) -> String {
+    let [r, g, b] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+// each line of `text` as its characters and where each one starts, relative
+// to the text's top left, along with the line's baseline
+fn lines(font: &Font, text: &str, px: f32) -> Vec<(f32, Vec<(char, f32)>)> {
+    let layout = font.layout(text, px);
+    let chars = text.chars().filter(|c| *c != '\n');
+    let mut lines: Vec<(f32, Vec<(char, f32)>)> = Vec::new();
+    for (c, glyph) in chars.zip(&layout.glyphs) {
+        match lines.last_mut() {
+            Some((baseline, line)) if *baseline == glyph.baseline => line.push((c, glyph.x)),
+            _ => lines.push((glyph.baseline, vec![(c, glyph.x)])),
+        }
+    }
+    lines
+}
+
+fn png(image: &ImageData) -> Vec<u8> {
+    let (width, height) = image.size();
+    let mut png = Vec::new();
+    if let Some(buffer) = ::image::RgbaImage::from_raw(width, height, image.pixels().to_vec())
+        && let Err(err) = buffer.write_to(&mut Cursor::new(&mut png), ::image::ImageFormat::Png)
+    {
+        log::warn!("couldn't encode an image for export: {err}");
+    }
+    png
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - i * 6)) as usize & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// sized in points with the layout in the view box, so it prints at the size
+// it's shown
+fn write_svg(page: &Page) -> String {
+    let (w, h) = page.size;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}pt" height="{}pt" viewBox="0 0 {w} {h}">"#,
+        w / LAYOUT_SCALE,
+        h / LAYOUT_SCALE,
+    );
+
+    let fonts = page.fonts();
+    if !fonts.is_empty() {
+        svg.push_str("<style>\n");
+        for (i, font) in fonts.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                r#"@font-face {{ font-family: "teacup-{i}"; src: url(data:font/ttf;base64,{}); }}"#,
+                base64(font.data()),
+            );
+        }
+        svg.push_str("</style>\n");
+    }
+
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{w}" height="{h}" fill="{}" fill-opacity="{}"/>"#,
+        to_hex([page.background.r, page.background.g, page.background.b]),
+        page.background_alpha,
+    );
+    let mut clips = 0;
+    for item in &page.items {
+        match item {
+            Item::Fill {
+                triangles,
+                color,
+                alpha,
+            } => {
+                let mut path = String::new();
+                for [a, b, c] in triangles {
+                    let _ = write!(
+                        path,
+                        "M{:.2} {:.2}L{:.2} {:.2}L{:.2} {:.2}Z",
+                        a.0, a.1, b.0, b.1, c.0, c.1
+                    );
+                }
+                let _ = writeln!(
+                    svg,
+                    r#"<path d="{path}" fill="{}" fill-opacity="{alpha:.3}"/>"#,
+                    to_hex(*color),
+                );
+            }
+            Item::Text {
+                font,
+                text,
+                px,
+                origin,
+                color,
+            } => {
+                let family = font_index(&fonts, font);
+                for (baseline, line) in lines(font, text, *px) {
+                    let xs: Vec<String> = line
+                        .iter()
+                        .map(|(_, x)| format!("{:.2}", origin.0 + x))
+                        .collect();
+                    let chars: String = line.iter().map(|(c, _)| *c).collect();
+                    let _ = writeln!(
+                        svg,
+                        r#"<text x="{}" y="{:.2}" font-family="teacup-{family}" font-size="{px}" fill="{}" xml:space="preserve">{}</text>"#,
+                        xs.join(" "),
+                        origin.1 + baseline,
+                        to_hex(*color),
+                        escape_xml(&chars),
+                    );
+                }
+            }
+            Item::Clip { position, extent } => {
+                let _ = writeln!(
+                    svg,
+                    r#"<clipPath id="clip-{clips}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath><g clip-path="url(#clip-{clips})">"#,
+                    position.0, position.1, extent.0, extent.1,
+                );
+                clips += 1;
+            }
+            Item::Unclip => svg.push_str("</g>\n"),
+            Item::Image {
+                image,
+                position,
+                extent,
+                opacity,
+            } => {
+                let _ = writeln!(
+                    svg,
+                    r#"<image x="{}" y="{}" width="{}" height="{}" opacity="{opacity}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+                    position.0,
+                    position.1,
+                    extent.0,
+                    extent.1,
+                    base64(&png(image)),
+                );
+            }
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// the objects of a pdf, numbered from one in the order they're added
+#[derive(Default)]
+struct PdfWriter {
+    objects: Vec<Vec<u8>>,
+}
+
+impl PdfWriter {
+    // a number to refer to an object before it's written
+    fn reserve(&mut self) -> usize {
+        self.objects.push(Vec::new());
+        self.objects.len()
+    }
+
+    fn set(&mut self, id: usize, body: impl Into<Vec<u8>>) {
+        self.objects[id - 1] = body.into();
+    }
+
+    fn add(&mut self, body: impl Into<Vec<u8>>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn add_stream(&mut self, dictionary: &str, data: &[u8]) -> usize {
+        let mut body = format!("<< {dictionary} /Length {} >>\nstream\n", data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.add(body)
+    }
+
+    fn finish(self, catalog: usize) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (i, body) in self.objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(body);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = pdf.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root {catalog} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            self.objects.len() + 1
+        );
+        pdf.extend_from_slice(table.as_bytes());
+        pdf
+    }
+}
+
+// switches to a graphics state with `alpha`, adding one when it's new
+fn gs(alpha: f32, alphas: &mut Vec<u8>) -> String {
+    let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let index = alphas.iter().position(|a| *a == alpha).unwrap_or_else(|| {
+        alphas.push(alpha);
+        alphas.len() - 1
+    });
+    format!("/GS{index} gs\n")
+}
+
+fn pdf_color(color: [f32; 3]) -> String {
+    format!("{:.4} {:.4} {:.4} rg\n", color[0], color[1], color[2])
+}
+
+// the glyphs of a font that a page uses, with the characters they came from
+#[derive(Default, Clone)]
+struct UsedGlyphs {
+    glyphs: HashMap<u16, char>,
+}
+
+// a single page the size of the window. text uses the fonts themselves as
+// cid fonts, so it's embedded and can be searched and copied
+fn write_pdf(page: &Page) -> Vec<u8> {
+    let mut pdf = PdfWriter::default();
+    let catalog = pdf.reserve();
+    let pages = pdf.reserve();
+    let page_id = pdf.reserve();
+
+    let (w, h) = page.size;
+    let fonts = page.fonts();
+    let mut used = vec![UsedGlyphs::default(); fonts.len()];
+    let mut alphas: Vec<u8> = Vec::new();
+    let mut images: Vec<(Arc<ImageData>, usize)> = Vec::new();
+
+    // layout pixels with y going down, over a page measured in points
+    let scale = 1.0 / LAYOUT_SCALE as f32;
+    let mut content = format!("{scale} 0 0 -{scale} 0 {} cm\n", h / LAYOUT_SCALE);
+    content.push_str(&gs(page.background_alpha, &mut alphas));
+    content.push_str(&pdf_color([
+        page.background.r,
+        page.background.g,
+        page.background.b,
+    ]));
+    let _ = writeln!(content, "0 0 {w} {h} re f");
+
+    for item in &page.items {
+        match item {
+            Item::Fill {
+                triangles,
+                color,
+                alpha,
+            } => {
+                content.push_str(&gs(*alpha, &mut alphas));
+                content.push_str(&pdf_color(*color));
+                for [a, b, c] in triangles {
+                    let _ = writeln!(
+                        content,
+                        "{:.2} {:.2} m {:.2} {:.2} l {:.2} {:.2} l h",
+                        a.0, a.1, b.0, b.1, c.0, c.1
+                    );
+                }
+                content.push_str("f\n");
+            }
+            Item::Text {
+                font,
+                text,
+                px,
+                origin,
+                color,
+            } => {
+                let index = font_index(&fonts, font);
+                content.push_str(&gs(1.0, &mut alphas));
+                content.push_str(&pdf_color(*color));
+                for (baseline, line) in lines(font, text, *px) {
+                    // flipped back so the glyphs stand upright
+                    let _ = write!(
+                        content,
+                        "BT /F{index} {px} Tf 1 0 0 -1 {:.2} {:.2} Tm [",
+                        origin.0 + line.first().map_or(0.0, |(_, x)| *x),
+                        origin.1 + baseline,
+                    );
+                    let mut expected: Option<f32> = None;
+                    for (c, x) in &line {
+                        let glyph = font.glyph_index(*c);
+                        used[index].glyphs.entry(glyph).or_insert(*c);
+                        // kerning, as the distance from where the pen ended up
+                        if let Some(expected) = expected {
+                            let adjust = (expected - x) * 1000.0 / px;
+                            if adjust.abs() > 0.01 {
+                                let _ = write!(content, " {adjust:.2} ");
+                            }
+                        }
+                        let _ = write!(content, "<{glyph:04x}>");
+                        expected = Some(x + font.glyph_advance(glyph, *px));
+                    }
+                    content.push_str("] TJ ET\n");
+                }
+            }
+            Item::Clip { position, extent } => {
+                let _ = writeln!(
+                    content,
+                    "q {} {} {} {} re W n",
+                    position.0, position.1, extent.0, extent.1,
+                );
+            }
+            Item::Unclip => content.push_str("Q\n"),
+            Item::Image {
+                image,
+                position,
+                extent,
+                opacity,
+            } => {
+                let index = match images
+                    .iter()
+                    .position(|(known, _)| Arc::ptr_eq(known, image))
+                {
+                    Some(index) => index,
+                    None => {
+                        images.push((image.clone(), pdf_image(&mut pdf, image)));
+                        images.len() - 1
+                    }
+                };
+                content.push_str(&gs(*opacity, &mut alphas));
+                // the image's first row is at the top of its unit square
+                let _ = writeln!(
+                    content,
+                    "q {} 0 0 -{} {} {} cm /Im{index} Do Q",
+                    extent.0,
+                    extent.1,
+                    position.0,
+                    position.1 + extent.1,
+                );
+            }
+        }
+    }
+
+    let font_refs: Vec<String> = fonts
+        .iter()
+        .zip(&used)
+        .enumerate()
+        .map(|(i, (font, used))| format!("/F{i} {} 0 R", pdf_font(&mut pdf, font, used)))
+        .collect();
+    let states: Vec<String> = alphas
+        .iter()
+        .enumerate()
+        .map(|(i, alpha)| {
+            let alpha = *alpha as f32 / 255.0;
+            format!("/GS{i} << /ca {alpha} /CA {alpha} >>")
+        })
+        .collect();
+    let image_refs: Vec<String> = images
+        .iter()
+        .enumerate()
+        .map(|(i, (_, id))| format!("/Im{i} {id} 0 R"))
+        .collect();
+    let contents = pdf.add_stream("", content.as_bytes());
+
+    pdf.set(catalog, format!("<< /Type /Catalog /Pages {pages} 0 R >>"));
+    pdf.set(
+        pages,
+        format!("<< /Type /Pages /Kids [{page_id} 0 R] /Count 1 >>"),
+    );
+    pdf.set(
+        page_id,
+        format!(
+            "<< /Type /Page /Parent {pages} 0 R /MediaBox [0 0 {} {}] /Contents {contents} 0 R /Resources << /Font << {} >> /ExtGState << {} >> /XObject << {} >> >> >>",
+            w / LAYOUT_SCALE,
+            h / LAYOUT_SCALE,
+            font_refs.join(" "),
+            states.join(" "),
+            image_refs.join(" "),
+        ),
+    );
+    pdf.finish(catalog)
+}
+
+// rgb samples with the alpha as a soft mask
+fn pdf_image(pdf: &mut PdfWriter, image: &ImageData) -> usize {
+    let (width, height) = image.size();
+    let pixels = image.pixels();
+    let rgb: Vec<u8> = pixels
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2]])
+        .collect();
+    let alpha: Vec<u8> = pixels.chunks_exact(4).map(|p| p[3]).collect();
+    let mask = pdf.add_stream(
+        &format!(
+            "/Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceGray /BitsPerComponent 8"
+        ),
+        &alpha,
+    );
+    pdf.add_stream(
+        &format!(
+            "/Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceRGB /BitsPerComponent 8 /SMask {mask} 0 R"
+        ),
+        &rgb,
+    )
+}
+
+// a type 0 font over the embedded truetype file, addressed by glyph index,
+// with a map back to unicode for copying text out
+fn pdf_font(pdf: &mut PdfWriter, font: &Font, used: &UsedGlyphs) -> usize {
+    let file = pdf.add_stream(&format!("/Length1 {}", font.data().len()), font.data());
+    let descriptor = pdf.add(format!(
+        "<< /Type /FontDescriptor /FontName /Teacup /Flags 32 /FontBBox [0 -250 1000 1000] /ItalicAngle 0 /Ascent {} /Descent -250 /CapHeight 700 /StemV 80 /FontFile2 {file} 0 R >>",
+        font.ascent(1000.0).round(),
+    ));
+
+    let mut glyphs: Vec<(u16, char)> = used.glyphs.iter().map(|(g, c)| (*g, *c)).collect();
+    glyphs.sort();
+    let widths: Vec<String> = glyphs
+        .iter()
+        .map(|(glyph, _)| format!("{glyph} [{:.0}]", font.glyph_advance(*glyph, 1000.0)))
+        .collect();
+    let cid_font = pdf.add(format!(
+        "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /Teacup /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {descriptor} 0 R /CIDToGIDMap /Identity /W [{}] >>",
+        widths.join(" "),
+    ));
+
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    // at most a hundred mappings to a block
+    for block in glyphs.chunks(100) {
+        let _ = writeln!(cmap, "{} beginbfchar", block.len());
+        for (glyph, c) in block {
+            let mut units = [0u16; 2];
+            let hex: String = c
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|unit| format!("{unit:04x}"))
+                .collect();
+            let _ = writeln!(cmap, "<{glyph:04x}> <{hex}>");
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    let to_unicode = pdf.add_stream("", cmap.as_bytes());
+
+    pdf.add(format!(
+        "<< /Type /Font /Subtype /Type0 /BaseFont /Teacup /Encoding /Identity-H /DescendantFonts [{cid_font} 0 R] /ToUnicode {to_unicode} 0 R >>"
+    ))
+}
@@ -0,0 +1,200 @@
+use std::hash::{Hash, Hasher};
+
+use tinycolors::srgb;
+
+use crate::{
+    renderer::{color::Color, mesh_builder::Mesh},
+    theme,
+};
+
+// times a gradient's mesh is split, each one turning every triangle into four.
+// enough that stops in the middle of a rect land close to where they should
+const GRADIENT_SUBDIVISIONS: u32 = 4;
+
+// what a rectangle is filled with. gradients are baked into the colors of a
+// finer mesh, so they draw the same on every backend the draw list goes to
+#[derive(Debug, Clone)]
+pub enum Fill {
+    Solid(srgb),
+    // `stops` are (offset from 0 to 1, color) along a line through the middle
+    // of the rect. `angle` is in degrees clockwise from pointing up like css,
+    // so 90 runs left to right and 180 top to bottom
+    LinearGradient {
+        stops: Vec<(f32, srgb)>,
+        angle: f32,
+    },
+    // circles out from `center`, as a fraction of the rect with (0.5, 0.5)
+    // the middle. `radius` is where the last stop lands, 1 being the corner
+    // furthest from the center
+    RadialGradient {
+        stops: Vec<(f32, srgb)>,
+        center: (f32, f32),
+        radius: f32,
+    },
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Fill::Solid(srgb::default())
+    }
+}
+
+impl From<srgb> for Fill {
+    fn from(color: srgb) -> Self {
+        Fill::Solid(color)
+    }
+}
+
+impl Fill {
+    pub fn linear(angle: f32, stops: impl IntoIterator<Item = (f32, srgb)>) -> Self {
+        Fill::LinearGradient {
+            stops: sorted(stops),
+            angle,
+        }
+    }
+
+    // centered and reaching the corners
+    pub fn radial(stops: impl IntoIterator<Item = (f32, srgb)>) -> Self {
+        Fill::RadialGradient {
+            stops: sorted(stops),
+            center: (0.5, 0.5),
+            radius: 1.0,
+        }
+    }
+
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Fill::Solid(_))
+    }
+
+    // one color standing in for the fill, for things that only take one like
+    // a widget tinting its parts. the first stop of a gradient
+    pub fn color(&self) -> srgb {
+        match self {
+            Fill::Solid(color) => *color,
+            Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops, .. } => {
+                stops.first().map(|(_, color)| *color).unwrap_or_default()
+            }
+        }
+    }
+
+    // the color at `point` of a rect with its top left at `origin`, all in
+    // layout pixels
+    pub fn color_at(&self, point: (f32, f32), origin: (f32, f32), extent: (f32, f32)) -> srgb {
+        let (w, h) = (extent.0.max(1.0), extent.1.max(1.0));
+        let (u, v) = (point.0 - origin.0, point.1 - origin.1);
+        match self {
+            Fill::Solid(color) => *color,
+            Fill::LinearGradient { stops, angle } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let direction = (sin, -cos);
+                // the gradient line is as long as the rect's shadow on it, so
+                // the first and last stops touch opposite corners
+                let length = (w * direction.0).abs() + (h * direction.1).abs();
+                let along = (u - w / 2.0) * direction.0 + (v - h / 2.0) * direction.1;
+                sample(stops, along / length.max(f32::EPSILON) + 0.5)
+            }
+            Fill::RadialGradient {
+                stops,
+                center,
+                radius,
+            } => {
+                let (cx, cy) = (center.0 * w, center.1 * h);
+                let corner = (cx.max(w - cx), cy.max(h - cy));
+                let reach = (corner.0.hypot(corner.1) * radius).max(f32::EPSILON);
+                sample(stops, (u - cx).hypot(v - cy) / reach)
+            }
+        }
+    }
+
+    // tells gradients apart in a mesh cache key. 0 for solid fills, whose
+    // color is already in the key
+    pub(crate) fn cache_key(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        let hash_stops = |stops: &[(f32, srgb)], hasher: &mut dyn Hasher| {
+            for (offset, color) in stops {
+                for value in [*offset, color.r, color.g, color.b] {
+                    hasher.write_u32(value.to_bits());
+                }
+            }
+        };
+        match self {
+            Fill::Solid(_) => return 0,
+            Fill::LinearGradient { stops, angle } => {
+                1u8.hash(&mut hasher);
+                angle.to_bits().hash(&mut hasher);
+                hash_stops(stops, &mut hasher);
+            }
+            Fill::RadialGradient {
+                stops,
+                center,
+                radius,
+            } => {
+                2u8.hash(&mut hasher);
+                for value in [center.0, center.1, *radius] {
+                    value.to_bits().hash(&mut hasher);
+                }
+                hash_stops(stops, &mut hasher);
+            }
+        }
+        hasher.finish().max(1)
+    }
+
+    // recolors `mesh`, built for a rect at `origin` with `extent` against
+    // `size`, with the gradient. `brightness` is the rect's, see
+    // `Rectangle::brightness`. solid fills are left as they were built
+    pub(crate) fn paint(
+        &self,
+        mesh: &mut Mesh,
+        origin: (f32, f32),
+        extent: (f32, f32),
+        brightness: Option<f32>,
+        size: (i32, i32),
+    ) {
+        if self.is_solid() {
+            return;
+        }
+        for _ in 0..GRADIENT_SUBDIVISIONS {
+            mesh.subdivide();
+        }
+        let (w, h) = (size.0 as f32, size.1 as f32);
+        for vertex in &mut mesh.verticies {
+            // back from normalized device coordinates, see `ss_to_ndc`
+            let [x, y, _] = vertex.position;
+            let point = ((x + 1.0) * w, (1.0 - y) * h);
+            let color = Color::from(self.color_at(point, origin, extent));
+            let color = match brightness {
+                Some(brightness) => color.with_brightness(brightness),
+                None => color,
+            };
+            vertex.color = color.components();
+            vertex.color_space = color.space as u32;
+        }
+    }
+}
+
+fn sorted(stops: impl IntoIterator<Item = (f32, srgb)>) -> Vec<(f32, srgb)> {
+    let mut stops: Vec<(f32, srgb)> = stops.into_iter().collect();
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    stops
+}
+
+// the color `t` of the way along `stops`, held at the ends
+fn sample(stops: &[(f32, srgb)], t: f32) -> srgb {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return srgb::default();
+    };
+    if t <= first.0 {
+        return first.1;
+    }
+    for pair in stops.windows(2) {
+        let ((from, a), (to, b)) = (pair[0], pair[1]);
+        if t <= to {
+            let span = to - from;
+            if span <= f32::EPSILON {
+                return b;
+            }
+            return theme::mix(a, b, (t - from) / span);
+        }
+    }
+    last.1
+}
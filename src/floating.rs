@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use crate::{builder::Node, layout::Primative, reconcile::NodeId};
+
+// a spot on an element's box, for lining a floating element up with its anchor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnchorPoint {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl AnchorPoint {
+    // where this is on a box at `position` that's `size` big
+    pub fn on(self, position: (i32, i32), size: (i32, i32)) -> (i32, i32) {
+        let (x, y) = match self {
+            AnchorPoint::TopLeft => (0, 0),
+            AnchorPoint::Top => (1, 0),
+            AnchorPoint::TopRight => (2, 0),
+            AnchorPoint::Left => (0, 1),
+            AnchorPoint::Center => (1, 1),
+            AnchorPoint::Right => (2, 1),
+            AnchorPoint::BottomLeft => (0, 2),
+            AnchorPoint::Bottom => (1, 2),
+            AnchorPoint::BottomRight => (2, 2),
+        };
+        (position.0 + size.0 * x / 2, position.1 + size.1 * y / 2)
+    }
+
+    // the same spot with top and bottom swapped
+    fn flipped(self) -> Self {
+        match self {
+            AnchorPoint::TopLeft => AnchorPoint::BottomLeft,
+            AnchorPoint::Top => AnchorPoint::Bottom,
+            AnchorPoint::TopRight => AnchorPoint::BottomRight,
+            AnchorPoint::BottomLeft => AnchorPoint::TopLeft,
+            AnchorPoint::Bottom => AnchorPoint::Top,
+            AnchorPoint::BottomRight => AnchorPoint::TopRight,
+            point => point,
+        }
+    }
+}
+
+// what a floating element is placed against
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnchorTarget {
+    #[default]
+    Parent,
+    // any element in the page with this id, see `ElementBuilder::id`
+    Element(NodeId),
+}
+
+// puts `origin` on the floating element over `point` on the target
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Anchor {
+    pub target: AnchorTarget,
+    pub point: AnchorPoint,
+    pub origin: AnchorPoint,
+}
+
+impl Anchor {
+    pub fn parent(point: AnchorPoint, origin: AnchorPoint) -> Self {
+        Self {
+            target: AnchorTarget::Parent,
+            point,
+            origin,
+        }
+    }
+
+    pub fn element(id: NodeId, point: AnchorPoint, origin: AnchorPoint) -> Self {
+        Self {
+            target: AnchorTarget::Element(id),
+            point,
+            origin,
+        }
+    }
+
+    // hanging off the bottom left corner, like a dropdown
+    pub fn below(target: AnchorTarget) -> Self {
+        Self {
+            target,
+            point: AnchorPoint::BottomLeft,
+            origin: AnchorPoint::TopLeft,
+        }
+    }
+
+    // centered over the top edge, like a tooltip
+    pub fn above(target: AnchorTarget) -> Self {
+        Self {
+            target,
+            point: AnchorPoint::Top,
+            origin: AnchorPoint::Bottom,
+        }
+    }
+
+    // the top left corner for something `size` big anchored to a box at
+    // `position` that's `target_size` big
+    pub fn place(
+        &self,
+        position: (i32, i32),
+        target_size: (i32, i32),
+        size: (i32, i32),
+    ) -> (i32, i32) {
+        let point = self.point.on(position, target_size);
+        let origin = self.origin.on((0, 0), size);
+        (point.0 - origin.0, point.1 - origin.1)
+    }
+
+    // on the other side of the target, e.g. a dropdown that opens upwards
+    fn flipped(self) -> Self {
+        Self {
+            target: self.target,
+            point: self.point.flipped(),
+            origin: self.origin.flipped(),
+        }
+    }
+}
+
+// how a primitive is placed by its parent
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Positioning {
+    #[default]
+    Flow,
+    // taken out of the parent's layout and put against `anchor`, then moved
+    // by `offset`. it's sized on its own, with grow filling the parent, drawn
+    // above the page and kept inside the window, for tooltips, context menus
+    // and dropdowns
+    Floating {
+        anchor: Anchor,
+        offset: (i32, i32),
+    },
+}
+
+impl Positioning {
+    pub fn is_floating(self) -> bool {
+        matches!(self, Positioning::Floating { .. })
+    }
+}
+
+// top left corner and size
+type Extent = ((i32, i32), (i32, i32));
+
+// of elements with an id
+type Bounds = HashMap<NodeId, Extent>;
+
+fn size(prim: &dyn Primative) -> (i32, i32) {
+    (prim.get_width(), prim.get_height())
+}
+
+// every floating element under `prim` with the bounds of its parent, parents
+// before the ones inside them
+fn floating_with_parents(prim: &dyn Primative, found: &mut Vec<(Node, Extent)>) {
+    for child in prim.children() {
+        if let Ok(child_prim) = child.lock() {
+            if child_prim.positioning().is_floating() {
+                found.push((child.clone(), (prim.get_position(), size(prim))));
+            }
+            floating_with_parents(&*child_prim, found);
+        }
+    }
+}
+
+// every floating element under `children`, parents before the ones inside them
+pub(crate) fn floating_nodes(children: &[Node]) -> Vec<Node> {
+    let mut found = Vec::new();
+    collect(children, &mut found);
+    found
+}
+
+fn collect(children: &[Node], found: &mut Vec<Node>) {
+    for child in children {
+        if let Ok(prim) = child.lock() {
+            if prim.positioning().is_floating() {
+                found.push(child.clone());
+            }
+            collect(prim.children(), found);
+        }
+    }
+}
+
+fn bounds(prim: &dyn Primative, found: &mut Bounds) {
+    if let Some(id) = prim.id() {
+        found.entry(id).or_insert((prim.get_position(), size(prim)));
+    }
+    for child in prim.children() {
+        if let Ok(child) = child.lock() {
+            bounds(&*child, found);
+        }
+    }
+}
+
+// the last layout step, once the page is positioned. elements anchored to
+// another element move next to it, ones that would run off the top or bottom
+// of the window go on the other side of their target if there's room, and
+// everything floating is pushed back inside the window. targets inside other
+// floating elements are found where they were before those moved
+pub(crate) fn resolve(root: &mut dyn Primative, window: (i32, i32)) {
+    let mut nodes = Vec::new();
+    floating_with_parents(root, &mut nodes);
+    if nodes.is_empty() {
+        return;
+    }
+    let mut targets = Bounds::new();
+    bounds(root, &mut targets);
+    for (node, parent) in nodes {
+        let Ok(mut prim) = node.lock() else {
+            continue;
+        };
+        let Positioning::Floating { anchor, offset } = prim.positioning() else {
+            continue;
+        };
+        let translation = prim.translation();
+        let mut position = prim.get_position();
+        let target = match anchor.target {
+            AnchorTarget::Parent => Some(parent),
+            AnchorTarget::Element(id) => {
+                let target = targets.get(&id).copied();
+                match target {
+                    Some((target, target_size)) => {
+                        let (x, y) = anchor.place(target, target_size, size(&*prim));
+                        position = (x + offset.0 + translation.0, y + offset.1 + translation.1);
+                    }
+                    None => log::warn!("nothing to anchor to with {id:?}"),
+                }
+                target
+            }
+        };
+        let height = prim.get_height();
+        let overflows = |y: i32| y < 0 || y + height > window.1;
+        if overflows(position.1)
+            && let Some((target, target_size)) = target
+        {
+            let (x, y) = anchor.flipped().place(target, target_size, size(&*prim));
+            let flipped = (x + offset.0 + translation.0, y - offset.1 + translation.1);
+            if !overflows(flipped.1) {
+                position = flipped;
+            }
+        }
+        let clamped = (
+            position.0.min(window.0 - prim.get_width()).max(0),
+            position.1.min(window.1 - prim.get_height()).max(0),
+        );
+        if clamped != prim.get_position() {
+            // set_position adds the translation back on
+            prim.set_position((clamped.0 - translation.0, clamped.1 - translation.1));
+            if let Some(container) = prim.as_container() {
+                container.set_child_positions();
+            }
+        }
+    }
+}
+
+// the topmost floating element under `point`, tried before the rest of the page
+pub(crate) fn at(nodes: &[Node], point: (i32, i32)) -> Option<Node> {
+    let mut nodes: Vec<&Node> = nodes.iter().collect();
+    nodes.sort_by_cached_key(|node| node.lock().map_or(0, |prim| prim.z_index()));
+    nodes
+        .into_iter()
+        .rev()
+        .find(|node| node.lock().is_ok_and(|prim| prim.contains(point)))
+        .cloned()
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+pub type Validator = Box<dyn Fn(&str) -> Result<(), String> + Send>;
+
+pub struct FormField {
+    pub name: String,
+    pub value: String,
+    pub error: Option<String>,
+    pub touched: bool,
+    validators: Vec<Validator>,
+}
+
+impl FormField {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: String::new(),
+            error: None,
+            touched: false,
+            validators: Vec::new(),
+        }
+    }
+
+    pub fn validator(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + Send + 'static,
+    ) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    pub fn validate(&mut self) -> bool {
+        self.error = self
+            .validators
+            .iter()
+            .find_map(|validator| validator(&self.value).err());
+        self.error.is_none()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validators
+            .iter()
+            .all(|validator| validator(&self.value).is_ok())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationTrigger {
+    #[default]
+    OnChange,
+    OnSubmit,
+}
+
+type ErrorCallback = Box<dyn FnMut(&str, Option<&str>) + Send>;
+type FocusCallback = Box<dyn FnMut(&str) + Send>;
+type SubmitCallback = Box<dyn FnMut(&HashMap<String, String>) + Send>;
+
+#[derive(Default)]
+pub struct Form {
+    pub trigger: ValidationTrigger,
+    fields: Vec<FormField>,
+    on_error_changed: Option<ErrorCallback>,
+    on_focus: Option<FocusCallback>,
+    on_submit: Option<SubmitCallback>,
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(mut self, field: FormField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    // called whenever a field's error message appears, changes or clears so the
+    // ui can show it next to the input
+    pub fn on_error_changed(mut self, f: impl FnMut(&str, Option<&str>) + Send + 'static) -> Self {
+        self.on_error_changed = Some(Box::new(f));
+        self
+    }
+
+    pub fn on_focus(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_focus = Some(Box::new(f));
+        self
+    }
+
+    pub fn on_submit(mut self, f: impl FnMut(&HashMap<String, String>) + Send + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+
+    pub fn fields(&self) -> &[FormField] {
+        &self.fields
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FormField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|f| f.value.as_str())
+    }
+
+    pub fn error(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(|f| f.error.as_deref())
+    }
+
+    pub fn set_value(&mut self, name: &str, value: &str) {
+        let Some(index) = self.fields.iter().position(|f| f.name == name) else {
+            log::warn!("form has no field named {name}");
+            return;
+        };
+        let field = &mut self.fields[index];
+        field.value = value.to_string();
+        field.touched = true;
+        if self.trigger == ValidationTrigger::OnChange {
+            self.validate_field(index);
+        }
+    }
+
+    pub fn can_submit(&self) -> bool {
+        self.fields.iter().all(FormField::is_valid)
+    }
+
+    pub fn validate(&mut self) -> bool {
+        let mut valid = true;
+        for i in 0..self.fields.len() {
+            valid &= self.validate_field(i);
+        }
+        valid
+    }
+
+    pub fn first_invalid(&self) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|f| !f.is_valid())
+            .map(|f| f.name.as_str())
+    }
+
+    pub fn focus_first_invalid(&mut self) -> Option<String> {
+        let name = self.first_invalid()?.to_string();
+        if let Some(on_focus) = &mut self.on_focus {
+            on_focus(&name);
+        }
+        Some(name)
+    }
+
+    pub fn values(&self) -> HashMap<String, String> {
+        self.fields
+            .iter()
+            .map(|f| (f.name.clone(), f.value.clone()))
+            .collect()
+    }
+
+    pub fn submit(&mut self) -> Result<HashMap<String, String>, Vec<(String, String)>> {
+        for field in &mut self.fields {
+            field.touched = true;
+        }
+        if !self.validate() {
+            self.focus_first_invalid();
+            return Err(self
+                .fields
+                .iter()
+                .filter_map(|f| f.error.clone().map(|e| (f.name.clone(), e)))
+                .collect());
+        }
+
+        let values = self.values();
+        if let Some(on_submit) = &mut self.on_submit {
+            on_submit(&values);
+        }
+        Ok(values)
+    }
+
+    pub fn reset(&mut self) {
+        for i in 0..self.fields.len() {
+            let field = &mut self.fields[i];
+            field.value.clear();
+            field.touched = false;
+            if field.error.take().is_some()
+                && let Some(on_error_changed) = &mut self.on_error_changed
+            {
+                on_error_changed(&self.fields[i].name, None);
+            }
+        }
+    }
+
+    fn validate_field(&mut self, index: usize) -> bool {
+        let field = &mut self.fields[index];
+        let previous = field.error.clone();
+        let valid = field.validate();
+        if field.error != previous
+            && let Some(on_error_changed) = &mut self.on_error_changed
+        {
+            on_error_changed(&field.name, field.error.as_deref());
+        }
+        valid
+    }
+}
+
+pub mod validators {
+    pub fn required(message: &str) -> impl Fn(&str) -> Result<(), String> + Send + 'static {
+        let message = message.to_string();
+        move |value| {
+            if value.trim().is_empty() {
+                Err(message.clone())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn min_length(min: usize) -> impl Fn(&str) -> Result<(), String> + Send + 'static {
+        move |value| {
+            if value.chars().count() < min {
+                Err(format!("must be at least {min} characters"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn max_length(max: usize) -> impl Fn(&str) -> Result<(), String> + Send + 'static {
+        move |value| {
+            if value.chars().count() > max {
+                Err(format!("must be at most {max} characters"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn number_in_range(
+        min: f64,
+        max: f64,
+    ) -> impl Fn(&str) -> Result<(), String> + Send + 'static {
+        move |value| match value.trim().parse::<f64>() {
+            Ok(n) if n >= min && n <= max => Ok(()),
+            Ok(_) => Err(format!("must be between {min} and {max}")),
+            Err(_) => Err("must be a number".to_string()),
+        }
+    }
+
+    pub fn email() -> impl Fn(&str) -> Result<(), String> + Send + 'static {
+        |value| {
+            let valid = value.split_once('@').is_some_and(|(user, domain)| {
+                !user.is_empty()
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+            });
+            if valid {
+                Ok(())
+            } else {
+                Err("must be a valid email address".to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use global_hotkey::{
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+    hotkey::{Code, HotKey, Modifiers as HotKeyModifiers},
+};
+
+use crate::{
+    input::Key,
+    shortcuts::{Shortcut, ShortcutRegistry},
+};
+
+// os wide hotkeys that fire even when the window isn't focused. each one is
+// tied to an action name in the ShortcutRegistry
+pub struct GlobalHotkeys {
+    manager: GlobalHotKeyManager,
+    registered: HashMap<u32, (String, HotKey)>,
+}
+
+impl GlobalHotkeys {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            manager: GlobalHotKeyManager::new()?,
+            registered: HashMap::new(),
+        })
+    }
+
+    pub fn register(&mut self, action: &str, shortcut: Shortcut) -> anyhow::Result<()> {
+        let hotkey = to_hotkey(shortcut)
+            .ok_or_else(|| anyhow::anyhow!("{shortcut} can't be used as a global hotkey"))?;
+        self.manager.register(hotkey)?;
+        self.registered
+            .insert(hotkey.id(), (action.to_string(), hotkey));
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, action: &str) {
+        self.registered.retain(|_, (name, hotkey)| {
+            if name != action {
+                return true;
+            }
+            if let Err(e) = self.manager.unregister(*hotkey) {
+                log::warn!("failed to unregister global hotkey for {action}: {e}");
+            }
+            false
+        });
+    }
+
+    // triggers the registry action for every hotkey pressed since the last poll
+    pub fn poll(&self, registry: &mut ShortcutRegistry) {
+        while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            if event.state != HotKeyState::Pressed {
+                continue;
+            }
+            if let Some((action, _)) = self.registered.get(&event.id)
+                && !registry.trigger(action)
+            {
+                log::warn!("global hotkey fired for unknown action {action}");
+            }
+        }
+    }
+}
+
+impl Drop for GlobalHotkeys {
+    fn drop(&mut self) {
+        let hotkeys: Vec<HotKey> = self.registered.values().map(|(_, h)| *h).collect();
+        if let Err(e) = self.manager.unregister_all(&hotkeys) {
+            log::warn!("failed to unregister global hotkeys: {e}");
+        }
+    }
+}
+
+fn to_hotkey(shortcut: Shortcut) -> Option<HotKey> {
+    let mut modifiers = HotKeyModifiers::empty();
+    modifiers.set(HotKeyModifiers::SHIFT, shortcut.modifiers.shift);
+    modifiers.set(HotKeyModifiers::CONTROL, shortcut.modifiers.control);
+    modifiers.set(HotKeyModifiers::ALT, shortcut.modifiers.alt);
+    modifiers.set(HotKeyModifiers::SUPER, shortcut.modifiers.super_key);
+
+    let code = match shortcut.key {
+        Key::Up => Code::ArrowUp,
+        Key::Down => Code::ArrowDown,
+        Key::Left => Code::ArrowLeft,
+        Key::Right => Code::ArrowRight,
+        Key::Enter => Code::Enter,
+        Key::Escape => Code::Escape,
+        Key::Tab => Code::Tab,
+        Key::Backspace => Code::Backspace,
+        Key::Delete => Code::Delete,
+        Key::Home => Code::Home,
+        Key::End => Code::End,
+        Key::PageUp => Code::PageUp,
+        Key::PageDown => Code::PageDown,
+        Key::Space => Code::Space,
+        Key::F2 => Code::F2,
+        Key::Char(c) => char_code(c)?,
+    };
+    Some(HotKey::new(Some(modifiers), code))
+}
+
+fn char_code(c: char) -> Option<Code> {
+    const LETTERS: [Code; 26] = [
+        Code::KeyA,
+        Code::KeyB,
+        Code::KeyC,
+        Code::KeyD,
+        Code::KeyE,
+        Code::KeyF,
+        Code::KeyG,
+        Code::KeyH,
+        Code::KeyI,
+        Code::KeyJ,
+        Code::KeyK,
+        Code::KeyL,
+        Code::KeyM,
+        Code::KeyN,
+        Code::KeyO,
+        Code::KeyP,
+        Code::KeyQ,
+        Code::KeyR,
+        Code::KeyS,
+        Code::KeyT,
+        Code::KeyU,
+        Code::KeyV,
+        Code::KeyW,
+        Code::KeyX,
+        Code::KeyY,
+        Code::KeyZ,
+    ];
+    const DIGITS: [Code; 10] = [
+        Code::Digit0,
+        Code::Digit1,
+        Code::Digit2,
+        Code::Digit3,
+        Code::Digit4,
+        Code::Digit5,
+        Code::Digit6,
+        Code::Digit7,
+        Code::Digit8,
+        Code::Digit9,
+    ];
+    let c = c.to_ascii_lowercase();
+    match c {
+        'a'..='z' => Some(LETTERS[(c as u8 - b'a') as usize]),
+        '0'..='9' => Some(DIGITS[(c as u8 - b'0') as usize]),
+        _ => None,
+    }
+}
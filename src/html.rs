@@ -0,0 +1,617 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    builder::{ElementBuilder, IntoNode, Node, column, rect, row},
+    dispatch::Phase,
+    event::{Event, PointerButton},
+    layout::{AlignCross, Image, SizingMode, Text},
+    renderer::{image::ImageData, text::Font},
+    theme::{Theme, from_hex},
+};
+
+type LinkCallback = Box<dyn FnMut(&str) + Send>;
+
+// fonts and colors the markup is drawn with. bold, italic and code fall back
+// to `font` when they're not given
+#[derive(Clone)]
+pub struct HtmlStyle {
+    pub font: Arc<Font>,
+    pub bold_font: Option<Arc<Font>>,
+    pub italic_font: Option<Arc<Font>>,
+    pub bold_italic_font: Option<Arc<Font>>,
+    pub code_font: Option<Arc<Font>>,
+    pub font_size: f32,
+    pub color: srgb,
+    pub link_color: srgb,
+    // space between paragraphs, headings and list items
+    pub block_gap: i32,
+    // where relative image paths are looked up
+    pub base_path: Option<PathBuf>,
+}
+
+impl HtmlStyle {
+    pub fn new(font: Arc<Font>, theme: &Theme) -> Self {
+        Self {
+            font,
+            bold_font: None,
+            italic_font: None,
+            bold_italic_font: None,
+            code_font: Font::monospace_default(),
+            font_size: 16.0,
+            color: theme.text,
+            link_color: theme.primary,
+            block_gap: 12,
+            base_path: None,
+        }
+    }
+}
+
+// turns a small subset of html into teacup elements, for help pages and
+// release notes. knows p, div, br, hr, h1 to h6, ul, ol, li, b, strong, i, em,
+// code, span, a and img, and the color, font-size, font-weight and font-style
+// properties of inline `style` attributes. everything else is read as its text
+pub struct Html {
+    style: HtmlStyle,
+    on_link: Option<Arc<Mutex<LinkCallback>>>,
+}
+
+impl Html {
+    pub fn new(style: HtmlStyle) -> Self {
+        Self {
+            style,
+            on_link: None,
+        }
+    }
+
+    // called with a link's href when it's clicked
+    pub fn on_link(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_link = Some(Arc::new(Mutex::new(Box::new(f))));
+        self
+    }
+
+    // a column that grows to its parent's width, with paragraphs wrapping inside
+    pub fn render(&self, source: &str) -> ElementBuilder {
+        let dom = parse(source);
+        self.blocks(&dom, &self.root_style())
+    }
+
+    fn root_style(&self) -> TextStyle {
+        TextStyle {
+            bold: false,
+            italic: false,
+            code: false,
+            font_size: self.style.font_size,
+            color: self.style.color,
+            link: None,
+        }
+    }
+
+    fn blocks(&self, nodes: &[Dom], style: &TextStyle) -> ElementBuilder {
+        let mut blocks = column()
+            .width(SizingMode::Grow)
+            .gap(self.style.block_gap)
+            .opacity(0.0);
+        let mut paragraph = Paragraph::default();
+        for node in nodes {
+            match node {
+                Dom::Element(element) if element.is_block() => {
+                    if let Some(lines) = paragraph.take(self.space(style)) {
+                        blocks = blocks.child(lines);
+                    }
+                    blocks = blocks.child(self.block(element, style));
+                }
+                node => self.inline(node, style, &mut paragraph),
+            }
+        }
+        if let Some(lines) = paragraph.take(self.space(style)) {
+            blocks = blocks.child(lines);
+        }
+        blocks
+    }
+
+    fn block(&self, element: &Element, style: &TextStyle) -> Node {
+        let style = style.with(element, &self.style);
+        match element.tag.as_str() {
+            "ul" => self.list(element, &style, false),
+            "ol" => self.list(element, &style, true),
+            "hr" => rect()
+                .width(SizingMode::Grow)
+                .height(SizingMode::Fixed(1))
+                .color(style.color)
+                .opacity(0.3)
+                .build(),
+            _ => self.blocks(&element.children, &style).build(),
+        }
+    }
+
+    fn list(&self, element: &Element, style: &TextStyle, ordered: bool) -> Node {
+        let mut list = column()
+            .width(SizingMode::Grow)
+            .gap(self.style.block_gap / 2)
+            .opacity(0.0);
+        let mut number = element
+            .attrs
+            .get("start")
+            .and_then(|start| start.parse::<i32>().ok())
+            .unwrap_or(1);
+        for item in element.children.iter().filter_map(Dom::element) {
+            if item.tag != "li" {
+                continue;
+            }
+            let marker = if ordered {
+                number += 1;
+                format!("{}.", number - 1)
+            } else {
+                "•".to_string()
+            };
+            let style = style.with(item, &self.style);
+            list = list.child(
+                row()
+                    .width(SizingMode::Grow)
+                    .gap(8)
+                    .opacity(0.0)
+                    .child(self.text(&marker, &style))
+                    .child(self.blocks(&item.children, &style)),
+            );
+        }
+        list.build()
+    }
+
+    fn inline(&self, node: &Dom, style: &TextStyle, paragraph: &mut Paragraph) {
+        let element = match node {
+            Dom::Text(text) => {
+                for word in text.split_ascii_whitespace() {
+                    paragraph.push(self.word(word, style));
+                }
+                return;
+            }
+            Dom::Element(element) => element,
+        };
+        match element.tag.as_str() {
+            "br" => paragraph.break_line(),
+            "img" => paragraph.push(self.image(element, style)),
+            _ => {
+                let style = style.with(element, &self.style);
+                for child in &element.children {
+                    self.inline(child, &style, paragraph);
+                }
+            }
+        }
+    }
+
+    fn font(&self, style: &TextStyle) -> Arc<Font> {
+        let font = match (style.code, style.bold, style.italic) {
+            (true, _, _) => &self.style.code_font,
+            (_, true, true) => &self.style.bold_italic_font,
+            (_, true, false) => &self.style.bold_font,
+            (_, false, true) => &self.style.italic_font,
+            _ => &None,
+        };
+        font.clone().unwrap_or_else(|| self.style.font.clone())
+    }
+
+    fn space(&self, style: &TextStyle) -> i32 {
+        self.font(style).advance(' ', style.font_size).round() as i32
+    }
+
+    fn text(&self, text: &str, style: &TextStyle) -> Node {
+        Text::new(text, self.font(style), style.font_size, style.color).into_node()
+    }
+
+    fn word(&self, word: &str, style: &TextStyle) -> Node {
+        let text = self.text(word, style);
+        match (&style.link, &self.on_link) {
+            (Some(href), Some(on_link)) => link(text, href.clone(), on_link.clone()),
+            _ => text,
+        }
+    }
+
+    // shows the alt text when the image can't be loaded
+    fn image(&self, element: &Element, style: &TextStyle) -> Node {
+        let src = element.attrs.get("src").map_or("", String::as_str);
+        let path = match &self.style.base_path {
+            Some(base) => base.join(src),
+            None => PathBuf::from(src),
+        };
+        let data = match ImageData::load(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                log::warn!("couldn't load {}: {err}", path.display());
+                let alt = element.attrs.get("alt").map_or(src, String::as_str);
+                return self.text(alt, style);
+            }
+        };
+        let mut image = Image::new(data);
+        let size = |name: &str| element.attrs.get(name)?.trim_end_matches("px").parse().ok();
+        if let Some(width) = size("width") {
+            image.rect.sizing.width = SizingMode::Fixed(width);
+        }
+        if let Some(height) = size("height") {
+            image.rect.sizing.height = SizingMode::Fixed(height);
+        }
+        image.into_node()
+    }
+}
+
+fn link(text: Node, href: String, on_link: Arc<Mutex<LinkCallback>>) -> Node {
+    row()
+        .fit()
+        .opacity(0.0)
+        .on_event(move |cx| {
+            if cx.phase() != Phase::Capture
+                && let Event::PointerReleased {
+                    button: PointerButton::Primary,
+                    ..
+                } = cx.event
+                && let Ok(mut on_link) = on_link.lock()
+            {
+                on_link(&href);
+            }
+        })
+        .child(text)
+        .build()
+}
+
+// what inline elements have set for the text inside them
+#[derive(Clone)]
+struct TextStyle {
+    bold: bool,
+    italic: bool,
+    code: bool,
+    font_size: f32,
+    color: srgb,
+    link: Option<String>,
+}
+
+impl TextStyle {
+    fn with(&self, element: &Element, html: &HtmlStyle) -> Self {
+        let mut style = self.clone();
+        match element.tag.as_str() {
+            "b" | "strong" => style.bold = true,
+            "i" | "em" => style.italic = true,
+            "code" => style.code = true,
+            "a" => {
+                style.link = element.attrs.get("href").cloned();
+                style.color = html.link_color;
+            }
+            tag => {
+                if let Some(scale) = heading_scale(tag) {
+                    style.bold = true;
+                    style.font_size = html.font_size * scale;
+                }
+            }
+        }
+        if let Some(css) = element.attrs.get("style") {
+            style.apply_css(css, html.font_size);
+        }
+        style
+    }
+
+    fn apply_css(&mut self, css: &str, root_size: f32) {
+        for declaration in css.split(';') {
+            let Some((property, value)) = declaration.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match property.trim() {
+                "color" => match from_hex(value) {
+                    Some(color) => self.color = color,
+                    None => log::warn!("unsupported color {value:?}"),
+                },
+                "font-size" => {
+                    let size = if let Some(px) = value.strip_suffix("px") {
+                        px.trim().parse().ok()
+                    } else if let Some(rem) = value.strip_suffix("rem") {
+                        rem.trim().parse::<f32>().ok().map(|rem| rem * root_size)
+                    } else if let Some(em) = value.strip_suffix("em") {
+                        em.trim().parse::<f32>().ok().map(|em| em * self.font_size)
+                    } else {
+                        None
+                    };
+                    if let Some(size) = size {
+                        self.font_size = size;
+                    }
+                }
+                "font-weight" => {
+                    self.bold = value == "bold"
+                        || value == "bolder"
+                        || value.parse::<u32>().is_ok_and(|weight| weight >= 600);
+                }
+                "font-style" => self.italic = value == "italic" || value == "oblique",
+                _ => {}
+            }
+        }
+    }
+}
+
+fn heading_scale(tag: &str) -> Option<f32> {
+    match tag {
+        "h1" => Some(2.0),
+        "h2" => Some(1.5),
+        "h3" => Some(1.25),
+        "h4" => Some(1.1),
+        "h5" => Some(1.0),
+        "h6" => Some(0.9),
+        _ => None,
+    }
+}
+
+// the words and images of a run of inline content, split into lines by <br>
+#[derive(Default)]
+struct Paragraph {
+    lines: Vec<Vec<Node>>,
+}
+
+impl Paragraph {
+    fn push(&mut self, node: Node) {
+        if self.lines.is_empty() {
+            self.lines.push(Vec::new());
+        }
+        if let Some(line) = self.lines.last_mut() {
+            line.push(node);
+        }
+    }
+
+    fn break_line(&mut self) {
+        if self.lines.is_empty() {
+            self.lines.push(Vec::new());
+        }
+        self.lines.push(Vec::new());
+    }
+
+    // words are spaced by the gap, so each line wraps like text would
+    fn take(&mut self, space: i32) -> Option<ElementBuilder> {
+        let lines = std::mem::take(&mut self.lines);
+        if lines.iter().all(Vec::is_empty) {
+            return None;
+        }
+        let lines = lines.into_iter().map(|words| {
+            row()
+                .width(SizingMode::Grow)
+                .wrap()
+                .gap(space)
+                .align_cross(AlignCross::End)
+                .opacity(0.0)
+                .children(words)
+        });
+        Some(
+            column()
+                .width(SizingMode::Grow)
+                .opacity(0.0)
+                .children(lines),
+        )
+    }
+}
+
+enum Dom {
+    Text(String),
+    Element(Element),
+}
+
+impl Dom {
+    fn element(&self) -> Option<&Element> {
+        match self {
+            Dom::Element(element) => Some(element),
+            Dom::Text(_) => None,
+        }
+    }
+}
+
+struct Element {
+    tag: String,
+    attrs: HashMap<String, String>,
+    children: Vec<Dom>,
+}
+
+impl Element {
+    fn is_block(&self) -> bool {
+        is_block(&self.tag)
+    }
+}
+
+fn is_block(tag: &str) -> bool {
+    matches!(
+        tag,
+        "p" | "div"
+            | "hr"
+            | "ul"
+            | "ol"
+            | "li"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "blockquote"
+            | "pre"
+            | "section"
+            | "header"
+            | "footer"
+    )
+}
+
+fn is_void(tag: &str) -> bool {
+    matches!(tag, "br" | "hr" | "img" | "meta" | "link" | "input")
+}
+
+// a forgiving parser: unknown tags are kept, stray closing tags are ignored,
+// and anything left open is closed at the end. opening a block closes an open
+// <p>, and opening an <li> closes the one before it
+fn parse(source: &str) -> Vec<Dom> {
+    // the element being filled is the last one, the finished document is the
+    // children of the first
+    let mut stack = vec![Element {
+        tag: String::new(),
+        attrs: HashMap::new(),
+        children: Vec::new(),
+    }];
+    let mut rest = source;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+        push_text(&mut stack, &rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim().to_ascii_lowercase();
+            if let Some(open) = stack.iter().rposition(|element| element.tag == name)
+                && open > 0
+            {
+                while stack.len() > open {
+                    close(&mut stack);
+                }
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let (name, attrs) = parse_tag(tag.trim_end_matches('/'));
+        if name.is_empty() {
+            continue;
+        }
+        let closes =
+            |open: &str| (open == "p" && is_block(&name)) || (open == "li" && name == "li");
+        if stack.len() > 1 && stack.last().is_some_and(|open| closes(&open.tag)) {
+            close(&mut stack);
+        }
+        let void = self_closing || is_void(&name);
+        stack.push(Element {
+            tag: name,
+            attrs,
+            children: Vec::new(),
+        });
+        if void {
+            close(&mut stack);
+        }
+    }
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+    stack.pop().map_or_else(Vec::new, |root| root.children)
+}
+
+fn close(stack: &mut Vec<Element>) {
+    if let Some(element) = stack.pop()
+        && let Some(parent) = stack.last_mut()
+    {
+        parent.children.push(Dom::Element(element));
+    }
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    if text.trim_ascii().is_empty() {
+        return;
+    }
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(Dom::Text(decode_entities(text)));
+    }
+}
+
+fn parse_tag(tag: &str) -> (String, HashMap<String, String>) {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_lowercase();
+    let mut attrs = HashMap::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (value, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            rest = remaining.trim_start();
+            decode_entities(value)
+        } else {
+            String::new()
+        };
+        if !key.is_empty() {
+            attrs.insert(key, value);
+        }
+    }
+    (name, attrs)
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(code) = name.strip_prefix('#') {
+        let code = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        "copy" => Some('©'),
+        "mdash" => Some('—'),
+        "ndash" => Some('–'),
+        "hellip" => Some('…'),
+        "bull" => Some('•'),
+        _ => None,
+    }
+}
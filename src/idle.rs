@@ -0,0 +1,116 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::context::UiContext;
+
+// returns whether there's more to do. callbacks that return true are called
+// again the next time the frame has time to spare
+pub(crate) type IdleCallback = Box<dyn FnMut(&mut UiContext, IdleDeadline) -> bool + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdleId(u64);
+
+// how long an idle callback has before it should hand the frame back. work
+// that can be split up should check it between pieces and return true to pick
+// up where it left off
+#[derive(Debug, Clone, Copy)]
+pub struct IdleDeadline {
+    end: Instant,
+}
+
+impl IdleDeadline {
+    pub fn time_remaining(&self) -> Duration {
+        self.end.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.end
+    }
+}
+
+struct IdleTask {
+    id: IdleId,
+    budget: Duration,
+    callback: IdleCallback,
+}
+
+// low priority work that runs between frames, after the frame is drawn and
+// only for as long as it has left before the next one is due. tasks take
+// turns, so one that always has more to do can't starve the rest
+#[derive(Default)]
+pub(crate) struct IdleQueue {
+    next_id: u64,
+    tasks: Vec<IdleTask>,
+    // the task whose callback is running, and whether it cancelled itself
+    running: Option<(IdleId, bool)>,
+}
+
+impl fmt::Debug for IdleQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdleQueue")
+            .field("pending", &self.tasks.len())
+            .finish()
+    }
+}
+
+impl IdleQueue {
+    pub fn add(&mut self, budget: Duration, callback: IdleCallback) -> IdleId {
+        let id = IdleId(self.next_id);
+        self.next_id += 1;
+        self.tasks.push(IdleTask {
+            id,
+            budget,
+            callback,
+        });
+        id
+    }
+
+    pub fn cancel(&mut self, id: IdleId) -> bool {
+        if let Some((running, cancelled)) = &mut self.running
+            && *running == id
+        {
+            *cancelled = true;
+            return true;
+        }
+        let count = self.tasks.len();
+        self.tasks.retain(|task| task.id != id);
+        self.tasks.len() != count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+// gives each waiting task up to its budget, stopping once `frame_end` is
+// reached. the tasks that didn't get a turn go first next time. returns
+// whether any ran
+pub(crate) fn run_idle(context: &mut UiContext, frame_end: Instant) -> bool {
+    let mut waiting = std::mem::take(&mut context.idle.tasks);
+    let mut done = Vec::new();
+    let mut ran = false;
+    while !waiting.is_empty() && Instant::now() < frame_end {
+        let mut task = waiting.remove(0);
+        ran = true;
+        let deadline = IdleDeadline {
+            end: (Instant::now() + task.budget).min(frame_end),
+        };
+        context.idle.running = Some((task.id, false));
+        let more = (task.callback)(context, deadline);
+        let cancelled = context
+            .idle
+            .running
+            .take()
+            .is_some_and(|(_, cancelled)| cancelled);
+        if more && !cancelled {
+            done.push(task);
+        }
+    }
+    // tasks added by callbacks wait their turn behind the ones already queued
+    waiting.append(&mut done);
+    waiting.append(&mut context.idle.tasks);
+    context.idle.tasks = waiting;
+    ran
+}
@@ -0,0 +1,217 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+// used where the os has no setting for it or it can't be read
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+pub const WHEEL_LINES: f32 = 3.0;
+
+// the resolved `InputSettings`, kept here like `text::text_scale` so widgets
+// can read them while handling events, which has no context to read from
+static DOUBLE_CLICK_MS: AtomicU32 = AtomicU32::new(DOUBLE_CLICK_INTERVAL.as_millis() as u32);
+static WHEEL_LINES_BITS: AtomicU32 = AtomicU32::new(WHEEL_LINES.to_bits());
+
+// overrides for the input settings teacup reads from the os. unset ones
+// follow the os, or the defaults above where it has none
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InputSettings {
+    // the longest gap between two clicks that still makes a double click
+    pub double_click_interval: Option<Duration>,
+    // how many lines one notch of a mouse wheel scrolls. trackpads scroll by
+    // distance and aren't affected
+    pub wheel_lines: Option<f32>,
+}
+
+pub fn double_click_interval() -> Duration {
+    Duration::from_millis(DOUBLE_CLICK_MS.load(Ordering::Relaxed) as u64)
+}
+
+pub fn wheel_lines() -> f32 {
+    f32::from_bits(WHEEL_LINES_BITS.load(Ordering::Relaxed))
+}
+
+pub(crate) fn set_double_click_interval(interval: Duration) {
+    let millis = interval.as_millis().clamp(100, 5000) as u32;
+    DOUBLE_CLICK_MS.store(millis, Ordering::Relaxed);
+}
+
+pub(crate) fn set_wheel_lines(lines: f32) {
+    WHEEL_LINES_BITS.store(lines.clamp(0.1, 100.0).to_bits(), Ordering::Relaxed);
+}
+// how much pointer history a velocity is measured over
+pub const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Space,
+    F2,
+    // letters are always lowercase
+    Char(char),
+}
+
+impl Key {
+    pub(crate) fn from_glfw(key: glfw::Key) -> Option<Key> {
+        Some(match key {
+            glfw::Key::Up => Key::Up,
+            glfw::Key::Down => Key::Down,
+            glfw::Key::Left => Key::Left,
+            glfw::Key::Right => Key::Right,
+            glfw::Key::Enter | glfw::Key::KpEnter => Key::Enter,
+            glfw::Key::Escape => Key::Escape,
+            glfw::Key::Tab => Key::Tab,
+            glfw::Key::Backspace => Key::Backspace,
+            glfw::Key::Delete => Key::Delete,
+            glfw::Key::Home => Key::Home,
+            glfw::Key::End => Key::End,
+            glfw::Key::PageUp => Key::PageUp,
+            glfw::Key::PageDown => Key::PageDown,
+            glfw::Key::Space => Key::Space,
+            glfw::Key::F2 => Key::F2,
+            glfw::Key::Equal => Key::Char('='),
+            glfw::Key::Minus | glfw::Key::KpSubtract => Key::Char('-'),
+            glfw::Key::KpAdd => Key::Char('+'),
+            key => {
+                let code = key as i32;
+                if (glfw::Key::A as i32..=glfw::Key::Z as i32).contains(&code)
+                    || (glfw::Key::Num0 as i32..=glfw::Key::Num9 as i32).contains(&code)
+                {
+                    // glfw key codes for letters and digits are their ascii values
+                    Key::Char((code as u8 as char).to_ascii_lowercase())
+                } else {
+                    return None;
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        control: false,
+        alt: false,
+        super_key: false,
+    };
+
+    pub(crate) fn from_glfw(modifiers: glfw::Modifiers) -> Self {
+        Self {
+            shift: modifiers.contains(glfw::Modifiers::Shift),
+            control: modifiers.contains(glfw::Modifiers::Control),
+            alt: modifiers.contains(glfw::Modifiers::Alt),
+            super_key: modifiers.contains(glfw::Modifiers::Super),
+        }
+    }
+
+    // control on most platforms, command on macos
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.super_key
+        } else {
+            self.control
+        }
+    }
+}
+
+// estimates how fast the pointer is moving from its recent positions, for
+// deciding where a fling lands. the velocity is the slope of a least squares
+// line through the samples in the window, so one jittery sample doesn't
+// dominate, and holding still before a release brings it back to zero
+#[derive(Debug, Clone)]
+pub struct VelocityTracker {
+    pub window: Duration,
+    samples: VecDeque<(Instant, (f32, f32))>,
+}
+
+impl Default for VelocityTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VelocityTracker {
+    pub fn new() -> Self {
+        Self {
+            window: VELOCITY_WINDOW,
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn add(&mut self, time: Instant, position: (f32, f32)) {
+        self.samples.push_back((time, position));
+        self.trim(time);
+    }
+
+    fn trim(&mut self, now: Instant) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|(time, _)| now.saturating_duration_since(*time) > self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    // in position units per second, as of `now`
+    pub fn velocity(&self, now: Instant) -> (f32, f32) {
+        let recent: Vec<(f32, (f32, f32))> = self
+            .samples
+            .iter()
+            .filter(|(time, _)| now.saturating_duration_since(*time) <= self.window)
+            .map(|(time, position)| {
+                let age = now.saturating_duration_since(*time).as_secs_f32();
+                (-age, *position)
+            })
+            .collect();
+        if recent.len() < 2 {
+            return (0.0, 0.0);
+        }
+        let n = recent.len() as f32;
+        let mean_t = recent.iter().map(|(t, _)| t).sum::<f32>() / n;
+        let mean_x = recent.iter().map(|(_, p)| p.0).sum::<f32>() / n;
+        let mean_y = recent.iter().map(|(_, p)| p.1).sum::<f32>() / n;
+        let spread: f32 = recent.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+        if spread <= f32::EPSILON {
+            return (0.0, 0.0);
+        }
+        let slope = |mean: f32, axis: fn(&(f32, f32)) -> f32| {
+            recent
+                .iter()
+                .map(|(t, p)| (t - mean_t) * (axis(p) - mean))
+                .sum::<f32>()
+                / spread
+        };
+        (slope(mean_x, |p| p.0), slope(mean_y, |p| p.1))
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    io,
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::{Value, json};
+use tungstenite::{Message, WebSocket};
+
+use crate::{
+    builder::Node,
+    layout::{Primative, UI},
+    theme::{from_hex, to_hex},
+};
+
+// how often the tree is sent. a browser doesn't need every frame
+const SNAPSHOT_INTERVAL: Duration = Duration::from_millis(250);
+
+type Clients = Arc<Mutex<Vec<WebSocket<TcpStream>>>>;
+
+// a debug server for inspecting a running app from a browser, maybe on another
+// machine. every connected websocket gets the element tree with its computed
+// layout and some frame stats as json a few times a second, and can send
+// property edits back:
+//
+//     {"path": [0, 2], "property": "color", "value": "#ff8800"}
+//
+// `path` is child indices from the root, as in the snapshot. editable
+// properties are min_width, max_width, min_height, max_height (null for none),
+// color, opacity and translation ([x, y]). there's no authentication, so only
+// bind it somewhere the network is trusted
+pub struct Inspector {
+    clients: Clients,
+    last_snapshot: Option<Instant>,
+}
+
+impl Inspector {
+    // starts listening right away. connections are accepted on a thread of
+    // their own, everything else happens in `poll` on the ui thread
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        log::info!("inspector listening on ws://{}", listener.local_addr()?);
+        let clients = Clients::default();
+        let accepted = clients.clone();
+        thread::Builder::new()
+            .name("teacup inspector".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            log::warn!("inspector connection failed: {e}");
+                            continue;
+                        }
+                    };
+                    let peer = stream.peer_addr().ok();
+                    match tungstenite::accept(stream) {
+                        Ok(socket) => {
+                            if let Err(e) = socket.get_ref().set_nonblocking(true) {
+                                log::warn!("inspector connection failed: {e}");
+                                continue;
+                            }
+                            log::info!("inspector connected from {peer:?}");
+                            if let Ok(mut clients) = accepted.lock() {
+                                clients.push(socket);
+                            }
+                        }
+                        Err(e) => log::warn!("inspector handshake failed: {e}"),
+                    }
+                }
+            })?;
+        Ok(Self {
+            clients,
+            last_snapshot: None,
+        })
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().map_or(0, |clients| clients.len())
+    }
+
+    // applies whatever edits came in and sends a snapshot when one is due.
+    // called once a frame
+    pub fn poll(&mut self, ui: &mut UI) {
+        let Ok(mut clients) = self.clients.lock() else {
+            return;
+        };
+        if clients.is_empty() {
+            return;
+        }
+
+        let mut edits = Vec::new();
+        clients.retain_mut(|socket| {
+            loop {
+                match socket.read() {
+                    Ok(Message::Text(text)) => edits.push(text.to_string()),
+                    Ok(_) => {}
+                    Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {
+                        return true;
+                    }
+                    Err(_) => return false,
+                }
+            }
+        });
+        for edit in edits {
+            if let Err(e) = apply_edit(ui, &edit) {
+                log::warn!("inspector edit {edit:?} failed: {e}");
+            }
+        }
+
+        let now = ui.context.clock().now();
+        if self
+            .last_snapshot
+            .is_some_and(|last| now - last < SNAPSHOT_INTERVAL)
+        {
+            return;
+        }
+        self.last_snapshot = Some(now);
+        let snapshot = self.snapshot(ui).to_string();
+        clients.retain_mut(|socket| {
+            match socket.send(Message::text(snapshot.clone())) {
+                Ok(()) => true,
+                // the socket is full, it'll get the next one
+                Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => true,
+                Err(e) => {
+                    log::info!("inspector disconnected: {e}");
+                    false
+                }
+            }
+        });
+    }
+
+    fn snapshot(&self, ui: &mut UI) -> Value {
+        let clock = ui.context.clock();
+        let delta = clock.delta().as_secs_f64();
+        let root = match ui.root_item.lock() {
+            Ok(mut root) => root.as_primative().map(|root| node_json(root)),
+            Err(_) => None,
+        };
+        json!({
+            "tree": root,
+            "stats": {
+                "frame": clock.frame(),
+                "frame_ms": delta * 1000.0,
+                "fps": if delta > 0.0 { 1.0 / delta } else { 0.0 },
+                "layout_ms": ui.layout_time().as_secs_f64() * 1000.0,
+                "input_latency_ms": ui.stats.input_latency().map(|d| d.as_secs_f64() * 1000.0),
+                "input_latency_p95_ms": ui
+                    .stats
+                    .input_latency_percentile(0.95)
+                    .map(|d| d.as_secs_f64() * 1000.0),
+                "size": [ui.size.0, ui.size.1],
+            },
+        })
+    }
+}
+
+fn node_json(prim: &dyn Primative) -> Value {
+    let children: Vec<Value> = prim
+        .children()
+        .iter()
+        .filter_map(|child| child.lock().ok().map(|child| node_json(&*child)))
+        .collect();
+    json!({
+        "kind": prim.kind().rsplit("::").next().unwrap_or_default(),
+        "id": prim.id().map(|id| id.raw()),
+        "position": [prim.get_position().0, prim.get_position().1],
+        "size": [prim.get_width(), prim.get_height()],
+        "min_size": [prim.get_min_width(), prim.get_min_height()],
+        "max_size": [prim.get_max_width(), prim.get_max_height()],
+        "translation": [prim.translation().0, prim.translation().1],
+        "z_index": prim.z_index(),
+        "color": prim.color().map(to_hex),
+        "opacity": prim.opacity(),
+        "children": children,
+    })
+}
+
+fn apply_edit(ui: &mut UI, edit: &str) -> anyhow::Result<()> {
+    let edit: Value = serde_json::from_str(edit)?;
+    let path: Vec<usize> = edit["path"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("missing path"))?
+        .iter()
+        .map(|index| index.as_u64().map(|index| index as usize))
+        .collect::<Option<_>>()
+        .ok_or_else(|| anyhow::anyhow!("path should be child indices"))?;
+    let property = edit["property"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing property"))?;
+    let value = &edit["value"];
+
+    let root_item = ui.root_item.clone();
+    let mut root = root_item
+        .lock()
+        .map_err(|_| anyhow::anyhow!("the tree is poisoned"))?;
+    let root = root
+        .as_primative()
+        .ok_or_else(|| anyhow::anyhow!("the root can't be edited"))?;
+    match path.split_first() {
+        None => set_property(root, property, value)?,
+        Some((first, rest)) => {
+            let node = find(root.children(), *first, rest)?;
+            let mut prim = node
+                .lock()
+                .map_err(|_| anyhow::anyhow!("the node is poisoned"))?;
+            set_property(&mut *prim, property, value)?;
+        }
+    }
+    ui.mark_layout_dirty();
+    Ok(())
+}
+
+fn find(children: &[Node], index: usize, rest: &[usize]) -> anyhow::Result<Node> {
+    let node = children
+        .get(index)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("no child {index}"))?;
+    match rest.split_first() {
+        None => Ok(node),
+        Some((next, rest)) => {
+            let children = node
+                .lock()
+                .map_err(|_| anyhow::anyhow!("the node is poisoned"))?
+                .children()
+                .to_vec();
+            find(&children, *next, rest)
+        }
+    }
+}
+
+fn set_property(prim: &mut dyn Primative, property: &str, value: &Value) -> anyhow::Result<()> {
+    let int = || {
+        value
+            .as_i64()
+            .map(|value| value as i32)
+            .ok_or_else(|| anyhow::anyhow!("{property} takes a number"))
+    };
+    let optional_int = || {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            int().map(Some)
+        }
+    };
+    match property {
+        "min_width" => prim.set_min_width(int()?),
+        "max_width" => prim.set_max_width(optional_int()?),
+        "min_height" => prim.set_min_height(int()?),
+        "max_height" => prim.set_max_height(optional_int()?),
+        "opacity" => prim.set_opacity(
+            value
+                .as_f64()
+                .ok_or_else(|| anyhow::anyhow!("opacity takes a number"))? as f32,
+        ),
+        "color" => prim.set_color(
+            value
+                .as_str()
+                .and_then(from_hex)
+                .ok_or_else(|| anyhow::anyhow!("color takes a #rrggbb string"))?,
+        ),
+        "translation" => {
+            let pair = value
+                .as_array()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| Some((pair[0].as_i64()? as i32, pair[1].as_i64()? as i32)))
+                .ok_or_else(|| anyhow::anyhow!("translation takes [x, y]"))?;
+            prim.set_translation(pair);
+        }
+        property => anyhow::bail!("{property} can't be edited"),
+    }
+    prim.mark_dirty();
+    Ok(())
+}
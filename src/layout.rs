@@ -1,8 +1,10 @@
 #![allow(dead_code)]
 
 use std::{
-    ops::{DerefMut, Not},
+    any::Any,
+    ops::{DerefMut, Not, Range},
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use cgmath::Zero;
@@ -10,15 +12,52 @@ use log::{log, Level};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use tinycolors::srgb;
 
-use crate::renderer::mesh_builder::{make_ss_rectangle, Mesh};
+#[cfg(feature = "global-hotkeys")]
+use crate::global_hotkeys::GlobalHotkeys;
+#[cfg(feature = "inspector")]
+use crate::inspector::Inspector;
+use crate::{
+    animation::{Animator, Fade},
+    binding::{Binding, SyncBinding},
+    builder::{ElementBuilder, Node},
+    color_vision::ColorVision,
+    console::{self, ConsoleInput, DevConsole},
+    context::{self, UiContext},
+    dispatch::{self, CursorRequest, EventContext, EventHandler, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    floating::{self, Positioning},
+    input,
+    overlay::OverlayLayer,
+    platform::ScrollbarVisibility,
+    reconcile::{self, NodeId},
+    renderer::{
+        color::Color,
+        draw_list::{self, DrawList},
+        frame::Frame,
+        image::ImageData,
+        mesh_builder::{make_ss_rounded_rectangle, make_ss_rounded_ring, Mesh},
+        mesh_cache::{GeometryKey, MeshCache},
+        post::PostEffect,
+        text::Font,
+    },
+    shortcuts::ShortcutRegistry,
+    stats::UiStats,
+    text,
+    theme::Theme,
+    timers::TimerId,
+    widgets::{
+        eyedropper::Eyedropper,
+        scrollbar::{ScrollTrack, Scrollbar},
+        tooltip::Tooltip,
+    },
+};
 
 pub trait Container: Send {
     fn fit_sizing(&mut self);
     fn grow_sizing(&mut self);
     fn set_child_positions(&mut self);
 
-    fn draw(&self, render_pass: &mut wgpu::RenderPass, device: &wgpu::Device, size: (i32, i32));
-
     fn get_sizing(&self) -> &Sizing;
     fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode;
     fn as_primative(&mut self) -> Option<&mut dyn Primative> {
@@ -51,21 +90,249 @@ pub trait Primative: Send {
     fn get_position(&self) -> (i32, i32);
     fn set_position(&mut self, position: (i32, i32));
 
-    #[allow(unused_variables)]
-    fn draw_prim(
-        &self,
-        render_pass: &mut wgpu::RenderPass,
-        device: &wgpu::Device,
-        size: (i32, i32),
-    ) {
+    // drawn above siblings with a lower z index. siblings with the same index
+    // keep tree order
+    fn z_index(&self) -> i32 {
+        0
+    }
+
+    // an offset added to the laid out position, moving the whole subtree
+    fn translation(&self) -> (i32, i32) {
+        (0, 0)
+    }
+
+    fn set_translation(&mut self, _translation: (i32, i32)) {}
+
+    // whether the parent lays this out with its other children or leaves it
+    // floating
+    fn positioning(&self) -> Positioning {
+        Positioning::Flow
+    }
+
+    // the fill, for primitives that have one
+    fn color(&self) -> Option<srgb> {
+        None
+    }
+
+    fn set_color(&mut self, _color: srgb) {}
+
+    fn opacity(&self) -> f32 {
+        1.0
+    }
+
+    fn set_opacity(&mut self, _opacity: f32) {}
+
+    // the subtree events are routed through, see `UI::dispatch_event`
+    fn children(&self) -> &[Node] {
+        &[]
+    }
+
+    // called for every event that passes through on its way to or from its
+    // target. `cx.phase()` says which way it's going
+    fn on_event(&mut self, _cx: &mut EventContext) {}
+
+    // text for the ui's tooltip while the pointer rests at `point`, see
+    // `UI::tooltip`. the deepest element under the pointer with some wins
+    fn tooltip(&self, _point: (i32, i32)) -> Option<String> {
+        None
+    }
+
+    // the text scale changed, for widgets that size themselves from a font
+    // outside of layout. see `UiContext::text_scale`
+    fn text_scale_changed(&mut self) {}
+
+    // hit test against the laid out bounds, in layout pixels
+    fn contains(&self, point: (i32, i32)) -> bool {
+        let (x, y) = self.get_position();
+        point.0 >= x
+            && point.0 < x + self.get_width()
+            && point.1 >= y
+            && point.1 < y + self.get_height()
     }
 
     fn get_mesh(&self, size: (i32, i32)) -> Mesh;
 
+    // adds what this draws to `list`, children included. the mesh and then the
+    // children in z order, so only primitives that draw some other way need
+    // their own. every backend draws from the list, the gpu included
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.get_mesh(size));
+        draw_list::record_children(self.children(), list, size);
+    }
+
     fn as_container(&mut self) -> Option<&mut dyn Container> {
         None
     }
+
+    // who this is across rebuilds, see `UI::rebuild`. without one a node is
+    // matched by its type and place among its siblings
+    fn id(&self) -> Option<NodeId> {
+        None
+    }
+
+    // only nodes of the same type are matched when reconciling
+    fn kind(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    // for `adopt_state` to get at the concrete type of the node it replaces
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
+
+    // called on a freshly built node with the one it replaces, to take over
+    // state the description doesn't set, like scroll offsets or a text cursor.
+    // children are handled separately
+    fn adopt_state(&mut self, _previous: &mut dyn Primative) {}
+
+    // whether this or anything under it changed in a way that needs another
+    // layout pass. primitives that don't track it are laid out every frame
+    fn is_dirty(&self) -> bool {
+        true
+    }
+
+    fn mark_dirty(&mut self) {}
+
+    // called on the whole tree right before a layout pass, so anything marked
+    // during the pass (like a running animation) stays dirty for the next one
+    fn clear_dirty(&mut self) {}
+}
+
+// implements the sizing and positioning half of `Primative` by forwarding to a
+// field that already implements it, so widgets can wrap a `Rectangle`. the
+// `sizing` form leaves out dirty tracking for widgets with layout state of
+// their own
+macro_rules! delegate_primative {
+    ($field:ident) => {
+        $crate::layout::delegate_primative!($field, sizing);
+
+        fn is_dirty(&self) -> bool {
+            self.$field.is_dirty()
+        }
+
+        fn mark_dirty(&mut self) {
+            self.$field.mark_dirty();
+        }
+
+        fn clear_dirty(&mut self) {
+            self.$field.clear_dirty();
+        }
+    };
+    ($field:ident, sizing) => {
+        fn get_width(&self) -> i32 {
+            self.$field.get_width()
+        }
+
+        fn get_min_width(&self) -> i32 {
+            self.$field.get_min_width()
+        }
+
+        fn get_max_width(&self) -> Option<i32> {
+            self.$field.get_max_width()
+        }
+
+        fn set_width(&mut self, width: i32) {
+            self.$field.set_width(width);
+        }
+
+        fn set_min_width(&mut self, width: i32) {
+            self.$field.set_min_width(width);
+        }
+
+        fn set_max_width(&mut self, width: Option<i32>) {
+            self.$field.set_max_width(width);
+        }
+
+        fn get_height(&self) -> i32 {
+            self.$field.get_height()
+        }
+
+        fn get_min_height(&self) -> i32 {
+            self.$field.get_min_height()
+        }
+
+        fn get_max_height(&self) -> Option<i32> {
+            self.$field.get_max_height()
+        }
+
+        fn set_height(&mut self, height: i32) {
+            self.$field.set_height(height);
+        }
+
+        fn set_min_height(&mut self, height: i32) {
+            self.$field.set_min_height(height);
+        }
+
+        fn set_max_height(&mut self, height: Option<i32>) {
+            self.$field.set_max_height(height);
+        }
+
+        fn get_size_along_axis(&self, axis: $crate::layout::Axis) -> i32 {
+            self.$field.get_size_along_axis(axis)
+        }
+
+        fn set_size_along_axis(&mut self, axis: $crate::layout::Axis, size: i32) {
+            self.$field.set_size_along_axis(axis, size);
+        }
+
+        fn get_min_along_axis(&self, axis: $crate::layout::Axis) -> i32 {
+            self.$field.get_min_along_axis(axis)
+        }
+
+        fn get_max_along_axis(&self, axis: $crate::layout::Axis) -> Option<i32> {
+            self.$field.get_max_along_axis(axis)
+        }
+
+        fn get_position(&self) -> (i32, i32) {
+            self.$field.get_position()
+        }
+
+        fn set_position(&mut self, position: (i32, i32)) {
+            self.$field.set_position(position);
+        }
+
+        fn z_index(&self) -> i32 {
+            self.$field.z_index()
+        }
+
+        fn translation(&self) -> (i32, i32) {
+            self.$field.translation()
+        }
+
+        fn set_translation(&mut self, translation: (i32, i32)) {
+            self.$field.set_translation(translation);
+        }
+
+        fn positioning(&self) -> $crate::floating::Positioning {
+            self.$field.positioning()
+        }
+
+        fn color(&self) -> Option<tinycolors::srgb> {
+            self.$field.color()
+        }
+
+        fn set_color(&mut self, color: tinycolors::srgb) {
+            self.$field.set_color(color);
+        }
+
+        fn opacity(&self) -> f32 {
+            self.$field.opacity()
+        }
+
+        fn set_opacity(&mut self, opacity: f32) {
+            self.$field.set_opacity(opacity);
+        }
+
+        fn children(&self) -> &[$crate::builder::Node] {
+            self.$field.children()
+        }
+
+        fn id(&self) -> Option<$crate::reconcile::NodeId> {
+            self.$field.id()
+        }
+    };
 }
+pub(crate) use delegate_primative;
 
 #[derive(Debug, Default)]
 pub enum SizingMode {
@@ -100,7 +367,32 @@ pub enum LayoutMode {
     LeftToRight,
 }
 
-#[derive(Debug, Clone, Copy)]
+// where children sit along the layout direction when they don't fill it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMain {
+    #[default]
+    Start,
+    Center,
+    End,
+    // first and last child touch the padding, the rest of the space goes
+    // between children
+    SpaceBetween,
+    // equal space on both sides of every child
+    SpaceAround,
+}
+
+// where children sit across the layout direction
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlignCross {
+    #[default]
+    Start,
+    Center,
+    End,
+    // sizes every child to the full cross size, up to its max
+    Stretch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Axis {
     Horizontal,
     Vertical,
@@ -140,13 +432,6 @@ impl Container for TCContainer {
         )
     }
 
-    fn draw(&self, _render_pass: &mut wgpu::RenderPass, _device: &wgpu::Device, _size: (i32, i32)) {
-        log!(
-            Level::Error,
-            "TCContainer can't be drawn as it is just a temp struct. replace with a proper container"
-        )
-    }
-
     fn get_sizing(&self) -> &Sizing {
         log!(
             Level::Error,
@@ -164,31 +449,413 @@ impl Container for TCContainer {
     }
 }
 
+// layout pixels per point. sizes in the tree are written against this
+// density, so they come out the same physical size on every display
+pub const LAYOUT_SCALE: i32 = 2;
+
 pub struct UI {
     pub background_color: srgb,
+    // only shows through when the window was created transparent
+    pub background_alpha: f32,
     pub size: (i32, i32),
+    scale_factor: f32,
     pub root_item: Arc<Mutex<dyn Container>>,
+    pub eyedropper: Eyedropper,
+    pub context: UiContext,
+    pub shortcuts: ShortcutRegistry,
+    // full screen effects applied after the ui is drawn, in order
+    pub post_effects: Vec<PostEffect>,
+    // debug mode that shows the whole window as it looks with a color vision
+    // deficiency. runs after `post_effects`
+    pub color_vision: Option<ColorVision>,
+    // popups and the like, drawn above `root_item`
+    pub overlays: OverlayLayer,
+    // shows `Primative::tooltip` for whatever the pointer rests on, above the
+    // overlays. the event loop sets one up with the system font
+    pub tooltip: Option<Tooltip>,
+    // wakes the loop when the tooltip's delay is up
+    tooltip_timer: Option<TimerId>,
+    pub console: DevConsole,
+    // outlines every element, toggled from the console
+    pub debug_bounds: bool,
+    // frame time and input latency in the corner, toggled from the console
+    pub debug_stats: bool,
+    pub stats: UiStats,
+    pub animator: Animator,
+    bindings: Vec<Box<dyn SyncBinding>>,
+    // where the pointer was last pressed. events without a position, like
+    // keys, are routed to whatever is there
+    pointer_focus: Option<(i32, i32)>,
+    // where the pointer was last seen, for the wheel
+    pointer: Option<(i32, i32)>,
+    // while a button is held, moves and the release go to whatever was
+    // pressed, so drags keep working once the pointer leaves it
+    pointer_down: bool,
+    // what the pointer was last over and last pressed, see `Phase::Exit`
+    pointer_paths: dispatch::PointerPaths,
+    #[cfg(feature = "global-hotkeys")]
+    pub global_hotkeys: Option<GlobalHotkeys>,
+    #[cfg(feature = "inspector")]
+    pub inspector: Option<Inspector>,
+    layout_dirty: bool,
+    // how long the last layout pass took, zero when it was skipped
+    layout_time: Duration,
+    resize_debounce: Option<Duration>,
+    // a size waiting out `resize_debounce`, and when it came in
+    pending_size: Option<((i32, i32), Instant)>,
 }
 impl Default for UI {
     fn default() -> Self {
         Self {
             root_item: Arc::new(Mutex::new(TCContainer {})),
             background_color: Default::default(),
+            background_alpha: 1.0,
             size: Default::default(),
+            scale_factor: 1.0,
+            eyedropper: Default::default(),
+            context: Default::default(),
+            shortcuts: Default::default(),
+            post_effects: Vec::new(),
+            color_vision: None,
+            overlays: OverlayLayer::default(),
+            tooltip: None,
+            tooltip_timer: None,
+            console: DevConsole::default(),
+            debug_bounds: false,
+            debug_stats: false,
+            stats: UiStats::default(),
+            animator: Animator::default(),
+            bindings: Vec::new(),
+            pointer_focus: None,
+            pointer: None,
+            pointer_down: false,
+            pointer_paths: Default::default(),
+            #[cfg(feature = "global-hotkeys")]
+            global_hotkeys: None,
+            #[cfg(feature = "inspector")]
+            inspector: None,
+            layout_dirty: true,
+            layout_time: Duration::ZERO,
+            resize_debounce: None,
+            pending_size: None,
         }
     }
 }
 
 impl UI {
+    pub fn set_root(&mut self, root: ElementBuilder) {
+        self.root_item = root.into_container();
+        self.layout_dirty = true;
+    }
+
+    // like `set_root`, but for a description rebuilt from app state every
+    // frame. nodes that match one in the current tree, by id or else by type
+    // and position, keep their scroll offsets, focus, cursors and the like
+    pub fn rebuild(&mut self, root: ElementBuilder) {
+        let next = root.into_container();
+        if let (Ok(mut previous), Ok(mut next)) = (self.root_item.lock(), next.lock())
+            && let (Some(previous), Some(next)) = (previous.as_primative(), next.as_primative())
+        {
+            reconcile::reconcile_node(previous, next);
+        }
+        self.root_item = next;
+        self.layout_dirty = true;
+    }
+
+    // forces a full layout next frame, for changes the tree can't see like
+    // swapping out `root_item`
+    pub fn mark_layout_dirty(&mut self) {
+        self.layout_dirty = true;
+    }
+
+    // resizes the viewport in points, keeping the existing tree and its state.
+    // layout space is `LAYOUT_SCALE` times that, and the next `compute_layout`
+    // lays the tree out against it. a minimized window reports a zero size,
+    // which is ignored so the layout survives restoring
+    pub fn set_size(&mut self, logical_size: (i32, i32)) {
+        let size = (logical_size.0 * LAYOUT_SCALE, logical_size.1 * LAYOUT_SCALE);
+        if size.0 <= 0 || size.1 <= 0 {
+            return;
+        }
+        if size == self.size {
+            self.pending_size = None;
+        } else if self.resize_debounce.is_some() && self.size != (0, 0) {
+            self.pending_size = Some((size, self.context.clock().now()));
+        } else {
+            self.size = size;
+            self.pending_size = None;
+            self.layout_dirty = true;
+        }
+    }
+
+    // keeps a node in step with a signal. the value is applied at the start
+    // of the next layout and again whenever the signal changes, marking just
+    // that node dirty
+    pub fn bind<T: Send + 'static>(&mut self, binding: Binding<T>) {
+        self.bindings.push(Box::new(binding));
+    }
+
+    fn sync_bindings(&mut self) {
+        let mut relayout = false;
+        self.bindings.retain_mut(|binding| match binding.sync() {
+            Some(untracked) => {
+                relayout |= untracked;
+                true
+            }
+            None => false,
+        });
+        self.layout_dirty |= relayout;
+    }
+
+    // lets every node know the text scale changed and lays the tree out again
+    // so text is measured at the new size
+    pub(crate) fn rescale_text(&mut self) {
+        fn visit(node: &Node) {
+            if let Ok(mut prim) = node.lock() {
+                prim.text_scale_changed();
+                for child in prim.children() {
+                    visit(child);
+                }
+            }
+        }
+        if let Ok(mut root) = self.root_item.lock()
+            && let Some(prim) = root.as_primative()
+        {
+            prim.text_scale_changed();
+            for child in prim.children() {
+                visit(child);
+            }
+        }
+        for node in self.overlays.nodes() {
+            visit(&node);
+        }
+        self.layout_dirty = true;
+    }
+
+    // holds a new size back until it's been stable for `debounce`, see
+    // `WindowOptions::resize_debounce`
+    pub fn set_resize_debounce(&mut self, debounce: Option<Duration>) {
+        self.resize_debounce = debounce;
+    }
+
+    // physical pixels per point on the display the window is on, from the os
+    // content scale. layout space doesn't depend on it, it only decides how
+    // many pixels each layout pixel covers when drawn
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor > 0.0 && scale_factor != self.scale_factor {
+            self.scale_factor = scale_factor;
+            self.layout_dirty = true;
+        }
+    }
+
+    // whether the next `compute_layout` has anything to do, e.g. because an
+    // animation is still running
+    pub fn needs_layout(&self) -> bool {
+        self.layout_dirty
+            || self.pending_size.is_some()
+            || self.bindings.iter().any(|binding| binding.is_stale())
+            || !self.animator.is_idle()
+            || self.overlays.is_dirty()
+            || self.root_item.lock().is_ok_and(|mut container| {
+                container.as_primative().is_some_and(|root| root.is_dirty())
+            })
+    }
+
+    // gives the dev console first look at input. returns whether it took the
+    // event, in which case it shouldn't go anywhere else
+    pub fn console_event(&mut self, event: &Event) -> bool {
+        match self.console.handle_event(event) {
+            ConsoleInput::Ignored => false,
+            ConsoleInput::Consumed => true,
+            ConsoleInput::Run(line) => {
+                console::run(self, &line);
+                self.layout_dirty = true;
+                true
+            }
+        }
+    }
+
+    // what drawing the ui puts on screen, as a list any backend can play back.
+    // leaves out the console and debug outlines
+    pub fn draw_list(&self) -> DrawList {
+        self.record_at((self.size.0 / LAYOUT_SCALE, self.size.1 / LAYOUT_SCALE))
+    }
+
+    // `draw_list` with meshes built against `size`, the logical size the frame
+    // is drawn at
+    fn record_at(&self, size: (i32, i32)) -> DrawList {
+        let mut list = DrawList::new((size.0 * LAYOUT_SCALE, size.1 * LAYOUT_SCALE));
+        list.clock = self.context.clock();
+        list.window_focused = self.context.window_focused();
+        if let Ok(mut root) = self.root_item.lock()
+            && let Some(root) = root.as_primative()
+        {
+            root.record(&mut list, size);
+        }
+        draw_list::record_floating(&self.floating_nodes(), &mut list, size);
+        self.overlays.record(&mut list, size);
+        if let Some(tooltip) = &self.tooltip {
+            tooltip.record(&mut list, size, self.context.clock().now());
+        }
+        list
+    }
+
+    // plays the ui's draw list back with the gpu, then the dev tools on top
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass, frame: &mut Frame, size: (i32, i32)) {
+        self.record_at(size).replay(render_pass, frame);
+        if self.debug_bounds
+            && let Ok(mut root) = self.root_item.lock()
+            && let Some(root) = root.as_primative()
+        {
+            console::draw_bounds(root, render_pass, frame, size);
+        }
+        if self.debug_stats {
+            self.console
+                .draw_stats(&self.stats, render_pass, frame, size);
+        }
+        self.console.draw(render_pass, frame, size);
+    }
+
+    // floating elements anywhere in the page
+    fn floating_nodes(&self) -> Vec<Node> {
+        match self.root_item.lock() {
+            Ok(mut root) => root
+                .as_primative()
+                .map(|root| floating::floating_nodes(root.children()))
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // routes `event` through the tree: down from the root to the element under
+    // the pointer, then back up. overlays are tried before the page, then
+    // floating elements. returns true when a handler stopped it
+    pub fn dispatch_event(&mut self, event: &Event) -> bool {
+        let point = match event {
+            Event::PointerMoved { position } => {
+                self.pointer = Some(*position);
+                if self.pointer_down {
+                    self.pointer_focus
+                } else {
+                    Some(*position)
+                }
+            }
+            Event::PointerPressed { position, .. } => {
+                self.pointer = Some(*position);
+                self.pointer_down = true;
+                self.pointer_focus = Some(*position);
+                Some(*position)
+            }
+            Event::PointerReleased { position, .. } => {
+                self.pointer = Some(*position);
+                if std::mem::take(&mut self.pointer_down) {
+                    self.pointer_focus
+                } else {
+                    Some(*position)
+                }
+            }
+            Event::PointerLeft => {
+                self.pointer = None;
+                self.pointer_focus
+            }
+            Event::Scroll { .. } => self.pointer,
+            _ => self.pointer_focus,
+        };
+        let overlay = point.and_then(|point| {
+            self.overlays
+                .at(point)
+                .and_then(|id| self.overlays.get(id))
+                .map(|overlay| overlay.node.clone())
+                .or_else(|| floating::at(&self.floating_nodes(), point))
+        });
+        let mut cursor = None;
+        let handled = dispatch::dispatch(
+            self.root_item.clone(),
+            overlay,
+            point,
+            event,
+            &mut self.pointer_paths,
+            &mut cursor,
+        );
+        match cursor {
+            Some(CursorRequest::Capture { raw_motion }) => self.context.capture_cursor(raw_motion),
+            Some(CursorRequest::Release) => self.context.release_cursor(),
+            None => {}
+        }
+        self.update_tooltip(event);
+        handled
+    }
+
+    // follows the pointer with the tooltip, hiding it once anything else
+    // happens. held buttons hide it too, so drags don't drag it along
+    fn update_tooltip(&mut self, event: &Event) {
+        let Some(tooltip) = &mut self.tooltip else {
+            return;
+        };
+        let now = self.context.clock().now();
+        let (text, point) = match event {
+            Event::PointerMoved { position } if !self.pointer_down => {
+                (self.pointer_paths.tooltip(*position), *position)
+            }
+            Event::PointerMoved { position }
+            | Event::PointerPressed { position, .. }
+            | Event::PointerReleased { position, .. } => (None, *position),
+            Event::PointerLeft | Event::KeyPressed { .. } | Event::Scroll { .. } => (None, (0, 0)),
+            _ => return,
+        };
+        if !tooltip.hover(text, point, now) {
+            return;
+        }
+        if let Some(timer) = self.tooltip_timer.take() {
+            self.context.clear_timer(timer);
+        }
+        if tooltip.is_active() {
+            self.tooltip_timer = Some(self.context.set_timeout(tooltip.delay, |_| {}));
+        }
+        self.layout_dirty = true;
+    }
+
+    // skips the layout passes when nothing in the tree is dirty
     pub fn compute_layout(&mut self) {
+        self.sync_bindings();
+        if let Some((size, since)) = self.pending_size
+            && self
+                .resize_debounce
+                .is_none_or(|debounce| self.context.clock().now() - since >= debounce)
+        {
+            self.size = size;
+            self.pending_size = None;
+            self.layout_dirty = true;
+        }
+        let layout_dirty = std::mem::take(&mut self.layout_dirty);
+        self.overlays.compute_layout(self.size, layout_dirty);
+        self.layout_time = Duration::ZERO;
         if let Ok(mut container) = self.root_item.lock() {
+            match container.as_primative() {
+                Some(root) if !layout_dirty && !root.is_dirty() => return,
+                Some(root) => root.clear_dirty(),
+                None => {}
+            }
+            let start = Instant::now();
             container.fit_sizing();
             self.grow_root(container.deref_mut());
             container.grow_sizing();
             container.set_child_positions();
+            if let Some(root) = container.as_primative() {
+                floating::resolve(root, self.size);
+            }
+            self.layout_time = start.elapsed();
         }
     }
 
+    pub fn layout_time(&self) -> Duration {
+        self.layout_time
+    }
+
     fn grow_root(&self, root: &mut dyn Container) {
         if let SizingMode::Grow = root.get_sizing().width {
             if let Some(prim) = root.as_primative() {
@@ -223,12 +890,6 @@ impl Container for UI {
         }
     }
 
-    fn draw(&self, render_pass: &mut wgpu::RenderPass, device: &wgpu::Device, size: (i32, i32)) {
-        if let Ok(root) = self.root_item.lock() {
-            root.draw(render_pass, device, size);
-        }
-    }
-
     fn get_sizing(&self) -> &Sizing {
         &Sizing::GROW
     }
@@ -238,7 +899,28 @@ impl Container for UI {
     }
 }
 
-#[derive(Default)]
+// whether a primitive needs laying out again. starts dirty so new elements are
+// always laid out once. code that changes layout fields directly, like
+// `children` or `padding`, marks it so the change is picked up
+#[derive(Debug, Default)]
+pub struct DirtyFlag {
+    clean: bool,
+}
+
+impl DirtyFlag {
+    pub fn is_dirty(&self) -> bool {
+        !self.clean
+    }
+
+    pub fn mark(&mut self) {
+        self.clean = false;
+    }
+
+    pub fn clear(&mut self) {
+        self.clean = true;
+    }
+}
+
 pub struct Rectangle {
     pub width: i32,
     pub height: i32,
@@ -251,57 +933,486 @@ pub struct Rectangle {
     pub sizing: Sizing,
     pub padding: i32,
     pub child_gap: i32,
-    pub color: srgb,
+    // flows children onto a new line once they run past the main axis, like
+    // flex-wrap. lines are child_gap apart too
+    pub wrap: bool,
+    pub align_main: AlignMain,
+    pub align_cross: AlignCross,
+    // a solid color or a gradient across the rect
+    pub fill: Fill,
+    // rounds all four corners, in layout pixels
+    pub corner_radius: f32,
+    // stroke drawn inside the edge. 0 for none
+    pub border_width: i32,
+    pub border_color: srgb,
+    // lays children out inside the border instead of underneath it
+    pub border_in_padding: bool,
+    // draws the fill this many times brighter than sdr white on hdr output
+    pub brightness: Option<f32>,
+    // of the fill and border, from 0 to 1. children keep their own
+    pub opacity: f32,
+    // a custom shader registered on the app, by name
+    pub shader: Option<String>,
+    pub z_index: i32,
+    // added to the position layout picks, see Primative::translation
+    pub translation: (i32, i32),
+    pub positioning: Positioning,
+    // cuts children off at the edge instead of letting them draw past it.
+    // nested clips only show where they overlap
+    pub clip_children: bool,
+    pub event_handler: Option<EventHandler>,
+    // matched on by `UI::rebuild`. the builders fill it in from where they
+    // were called
+    pub id: Option<NodeId>,
     pub children: Vec<Arc<Mutex<dyn Primative>>>,
+    pub mesh_cache: MeshCache,
+    pub dirty: DirtyFlag,
 }
 
-impl Primative for Rectangle {
-    fn get_width(&self) -> i32 {
-        self.width
+impl Default for Rectangle {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            min_width: 0,
+            min_height: 0,
+            max_width: None,
+            max_height: None,
+            position: (0, 0),
+            layout_mode: LayoutMode::default(),
+            sizing: Sizing::default(),
+            padding: 0,
+            child_gap: 0,
+            wrap: false,
+            align_main: AlignMain::default(),
+            align_cross: AlignCross::default(),
+            fill: Fill::default(),
+            corner_radius: 0.0,
+            border_width: 0,
+            border_color: srgb::default(),
+            border_in_padding: false,
+            brightness: None,
+            opacity: 1.0,
+            shader: None,
+            z_index: 0,
+            translation: (0, 0),
+            positioning: Positioning::Flow,
+            clip_children: false,
+            event_handler: None,
+            id: None,
+            children: Vec::new(),
+            mesh_cache: MeshCache::default(),
+            dirty: DirtyFlag::default(),
+        }
     }
+}
 
-    fn get_min_width(&self) -> i32 {
-        self.min_width
+impl Rectangle {
+    fn geometry_key(&self, size: (i32, i32)) -> GeometryKey {
+        GeometryKey::new(
+            self.position,
+            (self.width, self.height),
+            self.fill_color(),
+            size,
+        )
+        .with_corner_radius(self.corner_radius)
+        .with_border(self.border_width, self.border_color)
+        .with_opacity(self.opacity)
+        .with_fill(self.fill.cache_key())
     }
 
-    fn get_max_width(&self) -> Option<i32> {
-        self.max_width
+    // padding plus the border when the border takes up layout space
+    pub fn content_padding(&self) -> i32 {
+        if self.border_in_padding {
+            self.padding + self.border_width.max(0)
+        } else {
+            self.padding
+        }
     }
 
-    fn set_width(&mut self, width: i32) {
-        self.width = width;
+    // the solid fill, or the first stop of a gradient
+    pub fn fill_color(&self) -> Color {
+        let color = Color::from(self.fill.color());
+        match self.brightness {
+            Some(brightness) => color.with_brightness(brightness),
+            None => color,
+        }
     }
 
-    fn set_min_width(&mut self, width: i32) {
-        self.min_width = width;
+    fn axis(&self) -> Axis {
+        match self.layout_mode {
+            LayoutMode::TopToBottom => Axis::Vertical,
+            LayoutMode::LeftToRight => Axis::Horizontal,
+        }
     }
 
-    fn set_max_width(&mut self, width: Option<i32>) {
-        self.max_width = width;
+    // the children that take part in layout, leaving out floating ones
+    fn flow_children(&self) -> Vec<Node> {
+        self.children
+            .iter()
+            .filter(|child| {
+                child
+                    .lock()
+                    .is_ok_and(|prim| !prim.positioning().is_floating())
+            })
+            .cloned()
+            .collect()
     }
 
-    fn get_height(&self) -> i32 {
-        self.height
+    fn floating_children(&self) -> Vec<Node> {
+        self.children
+            .iter()
+            .filter(|child| {
+                child
+                    .lock()
+                    .is_ok_and(|prim| prim.positioning().is_floating())
+            })
+            .cloned()
+            .collect()
     }
 
-    fn get_min_height(&self) -> i32 {
-        self.min_height
+    // floating children have nothing to grow into but the parent
+    fn grow_floating(&self, floating: &[Node]) {
+        let content = (
+            self.width - 2 * self.content_padding(),
+            self.height - 2 * self.content_padding(),
+        );
+        for child in floating {
+            if let Ok(mut prim) = child.lock() {
+                let grow = prim.as_container().map(|container| {
+                    let sizing = container.get_sizing();
+                    (
+                        matches!(sizing.width, SizingMode::Grow),
+                        matches!(sizing.height, SizingMode::Grow),
+                    )
+                });
+                let min = (prim.get_min_width(), prim.get_min_height());
+                if let Some((grow_width, grow_height)) = grow {
+                    if grow_width {
+                        prim.set_width(content.0.max(min.0));
+                    }
+                    if grow_height {
+                        prim.set_height(content.1.max(min.1));
+                    }
+                }
+            }
+        }
     }
 
-    fn get_max_height(&self) -> Option<i32> {
-        self.max_height
+    // puts floating children against the parent. ones anchored to another
+    // element stay here until `floating::resolve` finds it
+    fn place_floating(&self, floating: &[Node]) {
+        for child in floating {
+            if let Ok(mut prim) = child.lock()
+                && let Positioning::Floating { anchor, offset } = prim.positioning()
+            {
+                let size = (prim.get_width(), prim.get_height());
+                let (x, y) = anchor.place(self.position, (self.width, self.height), size);
+                prim.set_position((x + offset.0, y + offset.1));
+                if let Some(container) = prim.as_container() {
+                    container.set_child_positions();
+                }
+            }
+        }
     }
 
-    fn set_height(&mut self, height: i32) {
-        self.height = height;
+    // how much room the children have along the axis before wrapping, when fit
+    // sizing already knows it. otherwise the lines are only worked out once
+    // grow sizing has settled the length
+    fn wrap_limit(&self, axis: Axis) -> Option<i32> {
+        let limit = match *self.get_sizing_along_axis(axis) {
+            SizingMode::Fixed(size) => Some(size),
+            SizingMode::Fit | SizingMode::Grow => self.get_max_along_axis(axis),
+        }?;
+        Some(limit - 2 * self.content_padding())
     }
 
-    fn set_min_height(&mut self, height: i32) {
-        self.min_height = height;
+    // splits the children into lines that fit in `available` along the axis. a
+    // child too long for any line gets one to itself
+    fn wrap_lines(&self, children: &[Node], axis: Axis, available: i32) -> Vec<Range<usize>> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut used = 0;
+        for (i, child) in children.iter().enumerate() {
+            let size = child
+                .lock()
+                .map_or(0, |prim| prim.get_size_along_axis(axis));
+            if i == start {
+                used = size;
+            } else if used + self.child_gap + size > available {
+                lines.push(start..i);
+                start = i;
+                used = size;
+            } else {
+                used += self.child_gap + size;
+            }
+        }
+        if start < children.len() {
+            lines.push(start..children.len());
+        }
+        lines
+    }
+
+    // how long each line is along the axis and how thick it is across it
+    fn line_sizes(&self, children: &[Node], axis: Axis, lines: &[Range<usize>]) -> Vec<(i32, i32)> {
+        lines
+            .iter()
+            .map(|line| {
+                let mut main = self.child_gap * (line.len() as i32 - 1).max(0);
+                let mut cross = 0;
+                for child in &children[line.clone()] {
+                    if let Ok(prim) = child.lock() {
+                        main += prim.get_size_along_axis(axis);
+                        cross = cross.max(prim.get_size_along_axis(!axis));
+                    }
+                }
+                (main, cross)
+            })
+            .collect()
+    }
+
+    fn wrapped_cross_size(&self, line_sizes: &[(i32, i32)]) -> i32 {
+        line_sizes.iter().map(|(_, cross)| cross).sum::<i32>()
+            + self.child_gap * (line_sizes.len() as i32 - 1).max(0)
+    }
+
+    // grow sizing for a wrapping container. growing children share what's left
+    // of their own line and stretch to the thickest child in it, then a fit
+    // container takes on the thickness of all its lines now its length is known
+    fn grow_lines(&mut self, axis: Axis) {
+        let children = self.flow_children();
+        let available = self.get_size_along_axis(axis) - 2 * self.content_padding();
+        let lines = self.wrap_lines(&children, axis, available);
+
+        for (line, (main, _)) in lines.iter().zip(self.line_sizes(&children, axis, &lines)) {
+            let growing: Vec<&Node> = children[line.clone()]
+                .iter()
+                .filter(|child| {
+                    child.lock().is_ok_and(|mut prim| {
+                        prim.as_container().is_some_and(|container| {
+                            matches!(container.get_sizing_along_axis(axis), SizingMode::Grow)
+                        })
+                    })
+                })
+                .collect();
+            let remaining = available - main;
+            if growing.is_empty() || remaining <= 0 {
+                continue;
+            }
+            let share = remaining / growing.len() as i32;
+            for child in growing {
+                if let Ok(mut prim) = child.lock() {
+                    let mut size = prim.get_size_along_axis(axis) + share;
+                    if let Some(max) = prim.get_max_along_axis(axis) {
+                        size = size.min(max);
+                    }
+                    prim.set_size_along_axis(axis, size);
+                }
+            }
+        }
+
+        let stretch = self.align_cross == AlignCross::Stretch;
+        let line_sizes = self.line_sizes(&children, axis, &lines);
+        for (line, (_, cross)) in lines.iter().zip(&line_sizes) {
+            for child in &children[line.clone()] {
+                if let Ok(mut prim) = child.lock() {
+                    let grow = stretch
+                        || prim.as_container().is_some_and(|container| {
+                            matches!(container.get_sizing_along_axis(!axis), SizingMode::Grow)
+                        });
+                    if grow {
+                        let size = match prim.get_max_along_axis(!axis) {
+                            Some(max) => (*cross).min(max),
+                            None => *cross,
+                        };
+                        prim.set_size_along_axis(!axis, size);
+                    }
+                }
+            }
+        }
+
+        let wrapped = self.wrapped_cross_size(&line_sizes) + 2 * self.content_padding();
+        let size = match *self.get_sizing_along_axis(!axis) {
+            SizingMode::Fixed(size) => size,
+            SizingMode::Fit => wrapped,
+            SizingMode::Grow => wrapped.max(self.get_size_along_axis(!axis)),
+        };
+        let mut size = size.max(self.get_min_along_axis(!axis));
+        if let Some(max) = self.get_max_along_axis(!axis) {
+            size = size.min(max);
+        }
+        self.set_size_along_axis(!axis, size);
+    }
+
+    // the opposite of growing. when the children overflow by `overflow`, the
+    // biggest fit and grow children give up space first, evening out towards
+    // the next biggest, until everything fits or they're all at their minimums
+    fn shrink_children(&self, children: &[Node], axis: Axis, mut overflow: i32) {
+        let mut shrink_list: Vec<&Node> = children
+            .iter()
+            .filter(|child| {
+                child.lock().is_ok_and(|mut prim| {
+                    prim.get_size_along_axis(axis) > prim.get_min_along_axis(axis)
+                        && is_shrinkable(&mut *prim, axis)
+                })
+            })
+            .collect();
+
+        while overflow > 0 && !shrink_list.is_empty() {
+            let sizes: Vec<i32> = shrink_list
+                .iter()
+                .map(|child| {
+                    child
+                        .lock()
+                        .map_or(0, |prim| prim.get_size_along_axis(axis))
+                })
+                .collect();
+            let largest = sizes.iter().copied().max().unwrap_or(0);
+            let largest_count = sizes.iter().filter(|size| **size == largest).count() as i32;
+            let even_share = (overflow / largest_count).max(1);
+            let step = match sizes.iter().copied().filter(|size| *size < largest).max() {
+                Some(second_largest) => (largest - second_largest).min(even_share),
+                None => even_share,
+            };
+
+            for (child, size) in shrink_list.iter().zip(&sizes) {
+                if *size < largest || overflow <= 0 {
+                    continue;
+                }
+                if let Ok(mut prim) = child.lock() {
+                    let shrunk = (size - step.min(overflow)).max(prim.get_min_along_axis(axis));
+                    overflow -= size - shrunk;
+                    prim.set_size_along_axis(axis, shrunk);
+                }
+            }
+
+            shrink_list.retain(|child| {
+                child.lock().is_ok_and(|prim| {
+                    prim.get_size_along_axis(axis) > prim.get_min_along_axis(axis)
+                })
+            });
+        }
+    }
+
+    // where the first child goes along the axis and the extra space between
+    // children, for `free_space` left over among `count` of them
+    fn main_offsets(&self, free_space: i32, count: i32) -> (i32, i32) {
+        match self.align_main {
+            AlignMain::Start => (0, 0),
+            AlignMain::Center => (free_space / 2, 0),
+            AlignMain::End => (free_space, 0),
+            AlignMain::SpaceBetween if count > 1 => (0, free_space / (count - 1)),
+            AlignMain::SpaceBetween => (0, 0),
+            AlignMain::SpaceAround if count > 0 => (free_space / (count * 2), free_space / count),
+            AlignMain::SpaceAround => (0, 0),
+        }
+    }
+
+    fn cross_offset(&self, cross_space: i32, cross: i32) -> i32 {
+        match self.align_cross {
+            AlignCross::Start | AlignCross::Stretch => 0,
+            AlignCross::Center => (cross_space - cross) / 2,
+            AlignCross::End => cross_space - cross,
+        }
+    }
+
+    fn place_child(&self, child: &Node, axis: Axis, main_offset: i32, cross_offset: i32) {
+        let padding = self.content_padding();
+        let child_position = match axis {
+            Axis::Horizontal => (
+                self.position.0 + padding + main_offset,
+                self.position.1 + padding + cross_offset,
+            ),
+            Axis::Vertical => (
+                self.position.0 + padding + cross_offset,
+                self.position.1 + padding + main_offset,
+            ),
+        };
+        if let Ok(mut prim) = child.lock() {
+            prim.set_position(child_position);
+
+            if let Some(container) = prim.as_container() {
+                container.set_child_positions();
+            }
+        }
+    }
+
+    // the children, floating ones left out and the rest in z order, clipped
+    // to this when `clip_children` is set
+    pub(crate) fn record_children(&self, list: &mut DrawList, size: (i32, i32)) {
+        if self.clip_children {
+            list.push_clip(self.position, (self.width, self.height));
+        }
+        draw_list::record_children(&self.children, list, size);
+        if self.clip_children {
+            list.pop_clip();
+        }
+    }
+}
+
+impl Primative for Rectangle {
+    fn get_width(&self) -> i32 {
+        self.width
+    }
+
+    fn id(&self) -> Option<NodeId> {
+        self.id
+    }
+
+    fn get_min_width(&self) -> i32 {
+        self.min_width
+    }
+
+    fn get_max_width(&self) -> Option<i32> {
+        self.max_width
+    }
+
+    fn set_width(&mut self, width: i32) {
+        self.width = width;
+    }
+
+    fn set_min_width(&mut self, width: i32) {
+        if self.min_width != width {
+            self.min_width = width;
+            self.dirty.mark();
+        }
+    }
+
+    fn set_max_width(&mut self, width: Option<i32>) {
+        if self.max_width != width {
+            self.max_width = width;
+            self.dirty.mark();
+        }
+    }
+
+    fn get_height(&self) -> i32 {
+        self.height
+    }
+
+    fn get_min_height(&self) -> i32 {
+        self.min_height
+    }
+
+    fn get_max_height(&self) -> Option<i32> {
+        self.max_height
+    }
+
+    fn set_height(&mut self, height: i32) {
+        self.height = height;
+    }
+
+    fn set_min_height(&mut self, height: i32) {
+        if self.min_height != height {
+            self.min_height = height;
+            self.dirty.mark();
+        }
     }
 
     fn set_max_height(&mut self, height: Option<i32>) {
-        self.max_height = height;
+        if self.max_height != height {
+            self.max_height = height;
+            self.dirty.mark();
+        }
     }
 
     fn get_size_along_axis(&self, axis: Axis) -> i32 {
@@ -337,39 +1448,129 @@ impl Primative for Rectangle {
     }
 
     fn set_position(&mut self, position: (i32, i32)) {
-        self.position = position;
+        self.position = (
+            position.0 + self.translation.0,
+            position.1 + self.translation.1,
+        );
+    }
+
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    fn translation(&self) -> (i32, i32) {
+        self.translation
+    }
+
+    fn set_translation(&mut self, translation: (i32, i32)) {
+        if self.translation != translation {
+            self.translation = translation;
+            self.mark_dirty();
+        }
+    }
+
+    fn positioning(&self) -> Positioning {
+        self.positioning
+    }
+
+    fn color(&self) -> Option<srgb> {
+        Some(self.fill.color())
+    }
+
+    fn set_color(&mut self, color: srgb) {
+        self.fill = Fill::Solid(color);
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        if let Some(handler) = &mut self.event_handler {
+            handler(cx);
+        }
     }
 
     fn as_container(&mut self) -> std::option::Option<&mut dyn Container> {
         Some(self as &mut dyn Container)
     }
 
-    fn draw_prim(
-        &self,
-        render_pass: &mut wgpu::RenderPass,
-        device: &wgpu::Device,
-        size: (i32, i32),
-    ) {
-        let mut mesh = make_ss_rectangle(
-            self.position.0,
-            self.position.1,
-            self.width,
-            self.height,
-            self.color,
-            size,
+    fn is_dirty(&self) -> bool {
+        self.dirty.is_dirty()
+            || self
+                .children
+                .iter()
+                .any(|child| child.lock().map_or(true, |prim| prim.is_dirty()))
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty.mark();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.dirty.clear();
+        for child in &self.children {
+            if let Ok(mut prim) = child.lock() {
+                prim.clear_dirty();
+            }
+        }
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        if self.shader.is_some() {
+            list.pipeline(self.shader.as_deref());
+        }
+        list.mesh(
+            self.mesh_cache
+                .get(self.geometry_key(size), || self.get_mesh(size)),
         );
-        mesh.draw(render_pass, device);
+        if self.shader.is_some() {
+            list.pipeline(None);
+        }
+        self.record_children(list, size);
     }
 
     fn get_mesh(&self, size: (i32, i32)) -> Mesh {
-        make_ss_rectangle(
+        let mut mesh = make_ss_rounded_rectangle(
             self.position.0,
             self.position.1,
             self.width,
             self.height,
-            self.color,
+            self.corner_radius,
+            self.fill_color(),
             size,
-        )
+        );
+        self.fill.paint(
+            &mut mesh,
+            (self.position.0 as f32, self.position.1 as f32),
+            (self.width as f32, self.height as f32),
+            self.brightness,
+            size,
+        );
+        if self.border_width > 0 {
+            mesh.append(make_ss_rounded_ring(
+                self.position.0,
+                self.position.1,
+                self.width,
+                self.height,
+                self.corner_radius,
+                self.border_width,
+                self.border_color,
+                size,
+            ));
+        }
+        if self.opacity < 1.0 {
+            mesh.multiply_alpha(self.opacity.max(0.0));
+        }
+        mesh
     }
 }
 
@@ -379,7 +1580,7 @@ impl Container for Rectangle {
             LayoutMode::TopToBottom => Axis::Vertical,
             LayoutMode::LeftToRight => Axis::Horizontal,
         };
-        let mut axis_size: i32 = 2 * self.padding;
+        let mut axis_size: i32 = 2 * self.content_padding();
         let mut off_axis_size: i32 = 0;
         let mut first = false;
         let mut gap = 0;
@@ -393,6 +1594,10 @@ impl Container for Rectangle {
                     let size = prim.get_min_along_axis(!axis);
                     prim.set_size_along_axis(!axis, size);
                 }
+                // sized like any other child, but takes up no room
+                if prim.positioning().is_floating() {
+                    continue;
+                }
 
                 axis_size += prim.get_size_along_axis(axis) + gap;
                 off_axis_size = off_axis_size.max(prim.get_size_along_axis(!axis));
@@ -404,7 +1609,23 @@ impl Container for Rectangle {
             }
         }
 
-        off_axis_size += 2 * self.padding;
+        // with a known length to wrap in, the container is as long as its
+        // longest line and as thick as all of them together
+        if self.wrap
+            && let Some(available) = self.wrap_limit(axis)
+        {
+            let children = self.flow_children();
+            let line_sizes = self.line_sizes(
+                &children,
+                axis,
+                &self.wrap_lines(&children, axis, available),
+            );
+            axis_size = 2 * self.content_padding()
+                + line_sizes.iter().map(|(main, _)| *main).max().unwrap_or(0);
+            off_axis_size = self.wrapped_cross_size(&line_sizes);
+        }
+
+        off_axis_size += 2 * self.content_padding();
         match self.layout_mode {
             LayoutMode::TopToBottom => {
                 match self.sizing.width {
@@ -465,8 +1686,22 @@ impl Container for Rectangle {
             LayoutMode::LeftToRight => Axis::Horizontal,
         };
 
-        let used_space: i32 = self
-            .children
+        self.grow_floating(&self.floating_children());
+
+        if self.wrap {
+            self.grow_lines(axis);
+            for child in &self.children {
+                if let Ok(mut prim) = child.lock()
+                    && let Some(container) = prim.as_container()
+                {
+                    container.grow_sizing();
+                }
+            }
+            return;
+        }
+
+        let children = self.flow_children();
+        let used_space: i32 = children
             .par_iter()
             .map(|prim| {
                 if let Ok(prim) = prim.lock() {
@@ -477,12 +1712,11 @@ impl Container for Rectangle {
             })
             .sum();
         let mut remaining_space = self.get_size_along_axis(axis)
-            - (self.padding * 2)
-            - (self.child_gap * ((self.children.len() as i32) - 1))
+            - (self.content_padding() * 2)
+            - (self.child_gap * ((children.len() as i32) - 1))
             - used_space;
 
-        let mut grow_list: Vec<Arc<Mutex<dyn Primative>>> = self
-            .children
+        let mut grow_list: Vec<Arc<Mutex<dyn Primative>>> = children
             .par_iter()
             .filter(|prim| {
                 if let Ok(mut prim) = prim.lock() {
@@ -588,8 +1822,7 @@ impl Container for Rectangle {
                     }
                 }
             }
-            let used_space: i32 = self
-                .children
+            let used_space: i32 = children
                 .par_iter()
                 .map(|prim| {
                     if let Ok(prim) = prim.lock() {
@@ -600,20 +1833,25 @@ impl Container for Rectangle {
                 })
                 .sum();
             remaining_space = self.get_size_along_axis(axis)
-                - (self.padding * 2)
-                - (self.child_gap * ((self.children.len() as i32) - 1).max(0))
+                - (self.content_padding() * 2)
+                - (self.child_gap * ((children.len() as i32) - 1).max(0))
                 - used_space;
         }
 
-        let grow_list: Vec<Arc<Mutex<dyn Primative>>> = self
-            .children
+        if remaining_space.is_negative() {
+            self.shrink_children(&children, axis, -remaining_space);
+        }
+
+        let stretch = self.align_cross == AlignCross::Stretch;
+        let grow_list: Vec<Arc<Mutex<dyn Primative>>> = children
             .par_iter()
             .filter(|prim| {
                 if let Ok(mut prim) = prim.lock() {
                     if let Some(container) = prim.as_container() {
-                        matches!(container.get_sizing_along_axis(!axis), SizingMode::Grow)
+                        stretch
+                            || matches!(container.get_sizing_along_axis(!axis), SizingMode::Grow)
                     } else {
-                        false
+                        stretch
                     }
                 } else {
                     false
@@ -622,11 +1860,27 @@ impl Container for Rectangle {
             .cloned()
             .collect();
 
-        let off_axis_size = self.get_size_along_axis(!axis) - (2 * self.padding);
+        let off_axis_size = self.get_size_along_axis(!axis) - (2 * self.content_padding());
 
         for child in grow_list {
             if let Ok(mut prim) = child.lock() {
-                prim.set_size_along_axis(!axis, off_axis_size);
+                let size = match prim.get_max_along_axis(!axis) {
+                    Some(max) if stretch => off_axis_size.min(max),
+                    _ => off_axis_size,
+                };
+                prim.set_size_along_axis(!axis, size);
+            }
+        }
+
+        // children thicker than the container come back down to it, as far as
+        // their minimums allow
+        for child in &children {
+            if let Ok(mut prim) = child.lock()
+                && prim.get_size_along_axis(!axis) > off_axis_size
+                && is_shrinkable(&mut *prim, !axis)
+            {
+                let size = off_axis_size.max(prim.get_min_along_axis(!axis));
+                prim.set_size_along_axis(!axis, size);
             }
         }
 
@@ -640,75 +1894,722 @@ impl Container for Rectangle {
     }
 
     fn set_child_positions(&mut self) {
-        match self.layout_mode {
-            LayoutMode::TopToBottom => {
-                let mut child_position = self.position;
-                child_position.0 += self.padding;
-                child_position.1 += self.padding;
-
-                for child in &self.children {
-                    if let Ok(mut prim) = child.lock() {
-                        prim.set_position(child_position);
-                        child_position.1 += prim.get_height() + self.child_gap;
+        self.place_floating(&self.floating_children());
+
+        let axis = self.axis();
+        let children = self.flow_children();
+        let sizes: Vec<(i32, i32)> = children
+            .iter()
+            .map(|child| {
+                if let Ok(prim) = child.lock() {
+                    (
+                        prim.get_size_along_axis(axis),
+                        prim.get_size_along_axis(!axis),
+                    )
+                } else {
+                    (0, 0)
+                }
+            })
+            .collect();
 
-                        if let Some(container) = prim.as_container() {
-                            container.set_child_positions();
-                        }
-                    }
+        if self.wrap {
+            let available = self.get_size_along_axis(axis) - (self.content_padding() * 2);
+            let mut line_offset = 0;
+            for line in self.wrap_lines(&children, axis, available) {
+                let line_sizes = &sizes[line.clone()];
+                let count = line.len() as i32;
+                let used_space = line_sizes.iter().map(|(main, _)| main).sum::<i32>()
+                    + self.child_gap * (count - 1).max(0);
+                let line_cross = line_sizes
+                    .iter()
+                    .map(|(_, cross)| *cross)
+                    .max()
+                    .unwrap_or(0);
+                let (mut main_offset, extra_gap) =
+                    self.main_offsets((available - used_space).max(0), count);
+                for (child, (main, cross)) in children[line].iter().zip(line_sizes) {
+                    let cross_offset = line_offset + self.cross_offset(line_cross, *cross);
+                    self.place_child(child, axis, main_offset, cross_offset);
+                    main_offset += main + self.child_gap + extra_gap;
                 }
+                line_offset += line_cross + self.child_gap;
             }
-            LayoutMode::LeftToRight => {
-                let mut child_position = self.position;
-                child_position.0 += self.padding;
-                child_position.1 += self.padding;
+            return;
+        }
 
-                for child in &self.children {
-                    if let Ok(mut prim) = child.lock() {
-                        prim.set_position(child_position);
-                        child_position.0 += prim.get_width() + self.child_gap;
+        let count = children.len() as i32;
+        let used_space: i32 =
+            sizes.iter().map(|(main, _)| main).sum::<i32>() + self.child_gap * (count - 1).max(0);
+        let free_space =
+            (self.get_size_along_axis(axis) - (self.content_padding() * 2) - used_space).max(0);
+        let cross_space = self.get_size_along_axis(!axis) - (self.content_padding() * 2);
 
-                        if let Some(container) = prim.as_container() {
-                            container.set_child_positions();
-                        }
-                    }
-                }
-            }
+        let (mut main_offset, extra_gap) = self.main_offsets(free_space, count);
+
+        for (child, (main, cross)) in children.iter().zip(sizes) {
+            let cross_offset = self.cross_offset(cross_space, cross);
+            self.place_child(child, axis, main_offset, cross_offset);
+            main_offset += main + self.child_gap + extra_gap;
         }
     }
 
-    fn draw(&self, render_pass: &mut wgpu::RenderPass, device: &wgpu::Device, size: (i32, i32)) {
-        let mut mesh = make_ss_rectangle(
-            self.position.0,
-            self.position.1,
-            self.width,
-            self.height,
+    fn get_sizing(&self) -> &Sizing {
+        &self.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        match axis {
+            Axis::Horizontal => &self.sizing.width,
+            Axis::Vertical => &self.sizing.height,
+        }
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+// containers sized by their content or their parent can be squeezed, passing
+// it on to their own children. fixed sizes keep theirs, as do leaves like text
+// and images, which have nothing to rearrange
+fn is_shrinkable(prim: &mut dyn Primative, axis: Axis) -> bool {
+    if prim.children().is_empty() {
+        return false;
+    }
+    prim.as_container().is_some_and(|container| {
+        matches!(
+            container.get_sizing_along_axis(axis),
+            SizingMode::Fit | SizingMode::Grow
+        )
+    })
+}
+
+// a run of text that sizes itself to fit its glyphs. lines are split on '\n'
+pub struct Text {
+    pub rect: Rectangle,
+    pub text: String,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub color: srgb,
+}
+
+impl Text {
+    pub fn new(text: &str, font: Arc<Font>, font_size: f32, color: srgb) -> Self {
+        Self {
+            rect: Rectangle::default(),
+            text: text.to_string(),
+            font,
+            font_size,
+            color,
+        }
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_string();
+            self.rect.mark_dirty();
+        }
+    }
+
+    pub fn set_font_size(&mut self, font_size: f32) {
+        if self.font_size != font_size {
+            self.font_size = font_size;
+            self.rect.mark_dirty();
+        }
+    }
+
+    pub fn measure(&self) -> (i32, i32) {
+        let (width, height) = self.font.measure(&self.text, text::scaled(self.font_size));
+        (width.ceil() as i32, height.ceil() as i32)
+    }
+}
+
+impl Primative for Text {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, _size: (i32, i32)) {
+        let (x, y) = self.rect.position;
+        list.text(
+            &self.font,
+            &self.text,
+            text::scaled(self.font_size),
+            (x as f32, y as f32),
             self.color,
-            size,
         );
-        mesh.draw(render_pass, device);
+    }
 
-        for child in &self.children {
-            if let Ok(mut prim) = child.lock() {
-                if let Some(container) = prim.as_container() {
-                    container.draw(render_pass, device, size);
-                } else {
-                    prim.draw_prim(render_pass, device, size);
-                }
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    // glyphs are drawn from the atlas, not as colored geometry
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
+
+impl Container for Text {
+    fn fit_sizing(&mut self) {
+        let (width, height) = self.measure();
+        let rect = &mut self.rect;
+        rect.width = match rect.sizing.width {
+            SizingMode::Fixed(w) => w,
+            SizingMode::Fit | SizingMode::Grow => {
+                let w = width.max(rect.min_width);
+                rect.max_width.map_or(w, |max| w.min(max))
+            }
+        };
+        rect.height = match rect.sizing.height {
+            SizingMode::Fixed(h) => h,
+            SizingMode::Fit | SizingMode::Grow => {
+                let h = height.max(rect.min_height);
+                rect.max_height.map_or(h, |max| h.min(max))
+            }
+        };
+    }
+
+    fn grow_sizing(&mut self) {}
+
+    fn set_child_positions(&mut self) {}
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+// a picture that fits to its pixel size. fixing one axis scales the other to
+// keep the aspect ratio
+pub struct Image {
+    pub rect: Rectangle,
+    pub image: Arc<ImageData>,
+    // hdr brightness relative to sdr white, for photo and video viewers
+    pub brightness: Option<f32>,
+}
+
+impl Image {
+    pub fn new(image: Arc<ImageData>) -> Self {
+        Self {
+            rect: Rectangle::default(),
+            image,
+            brightness: None,
+        }
+    }
+
+    pub fn intrinsic_size(&self) -> (i32, i32) {
+        let (width, height) = self.image.size();
+        (width as i32, height as i32)
+    }
+}
+
+impl Primative for Image {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, _size: (i32, i32)) {
+        list.image(
+            &self.image,
+            self.rect.position,
+            (self.rect.width, self.rect.height),
+            self.brightness.unwrap_or(1.0),
+            1.0,
+        );
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    // drawn from its texture, not as colored geometry
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
+
+impl Container for Image {
+    fn fit_sizing(&mut self) {
+        let (width, height) = self.intrinsic_size();
+        let rect = &mut self.rect;
+        let fit = |size: i32, min: i32, max: Option<i32>| {
+            let size = size.max(min);
+            max.map_or(size, |max| size.min(max))
+        };
+        match (&rect.sizing.width, &rect.sizing.height) {
+            (SizingMode::Fixed(w), SizingMode::Fixed(h)) => {
+                rect.width = *w;
+                rect.height = *h;
+            }
+            (SizingMode::Fixed(w), _) => {
+                rect.width = *w;
+                let h = if width > 0 { w * height / width } else { 0 };
+                rect.height = fit(h, rect.min_height, rect.max_height);
+            }
+            (_, SizingMode::Fixed(h)) => {
+                rect.height = *h;
+                let w = if height > 0 { h * width / height } else { 0 };
+                rect.width = fit(w, rect.min_width, rect.max_width);
+            }
+            _ => {
+                rect.width = fit(width, rect.min_width, rect.max_width);
+                rect.height = fit(height, rect.min_height, rect.max_height);
             }
         }
     }
 
+    fn grow_sizing(&mut self) {}
+
+    fn set_child_positions(&mut self) {}
+
     fn get_sizing(&self) -> &Sizing {
-        &self.sizing
+        &self.rect.sizing
     }
 
     fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+// how far one line scrolls, in layout pixels. a wheel notch scrolls
+// `input::wheel_lines` of them
+pub const SCROLL_LINE: i32 = 40;
+
+// how long a wheel notch takes to glide to where it scrolls to
+const WHEEL_GLIDE: Duration = Duration::from_millis(120);
+
+// how long after its last delta a trackpad gesture stays with the container it
+// was scrolling, so momentum running into the end doesn't carry on into the
+// container around it
+const SCROLL_LATCH: Duration = Duration::from_millis(200);
+
+// a rectangle whose children may overflow it. children are laid out as usual,
+// shifted by `scroll_offset`, and clipped to the container's bounds. the wheel
+// and the scrollbar thumbs drawn over the content scroll it
+pub struct ScrollContainer {
+    pub rect: Rectangle,
+    pub scroll_offset: (i32, i32),
+    pub vertical: Scrollbar,
+    pub horizontal: Scrollbar,
+    // eases wheel notches instead of jumping a whole line at a time
+    pub smooth_wheel: bool,
+    content_size: (i32, i32),
+    // where a wheel scroll is gliding from and to, while `gliding`
+    glide: (Fade, Fade),
+    gliding: bool,
+    // trackpad deltas smaller than a pixel, carried to the next one
+    remainder: (f64, f64),
+    // when a trackpad last scrolled this container, see `SCROLL_LATCH`
+    latched: Option<Instant>,
+}
+
+impl ScrollContainer {
+    pub fn new(rect: Rectangle) -> Self {
+        let theme = Theme::default();
+        Self {
+            rect,
+            scroll_offset: (0, 0),
+            vertical: Scrollbar::new(Axis::Vertical, &theme),
+            horizontal: Scrollbar::new(Axis::Horizontal, &theme),
+            smooth_wheel: true,
+            content_size: (0, 0),
+            glide: (Fade::new(WHEEL_GLIDE, 0.0), Fade::new(WHEEL_GLIDE, 0.0)),
+            gliding: false,
+            remainder: (0.0, 0.0),
+            latched: None,
+        }
+    }
+
+    // follows the platform's scrollbar style unless set here
+    pub fn set_scrollbar_visibility(&mut self, visibility: ScrollbarVisibility) {
+        self.vertical.visibility = visibility;
+        self.horizontal.visibility = visibility;
+    }
+
+    // size of the children including padding, as of the last layout
+    pub fn content_size(&self) -> (i32, i32) {
+        self.content_size
+    }
+
+    pub fn max_scroll(&self) -> (i32, i32) {
+        (
+            (self.content_size.0 - self.rect.width).max(0),
+            (self.content_size.1 - self.rect.height).max(0),
+        )
+    }
+
+    pub fn scroll_by(&mut self, delta: (i32, i32)) {
+        self.scroll_to((
+            self.scroll_offset.0 + delta.0,
+            self.scroll_offset.1 + delta.1,
+        ));
+    }
+
+    // jumps straight there, cutting off any wheel glide
+    pub fn scroll_to(&mut self, offset: (i32, i32)) {
+        self.gliding = false;
+        self.move_to(offset, context::frame_now());
+    }
+
+    fn move_to(&mut self, offset: (i32, i32), now: Instant) {
+        let previous = self.scroll_offset;
+        self.scroll_offset = offset;
+        self.clamp_scroll();
+        if self.scroll_offset.0 != previous.0 {
+            self.horizontal.touch(now);
+        }
+        if self.scroll_offset.1 != previous.1 {
+            self.vertical.touch(now);
+        }
+        self.rect.mark_dirty();
+    }
+
+    // `delta` in lines, as wheel events report it. returns whether anything
+    // moved, so a container already at its end can let the wheel through to
+    // the one around it
+    pub fn scroll_wheel(&mut self, delta: (f64, f64)) -> bool {
+        let line = SCROLL_LINE as f32 * input::wheel_lines();
+        let pixels = (-delta.0 as f32 * line, -delta.1 as f32 * line);
+        if !self.smooth_wheel {
+            let previous = self.scroll_offset;
+            self.scroll_by((pixels.0.round() as i32, pixels.1.round() as i32));
+            return self.scroll_offset != previous;
+        }
+        let now = context::frame_now();
+        if !self.gliding {
+            self.glide.0.snap(self.scroll_offset.0 as f32);
+            self.glide.1.snap(self.scroll_offset.1 as f32);
+        }
+        // notches in quick succession add up from where the last one was
+        // headed, not from wherever the glide has got to
+        let max = self.max_scroll();
+        let from = (self.glide.0.target(), self.glide.1.target());
+        let to = (
+            (from.0 + pixels.0).clamp(0.0, max.0 as f32),
+            (from.1 + pixels.1).clamp(0.0, max.1 as f32),
+        );
+        if to == from {
+            return false;
+        }
+        self.glide.0.set_target(to.0, now);
+        self.glide.1.set_target(to.1, now);
+        self.gliding = true;
+        self.rect.mark_dirty();
+        true
+    }
+
+    // `delta` in lines like `scroll_wheel`, but from a trackpad. moves right
+    // away, keeping fractions of a pixel for the next delta. once a gesture
+    // has moved this container it keeps taking it, even at the end, until
+    // the fingers and the momentum after them stop
+    pub fn scroll_precise(&mut self, delta: (f64, f64)) -> bool {
+        let now = context::frame_now();
+        self.gliding = false;
+        let pixels = (
+            -delta.0 * SCROLL_LINE as f64 + self.remainder.0,
+            -delta.1 * SCROLL_LINE as f64 + self.remainder.1,
+        );
+        self.remainder = (pixels.0.fract(), pixels.1.fract());
+        let previous = self.scroll_offset;
+        self.move_to(
+            (
+                previous.0 + pixels.0.trunc() as i32,
+                previous.1 + pixels.1.trunc() as i32,
+            ),
+            now,
+        );
+        let latched = self
+            .latched
+            .is_some_and(|since| now.saturating_duration_since(since) < SCROLL_LATCH);
+        let taken = self.scroll_offset != previous || latched;
+        self.latched = taken.then_some(now);
+        taken
+    }
+
+    // moves along the wheel glide, once per layout pass
+    fn step(&mut self) {
+        if !self.gliding {
+            return;
+        }
+        let now = context::frame_now();
+        let offset = (
+            self.glide.0.value(now).round() as i32,
+            self.glide.1.value(now).round() as i32,
+        );
+        self.move_to(offset, now);
+        self.gliding = !(self.glide.0.is_settled(now) && self.glide.1.is_settled(now));
+    }
+
+    // returns whether the press landed on a scrollbar
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        let now = context::frame_now();
+        let track = self.track(Axis::Vertical);
+        if let Some(offset) = self.vertical.press(&track, point, now) {
+            self.scroll_to((self.scroll_offset.0, offset));
+            return true;
+        }
+        let track = self.track(Axis::Horizontal);
+        if let Some(offset) = self.horizontal.press(&track, point, now) {
+            self.scroll_to((offset, self.scroll_offset.1));
+            return true;
+        }
+        false
+    }
+
+    // returns whether a thumb is being dragged
+    pub fn drag(&mut self, point: (i32, i32)) -> bool {
+        let now = context::frame_now();
+        let track = self.track(Axis::Vertical);
+        if let Some(offset) = self.vertical.drag(&track, point, now) {
+            self.scroll_to((self.scroll_offset.0, offset));
+            return true;
+        }
+        let track = self.track(Axis::Horizontal);
+        if let Some(offset) = self.horizontal.drag(&track, point, now) {
+            self.scroll_to((offset, self.scroll_offset.1));
+            return true;
+        }
+        false
+    }
+
+    pub fn release(&mut self) {
+        let now = context::frame_now();
+        self.vertical.release(now);
+        self.horizontal.release(now);
+    }
+
+    pub fn hover(&mut self, point: (i32, i32)) {
+        let now = context::frame_now();
+        let track = self.track(Axis::Vertical);
+        self.vertical.hover(&track, point, now);
+        let track = self.track(Axis::Horizontal);
+        self.horizontal.hover(&track, point, now);
+    }
+
+    pub fn is_dragging_scrollbar(&self) -> bool {
+        self.vertical.is_dragging() || self.horizontal.is_dragging()
+    }
+
+    // where the scrollbar for `axis` runs. when both axes scroll the bars stop
+    // short of the corner they would share
+    pub fn track(&self, axis: Axis) -> ScrollTrack {
+        let (x, y) = self.rect.position;
+        let (width, height) = (self.rect.width, self.rect.height);
+        let max = self.max_scroll();
         match axis {
-            Axis::Horizontal => &self.sizing.width,
-            Axis::Vertical => &self.sizing.height,
+            Axis::Vertical => {
+                let bar = &self.vertical;
+                let corner = if max.0 > 0 {
+                    self.horizontal.thickness + self.horizontal.margin
+                } else {
+                    0
+                };
+                ScrollTrack {
+                    across: x + width - bar.thickness - bar.margin,
+                    start: y + bar.margin,
+                    length: height - 2 * bar.margin - corner,
+                    viewport: height,
+                    content: self.content_size.1,
+                    offset: self.scroll_offset.1,
+                }
+            }
+            Axis::Horizontal => {
+                let bar = &self.horizontal;
+                let corner = if max.1 > 0 {
+                    self.vertical.thickness + self.vertical.margin
+                } else {
+                    0
+                };
+                ScrollTrack {
+                    across: y + height - bar.thickness - bar.margin,
+                    start: x + bar.margin,
+                    length: width - 2 * bar.margin - corner,
+                    viewport: width,
+                    content: self.content_size.0,
+                    offset: self.scroll_offset.0,
+                }
+            }
         }
     }
 
+    pub fn contains(&self, point: (i32, i32)) -> bool {
+        let (x, y) = self.rect.position;
+        point.0 >= x
+            && point.0 < x + self.rect.width
+            && point.1 >= y
+            && point.1 < y + self.rect.height
+    }
+
+    fn clamp_scroll(&mut self) {
+        let max = self.max_scroll();
+        self.scroll_offset.0 = self.scroll_offset.0.clamp(0, max.0);
+        self.scroll_offset.1 = self.scroll_offset.1.clamp(0, max.1);
+    }
+
+    fn measure_content(&self) -> (i32, i32) {
+        let axis = self.rect.axis();
+        let mut axis_size = 0;
+        let mut off_axis_size = 0;
+        let mut count = 0;
+        for child in &self.rect.flow_children() {
+            if let Ok(prim) = child.lock() {
+                axis_size += prim.get_size_along_axis(axis);
+                off_axis_size = off_axis_size.max(prim.get_size_along_axis(!axis));
+                count += 1;
+            }
+        }
+        axis_size += self.rect.child_gap * (count - 1).max(0) + 2 * self.rect.content_padding();
+        off_axis_size += 2 * self.rect.content_padding();
+        match axis {
+            Axis::Horizontal => (axis_size, off_axis_size),
+            Axis::Vertical => (off_axis_size, axis_size),
+        }
+    }
+}
+
+impl Primative for ScrollContainer {
+    delegate_primative!(rect, sizing);
+
+    // laid out every frame while the wheel glides
+    fn is_dirty(&self) -> bool {
+        self.gliding || self.rect.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.rect.get_mesh(size));
+        list.push_clip(self.rect.position, (self.rect.width, self.rect.height));
+        draw_list::record_children(&self.rect.children, list, size);
+        let now = list.clock.now();
+        for (bar, axis) in [
+            (&self.vertical, Axis::Vertical),
+            (&self.horizontal, Axis::Horizontal),
+        ] {
+            if let Some(mesh) = bar.get_mesh(&self.track(axis), now, size) {
+                list.mesh(mesh);
+            }
+        }
+        list.pop_clip();
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+
+    fn adopt_state(&mut self, previous: &mut dyn Primative) {
+        let Some(previous) = previous
+            .as_any_mut()
+            .and_then(|any| any.downcast_mut::<ScrollContainer>())
+        else {
+            return;
+        };
+        self.scroll_offset = previous.scroll_offset;
+        self.content_size = previous.content_size;
+        self.glide = previous.glide;
+        self.gliding = previous.gliding;
+        self.remainder = previous.remainder;
+        self.latched = previous.latched;
+        self.vertical.adopt_state(&previous.vertical);
+        self.horizontal.adopt_state(&previous.horizontal);
+    }
+
+    // the scrollbars take pointer input on the way down, before the children
+    // under them see it. the wheel goes on the way back up, so the innermost
+    // container that can still move takes it
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let downward = cx.phase() != Phase::Bubble;
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if downward => self.press(*position),
+            Event::PointerMoved { position } if downward => {
+                self.hover(*position);
+                self.drag(*position)
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                ..
+            } if downward && self.is_dragging_scrollbar() => {
+                self.release();
+                true
+            }
+            Event::Scroll { delta, precise } if cx.phase() != Phase::Capture => {
+                if *precise {
+                    self.scroll_precise(*delta)
+                } else {
+                    self.scroll_wheel(*delta)
+                }
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for ScrollContainer {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.content_size = self.measure_content();
+        self.step();
+        self.clamp_scroll();
+
+        // lay the children out from a shifted origin so nested containers
+        // pick up the offset too
+        let position = self.rect.position;
+        self.rect.position = (
+            position.0 - self.scroll_offset.0,
+            position.1 - self.scroll_offset.1,
+        );
+        self.rect.set_child_positions();
+        self.rect.position = position;
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
     fn as_primative(&mut self) -> Option<&mut dyn Primative> {
         Some(self as &mut dyn Primative)
     }
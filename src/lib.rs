@@ -1,62 +1,532 @@
+mod animation;
+mod app;
+mod binding;
+pub mod builder;
+mod coalesce;
+mod color_vision;
+mod console;
+mod context;
+mod dispatch;
+mod event;
+mod export;
+mod fill;
+mod floating;
+mod form;
+#[cfg(feature = "global-hotkeys")]
+mod global_hotkeys;
+mod html;
+mod idle;
+mod input;
+#[cfg(feature = "inspector")]
+mod inspector;
 mod layout;
+mod overlay;
+mod platform;
+mod preferences;
+mod reconcile;
 mod renderer;
+#[cfg(feature = "scripting")]
+mod scripting;
+mod search;
+mod selection;
+mod shortcuts;
+mod state_machine;
+mod stats;
+mod text;
+mod theme;
+mod timers;
+#[cfg(feature = "tray")]
+mod tray;
+#[cfg(feature = "vibrancy")]
+mod vibrancy;
+#[cfg(feature = "widget-docs")]
+mod widget_docs;
+mod widgets;
+mod window;
 
 use std::{
+    cell::RefCell,
     ops::Deref,
-    sync::{self, Arc},
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
-use glfw::{Action, Context, Key, PWindow, fail_on_errors};
-use layout::{Container, LayoutMode, Rectangle, Sizing, UI};
+use glfw::{Action, Context, PWindow, fail_on_errors};
+#[cfg(feature = "software")]
+use renderer::software::SoftwareSurface;
 use renderer::{
+    color::ColorOutput,
+    frame::{Frame, FrameBuffers},
+    image::ImageRenderer,
     mesh_builder::{self},
+    particles::ParticleRenderer,
     pipeline_builder::PipelineBuilder,
+    plugin::PluginRegistry,
+    post::PostProcessor,
+    projection::{Orientation, Projection},
+    readback, settings,
+    shaders::PipelineRegistry,
+    skeleton::SkeletonRenderer,
+    text::TextRenderer,
 };
 use tinycolors as color;
 use tokio::sync::Mutex;
 use wgpu::{
-    CommandEncoderDescriptor, Device, DeviceDescriptor, Instance, InstanceDescriptor, LoadOp,
-    Operations, PowerPreference, Queue, RenderPassColorAttachment, RenderPassDescriptor, StoreOp,
-    Surface, SurfaceConfiguration, SurfaceTargetUnsafe, TextureUsages,
+    CommandEncoderDescriptor, CompositeAlphaMode, Device, DeviceDescriptor, InstanceDescriptor,
+    LoadOp, Operations, Queue, RenderPassColorAttachment, RenderPassDescriptor, StoreOp, Surface,
+    SurfaceConfiguration, SurfaceTargetUnsafe, TextureUsages,
 };
 
-struct State<'a> {
+pub use animation::{Animator, Blink, Easing, Fade, Property, Spring};
+pub use app::{App, Teacup, TeacupBuilder};
+pub use binding::{
+    Binding, CollectionChange, ItemTransition, ListBinding, ObservableVec, Signal, TransitionKind,
+};
+pub use builder::{ElementBuilder, IntoNode, Node};
+pub use color_vision::{
+    ColorVision, OKABE_ITO, min_difference, pick_distinguishable, series_colors,
+};
+pub use console::{ConsoleCommand, ConsoleLayer, ConsoleLogger, DevConsole, LogLine};
+pub use context::{Announcement, FrameClock, Politeness, UiContext, ZOOM_LEVELS};
+pub use dispatch::{EventContext, Phase};
+pub use event::{Event, PointerButton};
+pub use export::ExportFormat;
+pub use fill::Fill;
+pub use floating::{Anchor, AnchorPoint, AnchorTarget, Positioning};
+pub use form::{Form, FormField, ValidationTrigger, Validator, validators};
+pub use html::{Html, HtmlStyle};
+pub use idle::{IdleDeadline, IdleId};
+pub use input::{InputSettings, Key, Modifiers, VelocityTracker};
+#[cfg(feature = "inspector")]
+pub use inspector::Inspector;
+pub use layout::{
+    AlignCross, AlignMain, Container, Image, LAYOUT_SCALE, LayoutMode, Primative, Rectangle,
+    ScrollContainer, Sizing, SizingMode, Text, UI,
+};
+pub use overlay::{Overlay, OverlayId, OverlayLayer};
+pub use platform::{
+    AcceleratorStyle, ButtonOrder, Conventions, DialogRole, Platform, ScrollbarVisibility,
+};
+pub use preferences::Preferences;
+pub use reconcile::NodeId;
+pub use renderer::color::{Color, ColorSpace, HdrSettings, OutputColorSpace};
+pub use renderer::draw_list::{DrawCommand, DrawList};
+#[cfg(feature = "gl")]
+pub use renderer::gl::GlRenderer;
+pub use renderer::headless::HeadlessRenderer;
+pub use renderer::icons::{IconEntry, IconSet};
+pub use renderer::image::ImageData;
+pub use renderer::plugin::{PluginFrame, PluginInit, PluginViewport, RenderPlugin};
+pub use renderer::post::{ColorLut, PostEffect};
+pub use renderer::settings::{
+    AdapterSelector, PresentMode, RedrawMode, RenderSettings, RendererKind, available_adapters,
+};
+pub use renderer::shaders::CustomShader;
+#[cfg(feature = "software")]
+pub use renderer::software::SoftwareRenderer;
+pub use renderer::text::Font;
+#[cfg(feature = "scripting")]
+pub use scripting::{ScriptElement, ScriptEngine, ScriptText};
+pub use search::{HighlightSpan, TextSearch, find_matches};
+pub use selection::{Marquee, SelectionMode, SelectionModel};
+pub use state_machine::{Interaction, InteractionEvent, StateMachine};
+pub use stats::UiStats;
+pub use theme::{
+    ContrastIssue, ContrastLevel, Theme, contrast_ratio, ensure_contrast, from_hex,
+    relative_luminance, to_hex,
+};
+pub use timers::TimerId;
+#[cfg(feature = "tray")]
+pub use tray::{TrayEvent, TrayMenuItem, TrayOptions};
+#[cfg(feature = "vibrancy")]
+pub use vibrancy::{NSVisualEffectMaterial, Tint, Vibrancy};
+#[cfg(feature = "widget-docs")]
+pub use widget_docs::{DocState, WidgetDoc, WidgetGallery};
+pub use widgets::async_content::{Async, AsyncState};
+pub use widgets::button::Button;
+pub use widgets::cell_editor::{
+    CellEditor, CheckboxEditor, DropdownEditor, NumberEditor, TextEditor,
+};
+pub use widgets::checkbox::Checkbox;
+pub use widgets::combo_box::{ComboBox, SuggestionFuture, SuggestionProvider};
+pub use widgets::error_boundary::{BoundaryError, ErrorBoundary, RetryHandle};
+pub use widgets::eyedropper::Eyedropper;
+pub use widgets::fader::Fader;
+pub use widgets::floating_panel::{Corner, FloatingPanel};
+pub use widgets::form_view::FormView;
+pub use widgets::icon::Icon;
+pub use widgets::input_mode::{InputMask, InputMode, PasswordMode};
+pub use widgets::knob::Knob;
+pub use widgets::list_view::ListView;
+pub use widgets::meter::{Meter, MeterOrientation, amplitude_to_db};
+pub use widgets::parameter::Parameter;
+pub use widgets::particle_emitter::{EmitterConfig, ParticleEmitter};
+pub use widgets::plugin_view::PluginView;
+pub use widgets::radio_group::RadioGroup;
+pub use widgets::rating::Rating;
+pub use widgets::ruler::{CanvasTransform, Guide, GuideOverlay, Ruler, SnapSettings, snap};
+pub use widgets::scrollbar::{ScrollTrack, Scrollbar};
+pub use widgets::segmented_control::SegmentedControl;
+pub use widgets::sheet::{Sheet, SheetEdge};
+pub use widgets::skeleton::{Loading, Skeleton, SkeletonShape};
+pub use widgets::table::{Cell, Column, Table};
+#[cfg(feature = "data")]
+pub use widgets::table_data::{ColumnType, DataSource, SortOrder, Value};
+#[cfg(feature = "terminal")]
+pub use widgets::terminal::Terminal;
+pub use widgets::text_input::TextInput;
+pub use widgets::tooltip::Tooltip;
+pub use widgets::waveform::Waveform;
+pub use window::WindowOptions;
+
+// longest an idle background window sleeps, so tray and hotkey polling still
+// get a look in
+const IDLE_WAKE: Duration = Duration::from_millis(100);
+
+// how long a frame is assumed to last when there's no frame rate cap, for
+// working out how much of it idle callbacks can have
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+struct State {
+    // draws into the window through its raw handles, so it has to go before
+    // the window does. fields drop in order, and holding the window here keeps
+    // it alive for as long as the backend is
+    backend: Backend,
     window: Arc<Mutex<PWindow>>,
-    instance: Instance,
-    surface: Surface<'a>,
+    // framebuffer size in physical pixels, what the surface is configured at
+    size: (i32, i32),
+    // minimized or zero sized, with nothing to draw into until it comes back
+    suspended: bool,
+    // window size in the os's window coordinates, which are points on some
+    // platforms and pixels on others
+    window_size: (i32, i32),
+    scale_factor: f32,
+    // the user's zoom, see `UiContext::zoom`
+    zoom: f32,
+}
+
+// what draws the window, see `RendererKind`
+enum Backend {
+    Gpu(Box<Gpu>),
+    #[cfg(feature = "software")]
+    Software(Box<SoftwareSurface>),
+    // drawn on the window's gl context, shown by swapping its buffers
+    #[cfg(feature = "gl")]
+    Gl(Box<GlRenderer>),
+}
+
+struct Gpu {
+    surface: Surface<'static>,
     device: Device,
     queue: Queue,
     config: SurfaceConfiguration,
-    size: (i32, i32),
+    // what the surface can present with, for switching at runtime
+    present_modes: Vec<wgpu::PresentMode>,
     render_pipeline: wgpu::RenderPipeline,
+    custom_pipelines: PipelineRegistry,
+    buffers: FrameBuffers,
+    projection: Projection,
+    text_renderer: TextRenderer,
+    image_renderer: ImageRenderer,
+    particle_renderer: ParticleRenderer,
+    skeleton_renderer: SkeletonRenderer,
+    plugins: PluginRegistry,
+    post: PostProcessor,
+    adapter_info: wgpu::AdapterInfo,
+    color_output: ColorOutput,
 }
 
-impl State<'_> {
-    async fn new(window: Arc<Mutex<PWindow>>) -> Self {
-        let size = window.lock().await.get_size();
+impl State {
+    async fn new(
+        window: Arc<Mutex<PWindow>>,
+        options: &WindowOptions,
+        shaders: &[(String, renderer::shaders::CustomShader)],
+    ) -> anyhow::Result<Self> {
+        let (size, window_size, scale_factor) = {
+            let window = window.lock().await;
+            (
+                window.get_framebuffer_size(),
+                window.get_size(),
+                window.get_content_scale().0,
+            )
+        };
+        let backend = Backend::new(&window, size, options, shaders).await?;
+
+        Ok(Self {
+            backend,
+            window,
+            size,
+            suspended: false,
+            window_size,
+            scale_factor: if scale_factor > 0.0 {
+                scale_factor
+            } else {
+                1.0
+            },
+            zoom: 1.0,
+        })
+    }
 
+    fn render(&mut self, ui: &mut UI) -> anyhow::Result<()> {
+        if self.suspended {
+            return Ok(());
+        }
+        let points_scale = self.points_scale();
+        let logical_size = self.logical_size();
+        // taken whatever the backend, so a request can't outlive the frame
+        // it was meant for
+        #[cfg(feature = "renderdoc")]
+        let capture = ui.context.take_capture_request();
+        #[cfg(feature = "renderdoc")]
+        if capture && !matches!(self.backend, Backend::Gpu(_)) {
+            log::warn!("frame capture only works with the wgpu backend, skipping it");
+        }
+        match &mut self.backend {
+            #[cfg(feature = "renderdoc")]
+            Backend::Gpu(gpu) if capture => gpu.render_captured(ui, points_scale, logical_size),
+            Backend::Gpu(gpu) => gpu.render(ui, points_scale, logical_size),
+            #[cfg(feature = "software")]
+            Backend::Software(surface) => {
+                ui.compute_layout();
+                surface.present(ui)
+            }
+            #[cfg(feature = "gl")]
+            Backend::Gl(gl) => {
+                ui.compute_layout();
+                gl.render(ui, (self.size.0 as u32, self.size.1 as u32));
+                if let Some(point) = ui.eyedropper.sample_request() {
+                    ui.eyedropper.deliver(gl.pixel(point));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn adapter_info(&self) -> Option<wgpu::AdapterInfo> {
+        match &self.backend {
+            Backend::Gpu(gpu) => Some(gpu.adapter_info.clone()),
+            #[cfg(feature = "software")]
+            Backend::Software(_) => None,
+            #[cfg(feature = "gl")]
+            Backend::Gl(_) => None,
+        }
+    }
+
+    fn add_plugin(&mut self, name: &str, plugin: Box<dyn RenderPlugin>) {
+        match &mut self.backend {
+            Backend::Gpu(gpu) => gpu.add_plugin(name, plugin),
+            #[cfg(feature = "software")]
+            Backend::Software(_) => {
+                log::warn!("render plugins need the gpu, {name} won't be drawn")
+            }
+            #[cfg(feature = "gl")]
+            Backend::Gl(_) => log::warn!("render plugins need wgpu, {name} won't be drawn"),
+        }
+    }
+
+    fn set_present_mode(&mut self, mode: PresentMode) {
+        match &mut self.backend {
+            Backend::Gpu(gpu) => {
+                gpu.config.present_mode = mode.pick(&gpu.present_modes);
+                gpu.surface.configure(&gpu.device, &gpu.config);
+            }
+            // softbuffer presents as soon as a frame is done
+            #[cfg(feature = "software")]
+            Backend::Software(_) => {}
+            // gl presents with the window's swap interval
+            #[cfg(feature = "gl")]
+            Backend::Gl(_) => {}
+        }
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) {
+        match &mut self.backend {
+            Backend::Gpu(gpu) => gpu.projection.set_orientation(&gpu.queue, orientation),
+            #[cfg(feature = "software")]
+            Backend::Software(_) => log::warn!("the software renderer only draws upright"),
+            #[cfg(feature = "gl")]
+            Backend::Gl(_) => log::warn!("the gl renderer only draws upright"),
+        }
+    }
+
+    fn resize(&mut self, new_size: (i32, i32)) {
+        self.suspended = !self.configure(new_size);
+    }
+
+    // the window was minimized or restored. a restored window may come back
+    // at another size, so the surface is configured again from the window
+    fn set_suspended(&mut self, window: &glfw::Window, suspended: bool) {
+        if suspended {
+            self.suspended = true;
+        } else {
+            self.window_size = window.get_size();
+            self.resize(window.get_framebuffer_size());
+        }
+    }
+
+    // reconfigures the surface at a new framebuffer size. returns false for
+    // the zero size a minimized window reports
+    fn configure(&mut self, new_size: (i32, i32)) -> bool {
+        if new_size.0 <= 0 || new_size.1 <= 0 {
+            return false;
+        }
+        self.size = new_size;
+        match &mut self.backend {
+            Backend::Gpu(gpu) => {
+                gpu.config.width = new_size.0 as u32;
+                gpu.config.height = new_size.1 as u32;
+                gpu.surface.configure(&gpu.device, &gpu.config);
+                gpu.plugins
+                    .resize(&gpu.device, (gpu.config.width, gpu.config.height));
+            }
+            #[cfg(feature = "software")]
+            Backend::Software(surface) => {
+                if let Err(e) = surface.resize(new_size) {
+                    log::error!("couldn't resize the software surface: {e}");
+                }
+            }
+            // the viewport follows the size every frame
+            #[cfg(feature = "gl")]
+            Backend::Gl(_) => {}
+        }
+        true
+    }
+
+    // draws a frame from inside a window callback, while the os holds the
+    // event loop in a live resize and the loop can't get to it. the resize
+    // events are still queued, so the new size is read off the window
+    fn live_frame(&mut self, window: &mut glfw::Window, ui: &mut UI) {
+        let size = window.get_framebuffer_size();
+        if size != self.size {
+            self.resize(size);
+            if !self.suspended {
+                self.window_size = window.get_size();
+                ui.set_size(ui.context.orientation().logical_size(self.logical_size()));
+            }
+        }
+        if ui.context.tick() {
+            ui.mark_layout_dirty();
+        }
+        ui.animator.tick(ui.context.clock().now());
+        if let Err(e) = self.render(ui) {
+            eprintln!("{:?}", e);
+        }
+        window.swap_buffers();
+    }
+
+    // physical pixels per point, with the zoom making points bigger
+    fn points_scale(&self) -> f32 {
+        self.scale_factor * self.zoom
+    }
+
+    // the framebuffer in points
+    fn logical_size(&self) -> (i32, i32) {
+        (
+            (self.size.0 as f32 / self.points_scale()).round() as i32,
+            (self.size.1 as f32 / self.points_scale()).round() as i32,
+        )
+    }
+
+    // physical pixels per window coordinate. 1 where window coordinates are
+    // already pixels, the content scale where they're points
+    fn pixel_ratio(&self) -> f64 {
+        if self.size.0 > 0 && self.window_size.0 > 0 {
+            self.size.0 as f64 / self.window_size.0 as f64
+        } else {
+            1.0
+        }
+    }
+
+    // layout pixels per window coordinate, for pointer positions
+    fn layout_scale(&self) -> f64 {
+        self.pixel_ratio() * layout::LAYOUT_SCALE as f64 / self.points_scale() as f64
+    }
+
+    // a pointer position in window coordinates as a surface pixel
+    fn to_physical(&self, (x, y): (f64, f64)) -> (u32, u32) {
+        let ratio = self.pixel_ratio();
+        ((x * ratio).max(0.0) as u32, (y * ratio).max(0.0) as u32)
+    }
+
+    async fn should_close(&self) -> bool {
+        self.window.lock().await.should_close()
+    }
+}
+
+impl Backend {
+    async fn new(
+        window: &Arc<Mutex<PWindow>>,
+        size: (i32, i32),
+        options: &WindowOptions,
+        shaders: &[(String, renderer::shaders::CustomShader)],
+    ) -> anyhow::Result<Self> {
+        let kind = options.render.renderer;
+        match kind {
+            RendererKind::Auto | RendererKind::Gpu => {}
+            #[cfg(feature = "software")]
+            RendererKind::Software => return Backend::software(window, size).await,
+            #[cfg(feature = "gl")]
+            RendererKind::Gl => return Backend::gl(window).await,
+            #[allow(unreachable_patterns)]
+            kind => log::warn!("teacup was built without the feature for {kind:?}, using wgpu"),
+        }
+        match Gpu::new(window, size, options, shaders).await {
+            Ok(gpu) => Ok(Backend::Gpu(Box::new(gpu))),
+            #[cfg(feature = "software")]
+            Err(e) if kind == RendererKind::Auto => {
+                log::warn!("can't render with the gpu, drawing on the cpu instead: {e:#}");
+                Backend::software(window, size).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(feature = "software")]
+    async fn software(window: &Arc<Mutex<PWindow>>, size: (i32, i32)) -> anyhow::Result<Self> {
+        let window = window.lock().await;
+        // the window outlives the surface, see `State::backend`
+        let surface = unsafe { SoftwareSurface::new(&*window, size)? };
+        Ok(Backend::Software(Box::new(surface)))
+    }
+
+    #[cfg(feature = "gl")]
+    async fn gl(window: &Arc<Mutex<PWindow>>) -> anyhow::Result<Self> {
+        let mut window = window.lock().await;
+        window.make_current();
+        // the context stays current on this thread, see `run_app`
+        let renderer =
+            unsafe { GlRenderer::new(|name| window.get_proc_address(name) as *const _)? };
+        Ok(Backend::Gl(Box::new(renderer)))
+    }
+}
+
+impl Gpu {
+    async fn new(
+        window: &Arc<Mutex<PWindow>>,
+        size: (i32, i32),
+        options: &WindowOptions,
+        shaders: &[(String, renderer::shaders::CustomShader)],
+    ) -> anyhow::Result<Self> {
         let instance = wgpu::Instance::new(&InstanceDescriptor {
-            backends: wgpu::Backends::all(),
+            backends: options.render.backends,
             ..Default::default()
         });
 
         let mutex_guard = window.lock().await;
         let temp_window = mutex_guard.deref();
 
-        let target = unsafe { SurfaceTargetUnsafe::from_window(temp_window).unwrap() };
+        let target = unsafe { SurfaceTargetUnsafe::from_window(temp_window)? };
 
         drop(mutex_guard);
 
-        let surface = unsafe { instance.create_surface_unsafe(target).unwrap() };
+        // the window outlives the surface, see `State::backend`
+        let surface = unsafe { instance.create_surface_unsafe(target)? };
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptionsBase {
-                power_preference: PowerPreference::default(),
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
+        let adapter = settings::select_adapter(&instance, &surface, &options.render)
             .await
-            .unwrap();
+            .ok_or_else(|| anyhow::anyhow!("no gpu adapter can present to this window"))?;
+        let adapter_info = adapter.get_info();
 
         let (device, queue) = adapter
             .request_device(&DeviceDescriptor {
@@ -66,22 +536,59 @@ impl State<'_> {
                 memory_hints: Default::default(),
                 trace: wgpu::Trace::Off,
             })
-            .await
-            .unwrap();
+            .await?;
 
         let surface_capabilities = surface.get_capabilities(&adapter);
+        // copy src lets the eyedropper read pixels back out of the frame
+        let usage = if surface_capabilities
+            .usages
+            .contains(TextureUsages::COPY_SRC)
+        {
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC
+        } else {
+            TextureUsages::RENDER_ATTACHMENT
+        };
+        // a transparent window needs an alpha mode the compositor will blend
+        let alpha_mode = if options.is_transparent() {
+            [
+                CompositeAlphaMode::PreMultiplied,
+                CompositeAlphaMode::PostMultiplied,
+                CompositeAlphaMode::Inherit,
+            ]
+            .into_iter()
+            .find(|mode| surface_capabilities.alpha_modes.contains(mode))
+            .unwrap_or_else(|| {
+                log::warn!("surface doesn't support a transparent alpha mode");
+                surface_capabilities.alpha_modes[0]
+            })
+        } else {
+            surface_capabilities.alpha_modes[0]
+        };
+        let formats = &surface_capabilities.formats;
+        let (format, color_space) = match options.render.color_space.pick_format(formats) {
+            Some(format) => (format, options.render.color_space),
+            None => {
+                log::warn!(
+                    "surface can't present {:?}, falling back to srgb",
+                    options.render.color_space
+                );
+                let format = OutputColorSpace::Srgb
+                    .pick_format(formats)
+                    .unwrap_or(formats[0]);
+                (format, OutputColorSpace::Srgb)
+            }
+        };
+        let color_output = ColorOutput::new(color_space, format, options.render.hdr);
         let config = SurfaceConfiguration {
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            format: surface_capabilities
-                .formats
-                .iter()
-                .copied()
-                .find(|f| f.is_srgb())
-                .unwrap_or(surface_capabilities.formats[0]),
+            usage,
+            format,
             width: size.0 as u32,
             height: size.1 as u32,
-            present_mode: surface_capabilities.present_modes[0],
-            alpha_mode: surface_capabilities.alpha_modes[0],
+            present_mode: options
+                .render
+                .present_mode
+                .pick(&surface_capabilities.present_modes),
+            alpha_mode,
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
@@ -89,25 +596,90 @@ impl State<'_> {
         surface.configure(&device, &config);
 
         let mut pipeline_builder = PipelineBuilder::new();
-        pipeline_builder.set_shader_module("shaders/shader.wgsl", "vs_main", "fs_main");
         pipeline_builder.set_pixel_format(config.format);
         pipeline_builder.set_buffer_layout(mesh_builder::Vertex::get_layout());
+        pipeline_builder.set_blend_state(wgpu::BlendState::ALPHA_BLENDING);
+        let projection = Projection::new(&device);
+        pipeline_builder.add_bind_group_layout(&projection.bind_group_layout);
+        for (name, value) in color_output.constants() {
+            pipeline_builder.set_constant(name, value);
+        }
         let render_pipeline = pipeline_builder.build_pipeline(&device);
+        let custom_pipelines = PipelineRegistry::build(
+            &device,
+            config.format,
+            &projection.bind_group_layout,
+            shaders,
+        )
+        .await;
+        let text_renderer = TextRenderer::new(
+            &device,
+            config.format,
+            &projection.bind_group_layout,
+            color_output,
+        );
+        let image_renderer = ImageRenderer::new(
+            &device,
+            config.format,
+            &projection.bind_group_layout,
+            color_output,
+        );
+        let particle_renderer = ParticleRenderer::new(
+            &device,
+            config.format,
+            &projection.bind_group_layout,
+            color_output,
+        );
+        let skeleton_renderer = SkeletonRenderer::new(
+            &device,
+            config.format,
+            &projection.bind_group_layout,
+            color_output,
+        );
 
-        Self {
-            window,
-            instance,
+        let post = PostProcessor::new(&device, &queue, config.format);
+
+        Ok(Self {
             surface,
             device,
             queue,
             config,
-            size,
+            present_modes: surface_capabilities.present_modes,
             render_pipeline,
-        }
+            custom_pipelines,
+            buffers: FrameBuffers::default(),
+            projection,
+            text_renderer,
+            image_renderer,
+            particle_renderer,
+            skeleton_renderer,
+            plugins: PluginRegistry::default(),
+            post,
+            adapter_info,
+            color_output,
+        })
     }
 
-    fn render(&mut self, ui: &mut UI) -> anyhow::Result<()> {
-        let drawable = self.surface.get_current_texture()?;
+    // `points_scale` and `logical_size` are the window's, see `State`
+    fn render(
+        &mut self,
+        ui: &mut UI,
+        points_scale: f32,
+        logical_size: (i32, i32),
+    ) -> anyhow::Result<()> {
+        let drawable = match self.surface.get_current_texture() {
+            Ok(drawable) => drawable,
+            // the surface went stale under us, after a display change or the
+            // gpu resetting. configuring it again brings it back for the next
+            // frame
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                log::debug!("surface lost, reconfiguring");
+                self.surface.configure(&self.device, &self.config);
+                return Ok(());
+            }
+            Err(wgpu::SurfaceError::Timeout) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
         let image_view = drawable
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
@@ -118,16 +690,33 @@ impl State<'_> {
                 label: Some("render encoder"),
             });
 
+        // with effects the ui is drawn offscreen and the last pass writes the surface
+        let target_size = (self.config.width, self.config.height);
+        let clear_color = self.clear_color(ui);
+        let post_effects: Vec<PostEffect> = ui
+            .post_effects
+            .iter()
+            .cloned()
+            .chain(ui.color_vision.map(ColorVision::effect))
+            .collect();
+        self.plugins.prepare(&mut PluginFrame {
+            device: &self.device,
+            queue: &self.queue,
+            encoder: &mut command_encoder,
+            target_size,
+            scale_factor: points_scale,
+            clock: ui.context.clock(),
+        });
+        let ui_view = if post_effects.is_empty() {
+            &image_view
+        } else {
+            self.post.begin(&self.device, target_size)
+        };
         let color_attatchment = RenderPassColorAttachment {
-            view: &image_view,
+            view: ui_view,
             resolve_target: None,
             ops: Operations {
-                load: LoadOp::Clear(wgpu::Color {
-                    r: 0.0,
-                    g: 0.0,
-                    b: 0.0,
-                    a: 1.0,
-                }),
+                load: LoadOp::Clear(clear_color),
                 store: StoreOp::Store,
             },
         };
@@ -140,161 +729,497 @@ impl State<'_> {
                 occlusion_query_set: None,
             });
             render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.projection.bind_group, &[]);
             ui.compute_layout();
-            ui.draw(&mut render_pass, &self.device, self.size);
+            let size = self.projection.orientation().logical_size(logical_size);
+            let mut frame = Frame::new(
+                &self.device,
+                &self.queue,
+                &mut self.buffers,
+                &mut self.text_renderer,
+                &mut self.image_renderer,
+                &mut self.particle_renderer,
+                &mut self.skeleton_renderer,
+                &self.render_pipeline,
+                &self.custom_pipelines,
+                target_size,
+                points_scale,
+                self.projection.orientation(),
+                ui.context.clock(),
+            );
+            frame.set_plugins(&mut self.plugins, &self.projection.bind_group);
+            ui.draw(&mut render_pass, &mut frame, size);
+            frame.flush(&mut render_pass);
+        }
+        if !post_effects.is_empty() {
+            self.post.apply(
+                &self.device,
+                &self.queue,
+                &mut command_encoder,
+                &post_effects,
+                &image_view,
+            );
         }
         self.queue.submit(std::iter::once(command_encoder.finish()));
 
+        if let Some(point) = ui.eyedropper.sample_request() {
+            let color = readback::read_pixel(&self.device, &self.queue, &drawable.texture, point);
+            ui.eyedropper.deliver(color);
+        }
+
         drawable.present();
 
         anyhow::Ok(())
     }
 
-    async fn resize(&mut self, new_size: (i32, i32)) {
-        if new_size.0 > 0 && new_size.1 > 0 {
-            self.size = new_size;
-            self.config.width = new_size.0 as u32;
-            self.config.height = new_size.1 as u32;
-            self.surface.configure(&self.device, &self.config);
-            self.update_surface().await;
+    // `render` inside a renderdoc capture. wgpu loads renderdoc's in app api
+    // itself, and the capture ends once the gpu has finished the frame
+    #[cfg(feature = "renderdoc")]
+    fn render_captured(
+        &mut self,
+        ui: &mut UI,
+        points_scale: f32,
+        logical_size: (i32, i32),
+    ) -> anyhow::Result<()> {
+        log::info!(
+            "capturing frame {} with renderdoc",
+            ui.context.clock().frame()
+        );
+        unsafe { self.device.start_graphics_debugger_capture() };
+        let result = self.render(ui, points_scale, logical_size);
+        if let Err(e) = self.device.poll(wgpu::PollType::Wait) {
+            log::error!("failed waiting for the captured frame: {e}");
         }
+        unsafe { self.device.stop_graphics_debugger_capture() };
+        result
     }
 
-    async fn update_surface(&mut self) {
-        let mutex_guard = self.window.lock().await;
-        let temp_window = mutex_guard.deref();
-
-        let target = unsafe { SurfaceTargetUnsafe::from_window(temp_window).unwrap() };
-
-        self.surface = unsafe { self.instance.create_surface_unsafe(target).unwrap() };
-
-        self.surface.configure(&self.device, &self.config);
+    fn clear_color(&self, ui: &UI) -> wgpu::Color {
+        let alpha = ui.background_alpha.clamp(0.0, 1.0) as f64;
+        // premultiplied surfaces expect the color already scaled by alpha
+        let scale = match self.config.alpha_mode {
+            CompositeAlphaMode::PreMultiplied => alpha,
+            _ => 1.0,
+        };
+        let [r, g, b] = self.color_output.convert(ui.background_color.into());
+        wgpu::Color {
+            r: r as f64 * scale,
+            g: g as f64 * scale,
+            b: b as f64 * scale,
+            a: alpha,
+        }
     }
 
-    async fn should_close(&self) -> bool {
-        self.window.lock().await.should_close()
+    fn add_plugin(&mut self, name: &str, plugin: Box<dyn RenderPlugin>) {
+        let cx = PluginInit {
+            device: &self.device,
+            queue: &self.queue,
+            format: self.config.format,
+            projection_layout: &self.projection.bind_group_layout,
+        };
+        self.plugins.add(name, plugin, &cx);
     }
 }
 
-pub async fn run() -> anyhow::Result<()> {
+async fn run_app(app: App) -> anyhow::Result<()> {
+    let App {
+        options,
+        theme,
+        mut build,
+        mut on_event,
+        mut on_frame,
+        mut on_announce,
+        shaders,
+        plugins,
+    } = app;
+    // reading them runs the os settings tools, so it happens while the window
+    // and renderer are created
+    let system_settings = platform::SystemSettings::read_in_background();
     let mut glfw = glfw::init(fail_on_errors!())?;
 
+    glfw.window_hint(glfw::WindowHint::TransparentFramebuffer(
+        options.is_transparent(),
+    ));
+    // the gl renderer's shaders need a 3.3 core context
+    #[cfg(feature = "gl")]
+    if options.render.renderer == RendererKind::Gl {
+        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
+        glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+            glfw::OpenGlProfileHint::Core,
+        ));
+        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+    }
     let (window, events) = glfw
-        .create_window(800, 600, "teacup", glfw::WindowMode::Windowed)
+        .create_window(
+            options.size.0,
+            options.size.1,
+            &options.title,
+            glfw::WindowMode::Windowed,
+        )
         .unwrap();
 
+    #[cfg(feature = "vibrancy")]
+    if let Some(vibrancy) = options.vibrancy
+        && let Err(e) = vibrancy::apply(&window, vibrancy)
+    {
+        log::warn!("couldn't apply window vibrancy: {e}");
+    }
+
     let arc_win = Arc::new(Mutex::new(window));
 
     {
         let mut window = arc_win.lock().await;
         // window.set_all_polling(true);
         window.set_key_polling(true);
+        window.set_char_polling(true);
         window.set_size_polling(true);
+        window.set_framebuffer_size_polling(true);
+        window.set_iconify_polling(true);
+        window.set_content_scale_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_focus_polling(true);
+        window.set_cursor_enter_polling(true);
+        window.set_scroll_polling(true);
+        window.set_drag_and_drop_polling(true);
+        if options.min_size.is_some() || options.max_size.is_some() {
+            window.set_size_limits(
+                options.min_size.map(|size| size.0),
+                options.min_size.map(|size| size.1),
+                options.max_size.map(|size| size.0),
+                options.max_size.map(|size| size.1),
+            );
+        }
+        if let Some((width, height)) = options.aspect_ratio {
+            window.set_aspect_ratio(width, height);
+        }
         window.make_current();
     }
 
-    let mut state = State::new(arc_win).await;
+    let mut state = State::new(arc_win, &options, &shaders).await?;
+    for (name, plugin) in plugins {
+        state.add_plugin(&name, plugin);
+    }
+
+    #[cfg(feature = "tray")]
+    let tray = match &options.tray {
+        Some(tray_options) => Some(tray::Tray::new(tray_options)?),
+        None => None,
+    };
+
+    let mut context = UiContext::default();
+    context.set_theme(theme);
+    if let Some(adapter_info) = state.adapter_info() {
+        context.set_adapter(adapter_info);
+    }
+    if let Some(path) = &options.preferences_path {
+        context.set_preferences(Preferences::load(path));
+    }
+    if let Ok(settings) = system_settings.recv() {
+        context.apply_system_settings(settings);
+    }
+    context.take_text_scale_change();
+    context.set_input_settings(options.input);
+    context.set_present_mode(options.render.present_mode);
+    context.take_present_mode_change();
+    context.set_max_frame_rate(options.render.max_frame_rate);
+    context.set_redraw_mode(options.render.redraw);
+    let mut ui = build(&mut context);
+    ui.context = context;
+    if ui.tooltip.is_none() {
+        ui.tooltip =
+            Font::system_default().map(|font| Tooltip::new(font, 14.0, ui.context.theme()));
+    }
+    if let Some(mode) = ui.context.take_present_mode_change() {
+        state.set_present_mode(mode);
+    }
+    ui.set_resize_debounce(options.resize_debounce);
+    ui.set_scale_factor(state.scale_factor);
+    state.zoom = ui.context.zoom();
+    ui.context.take_zoom_change();
+    ui.set_size(state.logical_size());
 
-    let mut ui = build_ui(state.size);
+    // some platforms hold the event loop inside the os while the window is
+    // dragged to a new size, so frames are drawn from the window callbacks
+    // instead. the renderer and tree are only lent to them while events are
+    // polled, the one time the loop isn't using them
+    let lent = Rc::new(RefCell::new(None::<(State, UI)>));
+    {
+        let mut window = state.window.lock().await;
+        let refresh = lent.clone();
+        window.set_refresh_callback(move |window| {
+            if let Ok(mut lent) = refresh.try_borrow_mut()
+                && let Some((state, ui)) = lent.as_mut()
+            {
+                state.live_frame(window, ui);
+            }
+        });
+        let resized = lent.clone();
+        window.set_framebuffer_size_callback(move |window, _, _| {
+            if let Ok(mut lent) = resized.try_borrow_mut()
+                && let Some((state, ui)) = lent.as_mut()
+            {
+                state.live_frame(window, ui);
+            }
+        });
+    }
 
+    let mut last_frame = Instant::now();
     while !state.should_close().await {
-        glfw.poll_events();
+        // a background window, or any window redrawing on demand, sleeps
+        // until an event or a timer while nothing is moving instead of
+        // redrawing every frame
+        let idle = ui.context.clock().animations_suspended()
+            || ui.context.redraw_mode() == RedrawMode::OnDemand
+            || state.suspended;
+        // pending idle work keeps the loop turning over so it gets its time
+        let sleeps = idle && !ui.needs_layout() && !ui.context.has_idle_work();
+        let timeout = sleeps.then(|| {
+            ui.context
+                .next_timer_deadline()
+                .map_or(IDLE_WAKE, |deadline| {
+                    deadline
+                        .saturating_duration_since(Instant::now())
+                        .min(IDLE_WAKE)
+                })
+        });
+        *lent.borrow_mut() = Some((state, ui));
+        match timeout {
+            Some(timeout) => glfw.wait_events_timeout(timeout.as_secs_f64()),
+            None => glfw.poll_events(),
+        }
+        (state, ui) = lent
+            .borrow_mut()
+            .take()
+            .expect("the window callbacks hand the renderer back");
+        // timer callbacks usually change state the tree reads
+        if ui.context.tick() {
+            ui.mark_layout_dirty();
+        }
+        ui.animator.tick(ui.context.clock().now());
+
+        #[cfg(feature = "tray")]
+        if let Some(tray) = &tray {
+            let mut window = state.window.lock().await;
+            if tray.minimize_to_tray && window.should_close() {
+                window.set_should_close(false);
+                window.hide();
+            }
+            for event in tray.poll() {
+                match event {
+                    tray::TrayEvent::Restore => {
+                        window.show();
+                        window.restore();
+                        window.focus();
+                    }
+                    tray::TrayEvent::Quit => window.set_should_close(true),
+                    tray::TrayEvent::MenuItem(id) => log::info!("tray menu item {id} selected"),
+                }
+            }
+        }
 
-        for (_, event) in glfw::flush_messages(&events) {
+        // set when the window is focused this frame, so the click that did it
+        // can be held back on platforms without click through
+        let mut activated = false;
+        // a drag resize queues a size per step, only the last one matters
+        let mut resized = None;
+        let pending: Vec<(f64, glfw::WindowEvent)> = glfw::flush_messages(&events).collect();
+        let input_at = stats::oldest_input(glfw.get_time(), &pending);
+        for coalesce::Coalesced { event, precise } in
+            coalesce::coalesce(pending.into_iter().map(|(_, event)| event))
+        {
+            match event {
+                glfw::WindowEvent::Focus(true) => activated = true,
+                glfw::WindowEvent::MouseButton(_, Action::Press, _) if activated => {
+                    activated = false;
+                    if !ui.context.conventions().click_through {
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            // keys a widget used, like typing in a text input, aren't also
+            // shortcuts
+            let mut handled = false;
+            // summed trackpad deltas can come out whole, so the device is
+            // taken from before they were
+            let translated =
+                Event::from_glfw(&event, ui.context.cursor_pos(), state.layout_scale()).map(
+                    |translated| match translated {
+                        Event::Scroll { delta, .. } => Event::Scroll { delta, precise },
+                        translated => translated,
+                    },
+                );
+            if let Some(translated) = translated {
+                // the console's keys shouldn't reach the page or the window
+                if ui.console_event(&translated) {
+                    continue;
+                }
+                if let Some(on_event) = &mut on_event {
+                    on_event(&mut ui, &translated);
+                }
+                handled = ui.dispatch_event(&translated);
+            }
             match event {
-                glfw::WindowEvent::Close
-                | glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _)
-                | glfw::WindowEvent::Key(Key::Q, _, Action::Press, _) => {
-                    state.window.lock().await.set_should_close(true)
+                glfw::WindowEvent::Close => state.window.lock().await.set_should_close(true),
+                glfw::WindowEvent::Focus(focused) => {
+                    ui.context.set_window_focused(focused);
+                    if focused {
+                        ui.context.reread_system_settings();
+                    }
                 }
                 glfw::WindowEvent::Size(x, y) => {
-                    state.resize((x, y)).await;
-                    ui = build_ui((x, y));
+                    if x > 0 && y > 0 {
+                        state.window_size = (x, y);
+                    }
+                }
+                glfw::WindowEvent::FramebufferSize(x, y) => resized = Some((x, y)),
+                glfw::WindowEvent::Iconify(iconified) => {
+                    let window = state.window.clone();
+                    state.set_suspended(&*window.lock().await, iconified);
+                    ui.set_size(ui.context.orientation().logical_size(state.logical_size()));
                 }
-                _ => {
-                    println!("{:?}", event);
+                glfw::WindowEvent::ContentScale(scale, _) => {
+                    if scale > 0.0 {
+                        state.scale_factor = scale;
+                        ui.set_scale_factor(scale);
+                        ui.set_size(ui.context.orientation().logical_size(state.logical_size()));
+                    }
                 }
+                glfw::WindowEvent::Key(key, _, Action::Press | Action::Repeat, modifiers)
+                    if !handled =>
+                {
+                    if let Some(key) = input::Key::from_glfw(key) {
+                        let modifiers = input::Modifiers::from_glfw(modifiers);
+                        if !ui.context.handle_zoom_key(key, modifiers) {
+                            ui.shortcuts.dispatch(key, modifiers);
+                        }
+                    }
+                }
+                glfw::WindowEvent::CursorPos(x, y) => {
+                    ui.context.cursor_moved((x, y));
+                    if ui.eyedropper.is_active() {
+                        ui.eyedropper.hover(state.to_physical((x, y)));
+                    }
+                }
+                glfw::WindowEvent::MouseButton(glfw::MouseButton::Button1, Action::Press, _)
+                    if ui.eyedropper.is_active() =>
+                {
+                    let cursor = state.window.lock().await.get_cursor_pos();
+                    ui.eyedropper.click(state.to_physical(cursor));
+                }
+                // only passed on to the app, or already used by a widget
+                glfw::WindowEvent::Key(..)
+                | glfw::WindowEvent::CursorEnter(_)
+                | glfw::WindowEvent::Scroll(..)
+                | glfw::WindowEvent::FileDrop(_) => {}
+                _ => log::trace!("unhandled window event {event:?}"),
+            }
+        }
+
+        if let Some(size) = resized {
+            state.resize(size);
+            // layout runs every frame, so the tree only needs the new size
+            ui.set_size(ui.context.orientation().logical_size(state.logical_size()));
+        }
+
+        #[cfg(feature = "global-hotkeys")]
+        if let Some(hotkeys) = &ui.global_hotkeys {
+            hotkeys.poll(&mut ui.shortcuts);
+        }
+
+        #[cfg(feature = "inspector")]
+        if let Some(mut inspector) = ui.inspector.take() {
+            inspector.poll(&mut ui);
+            ui.inspector = Some(inspector);
+        }
+
+        ui.context.apply(&mut *state.window.lock().await);
+        if let Some(orientation) = ui.context.take_orientation_change() {
+            state.set_orientation(orientation);
+            ui.set_size(orientation.logical_size(state.logical_size()));
+        }
+        ui.context.poll_system_settings();
+        if ui.context.take_text_scale_change() {
+            ui.rescale_text();
+        }
+        if let Some(mode) = ui.context.take_present_mode_change() {
+            state.set_present_mode(mode);
+        }
+        if let Some(zoom) = ui.context.take_zoom_change() {
+            state.zoom = zoom;
+            ui.set_size(ui.context.orientation().logical_size(state.logical_size()));
+        }
+        for announcement in ui.context.take_announcements() {
+            log::debug!(
+                "announce ({:?}): {}",
+                announcement.politeness,
+                announcement.text
+            );
+            if let Some(on_announce) = &mut on_announce {
+                on_announce(&announcement);
             }
         }
 
+        if let Some(on_frame) = &mut on_frame {
+            on_frame(&mut ui);
+        }
+
         match state.render(&mut ui) {
             Ok(_) => {}
             Err(e) => eprintln!("{:?}", e),
         }
 
         state.window.lock().await.swap_buffers();
+        ui.stats.record_present(Instant::now(), input_at);
+
+        let interval = ui
+            .context
+            .max_frame_rate()
+            .map_or(FRAME_INTERVAL, |rate| Duration::from_secs_f32(1.0 / rate));
+        if ui.context.has_idle_work() {
+            // nothing's waiting on a window that's sitting idle, so its work
+            // can go on until the loop would have woken up anyway
+            let frame_end = if idle {
+                Instant::now() + IDLE_WAKE
+            } else {
+                last_frame + interval
+            };
+            ui.context.run_idle(frame_end);
+        }
+
+        if ui.context.max_frame_rate().is_some() {
+            tokio::time::sleep_until((last_frame + interval).into()).await;
+        }
+        last_frame = Instant::now();
     }
 
     anyhow::Ok(())
 }
 
-fn build_ui(size: (i32, i32)) -> UI {
-    let mut ui = UI {
-        size: (size.0 * 2, size.1 * 2),
-        ..Default::default()
-    };
-    let mut root = Rectangle {
-        layout_mode: LayoutMode::LeftToRight,
-        sizing: Sizing::GROW,
-        padding: 16,
-        child_gap: 16,
-        color: color::srgb::RED,
-        ..Default::default()
-    };
-
-    let child = Rectangle {
-        sizing: Sizing::GROW,
-        color: color::srgb::GREEN,
-        min_width: 100,
-        max_width: Some(200),
-        ..Default::default()
-    };
-    root.children.push(Arc::new(sync::Mutex::new(child)));
-
-    let child = Rectangle {
-        sizing: Sizing::GROW,
-        color: color::srgb::PURPLE,
-        ..Default::default()
-    };
-    root.children.push(Arc::new(sync::Mutex::new(child)));
+// the demo app, run when `TeacupBuilder` isn't given one
+fn build_ui(_context: &mut UiContext) -> UI {
+    let mut ui = UI::default();
 
-    let child = Rectangle {
-        sizing: Sizing::GROW,
-        color: color::srgb::AQUA,
-        ..Default::default()
-    };
-    root.children.push(Arc::new(sync::Mutex::new(child)));
-
-    let mut child = Rectangle {
-        layout_mode: LayoutMode::TopToBottom,
-        sizing: Sizing::GROW,
-        padding: 16,
-        child_gap: 16,
-        color: color::srgb::BLUE,
-        ..Default::default()
-    };
+    let label =
+        Font::system_default().map(|font| Text::new("teacup", font, 48.0, color::srgb::WHITE));
+    let column = ui! {
+        column(padding: 16, gap: 16, grow, color: color::srgb::BLUE) {
+            rect(grow, min_width: 100, min_height: 50, color: color::srgb::WHITE);
+            rect(grow, min_width: 100, min_height: 50, color: color::srgb::BLACK);
+        }
+    }
+    .children(label);
 
-    let inner = Rectangle {
-        sizing: Sizing::GROW,
-        min_width: 100,
-        min_height: 50,
-        color: color::srgb::WHITE,
-        ..Default::default()
-    };
-    child.children.push(Arc::new(sync::Mutex::new(inner)));
-
-    let inner = Rectangle {
-        sizing: Sizing::GROW,
-        min_width: 100,
-        min_height: 50,
-        color: color::srgb::BLACK,
-        ..Default::default()
+    let root = ui! {
+        row(padding: 16, gap: 16, grow, color: color::srgb::RED) {
+            rect(grow, min_width: 100, max_width: 200).color(color::srgb::GREEN);
+            rect(grow).color(color::srgb::PURPLE);
+            rect(grow).color(color::srgb::AQUA);
+            (column)
+        }
     };
-    child.children.push(Arc::new(sync::Mutex::new(inner)));
-
-    root.children.push(Arc::new(sync::Mutex::new(child)));
-
-    ui.root_item = Arc::new(sync::Mutex::new(root));
+    ui.set_root(root);
 
     ui
 }
@@ -0,0 +1,176 @@
+use crate::{
+    builder::{IntoNode, Node},
+    floating,
+    layout::SizingMode,
+    renderer::draw_list::{self, DrawList},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OverlayId(u64);
+
+// a subtree drawn above the page at a fixed spot, like a popup, tooltip or
+// dropdown. grow sizing fills the window
+pub struct Overlay {
+    pub node: Node,
+    // top left corner in layout pixels. it's pushed back inside the window if
+    // it would hang off the edge
+    pub position: (i32, i32),
+    pub z_index: i32,
+}
+
+impl Overlay {
+    pub fn new(node: impl IntoNode, position: (i32, i32)) -> Self {
+        Self {
+            node: node.into_node(),
+            position,
+            z_index: 0,
+        }
+    }
+
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+}
+
+// everything floating above the root. overlays are laid out on their own,
+// drawn after the page in z order, and hit tested before it, so pointer input
+// should be offered to `at` first
+#[derive(Default)]
+pub struct OverlayLayer {
+    next_id: u64,
+    // kept sorted by z index, then by when they were shown
+    overlays: Vec<(OverlayId, Overlay)>,
+    dirty: bool,
+}
+
+impl OverlayLayer {
+    pub fn show(&mut self, overlay: Overlay) -> OverlayId {
+        let id = OverlayId(self.next_id);
+        self.next_id += 1;
+        let index = self
+            .overlays
+            .partition_point(|(_, shown)| shown.z_index <= overlay.z_index);
+        self.overlays.insert(index, (id, overlay));
+        self.dirty = true;
+        id
+    }
+
+    // returns false when it was already closed
+    pub fn close(&mut self, id: OverlayId) -> bool {
+        let count = self.overlays.len();
+        self.overlays.retain(|(shown, _)| *shown != id);
+        self.overlays.len() != count
+    }
+
+    pub fn clear(&mut self) {
+        self.overlays.clear();
+    }
+
+    pub fn move_to(&mut self, id: OverlayId, position: (i32, i32)) {
+        if let Some(overlay) = self.get_mut(id) {
+            overlay.position = position;
+            self.dirty = true;
+        }
+    }
+
+    pub fn get(&self, id: OverlayId) -> Option<&Overlay> {
+        self.overlays
+            .iter()
+            .find(|(shown, _)| *shown == id)
+            .map(|(_, overlay)| overlay)
+    }
+
+    fn get_mut(&mut self, id: OverlayId) -> Option<&mut Overlay> {
+        self.overlays
+            .iter_mut()
+            .find(|(shown, _)| *shown == id)
+            .map(|(_, overlay)| overlay)
+    }
+
+    pub fn is_open(&self, id: OverlayId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overlays.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.overlays.len()
+    }
+
+    // the topmost overlay under `point`, in layout pixels
+    pub fn at(&self, point: (i32, i32)) -> Option<OverlayId> {
+        self.overlays
+            .iter()
+            .rev()
+            .find(|(_, overlay)| overlay.node.lock().is_ok_and(|prim| prim.contains(point)))
+            .map(|(id, _)| *id)
+    }
+
+    pub(crate) fn nodes(&self) -> Vec<Node> {
+        self.overlays
+            .iter()
+            .map(|(_, overlay)| overlay.node.clone())
+            .collect()
+    }
+
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.dirty
+            || self
+                .overlays
+                .iter()
+                .any(|(_, overlay)| overlay.node.lock().is_ok_and(|prim| prim.is_dirty()))
+    }
+
+    // `force` lays everything out again, e.g. after the window resized
+    pub(crate) fn compute_layout(&mut self, size: (i32, i32), force: bool) {
+        if !std::mem::take(&mut self.dirty) && !force && !self.is_dirty() {
+            return;
+        }
+        for (_, overlay) in &self.overlays {
+            let Ok(mut prim) = overlay.node.lock() else {
+                continue;
+            };
+            prim.clear_dirty();
+            if let Some(container) = prim.as_container() {
+                container.fit_sizing();
+            }
+            // grow fills the window, the overlay has nothing else to grow into
+            if let Some(container) = prim.as_container() {
+                let sizing = container.get_sizing();
+                let grow = (
+                    matches!(sizing.width, SizingMode::Grow),
+                    matches!(sizing.height, SizingMode::Grow),
+                );
+                if grow.0 {
+                    prim.set_width(size.0);
+                }
+                if grow.1 {
+                    prim.set_height(size.1);
+                }
+            }
+            let x = overlay.position.0.min(size.0 - prim.get_width()).max(0);
+            let y = overlay.position.1.min(size.1 - prim.get_height()).max(0);
+            prim.set_position((x, y));
+            if let Some(container) = prim.as_container() {
+                container.grow_sizing();
+                container.set_child_positions();
+            }
+        }
+    }
+
+    pub(crate) fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        for (_, overlay) in &self.overlays {
+            let floating = match overlay.node.lock() {
+                Ok(prim) => {
+                    prim.record(list, size);
+                    floating::floating_nodes(prim.children())
+                }
+                Err(_) => continue,
+            };
+            draw_list::record_floating(&floating, list, size);
+        }
+    }
+}
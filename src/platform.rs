@@ -0,0 +1,307 @@
+use std::{
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+use crate::{input::Modifiers, shortcuts::Shortcut};
+
+// the text size the user picked in the os accessibility settings, as a
+// multiplier. none where the platform has no such setting or it can't be read
+pub fn system_text_scale() -> Option<f32> {
+    native::text_scale()
+}
+
+// the double click speed set for the mouse in the os settings
+pub fn system_double_click_interval() -> Option<Duration> {
+    native::double_click_interval()
+}
+
+// lines one wheel notch scrolls. only windows has a setting for it
+pub fn system_wheel_lines() -> Option<f32> {
+    native::wheel_lines()
+}
+
+#[cfg(target_os = "windows")]
+mod native {
+    use std::{ffi::c_void, ptr, time::Duration};
+
+    use windows_sys::{
+        Win32::{
+            Foundation::ERROR_SUCCESS,
+            System::Registry::{HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RegGetValueW},
+            UI::{
+                Input::KeyboardAndMouse::GetDoubleClickTime,
+                WindowsAndMessaging::{SPI_GETWHEELSCROLLLINES, SystemParametersInfoW},
+            },
+        },
+        w,
+    };
+
+    // what SPI_GETWHEELSCROLLLINES reports for "one screen at a time"
+    const WHEEL_PAGESCROLL: u32 = u32::MAX;
+
+    // a percentage, only there once the slider has been moved
+    pub fn text_scale() -> Option<f32> {
+        let mut percent = 0u32;
+        let mut size = size_of::<u32>() as u32;
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                w!(r"Software\Microsoft\Accessibility"),
+                w!("TextScaleFactor"),
+                RRF_RT_REG_DWORD,
+                ptr::null_mut(),
+                &mut percent as *mut u32 as *mut c_void,
+                &mut size,
+            )
+        };
+        (status == ERROR_SUCCESS).then(|| percent as f32 / 100.0)
+    }
+
+    pub fn double_click_interval() -> Option<Duration> {
+        let millis = unsafe { GetDoubleClickTime() };
+        (millis > 0).then(|| Duration::from_millis(millis.into()))
+    }
+
+    pub fn wheel_lines() -> Option<f32> {
+        let mut lines = 0u32;
+        let ok = unsafe {
+            SystemParametersInfoW(
+                SPI_GETWHEELSCROLLLINES,
+                0,
+                &mut lines as *mut u32 as *mut c_void,
+                0,
+            )
+        };
+        (ok != 0 && lines > 0 && lines != WHEEL_PAGESCROLL).then_some(lines as f32)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod native {
+    use std::time::Duration;
+
+    use objc2_app_kit::NSEvent;
+
+    // only the system ui follows the macos text size
+    pub fn text_scale() -> Option<f32> {
+        None
+    }
+
+    pub fn double_click_interval() -> Option<Duration> {
+        let seconds = NSEvent::doubleClickInterval();
+        (seconds > 0.0).then(|| Duration::from_secs_f64(seconds))
+    }
+
+    pub fn wheel_lines() -> Option<f32> {
+        None
+    }
+}
+
+// gnome keeps its settings in dconf, read through gsettings since there's no
+// library for it worth linking
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod native {
+    use std::{process::Command, time::Duration};
+
+    pub fn text_scale() -> Option<f32> {
+        read_number(&["get", "org.gnome.desktop.interface", "text-scaling-factor"])
+    }
+
+    // in milliseconds
+    pub fn double_click_interval() -> Option<Duration> {
+        let millis = read_number(&["get", "org.gnome.desktop.peripherals.mouse", "double-click"])?;
+        (millis > 0.0).then(|| Duration::from_secs_f32(millis / 1000.0))
+    }
+
+    pub fn wheel_lines() -> Option<f32> {
+        None
+    }
+
+    // runs gsettings and reads the number at the end of what it prints
+    fn read_number(args: &[&str]) -> Option<f32> {
+        let output = Command::new("gsettings").args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .last()?
+            .parse()
+            .ok()
+    }
+}
+
+// the os settings teacup follows. on linux each one is read by running
+// gsettings, slow enough to stall a frame, so they're read together on a
+// thread of their own
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct SystemSettings {
+    pub text_scale: Option<f32>,
+    pub double_click_interval: Option<Duration>,
+    pub wheel_lines: Option<f32>,
+}
+
+impl SystemSettings {
+    pub fn read() -> Self {
+        Self {
+            text_scale: system_text_scale(),
+            double_click_interval: system_double_click_interval(),
+            wheel_lines: system_wheel_lines(),
+        }
+    }
+
+    // hands the settings over once they've been read
+    pub fn read_in_background() -> Receiver<Self> {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(Self::read());
+        });
+        receiver
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    MacOs,
+    Windows,
+    Linux,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollbarVisibility {
+    Always,
+    // overlay scrollbars that fade out when scrolling stops
+    WhileScrolling,
+}
+
+// where the button that confirms a dialog goes relative to the one that
+// dismisses it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonOrder {
+    // [cancel] [ok], macos and gnome
+    AffirmativeLast,
+    // [ok] [cancel], windows
+    AffirmativeFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogRole {
+    Affirmative,
+    Dismissive,
+    // actions like "don't save" that sit apart from the main pair
+    Destructive,
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcceleratorStyle {
+    // ⌃⌥⇧⌘S
+    Symbols,
+    // Ctrl+Shift+S
+    Text,
+}
+
+// the small behaviors that make widgets feel at home on each os. defaults
+// follow the platform teacup was built for, but any of them can be overridden
+// through `UiContext::set_conventions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conventions {
+    pub platform: Platform,
+    pub scrollbars: ScrollbarVisibility,
+    // whether the click that focuses an inactive window also reaches the
+    // widget under the cursor
+    pub click_through: bool,
+    pub accelerators: AcceleratorStyle,
+    // underline access keys in menu and button labels
+    pub mnemonics: bool,
+    pub button_order: ButtonOrder,
+}
+
+impl Default for Conventions {
+    fn default() -> Self {
+        Self::for_platform(Platform::current())
+    }
+}
+
+impl Conventions {
+    pub fn for_platform(platform: Platform) -> Self {
+        match platform {
+            Platform::MacOs => Self {
+                platform,
+                scrollbars: ScrollbarVisibility::WhileScrolling,
+                click_through: false,
+                accelerators: AcceleratorStyle::Symbols,
+                mnemonics: false,
+                button_order: ButtonOrder::AffirmativeLast,
+            },
+            Platform::Windows => Self {
+                platform,
+                scrollbars: ScrollbarVisibility::Always,
+                click_through: true,
+                accelerators: AcceleratorStyle::Text,
+                mnemonics: true,
+                button_order: ButtonOrder::AffirmativeFirst,
+            },
+            Platform::Linux => Self {
+                platform,
+                scrollbars: ScrollbarVisibility::WhileScrolling,
+                click_through: true,
+                accelerators: AcceleratorStyle::Text,
+                mnemonics: true,
+                button_order: ButtonOrder::AffirmativeLast,
+            },
+        }
+    }
+
+    pub fn format_shortcut(&self, shortcut: &Shortcut) -> String {
+        match self.accelerators {
+            AcceleratorStyle::Text => shortcut.to_string(),
+            AcceleratorStyle::Symbols => {
+                let modifiers = shortcut.modifiers;
+                let mut text = String::new();
+                for (held, symbol) in [
+                    (modifiers.control, '⌃'),
+                    (modifiers.alt, '⌥'),
+                    (modifiers.shift, '⇧'),
+                    (modifiers.super_key, '⌘'),
+                ] {
+                    if held {
+                        text.push(symbol);
+                    }
+                }
+                let key = Shortcut::new(shortcut.key, Modifiers::NONE).to_string();
+                text.push_str(&key);
+                text
+            }
+        }
+    }
+
+    // sorts dialog buttons into the order this platform expects, reading left
+    // to right. help always leads and destructive actions stay next to it,
+    // away from the main pair
+    pub fn arrange_buttons<T>(&self, mut buttons: Vec<(DialogRole, T)>) -> Vec<T> {
+        let rank = |role: DialogRole| match (role, self.button_order) {
+            (DialogRole::Help, _) => 0,
+            (DialogRole::Destructive, _) => 1,
+            (DialogRole::Dismissive, ButtonOrder::AffirmativeLast)
+            | (DialogRole::Affirmative, ButtonOrder::AffirmativeFirst) => 2,
+            (DialogRole::Affirmative, ButtonOrder::AffirmativeLast)
+            | (DialogRole::Dismissive, ButtonOrder::AffirmativeFirst) => 3,
+        };
+        buttons.sort_by_key(|(role, _)| rank(*role));
+        buttons.into_iter().map(|(_, button)| button).collect()
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+// small user settings kept between runs, like the zoom level. stored as
+// `key = value` lines in the file given by `WindowOptions::preferences_path`,
+// or only in memory without one. every change is written straight away since
+// they're rare
+#[derive(Debug, Default)]
+pub struct Preferences {
+    path: Option<PathBuf>,
+    values: BTreeMap<String, String>,
+}
+
+impl Preferences {
+    // a missing file is just empty preferences. lines that don't parse are
+    // skipped
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let values = match std::fs::read_to_string(path) {
+            Ok(text) => text
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .filter(|(key, _)| !key.is_empty())
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                log::warn!("couldn't read preferences from {}: {e}", path.display());
+                BTreeMap::new()
+            }
+        };
+        Self {
+            path: Some(path.to_path_buf()),
+            values,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key)?.parse().ok()
+    }
+
+    pub fn set(&mut self, key: &str, value: impl ToString) {
+        let value = value.to_string();
+        if self.get(key) == Some(value.as_str()) {
+            return;
+        }
+        self.values.insert(key.to_string(), value);
+        self.save_or_log();
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.values.remove(key).is_some() {
+            self.save_or_log();
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text: String = self
+            .values
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}\n"))
+            .collect();
+        std::fs::write(path, text)
+    }
+
+    fn save_or_log(&self) {
+        if let Err(e) = self.save() {
+            log::warn!("couldn't save preferences: {e}");
+        }
+    }
+}
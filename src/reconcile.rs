@@ -0,0 +1,97 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    panic::Location,
+    sync::Arc,
+};
+
+use crate::{builder::Node, layout::Primative};
+
+// a primitive's identity across rebuilds of the tree. either a key the app
+// picked or the place in the source the node was declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(u64);
+
+impl NodeId {
+    pub fn new(key: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        Self(hasher.finish())
+    }
+
+    // the call site of whoever called this, through any `#[track_caller]`
+    // functions in between. the same line in a loop gives the same id, which
+    // `reconcile` tells apart by order
+    #[track_caller]
+    pub fn caller() -> Self {
+        let location = Location::caller();
+        Self::new((location.file(), location.line(), location.column()))
+    }
+
+    pub fn raw(self) -> u64 {
+        self.0
+    }
+}
+
+// what a child is matched on. ids repeat when a declaration sits in a loop, so
+// each is numbered by how many times it came up before. nodes without one match
+// the same type at the same place among their unkeyed siblings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    Id(NodeId, usize),
+    Kind(&'static str, usize),
+}
+
+fn keys(children: &[Node]) -> Vec<Option<Key>> {
+    let mut seen: HashMap<Key, usize> = HashMap::new();
+    children
+        .iter()
+        .map(|child| {
+            let child = child.lock().ok()?;
+            let base = match child.id() {
+                Some(id) => Key::Id(id, 0),
+                None => Key::Kind(child.kind(), 0),
+            };
+            let count = seen.entry(base).or_default();
+            let key = match base {
+                Key::Id(id, _) => Key::Id(id, *count),
+                Key::Kind(kind, _) => Key::Kind(kind, *count),
+            };
+            *count += 1;
+            Some(key)
+        })
+        .collect()
+}
+
+// carries state from `previous` into `next` when they're the same node, then
+// does the same for their children
+pub(crate) fn reconcile_node(previous: &mut dyn Primative, next: &mut dyn Primative) {
+    if previous.kind() != next.kind() || previous.id() != next.id() {
+        return;
+    }
+    next.adopt_state(previous);
+    let (old, new) = (previous.children().to_vec(), next.children().to_vec());
+    reconcile(&old, &new);
+}
+
+// matches each new child with the old child it replaces and hands over its
+// state. old children nothing matches are dropped with whatever they held
+pub(crate) fn reconcile(old: &[Node], new: &[Node]) {
+    let mut previous: HashMap<Key, &Node> = keys(old)
+        .into_iter()
+        .zip(old)
+        .filter_map(|(key, node)| Some((key?, node)))
+        .collect();
+    for (key, node) in keys(new).into_iter().zip(new) {
+        let Some(old_node) = key.and_then(|key| previous.remove(&key)) else {
+            continue;
+        };
+        // a node kept from last time, nothing to move
+        if Arc::ptr_eq(old_node, node) {
+            continue;
+        }
+        if let (Ok(mut old_node), Ok(mut node)) = (old_node.lock(), node.lock()) {
+            reconcile_node(&mut *old_node, &mut *node);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use tinycolors::srgb;
+
+// linear display-p3 to linear srgb, row major
+const P3_TO_SRGB: [[f32; 3]; 3] = [
+    [1.224_940_1, -0.224_940_4, 0.0],
+    [-0.042_056_9, 1.042_057_1, 0.0],
+    [-0.019_637_6, -0.078_636_1, 1.098_273_5],
+];
+
+const SRGB_TO_P3: [[f32; 3]; 3] = [
+    [0.822_462_1, 0.177_538, 0.0],
+    [0.033_194_1, 0.966_805_8, 0.0],
+    [0.017_082_7, 0.072_397_4, 0.910_519_9],
+];
+
+// the space a color's components are expressed in. the tag travels with every
+// vertex so the shader can convert to whatever the surface expects
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ColorSpace {
+    #[default]
+    Srgb = 0,
+    // same transfer curve as srgb, wider primaries
+    DisplayP3 = 1,
+    LinearSrgb = 2,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub space: ColorSpace,
+}
+
+impl Color {
+    pub fn new(r: f32, g: f32, b: f32, space: ColorSpace) -> Self {
+        Self { r, g, b, space }
+    }
+
+    pub fn display_p3(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, ColorSpace::DisplayP3)
+    }
+
+    // an hdr version of this color, `brightness` times as bright as sdr white.
+    // shown as is on an hdr surface and tone mapped everywhere else
+    pub fn with_brightness(&self, brightness: f32) -> Self {
+        let [r, g, b] = self.to_linear_srgb().map(|c| c * brightness.max(0.0));
+        Self::new(r, g, b, ColorSpace::LinearSrgb)
+    }
+
+    pub fn components(&self) -> [f32; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    // linear srgb primaries. p3 colors outside the srgb gamut come out negative
+    // or above one, which only an extended srgb surface can show
+    pub fn to_linear_srgb(&self) -> [f32; 3] {
+        let c = self.components();
+        match self.space {
+            ColorSpace::Srgb => c.map(srgb_to_linear),
+            ColorSpace::DisplayP3 => mul(&P3_TO_SRGB, c.map(srgb_to_linear)),
+            ColorSpace::LinearSrgb => c,
+        }
+    }
+
+    // clamped to what srgb can show, for outputs that don't take anything else
+    pub fn to_srgb(&self) -> [f32; 3] {
+        self.to_linear_srgb()
+            .map(|c| linear_to_srgb(c).clamp(0.0, 1.0))
+    }
+}
+
+impl ColorSpace {
+    // the tag on a vertex, see `Vertex::color_space`
+    pub fn from_tag(tag: u32) -> Self {
+        match tag {
+            1 => ColorSpace::DisplayP3,
+            2 => ColorSpace::LinearSrgb,
+            _ => ColorSpace::Srgb,
+        }
+    }
+}
+
+impl From<srgb> for Color {
+    fn from(color: srgb) -> Self {
+        Self::new(color.r, color.g, color.b, ColorSpace::Srgb)
+    }
+}
+
+// what the surface's pixels mean to the compositor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputColorSpace {
+    #[default]
+    Srgb,
+    // for displays that show untagged surfaces in their native p3 gamut, like
+    // most recent macs
+    DisplayP3,
+    // linear, unclamped srgb in a half float surface (scRGB)
+    ExtendedSrgb,
+    // scRGB with sdr content lifted to the paper white level, so elements with
+    // a brightness above one light up past it
+    Hdr,
+}
+
+impl OutputColorSpace {
+    // the surface format to ask for, in order of preference
+    pub fn pick_format(self, formats: &[wgpu::TextureFormat]) -> Option<wgpu::TextureFormat> {
+        match self {
+            OutputColorSpace::ExtendedSrgb | OutputColorSpace::Hdr => formats
+                .iter()
+                .copied()
+                .find(|f| *f == wgpu::TextureFormat::Rgba16Float),
+            OutputColorSpace::Srgb | OutputColorSpace::DisplayP3 => {
+                formats.iter().copied().find(|f| f.is_srgb())
+            }
+        }
+    }
+}
+
+// scRGB defines 1.0 as 80 nits
+pub const SCRGB_NITS: f32 = 80.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrSettings {
+    // how bright sdr white is drawn. 203 is the itu reference level
+    pub paper_white_nits: f32,
+    // brightest the display should be asked for; brighter elements get clipped
+    // to this while keeping their hue
+    pub peak_nits: f32,
+}
+
+impl Default for HdrSettings {
+    fn default() -> Self {
+        Self {
+            paper_white_nits: 203.0,
+            peak_nits: 1000.0,
+        }
+    }
+}
+
+// how colors get from linear srgb to the bytes in a particular surface
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ColorOutput {
+    pub space: OutputColorSpace,
+    // set when the surface format doesn't apply the srgb curve itself
+    pub encode: bool,
+    pub hdr: HdrSettings,
+}
+
+impl ColorOutput {
+    pub fn new(space: OutputColorSpace, format: wgpu::TextureFormat, hdr: HdrSettings) -> Self {
+        let linear_format = matches!(
+            format,
+            wgpu::TextureFormat::Rgba16Float | wgpu::TextureFormat::Rgba32Float
+        );
+        Self {
+            space,
+            encode: !format.is_srgb() && !linear_format,
+            hdr,
+        }
+    }
+
+    // values to write for `color`, matching what the mesh shader does per fragment
+    pub fn convert(&self, color: Color) -> [f32; 3] {
+        let mut c = color.to_linear_srgb();
+        if self.space == OutputColorSpace::DisplayP3 {
+            c = mul(&SRGB_TO_P3, c);
+        }
+        match self.space {
+            OutputColorSpace::ExtendedSrgb => {}
+            OutputColorSpace::Hdr => {
+                c = c.map(|v| v * self.hdr.paper_white_nits / SCRGB_NITS);
+                c = limit_peak(c, self.hdr.peak_nits / SCRGB_NITS);
+            }
+            OutputColorSpace::Srgb | OutputColorSpace::DisplayP3 => {
+                c = limit_peak(c, 1.0).map(|v| v.clamp(0.0, 1.0));
+            }
+        }
+        if self.encode {
+            c = c.map(linear_to_srgb);
+        }
+        c
+    }
+
+    // pipeline overridable constants for shaders that convert on the gpu
+    pub fn constants(&self) -> [(&'static str, f64); 4] {
+        let space = match self.space {
+            OutputColorSpace::Srgb => 0.0,
+            OutputColorSpace::DisplayP3 => 1.0,
+            OutputColorSpace::ExtendedSrgb => 2.0,
+            OutputColorSpace::Hdr => 3.0,
+        };
+        [
+            ("OUTPUT_SPACE", space),
+            ("ENCODE_OUTPUT", if self.encode { 1.0 } else { 0.0 }),
+            (
+                "PAPER_WHITE",
+                (self.hdr.paper_white_nits / SCRGB_NITS) as f64,
+            ),
+            ("PEAK", (self.hdr.peak_nits / SCRGB_NITS) as f64),
+        ]
+    }
+}
+
+// tone maps values over `peak` by scaling the whole color down, so bright
+// colors clip to the same hue instead of drifting toward white
+fn limit_peak(c: [f32; 3], peak: f32) -> [f32; 3] {
+    let max = c[0].max(c[1]).max(c[2]);
+    if max > peak {
+        c.map(|v| v * peak / max)
+    } else {
+        c
+    }
+}
+
+// mirrored around zero so extended values survive the round trip
+pub fn srgb_to_linear(v: f32) -> f32 {
+    let a = v.abs();
+    let linear = if a <= 0.04045 {
+        a / 12.92
+    } else {
+        ((a + 0.055) / 1.055).powf(2.4)
+    };
+    linear.copysign(v)
+}
+
+pub fn linear_to_srgb(v: f32) -> f32 {
+    let a = v.abs();
+    let encoded = if a <= 0.003_130_8 {
+        a * 12.92
+    } else {
+        1.055 * a.powf(1.0 / 2.4) - 0.055
+    };
+    encoded.copysign(v)
+}
+
+fn mul(m: &[[f32; 3]; 3], c: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * c[0] + row[1] * c[1] + row[2] * c[2])
+}
@@ -0,0 +1,499 @@
+use std::sync::Arc;
+
+use crate::{
+    builder::Node,
+    context::FrameClock,
+    layout::LAYOUT_SCALE,
+    renderer::{
+        color::{Color, ColorSpace},
+        frame::Frame,
+        image::ImageData,
+        mesh_builder::{Mesh, make_ss_polygon, make_ss_rounded_rectangle, ss_to_ndc},
+        particles::ParticleInstance,
+        skeleton::SkeletonInstance,
+        text::Font,
+    },
+};
+
+// one step of drawing the ui, in layout pixels
+#[derive(Clone)]
+pub enum DrawCommand {
+    // colored triangles from `Primative::get_mesh`, built against the list's
+    // `mesh_size`
+    Mesh(Arc<Mesh>),
+    // meshes up to the next of these are drawn with the custom pipeline
+    // registered under this name, or the built in one for none. backends
+    // without custom shaders draw them plainly
+    Pipeline(Option<String>),
+    // a run of text with its top left at `origin`. `px` is already scaled, see
+    // `text::scaled`
+    Text {
+        font: Arc<Font>,
+        text: String,
+        px: f32,
+        origin: (f32, f32),
+        color: Color,
+    },
+    Image {
+        image: Arc<ImageData>,
+        position: (i32, i32),
+        extent: (i32, i32),
+        brightness: f32,
+        opacity: f32,
+    },
+    // the `uv` rect of an atlas, given as [u0, v0, u1, v1], multiplied by
+    // `tint`. for icon sets
+    ImageRegion {
+        image: Arc<ImageData>,
+        uv: [f32; 4],
+        position: (f32, f32),
+        extent: (f32, f32),
+        tint: Color,
+        opacity: f32,
+    },
+    // loading placeholders, shimmering on the gpu
+    Skeletons(Vec<SkeletonInstance>),
+    // particles as of the list's clock, which their spawn times are on
+    Particles(Vec<ParticleInstance>),
+    // the box a `RenderPlugin` draws into. only the gpu can run plugins
+    Plugin {
+        name: String,
+        position: (i32, i32),
+        extent: (i32, i32),
+    },
+    // everything up to the matching pop is cut to this box, inside any clip
+    // already pushed
+    PushClip {
+        position: (i32, i32),
+        extent: (i32, i32),
+    },
+    PopClip,
+    // everything up to the matching pop is moved by `offset`, on top of any
+    // transform already pushed
+    PushTransform {
+        offset: (f32, f32),
+    },
+    PopTransform,
+}
+
+// what the ui draws, in order, with nothing tied to the gpu. recorded from the
+// tree with `UI::draw_list` and played back by a backend: `replay` for wgpu,
+// the software and gl renderers, the svg and pdf exporters, or a test checking
+// what was drawn
+#[derive(Clone)]
+pub struct DrawList {
+    // the layout size the list was recorded at
+    pub size: (i32, i32),
+    // the frame being recorded, for what animates or dims while the window is
+    // in the background
+    pub clock: FrameClock,
+    pub window_focused: bool,
+    pub commands: Vec<DrawCommand>,
+}
+
+impl Default for DrawList {
+    fn default() -> Self {
+        Self::new((0, 0))
+    }
+}
+
+impl DrawList {
+    pub fn new(size: (i32, i32)) -> Self {
+        Self {
+            size,
+            clock: FrameClock::default(),
+            window_focused: true,
+            commands: Vec::new(),
+        }
+    }
+
+    // what meshes in the list are built against, see `ss_to_ndc`
+    pub fn mesh_size(&self) -> (i32, i32) {
+        (self.size.0 / LAYOUT_SCALE, self.size.1 / LAYOUT_SCALE)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DrawCommand> {
+        self.commands.iter()
+    }
+
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    // empty meshes are dropped
+    pub fn mesh(&mut self, mesh: impl Into<Arc<Mesh>>) {
+        let mesh = mesh.into();
+        if !mesh.indices.is_empty() {
+            self.push(DrawCommand::Mesh(mesh));
+        }
+    }
+
+    pub fn pipeline(&mut self, name: Option<&str>) {
+        self.push(DrawCommand::Pipeline(name.map(str::to_string)));
+    }
+
+    pub fn text(
+        &mut self,
+        font: &Arc<Font>,
+        text: &str,
+        px: f32,
+        origin: (f32, f32),
+        color: impl Into<Color>,
+    ) {
+        if !text.is_empty() {
+            self.push(DrawCommand::Text {
+                font: font.clone(),
+                text: text.to_string(),
+                px,
+                origin,
+                color: color.into(),
+            });
+        }
+    }
+
+    pub fn image(
+        &mut self,
+        image: &Arc<ImageData>,
+        position: (i32, i32),
+        extent: (i32, i32),
+        brightness: f32,
+        opacity: f32,
+    ) {
+        self.push(DrawCommand::Image {
+            image: image.clone(),
+            position,
+            extent,
+            brightness,
+            opacity,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn image_region(
+        &mut self,
+        image: &Arc<ImageData>,
+        uv: [f32; 4],
+        position: (f32, f32),
+        extent: (f32, f32),
+        tint: impl Into<Color>,
+        opacity: f32,
+    ) {
+        self.push(DrawCommand::ImageRegion {
+            image: image.clone(),
+            uv,
+            position,
+            extent,
+            tint: tint.into(),
+            opacity,
+        });
+    }
+
+    pub fn skeletons(&mut self, blocks: Vec<SkeletonInstance>) {
+        if !blocks.is_empty() {
+            self.push(DrawCommand::Skeletons(blocks));
+        }
+    }
+
+    pub fn particles(&mut self, particles: Vec<ParticleInstance>) {
+        if !particles.is_empty() {
+            self.push(DrawCommand::Particles(particles));
+        }
+    }
+
+    pub fn plugin(&mut self, name: &str, position: (i32, i32), extent: (i32, i32)) {
+        self.push(DrawCommand::Plugin {
+            name: name.to_string(),
+            position,
+            extent,
+        });
+    }
+
+    pub fn push_clip(&mut self, position: (i32, i32), extent: (i32, i32)) {
+        self.push(DrawCommand::PushClip { position, extent });
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.push(DrawCommand::PopClip);
+    }
+
+    pub fn push_transform(&mut self, offset: (f32, f32)) {
+        self.push(DrawCommand::PushTransform { offset });
+    }
+
+    pub fn pop_transform(&mut self) {
+        self.push(DrawCommand::PopTransform);
+    }
+
+    // draws the list with the gpu. this is how the ui reaches the screen, see
+    // `UI::draw`
+    pub fn replay(&self, render_pass: &mut wgpu::RenderPass, frame: &mut Frame) {
+        let size = self.mesh_size();
+        let mut offsets = vec![(0.0f32, 0.0f32)];
+        for command in &self.commands {
+            let offset = offsets.last().copied().unwrap_or_default();
+            let moved = |(x, y): (i32, i32)| (x + offset.0 as i32, y + offset.1 as i32);
+            match command {
+                DrawCommand::Mesh(mesh) if offset == (0.0, 0.0) => mesh.draw(render_pass, frame),
+                DrawCommand::Mesh(mesh) => {
+                    let (origin, moved) = (ss_to_ndc((0.0, 0.0), size), ss_to_ndc(offset, size));
+                    let mut mesh = Mesh {
+                        verticies: mesh.verticies.clone(),
+                        indices: mesh.indices.clone(),
+                    };
+                    for vertex in &mut mesh.verticies {
+                        vertex.position[0] += moved.0 - origin.0;
+                        vertex.position[1] += moved.1 - origin.1;
+                    }
+                    mesh.draw(render_pass, frame);
+                }
+                DrawCommand::Pipeline(name) => frame.use_pipeline(render_pass, name.as_deref()),
+                DrawCommand::Text {
+                    font,
+                    text,
+                    px,
+                    origin,
+                    color,
+                } => frame.draw_text(
+                    render_pass,
+                    font,
+                    text,
+                    *px,
+                    (origin.0 + offset.0, origin.1 + offset.1),
+                    *color,
+                    size,
+                ),
+                DrawCommand::Image {
+                    image,
+                    position,
+                    extent,
+                    brightness,
+                    opacity,
+                } => frame.draw_image(
+                    render_pass,
+                    image,
+                    moved(*position),
+                    *extent,
+                    *brightness,
+                    *opacity,
+                    size,
+                ),
+                DrawCommand::ImageRegion {
+                    image,
+                    uv,
+                    position,
+                    extent,
+                    tint,
+                    opacity,
+                } => frame.draw_image_region(
+                    render_pass,
+                    image,
+                    *uv,
+                    (position.0 + offset.0, position.1 + offset.1),
+                    *extent,
+                    *tint,
+                    *opacity,
+                    size,
+                ),
+                DrawCommand::Skeletons(blocks) => {
+                    let mut blocks = blocks.clone();
+                    for block in &mut blocks {
+                        block.position[0] += offset.0;
+                        block.position[1] += offset.1;
+                    }
+                    frame.draw_skeletons(render_pass, &blocks, size);
+                }
+                DrawCommand::Particles(particles) => {
+                    let mut particles = particles.clone();
+                    for particle in &mut particles {
+                        particle.origin[0] += offset.0;
+                        particle.origin[1] += offset.1;
+                    }
+                    frame.draw_particles(render_pass, &particles, size);
+                }
+                DrawCommand::Plugin {
+                    name,
+                    position,
+                    extent,
+                } => frame.draw_plugin(render_pass, name, moved(*position), *extent, size),
+                DrawCommand::PushClip { position, extent } => {
+                    frame.push_clip(render_pass, moved(*position), *extent, size)
+                }
+                DrawCommand::PopClip => frame.pop_clip(render_pass),
+                DrawCommand::PushTransform { offset: by } => {
+                    offsets.push((offset.0 + by.0, offset.1 + by.1));
+                }
+                DrawCommand::PopTransform => {
+                    if offsets.len() > 1 {
+                        offsets.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl DrawList {
+    // the list in the commands every backend has, for the ones without the
+    // gpu's own pipelines: skeletons become flat blocks in their base color,
+    // particles squares where the list's clock has them, and atlas regions
+    // images of their own. custom pipelines draw as plain meshes and plugins
+    // are left out
+    pub(crate) fn lowered(&self) -> Vec<DrawCommand> {
+        let size = self.mesh_size();
+        let time = self.clock.elapsed().as_secs_f32();
+        let mut lowered = Vec::with_capacity(self.commands.len());
+        for command in &self.commands {
+            match command {
+                DrawCommand::Pipeline(_) | DrawCommand::Plugin { .. } => {}
+                DrawCommand::Skeletons(blocks) => {
+                    let mut mesh = Mesh::default();
+                    for block in blocks {
+                        mesh.append(make_ss_rounded_rectangle(
+                            block.position[0].round() as i32,
+                            block.position[1].round() as i32,
+                            block.size[0].round() as i32,
+                            block.size[1].round() as i32,
+                            block.radius,
+                            Color::new(
+                                block.base[0],
+                                block.base[1],
+                                block.base[2],
+                                ColorSpace::Srgb,
+                            ),
+                            size,
+                        ));
+                    }
+                    lowered.push(DrawCommand::Mesh(Arc::new(mesh)));
+                }
+                DrawCommand::Particles(particles) => {
+                    let mut mesh = Mesh::default();
+                    for particle in particles {
+                        let Some((corners, color, alpha)) = particle.quad(time) else {
+                            continue;
+                        };
+                        let center = (
+                            corners.iter().map(|p| p.0).sum::<f32>() / 4.0,
+                            corners.iter().map(|p| p.1).sum::<f32>() / 4.0,
+                        );
+                        let mut quad = make_ss_polygon(center, &corners, color, size);
+                        quad.multiply_alpha(alpha);
+                        mesh.append(quad);
+                    }
+                    lowered.push(DrawCommand::Mesh(Arc::new(mesh)));
+                }
+                DrawCommand::ImageRegion {
+                    image,
+                    uv,
+                    position,
+                    extent,
+                    tint,
+                    opacity,
+                } => {
+                    if let Some(region) = image.region(*uv, *tint) {
+                        lowered.push(DrawCommand::Image {
+                            image: region,
+                            position: (position.0.round() as i32, position.1.round() as i32),
+                            extent: (extent.0.round() as i32, extent.1.round() as i32),
+                            brightness: 1.0,
+                            opacity: *opacity,
+                        });
+                    }
+                }
+                command => lowered.push(command.clone()),
+            }
+        }
+        lowered
+    }
+}
+
+// a triangle of a mesh back in layout pixels, colored with the average of its
+// corners in 0 to 1 srgb. for backends that can't take meshes as they are
+pub(crate) struct Triangle {
+    pub points: [(f32, f32); 3],
+    pub color: [f32; 3],
+    pub alpha: f32,
+}
+
+impl Triangle {
+    pub fn rgba8(&self) -> [u8; 4] {
+        rgba8(self.color, self.alpha)
+    }
+}
+
+// as 8 bit color, for telling when two triangles can be filled as one shape
+pub(crate) fn rgba8(color: [f32; 3], alpha: f32) -> [u8; 4] {
+    let [r, g, b] = color;
+    [r, g, b, alpha].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+// the triangles of `mesh`, built against `size`, leaving out invisible ones
+pub(crate) fn triangles(mesh: &Mesh, size: (i32, i32)) -> impl Iterator<Item = Triangle> + '_ {
+    let (w, h) = (size.0 as f32, size.1 as f32);
+    mesh.indices.chunks_exact(3).filter_map(move |triangle| {
+        let verticies = triangle
+            .iter()
+            .map(|i| mesh.verticies.get(*i as usize))
+            .collect::<Option<Vec<_>>>()?;
+        let alpha = verticies.iter().map(|v| v.alpha).sum::<f32>() / 3.0;
+        if alpha <= 0.0 {
+            return None;
+        }
+        let mut color = [0.0; 3];
+        for vertex in &verticies {
+            for (c, v) in color.iter_mut().zip(vertex.color) {
+                *c += v / 3.0;
+            }
+        }
+        let space = ColorSpace::from_tag(verticies[0].color_space);
+        // back from normalized device coordinates, see `ss_to_ndc`
+        let point = |i: usize| {
+            let [x, y, _] = verticies[i].position;
+            ((x + 1.0) * w, (1.0 - y) * h)
+        };
+        Some(Triangle {
+            points: [point(0), point(1), point(2)],
+            color: Color::new(color[0], color[1], color[2], space).to_srgb(),
+            alpha,
+        })
+    })
+}
+
+// records `children` with floating ones left out, they go above the page, and
+// the rest in z order
+pub(crate) fn record_children(children: &[Node], list: &mut DrawList, size: (i32, i32)) {
+    let mut children: Vec<&Node> = children
+        .iter()
+        .filter(|child| {
+            child
+                .lock()
+                .is_ok_and(|prim| !prim.positioning().is_floating())
+        })
+        .collect();
+    // a stable sort, so tree order breaks ties
+    children.sort_by_cached_key(|child| child.lock().map_or(0, |prim| prim.z_index()));
+    for child in children {
+        if let Ok(prim) = child.lock() {
+            prim.record(list, size);
+        }
+    }
+}
+
+// floating elements are left out when their parent records, and recorded here
+// after everything else in z order
+pub(crate) fn record_floating(nodes: &[Node], list: &mut DrawList, size: (i32, i32)) {
+    let mut nodes: Vec<&Node> = nodes.iter().collect();
+    nodes.sort_by_cached_key(|node| node.lock().map_or(0, |prim| prim.z_index()));
+    for node in nodes {
+        if let Ok(prim) = node.lock() {
+            prim.record(list, size);
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use std::ops::Range;
+
+use crate::{
+    context::FrameClock,
+    layout::LAYOUT_SCALE,
+    renderer::{
+        color::Color,
+        image::{ImageData, ImageRenderer},
+        mesh_builder::Mesh,
+        particles::{ParticleInstance, ParticleRenderer},
+        plugin::{PluginRegistry, PluginViewport},
+        projection::Orientation,
+        shaders::PipelineRegistry,
+        skeleton::{SkeletonInstance, SkeletonRenderer},
+        text::{Font, TextRenderer},
+    },
+};
+
+// smallest allocation so the first few frames don't reallocate repeatedly
+const MIN_BUFFER_SIZE: u64 = 64 * 1024;
+
+// a gpu buffer that lives across frames. data is written through
+// Queue::write_buffer at a moving cursor so every mesh drawn in a frame gets its
+// own region, and the buffer doubles in size when a frame outgrows it
+pub struct DynamicBuffer {
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    buffer: Option<wgpu::Buffer>,
+    capacity: u64,
+    cursor: u64,
+}
+
+impl DynamicBuffer {
+    pub fn new(label: &'static str, usage: wgpu::BufferUsages) -> Self {
+        Self {
+            label,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            buffer: None,
+            capacity: 0,
+            cursor: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+    }
+
+    // returns the byte range the data was written to
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[u8]) -> Range<u64> {
+        let size = (data.len() as u64).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+        if self.buffer.is_none() || self.cursor + size > self.capacity {
+            // draws already recorded this frame keep the old buffer alive, so the
+            // new one can start from the beginning
+            self.capacity = (self.capacity * 2)
+                .max(size.next_power_of_two())
+                .max(MIN_BUFFER_SIZE);
+            self.buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(self.label),
+                size: self.capacity,
+                usage: self.usage,
+                mapped_at_creation: false,
+            }));
+            self.cursor = 0;
+        }
+
+        let buffer = self.buffer.as_ref().expect("buffer allocated above");
+        if size == data.len() as u64 {
+            queue.write_buffer(buffer, self.cursor, data);
+        } else {
+            let mut padded = data.to_vec();
+            padded.resize(size as usize, 0);
+            queue.write_buffer(buffer, self.cursor, &padded);
+        }
+
+        let range = self.cursor..self.cursor + data.len() as u64;
+        self.cursor += size;
+        range
+    }
+
+    pub fn slice(&self, range: Range<u64>) -> wgpu::BufferSlice<'_> {
+        self.buffer
+            .as_ref()
+            .expect("slice called before write")
+            .slice(range)
+    }
+}
+
+pub struct FrameBuffers {
+    pub verticies: DynamicBuffer,
+    pub indices: DynamicBuffer,
+}
+
+impl Default for FrameBuffers {
+    fn default() -> Self {
+        Self {
+            verticies: DynamicBuffer::new("vertex buffer", wgpu::BufferUsages::VERTEX),
+            indices: DynamicBuffer::new("index buffer", wgpu::BufferUsages::INDEX),
+        }
+    }
+}
+
+// a scissor rect in physical pixels of the render target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ClipRect {
+    pub fn intersect(&self, other: &ClipRect) -> ClipRect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        ClipRect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+// everything a draw call needs, threaded through the ui tree each frame
+pub struct Frame<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub buffers: &'a mut FrameBuffers,
+    pub text: &'a mut TextRenderer,
+    pub images: &'a mut ImageRenderer,
+    pub particles: &'a mut ParticleRenderer,
+    pub skeletons: &'a mut SkeletonRenderer,
+    // the mesh pipeline, rebound after anything that switches pipelines
+    pub pipeline: &'a wgpu::RenderPipeline,
+    pub pipelines: &'a PipelineRegistry,
+    // physical size of the render target
+    pub target_size: (u32, u32),
+    // surface pixels per point
+    pub scale_factor: f32,
+    pub orientation: Orientation,
+    pub clock: FrameClock,
+    // render plugins, and the projection to bind again after one draws
+    plugins: Option<(&'a mut PluginRegistry, &'a wgpu::BindGroup)>,
+    clip_stack: Vec<ClipRect>,
+    // geometry waiting to be drawn in one call, and the custom pipeline it's
+    // drawn with if not the mesh pipeline
+    batch: Mesh,
+    batch_pipeline: Option<&'a wgpu::RenderPipeline>,
+    draw_calls: u32,
+}
+
+impl<'a> Frame<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &'a wgpu::Device,
+        queue: &'a wgpu::Queue,
+        buffers: &'a mut FrameBuffers,
+        text: &'a mut TextRenderer,
+        images: &'a mut ImageRenderer,
+        particles: &'a mut ParticleRenderer,
+        skeletons: &'a mut SkeletonRenderer,
+        pipeline: &'a wgpu::RenderPipeline,
+        pipelines: &'a PipelineRegistry,
+        target_size: (u32, u32),
+        scale_factor: f32,
+        orientation: Orientation,
+        clock: FrameClock,
+    ) -> Self {
+        buffers.verticies.reset();
+        buffers.indices.reset();
+        text.reset();
+        images.reset();
+        particles.reset();
+        skeletons.reset();
+        Self {
+            device,
+            queue,
+            buffers,
+            text,
+            images,
+            particles,
+            skeletons,
+            pipeline,
+            pipelines,
+            target_size,
+            scale_factor,
+            orientation,
+            clock,
+            plugins: None,
+            clip_stack: Vec::new(),
+            batch: Mesh::default(),
+            batch_pipeline: None,
+            draw_calls: 0,
+        }
+    }
+
+    // lets `PluginView`s draw. without it they draw nothing
+    pub fn set_plugins(
+        &mut self,
+        plugins: &'a mut PluginRegistry,
+        projection: &'a wgpu::BindGroup,
+    ) {
+        self.plugins = Some((plugins, projection));
+    }
+
+    pub fn push_mesh(&mut self, mesh: &Mesh) {
+        let offset = self.batch.verticies.len() as u32;
+        self.batch.verticies.extend_from_slice(&mesh.verticies);
+        self.batch
+            .indices
+            .extend(mesh.indices.iter().map(|i| i + offset));
+    }
+
+    // draws everything batched so far. anything that switches pipelines or
+    // changes the scissor flushes first so draw order is preserved, and the
+    // frame flushes once more at the end
+    pub fn flush(&mut self, render_pass: &mut wgpu::RenderPass) {
+        if self.batch.indices.is_empty() {
+            return;
+        }
+        let mut batch = std::mem::take(&mut self.batch);
+        if let Some(pipeline) = self.batch_pipeline {
+            render_pass.set_pipeline(pipeline);
+        }
+        batch.submit(render_pass, self);
+        if self.batch_pipeline.is_some() {
+            render_pass.set_pipeline(self.pipeline);
+        }
+        batch.clear();
+        self.batch = batch;
+        self.draw_calls += 1;
+    }
+
+    // draws meshes pushed from now on with the custom shader registered under
+    // `name`, or the mesh pipeline for None. switching flushes the batch, so
+    // primitives sharing a shader still draw together
+    pub fn use_pipeline(&mut self, render_pass: &mut wgpu::RenderPass, name: Option<&str>) {
+        let pipeline = name.and_then(|name| self.pipelines.get(name));
+        let unchanged = match (pipeline, self.batch_pipeline) {
+            (Some(a), Some(b)) => std::ptr::eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        if !unchanged {
+            self.flush(render_pass);
+            self.batch_pipeline = pipeline;
+        }
+    }
+
+    // mesh draw calls issued so far this frame
+    pub fn draw_calls(&self) -> u32 {
+        self.draw_calls
+    }
+
+    fn full_target(&self) -> ClipRect {
+        ClipRect {
+            x: 0,
+            y: 0,
+            width: self.target_size.0,
+            height: self.target_size.1,
+        }
+    }
+
+    // restricts drawing to a screen space rect until the matching pop_clip.
+    // nested clips intersect with their parent
+    pub fn push_clip(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        position: (i32, i32),
+        extent: (i32, i32),
+        size: (i32, i32),
+    ) {
+        let (x, y, width, height) = self.surface_rect(position, extent, size);
+        let left = x.max(0.0).floor() as u32;
+        let top = y.max(0.0).floor() as u32;
+        let right = (x + width).max(0.0).ceil() as u32;
+        let bottom = (y + height).max(0.0).ceil() as u32;
+        let rect = ClipRect {
+            x: left,
+            y: top,
+            width: right.saturating_sub(left),
+            height: bottom.saturating_sub(top),
+        };
+
+        let parent = self
+            .clip_stack
+            .last()
+            .copied()
+            .unwrap_or_else(|| self.full_target());
+        let clip = rect.intersect(&parent);
+        self.flush(render_pass);
+        self.clip_stack.push(clip);
+        render_pass.set_scissor_rect(clip.x, clip.y, clip.width, clip.height);
+    }
+
+    // a rect in layout pixels as (x, y, width, height) in surface pixels,
+    // through points and the orientation
+    fn surface_rect(
+        &self,
+        position: (i32, i32),
+        extent: (i32, i32),
+        size: (i32, i32),
+    ) -> (f32, f32, f32, f32) {
+        let to_surface = |(x, y): (i32, i32)| {
+            let scale = LAYOUT_SCALE as f32;
+            let (x, y) = self
+                .orientation
+                .to_physical((x as f32 / scale, y as f32 / scale), size);
+            (x * self.scale_factor, y * self.scale_factor)
+        };
+        let a = to_surface(position);
+        let b = to_surface((position.0 + extent.0, position.1 + extent.1));
+        let (x, y) = (a.0.min(b.0), a.1.min(b.1));
+        (x, y, a.0.max(b.0) - x, a.1.max(b.1) - y)
+    }
+
+    // hands a rect of the frame to the render plugin registered under `name`,
+    // clipped to it
+    pub fn draw_plugin(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        name: &str,
+        position: (i32, i32),
+        extent: (i32, i32),
+        size: (i32, i32),
+    ) {
+        let (x, y, width, height) = self.surface_rect(position, extent, size);
+        let viewport = PluginViewport {
+            x,
+            y,
+            width,
+            height,
+            scale_factor: self.scale_factor,
+        };
+        self.push_clip(render_pass, position, extent, size);
+        if let Some((plugins, projection)) = &mut self.plugins
+            && let Some(plugin) = plugins.get_mut(name)
+        {
+            plugin.draw(render_pass, viewport);
+            let (width, height) = (self.target_size.0 as f32, self.target_size.1 as f32);
+            render_pass.set_viewport(0.0, 0.0, width, height, 0.0, 1.0);
+            render_pass.set_pipeline(self.pipeline);
+            render_pass.set_bind_group(0, *projection, &[]);
+        }
+        self.pop_clip(render_pass);
+    }
+
+    pub fn pop_clip(&mut self, render_pass: &mut wgpu::RenderPass) {
+        self.flush(render_pass);
+        self.clip_stack.pop();
+        let clip = self
+            .clip_stack
+            .last()
+            .copied()
+            .unwrap_or_else(|| self.full_target());
+        render_pass.set_scissor_rect(clip.x, clip.y, clip.width, clip.height);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        font: &Font,
+        text: &str,
+        px: f32,
+        origin: (f32, f32),
+        color: impl Into<Color>,
+        size: (i32, i32),
+    ) {
+        self.flush(render_pass);
+        self.text.draw(
+            render_pass,
+            self.device,
+            self.queue,
+            font,
+            text,
+            px,
+            origin,
+            color,
+            size,
+        );
+        render_pass.set_pipeline(self.pipeline);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_image(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        image: &ImageData,
+        position: (i32, i32),
+        extent: (i32, i32),
+        brightness: f32,
+        opacity: f32,
+        size: (i32, i32),
+    ) {
+        self.flush(render_pass);
+        self.images.draw(
+            render_pass,
+            self.device,
+            self.queue,
+            image,
+            (position.0 as f32, position.1 as f32),
+            (extent.0 as f32, extent.1 as f32),
+            brightness,
+            opacity,
+            size,
+        );
+        render_pass.set_pipeline(self.pipeline);
+    }
+
+    // the `uv` rect of an atlas, multiplied by `tint`
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_image_region(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        image: &ImageData,
+        uv: [f32; 4],
+        position: (f32, f32),
+        extent: (f32, f32),
+        tint: impl Into<Color>,
+        opacity: f32,
+        size: (i32, i32),
+    ) {
+        self.flush(render_pass);
+        self.images.draw_region(
+            render_pass,
+            self.device,
+            self.queue,
+            image,
+            uv,
+            position,
+            extent,
+            1.0,
+            opacity,
+            tint.into().to_linear_srgb(),
+            size,
+        );
+        render_pass.set_pipeline(self.pipeline);
+    }
+
+    // `particles` spawn times are on the frame clock's elapsed timeline
+    pub fn draw_particles(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        particles: &[ParticleInstance],
+        size: (i32, i32),
+    ) {
+        if particles.is_empty() {
+            return;
+        }
+        self.flush(render_pass);
+        self.particles.draw(
+            render_pass,
+            self.device,
+            self.queue,
+            particles,
+            self.clock.elapsed().as_secs_f32(),
+            size,
+        );
+        render_pass.set_pipeline(self.pipeline);
+    }
+
+    pub fn draw_skeletons(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        blocks: &[SkeletonInstance],
+        size: (i32, i32),
+    ) {
+        if blocks.is_empty() {
+            return;
+        }
+        self.flush(render_pass);
+        self.skeletons.draw(
+            render_pass,
+            self.device,
+            self.queue,
+            blocks,
+            self.clock.elapsed().as_secs_f32(),
+            size,
+        );
+        render_pass.set_pipeline(self.pipeline);
+    }
+}
@@ -0,0 +1,623 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::c_void,
+};
+
+use glow::HasContext;
+use tinycolors::srgb;
+
+use crate::{
+    layout::UI,
+    renderer::{
+        color::Color,
+        draw_list::{DrawCommand, DrawList},
+        image::ImageData,
+        mesh_builder::{Mesh, Vertex},
+        text::{Font, GlyphKey, RasterizedGlyph},
+    },
+};
+
+// glyph textures kept before the cache starts over
+const MAX_GLYPHS: usize = 4096;
+
+// the default wgpu shader's vertex stage, see `pipeline_builder`. positions
+// come in already in clip space, `offset` moves them for pushed transforms
+const MESH_VERTEX: &str = r#"#version 330 core
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 color;
+layout(location = 2) in uint color_space;
+layout(location = 3) in float alpha;
+uniform vec2 offset;
+out vec3 v_color;
+flat out uint v_color_space;
+out float v_alpha;
+void main() {
+    gl_Position = vec4(position.xy + offset, position.z, 1.0);
+    v_color = color;
+    v_color_space = color_space;
+    v_alpha = alpha;
+}
+"#;
+
+// the default shader's color handling, always out to srgb
+const MESH_FRAGMENT: &str = r#"#version 330 core
+in vec3 v_color;
+flat in uint v_color_space;
+in float v_alpha;
+out vec4 out_color;
+const mat3 P3_TO_SRGB = mat3(
+    1.2249401, -0.0420569, -0.0196376,
+    -0.2249404, 1.0420571, -0.0786361,
+    0.0, 0.0, 1.0982735
+);
+vec3 srgb_to_linear(vec3 c) {
+    vec3 a = abs(c);
+    return sign(c) * mix(pow((a + 0.055) / 1.055, vec3(2.4)), a / 12.92, lessThanEqual(a, vec3(0.04045)));
+}
+vec3 linear_to_srgb(vec3 c) {
+    vec3 a = abs(c);
+    return sign(c) * mix(1.055 * pow(a, vec3(1.0 / 2.4)) - 0.055, a * 12.92, lessThanEqual(a, vec3(0.0031308)));
+}
+vec3 limit_peak(vec3 c) {
+    float m = max(max(c.r, c.g), c.b);
+    return m > 1.0 ? c / m : c;
+}
+void main() {
+    vec3 color = v_color;
+    if (v_color_space == 1u) {
+        color = P3_TO_SRGB * srgb_to_linear(color);
+    } else if (v_color_space != 2u) {
+        color = srgb_to_linear(color);
+    }
+    color = clamp(limit_peak(color), 0.0, 1.0);
+    out_color = vec4(linear_to_srgb(color), v_alpha);
+}
+"#;
+
+const TEXTURE_VERTEX: &str = r#"#version 330 core
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 uv;
+out vec2 v_uv;
+void main() {
+    gl_Position = vec4(position, 0.0, 1.0);
+    v_uv = uv;
+}
+"#;
+
+// images are tinted by their brightness, glyphs are coverage in the red
+// channel colored by the tint
+const TEXTURE_FRAGMENT: &str = r#"#version 330 core
+in vec2 v_uv;
+uniform sampler2D image;
+uniform int coverage;
+uniform vec3 tint;
+uniform float opacity;
+out vec4 out_color;
+void main() {
+    vec4 texel = texture(image, v_uv);
+    if (coverage == 1) {
+        out_color = vec4(tint, texel.r * opacity);
+    } else {
+        out_color = vec4(texel.rgb * tint, texel.a * opacity);
+    }
+}
+"#;
+
+// draws a `UI` with opengl 3.3 through glow, from its draw list. a lighter
+// path than wgpu for machines where that's all there is. custom shaders,
+// post effects, plugins and widgets the draw list leaves out aren't drawn,
+// see `UI::draw_list`
+pub struct GlRenderer {
+    gl: glow::Context,
+    mesh_program: glow::Program,
+    offset: Option<glow::UniformLocation>,
+    texture_program: glow::Program,
+    coverage: Option<glow::UniformLocation>,
+    tint: Option<glow::UniformLocation>,
+    opacity: Option<glow::UniformLocation>,
+    mesh_vertex_array: glow::VertexArray,
+    mesh_verticies: glow::Buffer,
+    mesh_indices: glow::Buffer,
+    quad_vertex_array: glow::VertexArray,
+    quad_verticies: glow::Buffer,
+    glyphs: HashMap<GlyphKey, Glyph>,
+    // textures for the images drawn last frame, by image id
+    images: HashMap<usize, glow::Texture>,
+    used: HashSet<usize>,
+    // the last frame's size in pixels
+    size: (u32, u32),
+}
+
+// a glyph's texture, none for glyphs with nothing to draw like spaces, and
+// where it goes. the coverage is dropped once it's uploaded
+type Glyph = (Option<glow::Texture>, RasterizedGlyph);
+
+// a clip as (x, y, width, height) in pixels from the top left
+type ClipRect = (i32, i32, i32, i32);
+
+impl GlRenderer {
+    // `loader` looks gl functions up on the context to draw with, like glfw's
+    // `Window::get_proc_address`. that context has to be current whenever the
+    // renderer is used
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn new(loader: impl FnMut(&str) -> *const c_void) -> anyhow::Result<Self> {
+        let gl = unsafe { glow::Context::from_loader_function(loader) };
+        unsafe {
+            let mesh_program = program(&gl, MESH_VERTEX, MESH_FRAGMENT)?;
+            let texture_program = program(&gl, TEXTURE_VERTEX, TEXTURE_FRAGMENT)?;
+
+            let mesh_vertex_array = gl.create_vertex_array().map_err(anyhow::Error::msg)?;
+            let mesh_verticies = gl.create_buffer().map_err(anyhow::Error::msg)?;
+            let mesh_indices = gl.create_buffer().map_err(anyhow::Error::msg)?;
+            gl.bind_vertex_array(Some(mesh_vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(mesh_verticies));
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(mesh_indices));
+            // the same layout as `Vertex::get_layout`
+            let stride = std::mem::size_of::<Vertex>() as i32;
+            gl.enable_vertex_attrib_array(0);
+            gl.vertex_attrib_pointer_f32(0, 3, glow::FLOAT, false, stride, 0);
+            gl.enable_vertex_attrib_array(1);
+            gl.vertex_attrib_pointer_f32(1, 3, glow::FLOAT, false, stride, 12);
+            gl.enable_vertex_attrib_array(2);
+            gl.vertex_attrib_pointer_i32(2, 1, glow::UNSIGNED_INT, stride, 24);
+            gl.enable_vertex_attrib_array(3);
+            gl.vertex_attrib_pointer_f32(3, 1, glow::FLOAT, false, stride, 28);
+
+            let quad_vertex_array = gl.create_vertex_array().map_err(anyhow::Error::msg)?;
+            let quad_verticies = gl.create_buffer().map_err(anyhow::Error::msg)?;
+            gl.bind_vertex_array(Some(quad_vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(quad_verticies));
+            // position then uv, both vec2
+            gl.enable_vertex_attrib_array(0);
+            gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, 16, 0);
+            gl.enable_vertex_attrib_array(1);
+            gl.vertex_attrib_pointer_f32(1, 2, glow::FLOAT, false, 16, 8);
+            gl.bind_vertex_array(None);
+
+            log::info!(
+                "rendering with opengl {}",
+                gl.get_parameter_string(glow::VERSION)
+            );
+            Ok(Self {
+                offset: gl.get_uniform_location(mesh_program, "offset"),
+                coverage: gl.get_uniform_location(texture_program, "coverage"),
+                tint: gl.get_uniform_location(texture_program, "tint"),
+                opacity: gl.get_uniform_location(texture_program, "opacity"),
+                gl,
+                mesh_program,
+                texture_program,
+                mesh_vertex_array,
+                mesh_verticies,
+                mesh_indices,
+                quad_vertex_array,
+                quad_verticies,
+                glyphs: HashMap::new(),
+                images: HashMap::new(),
+                used: HashSet::new(),
+                size: (0, 0),
+            })
+        }
+    }
+
+    // draws the laid out `ui` over `target_size` pixels of the default
+    // framebuffer. showing it is up to the caller, like swapping buffers
+    pub fn render(&mut self, ui: &UI, target_size: (u32, u32)) {
+        let list = ui.draw_list();
+        self.render_list(
+            &list,
+            ui.background_color.into(),
+            ui.background_alpha,
+            target_size,
+        );
+    }
+
+    pub fn render_list(
+        &mut self,
+        list: &DrawList,
+        background: Color,
+        background_alpha: f32,
+        target_size: (u32, u32),
+    ) {
+        let target_size = (target_size.0.max(1), target_size.1.max(1));
+        self.size = target_size;
+        let [r, g, b] = background.to_srgb();
+        unsafe {
+            let gl = &self.gl;
+            gl.viewport(0, 0, target_size.0 as i32, target_size.1 as i32);
+            gl.disable(glow::SCISSOR_TEST);
+            gl.clear_color(r, g, b, background_alpha.clamp(0.0, 1.0));
+            gl.clear(glow::COLOR_BUFFER_BIT);
+            gl.enable(glow::BLEND);
+            gl.blend_func_separate(
+                glow::SRC_ALPHA,
+                glow::ONE_MINUS_SRC_ALPHA,
+                glow::ONE,
+                glow::ONE_MINUS_SRC_ALPHA,
+            );
+        }
+
+        // layout pixels to target pixels
+        let scale = (
+            target_size.0 as f32 / list.size.0.max(1) as f32,
+            target_size.1 as f32 / list.size.1.max(1) as f32,
+        );
+        let to_target = |(x, y): (f32, f32)| (x * scale.0, y * scale.1);
+        self.used.clear();
+        if self.glyphs.len() > MAX_GLYPHS {
+            for (texture, _) in std::mem::take(&mut self.glyphs).into_values() {
+                if let Some(texture) = texture {
+                    unsafe { self.gl.delete_texture(texture) };
+                }
+            }
+        }
+
+        let mut offsets = vec![(0.0f32, 0.0f32)];
+        let mut clips: Vec<ClipRect> = Vec::new();
+        let mesh_size = list.mesh_size();
+        for command in &list.lowered() {
+            let offset = offsets.last().copied().unwrap_or_default();
+            let moved = |(x, y): (i32, i32)| (x as f32 + offset.0, y as f32 + offset.1);
+            match command {
+                DrawCommand::Mesh(mesh) => {
+                    // layout pixels to clip space, see `ss_to_ndc`
+                    let offset = (
+                        offset.0 / mesh_size.0.max(1) as f32,
+                        -offset.1 / mesh_size.1.max(1) as f32,
+                    );
+                    self.draw_mesh(mesh, offset);
+                }
+                DrawCommand::Text {
+                    font,
+                    text,
+                    px,
+                    origin,
+                    color,
+                } => self.draw_text(
+                    font,
+                    text,
+                    px * scale.0,
+                    to_target((origin.0 + offset.0, origin.1 + offset.1)),
+                    *color,
+                ),
+                DrawCommand::Image {
+                    image,
+                    position,
+                    extent,
+                    brightness,
+                    opacity,
+                } => {
+                    let (x, y) = to_target(moved(*position));
+                    let (w, h) = to_target((extent.0 as f32, extent.1 as f32));
+                    self.draw_image(image, (x, y, w, h), *brightness, *opacity);
+                }
+                DrawCommand::PushClip { position, extent } => {
+                    let (x, y) = to_target(moved(*position));
+                    let (w, h) = to_target((extent.0 as f32, extent.1 as f32));
+                    let mut clip = (
+                        x.round() as i32,
+                        y.round() as i32,
+                        w.round() as i32,
+                        h.round() as i32,
+                    );
+                    if let Some(parent) = clips.last() {
+                        clip = intersect(clip, *parent);
+                    }
+                    clips.push(clip);
+                    self.scissor(Some(clip));
+                }
+                DrawCommand::PopClip => {
+                    clips.pop();
+                    self.scissor(clips.last().copied());
+                }
+                DrawCommand::PushTransform { offset: by } => {
+                    offsets.push((offset.0 + by.0, offset.1 + by.1));
+                }
+                DrawCommand::PopTransform => {
+                    if offsets.len() > 1 {
+                        offsets.pop();
+                    }
+                }
+                // gone once the list is lowered
+                DrawCommand::Pipeline(_)
+                | DrawCommand::ImageRegion { .. }
+                | DrawCommand::Skeletons(_)
+                | DrawCommand::Particles(_)
+                | DrawCommand::Plugin { .. } => {}
+            }
+        }
+
+        let unused: Vec<usize> = self
+            .images
+            .keys()
+            .filter(|id| !self.used.contains(id))
+            .copied()
+            .collect();
+        for id in unused {
+            if let Some(texture) = self.images.remove(&id) {
+                unsafe { self.gl.delete_texture(texture) };
+            }
+        }
+        unsafe {
+            self.gl.disable(glow::SCISSOR_TEST);
+            self.gl.bind_vertex_array(None);
+        }
+    }
+
+    // the last frame's color at a pixel, for the eyedropper. reads the
+    // default framebuffer, so call it before swapping buffers
+    pub fn pixel(&self, point: (u32, u32)) -> Option<srgb> {
+        if point.0 >= self.size.0 || point.1 >= self.size.1 {
+            return None;
+        }
+        let mut pixel = [0u8; 4];
+        unsafe {
+            self.gl.read_pixels(
+                point.0 as i32,
+                (self.size.1 - 1 - point.1) as i32,
+                1,
+                1,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(Some(&mut pixel)),
+            );
+        }
+        Some(crate::theme::rgb(
+            pixel[0] as f32 / 255.0,
+            pixel[1] as f32 / 255.0,
+            pixel[2] as f32 / 255.0,
+        ))
+    }
+
+    // `offset` is in clip space
+    fn draw_mesh(&self, mesh: &Mesh, offset: (f32, f32)) {
+        if mesh.indices.is_empty() {
+            return;
+        }
+        let gl = &self.gl;
+        unsafe {
+            gl.use_program(Some(self.mesh_program));
+            gl.uniform_2_f32(self.offset.as_ref(), offset.0, offset.1);
+            gl.bind_vertex_array(Some(self.mesh_vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.mesh_verticies));
+            gl.buffer_data_u8_slice(
+                glow::ARRAY_BUFFER,
+                bytemuck::cast_slice(&mesh.verticies),
+                glow::STREAM_DRAW,
+            );
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.mesh_indices));
+            gl.buffer_data_u8_slice(
+                glow::ELEMENT_ARRAY_BUFFER,
+                bytemuck::cast_slice(&mesh.indices),
+                glow::STREAM_DRAW,
+            );
+            gl.draw_elements(
+                glow::TRIANGLES,
+                mesh.indices.len() as i32,
+                glow::UNSIGNED_INT,
+                0,
+            );
+        }
+    }
+
+    // `px` and `origin` are already in target pixels
+    fn draw_text(&mut self, font: &Font, text: &str, px: f32, origin: (f32, f32), color: Color) {
+        let [r, g, b] = color.to_srgb();
+        for glyph in &font.layout(text, px).glyphs {
+            let key = GlyphKey::new(font, glyph.index, px);
+            if !self.glyphs.contains_key(&key) {
+                let entry = self.rasterize(font, glyph.index, px);
+                self.glyphs.insert(key, entry);
+            }
+            let Some((Some(texture), entry)) = self.glyphs.get(&key) else {
+                continue;
+            };
+            let texture = *texture;
+            // snapped to whole pixels like the wgpu text
+            let x = (origin.0 + glyph.x + entry.offset.0).round();
+            let y = (origin.1 + glyph.baseline + entry.offset.1).round();
+            let bounds = (x, y, entry.size.0 as f32, entry.size.1 as f32);
+            self.draw_texture(texture, bounds, true, [r, g, b], 1.0);
+        }
+    }
+
+    fn rasterize(&self, font: &Font, index: u16, px: f32) -> Glyph {
+        let mut glyph = font.rasterize_glyph(index, px);
+        let (w, h) = glyph.size;
+        let texture = (w > 0 && h > 0)
+            .then(|| self.upload(w, h, glow::R8, glow::RED, &glyph.coverage))
+            .flatten();
+        glyph.coverage = Vec::new();
+        (texture, glyph)
+    }
+
+    // `bounds` is (x, y, width, height) in target pixels
+    fn draw_image(
+        &mut self,
+        image: &ImageData,
+        bounds: (f32, f32, f32, f32),
+        brightness: f32,
+        opacity: f32,
+    ) {
+        let (width, height) = image.size();
+        if width == 0 || height == 0 || bounds.2 <= 0.0 || bounds.3 <= 0.0 {
+            return;
+        }
+        self.used.insert(image.id());
+        let texture = match self.images.get(&image.id()) {
+            Some(texture) => *texture,
+            None => {
+                let Some(texture) =
+                    self.upload(width, height, glow::RGBA8, glow::RGBA, image.pixels())
+                else {
+                    return;
+                };
+                self.images.insert(image.id(), texture);
+                texture
+            }
+        };
+        self.draw_texture(texture, bounds, false, [brightness; 3], opacity);
+    }
+
+    fn upload(
+        &self,
+        width: u32,
+        height: u32,
+        internal_format: u32,
+        format: u32,
+        pixels: &[u8],
+    ) -> Option<glow::Texture> {
+        let gl = &self.gl;
+        unsafe {
+            let texture = match gl.create_texture() {
+                Ok(texture) => texture,
+                Err(e) => {
+                    log::error!("couldn't create a gl texture: {e}");
+                    return None;
+                }
+            };
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                internal_format as i32,
+                width as i32,
+                height as i32,
+                0,
+                format,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(Some(pixels)),
+            );
+            for (parameter, value) in [
+                (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+                (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+                (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+            ] {
+                gl.tex_parameter_i32(glow::TEXTURE_2D, parameter, value as i32);
+            }
+            Some(texture)
+        }
+    }
+
+    // a textured quad over `bounds`, (x, y, width, height) in target pixels
+    fn draw_texture(
+        &self,
+        texture: glow::Texture,
+        bounds: (f32, f32, f32, f32),
+        coverage: bool,
+        tint: [f32; 3],
+        opacity: f32,
+    ) {
+        let (w, h) = (self.size.0 as f32, self.size.1 as f32);
+        let ndc = |x: f32, y: f32| [x / w * 2.0 - 1.0, 1.0 - y / h * 2.0];
+        let (x0, y0, x1, y1) = (bounds.0, bounds.1, bounds.0 + bounds.2, bounds.1 + bounds.3);
+        let mut verticies: Vec<f32> = Vec::with_capacity(24);
+        for (x, y, u, v) in [
+            (x0, y0, 0.0, 0.0),
+            (x0, y1, 0.0, 1.0),
+            (x1, y0, 1.0, 0.0),
+            (x1, y1, 1.0, 1.0),
+            (x1, y0, 1.0, 0.0),
+            (x0, y1, 0.0, 1.0),
+        ] {
+            verticies.extend(ndc(x, y));
+            verticies.extend([u, v]);
+        }
+        let gl = &self.gl;
+        unsafe {
+            gl.use_program(Some(self.texture_program));
+            gl.uniform_1_i32(self.coverage.as_ref(), coverage as i32);
+            gl.uniform_3_f32(self.tint.as_ref(), tint[0], tint[1], tint[2]);
+            gl.uniform_1_f32(self.opacity.as_ref(), opacity.clamp(0.0, 1.0));
+            gl.active_texture(glow::TEXTURE0);
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            gl.bind_vertex_array(Some(self.quad_vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.quad_verticies));
+            gl.buffer_data_u8_slice(
+                glow::ARRAY_BUFFER,
+                bytemuck::cast_slice(&verticies),
+                glow::STREAM_DRAW,
+            );
+            gl.draw_arrays(glow::TRIANGLES, 0, 6);
+        }
+    }
+
+    fn scissor(&self, clip: Option<ClipRect>) {
+        let gl = &self.gl;
+        unsafe {
+            match clip {
+                Some((x, y, w, h)) => {
+                    gl.enable(glow::SCISSOR_TEST);
+                    // gl counts rows up from the bottom
+                    let bottom = self.size.1 as i32 - (y + h.max(0));
+                    gl.scissor(x, bottom, w.max(0), h.max(0));
+                }
+                None => gl.disable(glow::SCISSOR_TEST),
+            }
+        }
+    }
+}
+
+impl Drop for GlRenderer {
+    fn drop(&mut self) {
+        let gl = &self.gl;
+        unsafe {
+            for texture in self
+                .glyphs
+                .values()
+                .filter_map(|(texture, _)| *texture)
+                .chain(self.images.values().copied())
+            {
+                gl.delete_texture(texture);
+            }
+            gl.delete_buffer(self.mesh_verticies);
+            gl.delete_buffer(self.mesh_indices);
+            gl.delete_buffer(self.quad_verticies);
+            gl.delete_vertex_array(self.mesh_vertex_array);
+            gl.delete_vertex_array(self.quad_vertex_array);
+            gl.delete_program(self.mesh_program);
+            gl.delete_program(self.texture_program);
+        }
+    }
+}
+
+fn intersect(a: ClipRect, b: ClipRect) -> ClipRect {
+    let (x, y) = (a.0.max(b.0), a.1.max(b.1));
+    let right = (a.0 + a.2).min(b.0 + b.2);
+    let bottom = (a.1 + a.3).min(b.1 + b.3);
+    (x, y, (right - x).max(0), (bottom - y).max(0))
+}
+
+unsafe fn program(
+    gl: &glow::Context,
+    vertex: &str,
+    fragment: &str,
+) -> anyhow::Result<glow::Program> {
+    unsafe {
+        let program = gl.create_program().map_err(anyhow::Error::msg)?;
+        let mut shaders = Vec::new();
+        for (kind, source) in [
+            (glow::VERTEX_SHADER, vertex),
+            (glow::FRAGMENT_SHADER, fragment),
+        ] {
+            let shader = gl.create_shader(kind).map_err(anyhow::Error::msg)?;
+            gl.shader_source(shader, source);
+            gl.compile_shader(shader);
+            if !gl.get_shader_compile_status(shader) {
+                anyhow::bail!("shader didn't compile: {}", gl.get_shader_info_log(shader));
+            }
+            gl.attach_shader(program, shader);
+            shaders.push(shader);
+        }
+        gl.link_program(program);
+        for shader in shaders {
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+        }
+        if !gl.get_program_link_status(program) {
+            anyhow::bail!("program didn't link: {}", gl.get_program_info_log(program));
+        }
+        Ok(program)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    layout::UI,
+    renderer::{
+        color::{ColorOutput, HdrSettings, OutputColorSpace},
+        frame::{Frame, FrameBuffers},
+        image::ImageRenderer,
+        mesh_builder,
+        particles::ParticleRenderer,
+        pipeline_builder::PipelineBuilder,
+        projection::{Orientation, Projection},
+        readback,
+        shaders::PipelineRegistry,
+        skeleton::SkeletonRenderer,
+        text::TextRenderer,
+    },
+};
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+// draws a `UI` into an offscreen texture and reads it back as an image, with
+// no window or surface. for screenshots and docs rather than interactive use,
+// so post effects and the eyedropper are left out
+pub struct HeadlessRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    render_pipeline: wgpu::RenderPipeline,
+    custom_pipelines: PipelineRegistry,
+    buffers: FrameBuffers,
+    projection: Projection,
+    text_renderer: TextRenderer,
+    image_renderer: ImageRenderer,
+    particle_renderer: ParticleRenderer,
+    skeleton_renderer: SkeletonRenderer,
+    color_output: ColorOutput,
+}
+
+impl HeadlessRenderer {
+    pub async fn new() -> anyhow::Result<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            })
+            .await?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+                label: Some("headless device"),
+                memory_hints: Default::default(),
+                trace: wgpu::Trace::Off,
+            })
+            .await?;
+
+        let color_output = ColorOutput::new(OutputColorSpace::Srgb, FORMAT, HdrSettings::default());
+        let projection = Projection::new(&device);
+        let mut pipeline_builder = PipelineBuilder::new();
+        pipeline_builder.set_pixel_format(FORMAT);
+        pipeline_builder.set_buffer_layout(mesh_builder::Vertex::get_layout());
+        pipeline_builder.set_blend_state(wgpu::BlendState::ALPHA_BLENDING);
+        pipeline_builder.add_bind_group_layout(&projection.bind_group_layout);
+        for (name, value) in color_output.constants() {
+            pipeline_builder.set_constant(name, value);
+        }
+        let render_pipeline = pipeline_builder.build_pipeline(&device);
+        let custom_pipelines =
+            PipelineRegistry::build(&device, FORMAT, &projection.bind_group_layout, &[]).await;
+        let layout = &projection.bind_group_layout;
+
+        Ok(Self {
+            text_renderer: TextRenderer::new(&device, FORMAT, layout, color_output),
+            image_renderer: ImageRenderer::new(&device, FORMAT, layout, color_output),
+            particle_renderer: ParticleRenderer::new(&device, FORMAT, layout, color_output),
+            skeleton_renderer: SkeletonRenderer::new(&device, FORMAT, layout, color_output),
+            render_pipeline,
+            custom_pipelines,
+            buffers: FrameBuffers::default(),
+            projection,
+            color_output,
+            device,
+            queue,
+        })
+    }
+
+    // lays `ui` out at `logical_size` points and renders it at `scale_factor`
+    // pixels per point
+    pub fn render(
+        &mut self,
+        ui: &mut UI,
+        logical_size: (i32, i32),
+        scale_factor: f32,
+    ) -> anyhow::Result<::image::RgbaImage> {
+        let target_size = (
+            ((logical_size.0 as f32 * scale_factor).round() as u32).max(1),
+            ((logical_size.1 as f32 * scale_factor).round() as u32).max(1),
+        );
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("headless target"),
+            size: wgpu::Extent3d {
+                width: target_size.0,
+                height: target_size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        ui.set_scale_factor(scale_factor);
+        ui.set_size(logical_size);
+        ui.compute_layout();
+
+        let [r, g, b] = self.color_output.convert(ui.background_color.into());
+        let clear_color = wgpu::Color {
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: ui.background_alpha.clamp(0.0, 1.0) as f64,
+        };
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("headless encoder"),
+            });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("headless renderpass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(clear_color),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.projection.bind_group, &[]);
+            let mut frame = Frame::new(
+                &self.device,
+                &self.queue,
+                &mut self.buffers,
+                &mut self.text_renderer,
+                &mut self.image_renderer,
+                &mut self.particle_renderer,
+                &mut self.skeleton_renderer,
+                &self.render_pipeline,
+                &self.custom_pipelines,
+                target_size,
+                scale_factor,
+                Orientation::default(),
+                ui.context.clock(),
+            );
+            ui.draw(&mut render_pass, &mut frame, logical_size);
+            frame.flush(&mut render_pass);
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        readback::read_image(&self.device, &self.queue, &texture)
+            .ok_or_else(|| anyhow::anyhow!("couldn't read back the rendered image"))
+    }
+}
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::renderer::{image::ImageData, text::Font};
+
+// how big font icons are rasterized. they're drawn scaled from this with linear
+// filtering, so it's picked for the larger toolbar sizes
+const RASTER_SIZE: u32 = 64;
+// empty space around each icon so sampling doesn't bleed into neighbours
+const ICON_PADDING: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IconEntry {
+    // [u0, v0, u1, v1] in the atlas
+    pub uv: [f32; 4],
+    // width over height
+    pub aspect: f32,
+}
+
+// named icons packed into one texture, drawn by `Icon` through the image
+// pipeline. font icons are white coverage masks and sprite sheets keep their
+// own pixels, so a tint colors masks and white sheet art alike. vector sheets
+// need rasterizing to png first, there's no svg renderer here
+pub struct IconSet {
+    atlas: Arc<ImageData>,
+    icons: HashMap<String, IconEntry>,
+}
+
+impl IconSet {
+    // rasterizes each named character of an icon font into the atlas. icons
+    // the font has no glyph for are logged and left out
+    pub fn from_font(font: &Font, icons: &[(&str, char)]) -> anyhow::Result<Arc<IconSet>> {
+        let icons: Vec<(&str, char)> = icons
+            .iter()
+            .copied()
+            .filter(|(name, c)| {
+                let found = font.has_glyph(*c);
+                if !found {
+                    log::warn!("icon font has no glyph for {name:?} ({c:?})");
+                }
+                found
+            })
+            .collect();
+
+        let cell = RASTER_SIZE + 2 * ICON_PADDING;
+        let columns = (icons.len() as f32).sqrt().ceil().max(1.0) as u32;
+        let rows = (icons.len() as u32).div_ceil(columns).max(1);
+        let (width, height) = (columns * cell, rows * cell);
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let mut entries = HashMap::new();
+
+        for (i, (name, c)) in icons.into_iter().enumerate() {
+            let (column, row) = (i as u32 % columns, i as u32 / columns);
+            let (metrics, coverage) = font.rasterize(c, RASTER_SIZE as f32);
+            let (glyph_width, glyph_height) = (
+                (metrics.width as u32).min(RASTER_SIZE),
+                (metrics.height as u32).min(RASTER_SIZE),
+            );
+            // centered in its square so icons of different widths line up
+            let x0 = column * cell + ICON_PADDING + (RASTER_SIZE - glyph_width) / 2;
+            let y0 = row * cell + ICON_PADDING + (RASTER_SIZE - glyph_height) / 2;
+            for y in 0..glyph_height {
+                for x in 0..glyph_width {
+                    let alpha = coverage[(y * metrics.width as u32 + x) as usize];
+                    let offset = (((y0 + y) * width + x0 + x) * 4) as usize;
+                    pixels[offset..offset + 4].copy_from_slice(&[255, 255, 255, alpha]);
+                }
+            }
+            let (u0, v0) = (
+                (column * cell + ICON_PADDING) as f32 / width as f32,
+                (row * cell + ICON_PADDING) as f32 / height as f32,
+            );
+            entries.insert(
+                name.to_string(),
+                IconEntry {
+                    uv: [
+                        u0,
+                        v0,
+                        u0 + RASTER_SIZE as f32 / width as f32,
+                        v0 + RASTER_SIZE as f32 / height as f32,
+                    ],
+                    aspect: 1.0,
+                },
+            );
+        }
+
+        Ok(Arc::new(Self {
+            atlas: ImageData::from_rgba(width, height, pixels)?,
+            icons: entries,
+        }))
+    }
+
+    // a sheet laid out on a grid of `cell` sized icons. each name picks its
+    // (column, row), and the sheet itself becomes the atlas
+    pub fn from_sprite_sheet(
+        sheet: Arc<ImageData>,
+        cell: (u32, u32),
+        icons: &[(&str, (u32, u32))],
+    ) -> anyhow::Result<Arc<IconSet>> {
+        let (width, height) = sheet.size();
+        if cell.0 == 0 || cell.1 == 0 {
+            anyhow::bail!("sprite sheet cells need a size");
+        }
+        let mut entries = HashMap::new();
+        for (name, (column, row)) in icons {
+            let (x0, y0) = (column * cell.0, row * cell.1);
+            if x0 + cell.0 > width || y0 + cell.1 > height {
+                anyhow::bail!(
+                    "icon {name:?} at ({column}, {row}) is outside the {width}x{height} sheet"
+                );
+            }
+            entries.insert(
+                name.to_string(),
+                IconEntry {
+                    uv: [
+                        x0 as f32 / width as f32,
+                        y0 as f32 / height as f32,
+                        (x0 + cell.0) as f32 / width as f32,
+                        (y0 + cell.1) as f32 / height as f32,
+                    ],
+                    aspect: cell.0 as f32 / cell.1 as f32,
+                },
+            );
+        }
+        Ok(Arc::new(Self {
+            atlas: sheet,
+            icons: entries,
+        }))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&IconEntry> {
+        self.icons.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.icons.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.icons.keys().map(String::as_str)
+    }
+
+    pub fn atlas(&self) -> &Arc<ImageData> {
+        &self.atlas
+    }
+}
@@ -0,0 +1,420 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::renderer::{
+    color::{Color, ColorOutput},
+    frame::DynamicBuffer,
+    mesh_builder::ss_to_ndc,
+    pipeline_builder::{PipelineBuilder, sampled_texture_layout},
+};
+
+static NEXT_IMAGE_ID: AtomicUsize = AtomicUsize::new(0);
+
+// decoded rgba8 pixels in srgb. uploaded to the gpu the first time it's drawn
+pub struct ImageData {
+    id: usize,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl ImageData {
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> anyhow::Result<Arc<ImageData>> {
+        if pixels.len() != (width * height * 4) as usize {
+            anyhow::bail!(
+                "expected {} bytes of rgba for a {width}x{height} image, got {}",
+                width * height * 4,
+                pixels.len()
+            );
+        }
+        Ok(Arc::new(Self {
+            id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+            width,
+            height,
+            pixels,
+        }))
+    }
+
+    // png, jpeg, or anything else the image crate recognizes
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Arc<ImageData>> {
+        let image = ::image::load_from_memory(bytes)?.into_rgba8();
+        let (width, height) = image.dimensions();
+        Self::from_rgba(width, height, image.into_raw())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Arc<ImageData>> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    #[cfg(any(feature = "gl", feature = "software"))]
+    pub(crate) fn id(&self) -> usize {
+        self.id
+    }
+
+    pub(crate) fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    // a copy of the `uv` rect, given as [u0, v0, u1, v1], multiplied by `tint`
+    // the way the image pipeline draws atlas regions. for backends that can
+    // only draw whole images
+    pub(crate) fn region(&self, uv: [f32; 4], tint: Color) -> Option<Arc<ImageData>> {
+        let [u0, v0, u1, v1] = uv;
+        let x0 = (u0 * self.width as f32)
+            .round()
+            .clamp(0.0, self.width as f32) as u32;
+        let y0 = (v0 * self.height as f32)
+            .round()
+            .clamp(0.0, self.height as f32) as u32;
+        let x1 = (u1 * self.width as f32)
+            .round()
+            .clamp(0.0, self.width as f32) as u32;
+        let y1 = (v1 * self.height as f32)
+            .round()
+            .clamp(0.0, self.height as f32) as u32;
+        let (width, height) = (x1.checked_sub(x0)?, y1.checked_sub(y0)?);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let tint = tint.to_srgb();
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for y in y0..y1 {
+            let row = ((y * self.width + x0) * 4) as usize;
+            for pixel in self.pixels[row..row + (width * 4) as usize].chunks_exact(4) {
+                for (channel, tint) in pixel[..3].iter().zip(tint) {
+                    pixels.push((*channel as f32 * tint).round() as u8);
+                }
+                pixels.push(pixel[3]);
+            }
+        }
+        Self::from_rgba(width, height, pixels).ok()
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct ImageVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub brightness: f32,
+    pub opacity: f32,
+    // multiplies the texel, in linear srgb. white leaves it alone
+    pub tint: [f32; 3],
+}
+
+impl ImageVertex {
+    pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x2,
+            2 => Float32,
+            3 => Float32,
+            4 => Float32x3
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ImageVertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+struct GpuImage {
+    bind_group: wgpu::BindGroup,
+}
+
+// draws textured quads. textures live as long as their image keeps getting
+// drawn and are dropped after a frame that doesn't use them
+pub struct ImageRenderer {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    textures: HashMap<usize, GpuImage>,
+    used: HashSet<usize>,
+    verticies: DynamicBuffer,
+    indices: DynamicBuffer,
+}
+
+impl ImageRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        projection_layout: &wgpu::BindGroupLayout,
+        color_output: ColorOutput,
+    ) -> Self {
+        let layout = sampled_texture_layout(device, "image bind group layout");
+
+        let mut pipeline_builder = PipelineBuilder::new();
+        pipeline_builder.set_shader_source(image_shader::SOURCE, "vs_image", "fs_image");
+        pipeline_builder.set_pixel_format(format);
+        pipeline_builder.set_buffer_layout(ImageVertex::get_layout());
+        pipeline_builder.add_bind_group_layout(projection_layout);
+        pipeline_builder.add_bind_group_layout(&layout);
+        pipeline_builder.set_blend_state(wgpu::BlendState::ALPHA_BLENDING);
+        for (name, value) in color_output.constants() {
+            pipeline_builder.set_constant(name, value);
+        }
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("image sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline: pipeline_builder.build_pipeline(device),
+            layout,
+            sampler,
+            textures: HashMap::new(),
+            used: HashSet::new(),
+            verticies: DynamicBuffer::new("image vertex buffer", wgpu::BufferUsages::VERTEX),
+            indices: DynamicBuffer::new("image index buffer", wgpu::BufferUsages::INDEX),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.verticies.reset();
+        self.indices.reset();
+        self.textures.retain(|id, _| self.used.contains(id));
+        self.used.clear();
+    }
+
+    fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, image: &ImageData) {
+        if self.textures.contains_key(&image.id) {
+            return;
+        }
+        let size = wgpu::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("image texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            // sampling decodes to linear, which is what the shader works in
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &image.pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(image.width * 4),
+                rows_per_image: Some(image.height),
+            },
+            size,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("image bind group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        self.textures.insert(image.id, GpuImage { bind_group });
+    }
+
+    // stretches `image` over the rect at `position` in layout space, with its
+    // alpha scaled by `opacity`. leaves the image pipeline bound
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        image: &ImageData,
+        position: (f32, f32),
+        extent: (f32, f32),
+        brightness: f32,
+        opacity: f32,
+        size: (i32, i32),
+    ) {
+        self.draw_region(
+            render_pass,
+            device,
+            queue,
+            image,
+            [0.0, 0.0, 1.0, 1.0],
+            position,
+            extent,
+            brightness,
+            opacity,
+            [1.0; 3],
+            size,
+        );
+    }
+
+    // like `draw` for just the `uv` rect of `image`, given as [u0, v0, u1, v1],
+    // multiplied by `tint`. used for atlases like icon sets
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_region(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        image: &ImageData,
+        uv: [f32; 4],
+        position: (f32, f32),
+        extent: (f32, f32),
+        brightness: f32,
+        opacity: f32,
+        tint: [f32; 3],
+        size: (i32, i32),
+    ) {
+        if image.width == 0 || image.height == 0 || extent.0 <= 0.0 || extent.1 <= 0.0 {
+            return;
+        }
+        self.upload(device, queue, image);
+        self.used.insert(image.id);
+
+        let (x0, y0) = position;
+        let (x1, y1) = (x0 + extent.0, y0 + extent.1);
+        let [u0, v0, u1, v1] = uv;
+        let verticies: Vec<ImageVertex> = [
+            ((x0, y0), [u0, v0]),
+            ((x1, y0), [u1, v0]),
+            ((x0, y1), [u0, v1]),
+            ((x1, y1), [u1, v1]),
+        ]
+        .into_iter()
+        .map(|(point, uv)| {
+            let (x, y) = ss_to_ndc(point, size);
+            ImageVertex {
+                position: [x, y],
+                uv,
+                brightness,
+                opacity,
+                tint,
+            }
+        })
+        .collect();
+        let indices: [u32; 6] = [0, 2, 1, 3, 1, 2];
+
+        let vertex_range = self
+            .verticies
+            .write(device, queue, bytemuck::cast_slice(&verticies));
+        let index_range = self
+            .indices
+            .write(device, queue, bytemuck::cast_slice(&indices));
+        let Some(gpu_image) = self.textures.get(&image.id) else {
+            return;
+        };
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(1, &gpu_image.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.verticies.slice(vertex_range));
+        render_pass.set_index_buffer(self.indices.slice(index_range), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..indices.len() as u32, 0, 0..1);
+    }
+}
+
+mod image_shader {
+    wgsl_inline::wgsl!(
+    struct ImageVertex {
+        @location(0) position: vec2<f32>,
+        @location(1) uv: vec2<f32>,
+        @location(2) brightness: f32,
+        @location(3) opacity: f32,
+        @location(4) tint: vec3<f32>,
+    }
+
+    struct ImagePayload {
+        @builtin(position) position: vec4<f32>,
+        @location(0) uv: vec2<f32>,
+        @location(1) brightness: f32,
+        @location(2) opacity: f32,
+        @location(3) tint: vec3<f32>,
+    };
+
+    @group(0) @binding(0)
+    var<uniform> projection: mat4x4<f32>;
+
+    @group(1) @binding(0)
+    var image_texture: texture_2d<f32>;
+    @group(1) @binding(1)
+    var image_sampler: sampler;
+
+    // same meaning as in the mesh shader, see ColorOutput
+    override OUTPUT_SPACE: u32 = 0u;
+    override ENCODE_OUTPUT: bool = false;
+    override PAPER_WHITE: f32 = 2.5375;
+    override PEAK: f32 = 12.5;
+
+    const SRGB_TO_P3 = mat3x3<f32>(
+        vec3<f32>(0.8224621, 0.0331941, 0.0170827),
+        vec3<f32>(0.1775380, 0.9668058, 0.0723974),
+        vec3<f32>(0.0, 0.0, 0.9105199)
+    );
+
+    fn limit_peak(c: vec3<f32>, peak: f32) -> vec3<f32> {
+        let m = max(max(c.r, c.g), c.b);
+        return select(c, c * (peak / m), m > peak);
+    }
+
+    fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+        let a = abs(c);
+        let encoded = select(1.055 * pow(a, vec3<f32>(1.0 / 2.4)) - 0.055, a * 12.92, a <= vec3<f32>(0.0031308));
+        return sign(c) * encoded;
+    }
+
+    @vertex
+    fn vs_image(vertex: ImageVertex) -> ImagePayload {
+        var out: ImagePayload;
+        out.position = projection * vec4<f32>(vertex.position, 0.0, 1.0);
+        out.uv = vertex.uv;
+        out.brightness = vertex.brightness;
+        out.opacity = vertex.opacity;
+        out.tint = vertex.tint;
+        return out;
+    }
+
+    @fragment
+    fn fs_image(in: ImagePayload) -> @location(0) vec4<f32> {
+        let texel = textureSample(image_texture, image_sampler, in.uv);
+        var color = texel.rgb * in.tint * in.brightness;
+        if OUTPUT_SPACE == 1u {
+            color = SRGB_TO_P3 * color;
+        }
+        if OUTPUT_SPACE == 3u {
+            color = limit_peak(color * PAPER_WHITE, PEAK);
+        } else if OUTPUT_SPACE != 2u {
+            color = clamp(limit_peak(color, 1.0), vec3<f32>(0.0), vec3<f32>(1.0));
+        }
+        if ENCODE_OUTPUT {
+            color = linear_to_srgb(color);
+        }
+        return vec4<f32>(color, texel.a * in.opacity);
+    }
+    );
+}
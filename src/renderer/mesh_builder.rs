@@ -1,36 +1,177 @@
-use std::ops::DerefMut;
+use std::collections::HashMap;
 
-use cgmath::Vector3;
-use tinycolors::srgb;
-use wgpu::util::DeviceExt;
+use crate::renderer::{color::Color, frame::Frame};
+use bytemuck::{Pod, Zeroable};
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct Vertex {
-    pub position: Vector3<f32>,
-    pub color: srgb,
+    pub position: [f32; 3],
+    pub color: [f32; 3],
+    pub color_space: u32,
+    pub alpha: f32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Mesh {
     pub verticies: Vec<Vertex>,
-    pub indices: Vec<u16>,
+    pub indices: Vec<u32>,
 }
 
+// the most verticies a mesh can have and still be drawn with 16 bit indices
+pub const U16_INDEX_LIMIT: usize = u16::MAX as usize + 1;
+
 impl Mesh {
-    pub fn draw(&mut self, render_pass: &mut wgpu::RenderPass, device: &wgpu::Device) {
-        let vertex_buffer = make_verticies(device, self.verticies.deref_mut());
-        let index_buffer = make_indecies(device, self.indices.deref_mut());
-        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-        render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+    pub fn append(&mut self, mut other: Mesh) {
+        let offset = self.verticies.len() as u32;
+        self.verticies.append(&mut other.verticies);
+        self.indices
+            .extend(other.indices.into_iter().map(|i| i + offset));
+    }
+
+    // u16 whenever the verticies fit, halving the index buffer
+    pub fn index_format(&self) -> wgpu::IndexFormat {
+        if self.verticies.len() <= U16_INDEX_LIMIT {
+            wgpu::IndexFormat::Uint16
+        } else {
+            wgpu::IndexFormat::Uint32
+        }
+    }
+
+    // scales the alpha of every vertex, e.g. to fade the whole mesh
+    pub fn multiply_alpha(&mut self, opacity: f32) {
+        for vertex in &mut self.verticies {
+            vertex.alpha *= opacity;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.verticies.clear();
+        self.indices.clear();
+    }
+
+    // queues the mesh in the frame's batch. consecutive meshes go out together
+    // in one draw call when the frame flushes
+    pub fn draw(&self, _render_pass: &mut wgpu::RenderPass, frame: &mut Frame) {
+        frame.push_mesh(self);
+    }
+
+    // uploads and draws the mesh right away with the mesh pipeline bound
+    pub fn submit(&self, render_pass: &mut wgpu::RenderPass, frame: &mut Frame) {
+        if self.indices.is_empty() {
+            return;
+        }
+        let max_buffer_size = frame.device.limits().max_buffer_size as usize;
+        let max_verticies = max_buffer_size / std::mem::size_of::<Vertex>();
+        let max_indices = max_buffer_size / std::mem::size_of::<u32>();
+        if self.verticies.len() > max_verticies || self.indices.len() > max_indices {
+            for part in self.split(max_verticies.min(max_indices / 3)) {
+                part.submit(render_pass, frame);
+            }
+            return;
+        }
+
+        let format = self.index_format();
+        let vertex_range = frame.buffers.verticies.write(
+            frame.device,
+            frame.queue,
+            bytemuck::cast_slice(&self.verticies),
+        );
+        let index_range = match format {
+            wgpu::IndexFormat::Uint16 => {
+                let indices: Vec<u16> = self.indices.iter().map(|i| *i as u16).collect();
+                frame.buffers.indices.write(
+                    frame.device,
+                    frame.queue,
+                    bytemuck::cast_slice(&indices),
+                )
+            }
+            wgpu::IndexFormat::Uint32 => frame.buffers.indices.write(
+                frame.device,
+                frame.queue,
+                bytemuck::cast_slice(&self.indices),
+            ),
+        };
+        render_pass.set_vertex_buffer(0, frame.buffers.verticies.slice(vertex_range));
+        render_pass.set_index_buffer(frame.buffers.indices.slice(index_range), format);
         render_pass.draw_indexed(0..self.indices.len() as u32, 0, 0..1);
     }
+
+    // breaks the mesh into pieces of at most `max_verticies` verticies each,
+    // keeping triangles whole. verticies shared across a split get duplicated
+    pub fn split(&self, max_verticies: usize) -> Vec<Mesh> {
+        let max_verticies = max_verticies.max(3);
+        let mut parts = Vec::new();
+        let mut part = Mesh::default();
+        let mut remap: HashMap<u32, u32> = HashMap::new();
+
+        for triangle in self.indices.chunks_exact(3) {
+            let new_verticies = triangle.iter().filter(|i| !remap.contains_key(i)).count();
+            if part.verticies.len() + new_verticies > max_verticies {
+                parts.push(std::mem::take(&mut part));
+                remap.clear();
+            }
+            for i in triangle {
+                let index = *remap.entry(*i).or_insert_with(|| {
+                    part.verticies.push(self.verticies[*i as usize]);
+                    part.verticies.len() as u32 - 1
+                });
+                part.indices.push(index);
+            }
+        }
+        if !part.indices.is_empty() {
+            parts.push(part);
+        }
+        parts
+    }
+
+    // splits every triangle into four through the middles of its edges, for
+    // when colors are set per vertex after building. shared edges share their
+    // middle so the mesh stays closed
+    pub fn subdivide(&mut self) {
+        let mut middles: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut indices = Vec::with_capacity(self.indices.len() * 4);
+        let verticies = &mut self.verticies;
+        let mut middle = |a: u32, b: u32| {
+            *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let (va, vb) = (verticies[a as usize], verticies[b as usize]);
+                let mut vertex = va;
+                for i in 0..3 {
+                    vertex.position[i] = (va.position[i] + vb.position[i]) / 2.0;
+                    vertex.color[i] = (va.color[i] + vb.color[i]) / 2.0;
+                }
+                vertex.alpha = (va.alpha + vb.alpha) / 2.0;
+                verticies.push(vertex);
+                verticies.len() as u32 - 1
+            })
+        };
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+            let (ab, bc, ca) = (middle(a, b), middle(b, c), middle(c, a));
+            indices.extend([a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca]);
+        }
+        self.indices = indices;
+    }
 }
 
 impl Vertex {
+    pub fn new(x: f32, y: f32, color: impl Into<Color>) -> Self {
+        let color = color.into();
+        Self {
+            position: [x, y, 0.0],
+            color: color.components(),
+            color_space: color.space as u32,
+            alpha: 1.0,
+        }
+    }
+
     pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
-        const ATTRIBUTES: [wgpu::VertexAttribute; 2] =
-            wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+        const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+            0 => Float32x3,
+            1 => Float32x3,
+            2 => Uint32,
+            3 => Float32
+        ];
 
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as u64,
@@ -40,89 +181,241 @@ impl Vertex {
     }
 }
 
-fn any_as_u8_slice<T: Sized>(p: &T) -> &[u8] {
-    unsafe {
-        ::core::slice::from_raw_parts((p as *const T) as *const u8, ::core::mem::size_of::<T>())
+pub fn make_rectangle(x: f32, y: f32, w: f32, h: f32, color: impl Into<Color> + Copy) -> Mesh {
+    let verticies = vec![
+        Vertex::new(x, y, color),
+        Vertex::new(x + w, y, color),
+        Vertex::new(x, y - h, color),
+        Vertex::new(x + w, y - h, color),
+    ];
+
+    let indices: Vec<u32> = vec![0, 2, 1, 3, 1, 2];
+
+    Mesh { verticies, indices }
+}
+
+pub fn make_ss_rectangle(
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let (x, y) = ss_to_ndc((x as f32, y as f32), size);
+    let w = w as f32 / size.0 as f32;
+    let h = h as f32 / size.1 as f32;
+
+    make_rectangle(x, y, w, h, color)
+}
+
+// rectangle with each corner replaced by a quarter circle. the radius is
+// clamped to half the shorter side
+pub fn make_ss_rounded_rectangle(
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radius: f32,
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let radius = radius.min(w as f32 / 2.0).min(h as f32 / 2.0);
+    if radius <= 0.0 {
+        return make_ss_rectangle(x, y, w, h, color, size);
     }
+
+    let (x, y, w, h) = (x as f32, y as f32, w as f32, h as f32);
+    let points = rounded_outline((x, y, w, h), radius, corner_segments(radius));
+    make_ss_polygon((x + w / 2.0, y + h / 2.0), &points, color, size)
 }
 
-fn array_to_u8_vec<T: Sized>(p: &mut [T]) -> Vec<u8> {
-    let temp: Vec<u8> = p
-        .iter_mut()
-        .map(|f| any_as_u8_slice(f))
-        .flat_map(|s| s.iter())
-        .cloned()
+// the band between the rect's outline and the same outline inset by `width`,
+// with the inner corners following the outer ones
+#[allow(clippy::too_many_arguments)]
+pub fn make_ss_rounded_ring(
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radius: f32,
+    width: i32,
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let width = width.min(w / 2).min(h / 2);
+    if width <= 0 {
+        return Mesh::default();
+    }
+    let radius = radius.min(w as f32 / 2.0).min(h as f32 / 2.0).max(0.0);
+    let segments = corner_segments(radius);
+    let (x, y, w, h, width) = (x as f32, y as f32, w as f32, h as f32, width as f32);
+    let outer = rounded_outline((x, y, w, h), radius, segments);
+    let inner = rounded_outline(
+        (x + width, y + width, w - width * 2.0, h - width * 2.0),
+        (radius - width).max(0.0),
+        segments,
+    );
+
+    let verticies: Vec<Vertex> = outer
+        .iter()
+        .chain(&inner)
+        .map(|point| {
+            let (x, y) = ss_to_ndc(*point, size);
+            Vertex::new(x, y, color)
+        })
         .collect();
-    temp
-}
+    let n = outer.len() as u32;
+    let mut indices = Vec::with_capacity(outer.len() * 6);
+    for i in 0..n {
+        let j = (i + 1) % n;
+        for [a, b, c] in [[i, j, n + j], [i, n + j, n + i]] {
+            let (pa, pb, pc) = (
+                verticies[a as usize].position,
+                verticies[b as usize].position,
+                verticies[c as usize].position,
+            );
+            let cross = (pb[0] - pa[0]) * (pc[1] - pa[1]) - (pb[1] - pa[1]) * (pc[0] - pa[0]);
+            if cross >= 0.0 {
+                indices.extend([a, b, c]);
+            } else {
+                indices.extend([a, c, b]);
+            }
+        }
+    }
 
-pub fn make_verticies<'a, T: Into<&'a mut [Vertex]>>(
-    device: &wgpu::Device,
-    vertecies: T,
-) -> wgpu::Buffer {
-    let verticies = array_to_u8_vec(vertecies.into());
-    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("vertex buffer"),
-        contents: &verticies,
-        usage: wgpu::BufferUsages::VERTEX,
-    })
+    Mesh { verticies, indices }
 }
 
-pub fn make_indecies<'a, T: Into<&'a mut [u16]>>(
-    device: &wgpu::Device,
-    indices: T,
-) -> wgpu::Buffer {
-    let indices = array_to_u8_vec(indices.into());
-
-    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("index buffer"),
-        contents: &indices,
-        usage: wgpu::BufferUsages::INDEX,
-    })
+fn corner_segments(radius: f32) -> usize {
+    if radius <= 0.0 {
+        1
+    } else {
+        ((radius * 0.25) as usize).clamp(3, 16)
+    }
 }
 
-pub fn make_rectangle(x: f32, y: f32, w: f32, h: f32, color: srgb) -> Mesh {
-    let verticies = vec![
-        Vertex {
-            position: Vector3 { x, y, z: 0.0 },
-            color,
-        },
-        Vertex {
-            position: Vector3 {
-                x: x + w,
-                y,
-                z: 0.0,
-            },
-            color,
-        },
-        Vertex {
-            position: Vector3 {
-                x,
-                y: y - h,
-                z: 0.0,
-            },
-            color,
-        },
-        Vertex {
-            position: Vector3 {
-                x: x + w,
-                y: y - h,
-                z: 0.0,
-            },
-            color,
-        },
+// outline points clockwise on screen from the top right corner. every corner
+// gets `segments + 1` points, even square ones, so outlines of the same
+// segment count line up point for point
+fn rounded_outline(rect: (f32, f32, f32, f32), radius: f32, segments: usize) -> Vec<(f32, f32)> {
+    let (x, y, w, h) = rect;
+    // corner centers, each with the angle its arc starts at
+    let corners = [
+        ((x + w - radius, y + radius), -std::f32::consts::FRAC_PI_2),
+        ((x + w - radius, y + h - radius), 0.0),
+        ((x + radius, y + h - radius), std::f32::consts::FRAC_PI_2),
+        ((x + radius, y + radius), std::f32::consts::PI),
     ];
+    let mut points = Vec::with_capacity(corners.len() * (segments + 1));
+    for ((cx, cy), start) in corners {
+        for i in 0..=segments {
+            let angle = start + i as f32 / segments as f32 * std::f32::consts::FRAC_PI_2;
+            points.push((cx + radius * angle.cos(), cy + radius * angle.sin()));
+        }
+    }
+    points
+}
 
-    let indices: Vec<u16> = vec![0, 2, 1, 3, 1, 2];
+pub fn ss_to_ndc(point: (f32, f32), size: (i32, i32)) -> (f32, f32) {
+    (
+        (point.0 / size.0 as f32) - 1.0,
+        1.0 - (point.1 / size.1 as f32),
+    )
+}
+
+// triangle fan around `center`. points are in screen space and may wind either way
+pub fn make_ss_polygon(
+    center: (f32, f32),
+    points: &[(f32, f32)],
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let mut verticies = Vec::with_capacity(points.len() + 1);
+    for point in std::iter::once(&center).chain(points) {
+        let (x, y) = ss_to_ndc(*point, size);
+        verticies.push(Vertex::new(x, y, color));
+    }
+
+    let mut indices = Vec::with_capacity(points.len() * 3);
+    for i in 0..points.len() {
+        let a = i as u32 + 1;
+        let b = ((i + 1) % points.len()) as u32 + 1;
+        let (pa, pb) = (
+            verticies[a as usize].position,
+            verticies[b as usize].position,
+        );
+        let c = verticies[0].position;
+        let cross = (pa[0] - c[0]) * (pb[1] - c[1]) - (pa[1] - c[1]) * (pb[0] - c[0]);
+        if cross >= 0.0 {
+            indices.extend([0, a, b]);
+        } else {
+            indices.extend([0, b, a]);
+        }
+    }
 
     Mesh { verticies, indices }
 }
 
-pub fn make_ss_rectangle(x: i32, y: i32, w: i32, h: i32, color: srgb, size: (i32, i32)) -> Mesh {
-    let x = (x as f32 / size.0 as f32) - 1.0;
-    let y = 1.0 - (y as f32 / size.1 as f32);
-    let w = w as f32 / size.0 as f32;
-    let h = h as f32 / size.1 as f32;
+pub fn make_ss_star(
+    center: (f32, f32),
+    outer_radius: f32,
+    inner_radius: f32,
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let points: Vec<(f32, f32)> = (0..10)
+        .map(|i| {
+            let radius = if i % 2 == 0 {
+                outer_radius
+            } else {
+                inner_radius
+            };
+            let angle = -std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::PI / 5.0;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect();
+    make_ss_polygon(center, &points, color, size)
+}
+pub fn make_ss_circle(
+    center: (f32, f32),
+    radius: f32,
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let segments = ((radius * 0.5) as usize).clamp(12, 64);
+    let points: Vec<(f32, f32)> = (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect();
+    make_ss_polygon(center, &points, color, size)
+}
 
-    make_rectangle(x, y, w, h, color)
+// one mesh of vertical bars sharing a color. each bar is (x, top, bottom) in screen space
+pub fn make_ss_bars(
+    bars: &[(f32, f32, f32)],
+    bar_width: f32,
+    color: impl Into<Color> + Copy,
+    size: (i32, i32),
+) -> Mesh {
+    let mut verticies = Vec::with_capacity(bars.len() * 4);
+    let mut indices = Vec::with_capacity(bars.len() * 6);
+    for (i, (x, top, bottom)) in bars.iter().enumerate() {
+        let (x0, y0) = ss_to_ndc((*x, *top), size);
+        let (x1, y1) = ss_to_ndc((x + bar_width, *bottom), size);
+        for (x, y) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+            verticies.push(Vertex::new(x, y, color));
+        }
+        let base = (i * 4) as u32;
+        indices.extend([0, 2, 1, 3, 1, 2].map(|j| base + j));
+    }
+    Mesh { verticies, indices }
 }
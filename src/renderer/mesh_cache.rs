@@ -0,0 +1,99 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::renderer::{
+    color::{Color, ColorSpace},
+    mesh_builder::Mesh,
+};
+
+// everything a primitive's generated geometry depends on. `viewport` is the
+// size passed through record, since screen space meshes bake it into their
+// vertices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometryKey {
+    pub position: (i32, i32),
+    pub size: (i32, i32),
+    pub color: [f32; 3],
+    pub color_space: ColorSpace,
+    pub corner_radius: f32,
+    pub border_width: i32,
+    pub border_color: [f32; 3],
+    pub border_color_space: ColorSpace,
+    pub opacity: f32,
+    // `Fill::cache_key` of a gradient, 0 when the color above is the fill
+    pub fill: u64,
+    pub viewport: (i32, i32),
+}
+
+impl GeometryKey {
+    pub fn new(
+        position: (i32, i32),
+        size: (i32, i32),
+        color: impl Into<Color>,
+        viewport: (i32, i32),
+    ) -> Self {
+        let color = color.into();
+        Self {
+            position,
+            size,
+            color: color.components(),
+            color_space: color.space,
+            corner_radius: 0.0,
+            border_width: 0,
+            border_color: [0.0; 3],
+            border_color_space: ColorSpace::Srgb,
+            opacity: 1.0,
+            fill: 0,
+            viewport,
+        }
+    }
+
+    pub fn with_corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn with_fill(mut self, fill: u64) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    pub fn with_border(mut self, width: i32, color: impl Into<Color>) -> Self {
+        let color = color.into();
+        self.border_width = width;
+        self.border_color = color.components();
+        self.border_color_space = color.space;
+        self
+    }
+}
+
+// holds on to the last mesh a primitive generated and only rebuilds it when
+// its key changes. recording takes &self so the cache locks internally
+#[derive(Default)]
+pub struct MeshCache {
+    inner: Mutex<Option<(GeometryKey, Arc<Mesh>)>>,
+}
+
+impl MeshCache {
+    // the mesh for `key`, shared with the draw list rather than copied
+    pub fn get(&self, key: GeometryKey, build: impl FnOnce() -> Mesh) -> Arc<Mesh> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        match inner.as_ref() {
+            Some((cached, mesh)) if *cached == key => mesh.clone(),
+            _ => {
+                let mesh = Arc::new(build());
+                *inner = Some((key, mesh.clone()));
+                mesh
+            }
+        }
+    }
+
+    // forces a rebuild on the next draw, for changes the key doesn't cover
+    pub fn invalidate(&self) {
+        *self.inner.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
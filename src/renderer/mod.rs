@@ -1,2 +1,22 @@
+pub mod color;
+pub mod draw_list;
+pub mod frame;
+#[cfg(feature = "gl")]
+pub mod gl;
+pub mod headless;
+pub mod icons;
+pub mod image;
 pub mod mesh_builder;
+pub mod mesh_cache;
+pub mod particles;
 pub mod pipeline_builder;
+pub mod plugin;
+pub mod post;
+pub mod projection;
+pub mod readback;
+pub mod settings;
+pub mod shaders;
+pub mod skeleton;
+#[cfg(feature = "software")]
+pub mod software;
+pub mod text;
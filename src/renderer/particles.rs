@@ -0,0 +1,266 @@
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::renderer::{
+    color::{Color, ColorOutput, ColorSpace},
+    frame::DynamicBuffer,
+    pipeline_builder::PipelineBuilder,
+};
+
+// one particle as the gpu sees it. motion is a closed form of the spawn state,
+// so particles only need uploading, never simulating on the cpu
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct ParticleInstance {
+    // layout space, y down
+    pub origin: [f32; 2],
+    // layout pixels per second
+    pub velocity: [f32; 2],
+    pub color: [f32; 4],
+    // spawn time and lifetime in seconds, edge length in layout pixels, and
+    // downward acceleration in layout pixels per second squared
+    pub spawn_time: f32,
+    pub lifetime: f32,
+    pub size: f32,
+    pub gravity: f32,
+    // starting angle and angular velocity, in radians
+    pub angle: f32,
+    pub spin: f32,
+}
+
+impl ParticleInstance {
+    pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x2,
+            2 => Float32x4,
+            3 => Float32x4,
+            4 => Float32x2
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ParticleInstance>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+
+    pub fn is_alive(&self, time: f32) -> bool {
+        time - self.spawn_time < self.lifetime
+    }
+
+    // where the shader below puts the particle at `time`: its corners in
+    // layout pixels, color and faded alpha. none once it's dead
+    pub(crate) fn quad(&self, time: f32) -> Option<(Quad, Color, f32)> {
+        let age = time - self.spawn_time;
+        if age < 0.0 || age >= self.lifetime {
+            return None;
+        }
+        let center = (
+            self.origin[0] + self.velocity[0] * age,
+            self.origin[1] + self.velocity[1] * age + 0.5 * self.gravity * age * age,
+        );
+        let (s, c) = (self.angle + self.spin * age).sin_cos();
+        let half = self.size / 2.0;
+        let corners = [(-half, -half), (half, -half), (half, half), (-half, half)]
+            .map(|(x, y)| (center.0 + x * c - y * s, center.1 + x * s + y * c));
+        let t = (age / self.lifetime).clamp(0.0, 1.0);
+        let edge = ((t - 0.5) / 0.5).clamp(0.0, 1.0);
+        let fade = 1.0 - edge * edge * (3.0 - 2.0 * edge);
+        let [r, g, b, a] = self.color;
+        Some((corners, Color::new(r, g, b, ColorSpace::Srgb), a * fade))
+    }
+}
+
+// corners in drawing order
+pub(crate) type Quad = [(f32, f32); 4];
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod, Zeroable)]
+struct ParticleGlobals {
+    viewport: [f32; 2],
+    time: f32,
+    _padding: f32,
+}
+
+// draws particles as instanced quads, one instance per particle
+pub struct ParticleRenderer {
+    pipeline: wgpu::RenderPipeline,
+    globals: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instances: DynamicBuffer,
+    color_output: ColorOutput,
+}
+
+impl ParticleRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        projection_layout: &wgpu::BindGroupLayout,
+        color_output: ColorOutput,
+    ) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let globals = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("particle globals"),
+            contents: bytemuck::bytes_of(&ParticleGlobals::default()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle bind group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: globals.as_entire_binding(),
+            }],
+        });
+
+        let mut pipeline_builder = PipelineBuilder::new();
+        pipeline_builder.set_shader_source(particle_shader::SOURCE, "vs_particle", "fs_particle");
+        pipeline_builder.set_pixel_format(format);
+        pipeline_builder.set_buffer_layout(ParticleInstance::get_layout());
+        pipeline_builder.add_bind_group_layout(projection_layout);
+        pipeline_builder.add_bind_group_layout(&layout);
+        pipeline_builder.set_blend_state(wgpu::BlendState::ALPHA_BLENDING);
+
+        Self {
+            pipeline: pipeline_builder.build_pipeline(device),
+            globals,
+            bind_group,
+            instances: DynamicBuffer::new("particle instance buffer", wgpu::BufferUsages::VERTEX),
+            color_output,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.instances.reset();
+    }
+
+    // particle colors are srgb, converted for the output here. `time` is the
+    // frame clock in seconds, on the same timeline as the spawn times. leaves
+    // the particle pipeline bound
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        particles: &[ParticleInstance],
+        time: f32,
+        size: (i32, i32),
+    ) {
+        if particles.is_empty() {
+            return;
+        }
+        let globals = ParticleGlobals {
+            viewport: [size.0 as f32, size.1 as f32],
+            time,
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.globals, 0, bytemuck::bytes_of(&globals));
+
+        let instances: Vec<ParticleInstance> = particles
+            .iter()
+            .map(|particle| {
+                let [r, g, b, a] = particle.color;
+                let [r, g, b] = self
+                    .color_output
+                    .convert(Color::new(r, g, b, ColorSpace::Srgb));
+                ParticleInstance {
+                    color: [r, g, b, a],
+                    ..*particle
+                }
+            })
+            .collect();
+        let range = self
+            .instances
+            .write(device, queue, bytemuck::cast_slice(&instances));
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instances.slice(range));
+        render_pass.draw(0..6, 0..instances.len() as u32);
+    }
+}
+
+mod particle_shader {
+    wgsl_inline::wgsl!(
+    struct ParticleInstance {
+        @location(0) origin: vec2<f32>,
+        @location(1) velocity: vec2<f32>,
+        @location(2) color: vec4<f32>,
+        // spawn time, lifetime, size, gravity
+        @location(3) motion: vec4<f32>,
+        // angle, spin
+        @location(4) rotation: vec2<f32>,
+    }
+
+    struct ParticleGlobals {
+        viewport: vec2<f32>,
+        time: f32,
+        padding: f32,
+    }
+
+    struct ParticlePayload {
+        @builtin(position) position: vec4<f32>,
+        @location(0) color: vec4<f32>,
+    };
+
+    @group(0) @binding(0)
+    var<uniform> projection: mat4x4<f32>;
+
+    @group(1) @binding(0)
+    var<uniform> globals: ParticleGlobals;
+
+    @vertex
+    fn vs_particle(@builtin(vertex_index) index: u32, particle: ParticleInstance) -> ParticlePayload {
+        // two triangles, wound to match the mesh pipeline once y is flipped
+        var corners = array<vec2<f32>, 6>(
+            vec2<f32>(-0.5, -0.5),
+            vec2<f32>(-0.5, 0.5),
+            vec2<f32>(0.5, -0.5),
+            vec2<f32>(0.5, 0.5),
+            vec2<f32>(0.5, -0.5),
+            vec2<f32>(-0.5, 0.5)
+        );
+
+        let age = globals.time - particle.motion.x;
+        let lifetime = particle.motion.y;
+        let alive = age >= 0.0 && age < lifetime;
+        let center = particle.origin + particle.velocity * age + vec2<f32>(0.0, 0.5 * particle.motion.w * age * age);
+
+        let angle = particle.rotation.x + particle.rotation.y * age;
+        let c = cos(angle);
+        let s = sin(angle);
+        // dead particles collapse to a point and rasterize nothing
+        let corner = corners[index] * particle.motion.z * select(0.0, 1.0, alive);
+        let point = center + vec2<f32>(corner.x * c - corner.y * s, corner.x * s + corner.y * c);
+        let ndc = vec2<f32>(point.x / globals.viewport.x - 1.0, 1.0 - point.y / globals.viewport.y);
+
+        // fade out over the second half of the lifetime
+        let t = clamp(age / lifetime, 0.0, 1.0);
+        let fade = 1.0 - smoothstep(0.5, 1.0, t);
+
+        var out: ParticlePayload;
+        out.position = projection * vec4<f32>(ndc, 0.0, 1.0);
+        out.color = vec4<f32>(particle.color.rgb, particle.color.a * fade);
+        return out;
+    }
+
+    @fragment
+    fn fs_particle(in: ParticlePayload) -> @location(0) vec4<f32> {
+        return in.color;
+    }
+    );
+}
@@ -1,33 +1,53 @@
+use std::borrow::Cow;
+
 pub struct PipelineBuilder {
-    shader_filename: String,
     vertex_entry: String,
     fragment_entry: String,
     pixel_format: wgpu::TextureFormat,
     vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'static>>,
+    bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    shader_source: Option<Cow<'static, str>>,
+    blend_state: wgpu::BlendState,
+    constants: Vec<(&'static str, f64)>,
 }
 
 impl PipelineBuilder {
     pub fn new() -> Self {
         PipelineBuilder {
-            shader_filename: "dummy".to_string(),
-            vertex_entry: "dummy".to_string(),
-            fragment_entry: "dummy".to_string(),
+            vertex_entry: "vs_main".to_string(),
+            fragment_entry: "fs_main".to_string(),
             pixel_format: wgpu::TextureFormat::Rgba8Unorm,
             vertex_buffer_layouts: Vec::new(),
+            bind_group_layouts: Vec::new(),
+            shader_source: None,
+            blend_state: wgpu::BlendState::REPLACE,
+            constants: Vec::new(),
         }
     }
 
-    pub fn set_shader_module(
+    // wgsl used in place of the default shader
+    pub fn set_shader_source(
         &mut self,
-        shader_filename: &str,
+        source: impl Into<Cow<'static, str>>,
         vertex_entry: &str,
         fragment_entry: &str,
     ) {
-        self.shader_filename = shader_filename.to_string();
+        self.shader_source = Some(source.into());
         self.vertex_entry = vertex_entry.to_string();
         self.fragment_entry = fragment_entry.to_string();
     }
 
+    pub fn set_blend_state(&mut self, blend_state: wgpu::BlendState) {
+        self.blend_state = blend_state;
+    }
+
+    // values for `override` declarations in the shader. every name has to exist
+    // in the module
+    pub fn set_constant(&mut self, name: &'static str, value: f64) {
+        self.constants.retain(|(n, _)| *n != name);
+        self.constants.push((name, value));
+    }
+
     pub fn set_pixel_format(&mut self, pixel_format: wgpu::TextureFormat) {
         self.pixel_format = pixel_format;
     }
@@ -36,23 +56,34 @@ impl PipelineBuilder {
         self.vertex_buffer_layouts.push(layout);
     }
 
+    // bound in the order they're added, starting at group 0
+    pub fn add_bind_group_layout(&mut self, layout: &wgpu::BindGroupLayout) {
+        self.bind_group_layouts.push(layout.clone());
+    }
+
     pub fn build_pipeline(&self, device: &wgpu::Device) -> wgpu::RenderPipeline {
         let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("shader module"),
-            source: wgpu::ShaderSource::Wgsl(default_shader::SOURCE.into()),
+            source: wgpu::ShaderSource::Wgsl(
+                self.shader_source
+                    .clone()
+                    .unwrap_or(Cow::Borrowed(default_shader::SOURCE)),
+            ),
         });
 
+        let bind_group_layouts: Vec<&wgpu::BindGroupLayout> =
+            self.bind_group_layouts.iter().collect();
         let render_pipeline_layout = device.create_pipeline_layout(
             &(wgpu::PipelineLayoutDescriptor {
                 label: Some("render pipeline layout"),
-                bind_group_layouts: &[],
+                bind_group_layouts: &bind_group_layouts,
                 push_constant_ranges: &[],
             }),
         );
 
         let render_targets = [Some(wgpu::ColorTargetState {
             format: self.pixel_format,
-            blend: Some(wgpu::BlendState::REPLACE),
+            blend: Some(self.blend_state),
             write_mask: wgpu::ColorWrites::ALL,
         })];
 
@@ -78,7 +109,10 @@ impl PipelineBuilder {
                 module: &shader_module,
                 entry_point: Some(&self.fragment_entry),
                 targets: &render_targets,
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &self.constants,
+                    ..Default::default()
+                },
             }),
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
@@ -92,30 +126,122 @@ impl PipelineBuilder {
     }
 }
 
+// a texture at binding 0 and a filtering sampler at binding 1, both visible to
+// the fragment stage
+pub fn sampled_texture_layout(device: &wgpu::Device, label: &str) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(label),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    })
+}
+
 mod default_shader {
     wgsl_inline::wgsl!(
     struct Vertex {
         @location(0) position: vec3<f32>,
         @location(1) color: vec3<f32>,
+        @location(2) color_space: u32,
+        @location(3) alpha: f32,
     }
 
     struct VertexPayload {
         @builtin(position) position: vec4<f32>,
         @location(0) color: vec3<f32>,
+        @location(1) @interpolate(flat) color_space: u32,
+        @location(2) alpha: f32,
     };
 
+    @group(0) @binding(0)
+    var<uniform> projection: mat4x4<f32>;
+
+    // 0 srgb, 1 display p3, 2 extended srgb, 3 hdr. see ColorOutput
+    override OUTPUT_SPACE: u32 = 0u;
+    override ENCODE_OUTPUT: bool = false;
+    // scRGB levels for sdr white and the brightest hdr value
+    override PAPER_WHITE: f32 = 2.5375;
+    override PEAK: f32 = 12.5;
+
+    // columns, linear to linear
+    const P3_TO_SRGB = mat3x3<f32>(
+        vec3<f32>(1.2249401, -0.0420569, -0.0196376),
+        vec3<f32>(-0.2249404, 1.0420571, -0.0786361),
+        vec3<f32>(0.0, 0.0, 1.0982735)
+    );
+    const SRGB_TO_P3 = mat3x3<f32>(
+        vec3<f32>(0.8224621, 0.0331941, 0.0170827),
+        vec3<f32>(0.1775380, 0.9668058, 0.0723974),
+        vec3<f32>(0.0, 0.0, 0.9105199)
+    );
+
+    fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+        let a = abs(c);
+        let linear = select(pow((a + 0.055) / 1.055, vec3<f32>(2.4)), a / 12.92, a <= vec3<f32>(0.04045));
+        return sign(c) * linear;
+    }
+
+    fn limit_peak(c: vec3<f32>, peak: f32) -> vec3<f32> {
+        let m = max(max(c.r, c.g), c.b);
+        return select(c, c * (peak / m), m > peak);
+    }
+
+    fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+        let a = abs(c);
+        let encoded = select(1.055 * pow(a, vec3<f32>(1.0 / 2.4)) - 0.055, a * 12.92, a <= vec3<f32>(0.0031308));
+        return sign(c) * encoded;
+    }
+
     @vertex
     fn vs_main(vertex: Vertex) -> VertexPayload {
 
         var out: VertexPayload;
-        out.position = vec4<f32>(vertex.position, 1.0);
+        out.position = projection * vec4<f32>(vertex.position, 1.0);
         out.color = vertex.color;
+        out.color_space = vertex.color_space;
+        out.alpha = vertex.alpha;
         return out;
     }
 
     @fragment
     fn fs_main(in: VertexPayload) -> @location(0) vec4<f32> {
-        return vec4<f32>(in.color, 1.0);
+        var color = in.color;
+        switch in.color_space {
+            case 1u: {
+                color = P3_TO_SRGB * srgb_to_linear(color);
+            }
+            case 2u: {}
+            default: {
+                color = srgb_to_linear(color);
+            }
+        }
+        if OUTPUT_SPACE == 1u {
+            color = SRGB_TO_P3 * color;
+        }
+        if OUTPUT_SPACE == 3u {
+            color = limit_peak(color * PAPER_WHITE, PEAK);
+        } else if OUTPUT_SPACE != 2u {
+            color = clamp(limit_peak(color, 1.0), vec3<f32>(0.0), vec3<f32>(1.0));
+        }
+        if ENCODE_OUTPUT {
+            color = linear_to_srgb(color);
+        }
+        return vec4<f32>(color, in.alpha);
     }
     );
 }
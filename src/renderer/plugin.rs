@@ -0,0 +1,104 @@
+use std::{collections::HashSet, sync::Mutex};
+
+use crate::context::FrameClock;
+
+// what a plugin sets itself up with, once the renderer exists
+pub struct PluginInit<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    // of the surface, and of the render pass `RenderPlugin::draw` runs in
+    pub format: wgpu::TextureFormat,
+    // teacup's projection at group 0, for plugins that want to draw in layout
+    // coordinates like the built in pipelines
+    pub projection_layout: &'a wgpu::BindGroupLayout,
+}
+
+// the frame being built, before the ui is drawn into it
+pub struct PluginFrame<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    // physical size of the surface
+    pub target_size: (u32, u32),
+    // surface pixels per point
+    pub scale_factor: f32,
+    pub clock: FrameClock,
+}
+
+// where a `PluginView` landed this frame, in surface pixels. the scissor is
+// already set to the visible part of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PluginViewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub scale_factor: f32,
+}
+
+// custom drawing that lives inside teacup's frame, like a map, a terminal or
+// video. register one with `App::with_plugin` and place it in the tree with
+// `PluginView`. everything but `init` is optional
+pub trait RenderPlugin: Send {
+    fn init(&mut self, cx: &PluginInit);
+
+    // once a frame before the ui's render pass. offscreen passes, compute and
+    // uploads go here, recorded into `cx.encoder`
+    fn prepare(&mut self, _cx: &mut PluginFrame) {}
+
+    // inside the ui's render pass, once for each `PluginView` of this plugin,
+    // in tree order with the ui around it. teacup's pipeline and projection
+    // are bound again afterwards, so the pass can be left in any state
+    fn draw(&mut self, _render_pass: &mut wgpu::RenderPass, _viewport: PluginViewport) {}
+
+    // the surface changed size, in physical pixels
+    fn resize(&mut self, _device: &wgpu::Device, _target_size: (u32, u32)) {}
+}
+
+// the plugins an app registered, by name
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<(String, Box<dyn RenderPlugin>)>,
+    // names already reported as missing, so a bad name logs once
+    missing: Mutex<HashSet<String>>,
+}
+
+impl PluginRegistry {
+    // replaces a plugin already registered under `name`
+    pub fn add(&mut self, name: &str, mut plugin: Box<dyn RenderPlugin>, cx: &PluginInit) {
+        plugin.init(cx);
+        self.plugins.retain(|(existing, _)| existing != name);
+        self.plugins.push((name.to_string(), plugin));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut dyn RenderPlugin> {
+        let found = self
+            .plugins
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, plugin)| plugin.as_mut() as &mut dyn RenderPlugin);
+        if found.is_none()
+            && let Ok(mut missing) = self.missing.lock()
+            && missing.insert(name.to_string())
+        {
+            log::warn!("no render plugin named {name:?}");
+        }
+        found
+    }
+
+    pub fn prepare(&mut self, cx: &mut PluginFrame) {
+        for (_, plugin) in &mut self.plugins {
+            plugin.prepare(cx);
+        }
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, target_size: (u32, u32)) {
+        for (_, plugin) in &mut self.plugins {
+            plugin.resize(device, target_size);
+        }
+    }
+}
@@ -0,0 +1,536 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::renderer::pipeline_builder::PipelineBuilder;
+
+static NEXT_LUT_ID: AtomicUsize = AtomicUsize::new(0);
+
+// a 3d color lookup table with `size` entries per axis, red varying fastest.
+// works on display encoded (srgb) values like most grading tools export
+pub struct ColorLut {
+    id: usize,
+    size: u32,
+    texels: Vec<u8>,
+}
+
+impl ColorLut {
+    pub fn from_fn(size: u32, f: impl Fn([f32; 3]) -> [f32; 3]) -> Arc<ColorLut> {
+        let size = size.max(2);
+        let step = 1.0 / (size - 1) as f32;
+        let mut texels = Vec::with_capacity((size * size * size * 4) as usize);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let graded = f([r as f32 * step, g as f32 * step, b as f32 * step]);
+                    texels.extend(graded.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+                    texels.push(255);
+                }
+            }
+        }
+        Arc::new(Self {
+            id: NEXT_LUT_ID.fetch_add(1, Ordering::Relaxed),
+            size,
+            texels,
+        })
+    }
+
+    pub fn identity(size: u32) -> Arc<ColorLut> {
+        Self::from_fn(size, |c| c)
+    }
+
+    // the .cube format most editors export. only 3d luts over 0..1 are supported
+    pub fn parse_cube(source: &str) -> anyhow::Result<Arc<ColorLut>> {
+        let mut size = None;
+        let mut entries = Vec::new();
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("LUT_3D_SIZE") {
+                size = Some(value.trim().parse::<u32>()?);
+                continue;
+            }
+            if line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                // TITLE, DOMAIN_MIN and friends
+                continue;
+            }
+            let values: Vec<f32> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?;
+            let [r, g, b] = values[..] else {
+                anyhow::bail!("expected three values per lut entry, got {line:?}");
+            };
+            entries.push([r, g, b]);
+        }
+
+        let Some(size) = size else {
+            anyhow::bail!("missing LUT_3D_SIZE");
+        };
+        if size < 2 || entries.len() != (size * size * size) as usize {
+            anyhow::bail!(
+                "expected {} entries for a size {size} lut, got {}",
+                size * size * size,
+                entries.len()
+            );
+        }
+        let texels = entries
+            .iter()
+            .flat_map(|c| {
+                let [r, g, b] = c.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect();
+        Ok(Arc::new(Self {
+            id: NEXT_LUT_ID.fetch_add(1, Ordering::Relaxed),
+            size,
+            texels,
+        }))
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+// full screen passes run over the finished ui, in order
+#[derive(Clone)]
+pub enum PostEffect {
+    // darkens toward the corners. `radius` is where the falloff ends, as a
+    // fraction of the distance from the center to a corner
+    Vignette {
+        strength: f32,
+        radius: f32,
+        softness: f32,
+    },
+    ColorGrade {
+        lut: Arc<ColorLut>,
+        strength: f32,
+    },
+    // animated film grain
+    Grain {
+        strength: f32,
+    },
+}
+
+impl PostEffect {
+    pub fn vignette(strength: f32) -> Self {
+        PostEffect::Vignette {
+            strength,
+            radius: 1.0,
+            softness: 0.6,
+        }
+    }
+
+    pub fn color_grade(lut: Arc<ColorLut>) -> Self {
+        PostEffect::ColorGrade { lut, strength: 1.0 }
+    }
+
+    pub fn grain(strength: f32) -> Self {
+        PostEffect::Grain { strength }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod, Zeroable)]
+struct PostParams {
+    kind: u32,
+    seed: u32,
+    linear_source: u32,
+    lut_size: f32,
+    strength: f32,
+    radius: f32,
+    softness: f32,
+    padding: f32,
+}
+
+struct Target {
+    view: wgpu::TextureView,
+}
+
+// renders the ui into an offscreen texture when effects are active, then
+// ping-pongs between two textures, one pass per effect, ending on the surface
+pub struct PostProcessor {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    format: wgpu::TextureFormat,
+    // sampling an srgb or float target yields linear values, which luts
+    // need encoded
+    linear_source: bool,
+    size: (u32, u32),
+    targets: Vec<Target>,
+    params: Vec<wgpu::Buffer>,
+    luts: HashMap<usize, Target>,
+    // bound for passes that don't grade
+    empty_lut: Target,
+    frame: u32,
+}
+
+impl PostProcessor {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post bind group layout"),
+            entries: &[
+                texture_entry(0, wgpu::TextureViewDimension::D2),
+                sampler_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(3, wgpu::TextureViewDimension::D3),
+                sampler_entry(4),
+            ],
+        });
+
+        let mut pipeline_builder = PipelineBuilder::new();
+        pipeline_builder.set_shader_source(post_shader::SOURCE, "vs_post", "fs_post");
+        pipeline_builder.set_pixel_format(format);
+        pipeline_builder.add_bind_group_layout(&layout);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let linear_source = format.is_srgb()
+            || matches!(
+                format,
+                wgpu::TextureFormat::Rgba16Float | wgpu::TextureFormat::Rgba32Float
+            );
+        let identity = ColorLut::identity(2);
+        Self {
+            pipeline: pipeline_builder.build_pipeline(device),
+            layout,
+            sampler,
+            format,
+            linear_source,
+            size: (0, 0),
+            targets: Vec::new(),
+            params: Vec::new(),
+            luts: HashMap::new(),
+            empty_lut: upload_lut(device, queue, &identity),
+            frame: 0,
+        }
+    }
+
+    // the view the ui should render into this frame
+    pub fn begin(&mut self, device: &wgpu::Device, size: (u32, u32)) -> &wgpu::TextureView {
+        if self.size != size || self.targets.is_empty() {
+            self.size = size;
+            self.targets = (0..2)
+                .map(|_| {
+                    let texture = device.create_texture(&wgpu::TextureDescriptor {
+                        label: Some("post target"),
+                        size: wgpu::Extent3d {
+                            width: size.0.max(1),
+                            height: size.1.max(1),
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format: self.format,
+                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                            | wgpu::TextureUsages::TEXTURE_BINDING,
+                        view_formats: &[],
+                    });
+                    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                    Target { view }
+                })
+                .collect();
+        }
+        &self.targets[0].view
+    }
+
+    // runs every effect over what was rendered into `begin`'s view, writing
+    // the last one to `output`
+    pub fn apply(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        effects: &[PostEffect],
+        output: &wgpu::TextureView,
+    ) {
+        self.frame = self.frame.wrapping_add(1);
+        while self.params.len() < effects.len() {
+            self.params.push(
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("post params"),
+                    contents: bytemuck::bytes_of(&PostParams::default()),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                }),
+            );
+        }
+        for effect in effects {
+            if let PostEffect::ColorGrade { lut, .. } = effect {
+                self.luts
+                    .entry(lut.id)
+                    .or_insert_with(|| upload_lut(device, queue, lut));
+            }
+        }
+
+        for (i, effect) in effects.iter().enumerate() {
+            let params = self.params(effect);
+            queue.write_buffer(&self.params[i], 0, bytemuck::bytes_of(&params));
+
+            let lut = match effect {
+                PostEffect::ColorGrade { lut, .. } => &self.luts[&lut.id],
+                _ => &self.empty_lut,
+            };
+            let source = &self.targets[i % 2].view;
+            let destination = if i + 1 == effects.len() {
+                output
+            } else {
+                &self.targets[(i + 1) % 2].view
+            };
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("post bind group"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.params[i].as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::TextureView(&lut.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("post pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: destination,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        // only keep luts that are still in the chain
+        self.luts.retain(|id, _| {
+            effects
+                .iter()
+                .any(|e| matches!(e, PostEffect::ColorGrade { lut, .. } if lut.id == *id))
+        });
+    }
+
+    fn params(&self, effect: &PostEffect) -> PostParams {
+        let mut params = PostParams {
+            seed: self.frame,
+            linear_source: self.linear_source as u32,
+            ..Default::default()
+        };
+        match effect {
+            PostEffect::Vignette {
+                strength,
+                radius,
+                softness,
+            } => {
+                params.kind = 0;
+                params.strength = *strength;
+                params.radius = *radius;
+                params.softness = *softness;
+            }
+            PostEffect::ColorGrade { lut, strength } => {
+                params.kind = 1;
+                params.strength = *strength;
+                params.lut_size = lut.size as f32;
+            }
+            PostEffect::Grain { strength } => {
+                params.kind = 2;
+                params.strength = *strength;
+            }
+        }
+        params
+    }
+}
+
+fn texture_entry(
+    binding: u32,
+    view_dimension: wgpu::TextureViewDimension,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+fn sampler_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+fn upload_lut(device: &wgpu::Device, queue: &wgpu::Queue, lut: &ColorLut) -> Target {
+    let size = wgpu::Extent3d {
+        width: lut.size,
+        height: lut.size,
+        depth_or_array_layers: lut.size,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("color lut"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D3,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &lut.texels,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(lut.size * 4),
+            rows_per_image: Some(lut.size),
+        },
+        size,
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    Target { view }
+}
+
+mod post_shader {
+    wgsl_inline::wgsl!(
+    struct PostParams {
+        kind: u32,
+        seed: u32,
+        linear_source: u32,
+        lut_size: f32,
+        strength: f32,
+        radius: f32,
+        softness: f32,
+        padding: f32,
+    };
+
+    struct PostPayload {
+        @builtin(position) position: vec4<f32>,
+        @location(0) uv: vec2<f32>,
+    };
+
+    @group(0) @binding(0)
+    var source: texture_2d<f32>;
+    @group(0) @binding(1)
+    var source_sampler: sampler;
+    @group(0) @binding(2)
+    var<uniform> params: PostParams;
+    @group(0) @binding(3)
+    var lut: texture_3d<f32>;
+    @group(0) @binding(4)
+    var lut_sampler: sampler;
+
+    fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+        return select(pow((c + 0.055) / 1.055, vec3<f32>(2.4)), c / 12.92, c <= vec3<f32>(0.04045));
+    }
+
+    fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+        return select(1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3<f32>(0.0031308));
+    }
+
+    fn hash(p: vec2<u32>, seed: u32) -> f32 {
+        var h = p.x * 374761393u + p.y * 668265263u + seed * 2246822519u;
+        h = (h ^ (h >> 13u)) * 1274126177u;
+        h = h ^ (h >> 16u);
+        return f32(h) / 4294967295.0;
+    }
+
+    // one triangle that covers the whole target
+    @vertex
+    fn vs_post(@builtin(vertex_index) index: u32) -> PostPayload {
+        let uv = vec2<f32>(f32(index & 2u), f32((index << 1u) & 2u));
+        var out: PostPayload;
+        out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+        out.uv = uv;
+        return out;
+    }
+
+    @fragment
+    fn fs_post(in: PostPayload) -> @location(0) vec4<f32> {
+        let texel = textureSample(source, source_sampler, in.uv);
+        var color = texel.rgb;
+        switch params.kind {
+            case 0u: {
+                let d = distance(in.uv, vec2<f32>(0.5)) * 1.4142135;
+                let v = 1.0 - smoothstep(params.radius - params.softness, params.radius, d);
+                color = color * mix(1.0, v, params.strength);
+            }
+            case 1u: {
+                var c = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+                if params.linear_source != 0u {
+                    c = linear_to_srgb(c);
+                }
+                // sample texel centers so the ends of the table map to 0 and 1
+                let scale = (params.lut_size - 1.0) / params.lut_size;
+                let offset = 0.5 / params.lut_size;
+                var graded = textureSampleLevel(lut, lut_sampler, c * scale + offset, 0.0).rgb;
+                if params.linear_source != 0u {
+                    graded = srgb_to_linear(graded);
+                }
+                color = mix(color, graded, params.strength);
+            }
+            default: {
+                let n = hash(vec2<u32>(in.position.xy), params.seed) - 0.5;
+                color = color + vec3<f32>(n * params.strength);
+            }
+        }
+        return vec4<f32>(color, texel.a);
+    }
+    );
+}
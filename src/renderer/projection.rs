@@ -0,0 +1,117 @@
+use cgmath::{Deg, Matrix4};
+use wgpu::util::DeviceExt;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Landscape,
+    Portrait,
+    LandscapeFlipped,
+    PortraitFlipped,
+}
+
+impl Orientation {
+    // clockwise rotation of the display relative to its natural orientation
+    pub fn degrees(self) -> f32 {
+        match self {
+            Orientation::Landscape => 0.0,
+            Orientation::Portrait => 90.0,
+            Orientation::LandscapeFlipped => 180.0,
+            Orientation::PortraitFlipped => 270.0,
+        }
+    }
+
+    pub fn is_quarter_turn(self) -> bool {
+        matches!(self, Orientation::Portrait | Orientation::PortraitFlipped)
+    }
+
+    // the size layout should see for a surface of `size`. quarter turns swap
+    // width and height
+    pub fn logical_size(self, size: (i32, i32)) -> (i32, i32) {
+        if self.is_quarter_turn() {
+            (size.1, size.0)
+        } else {
+            size
+        }
+    }
+
+    // maps a point in logical pixels onto the physical surface, matching the
+    // rotation `transform` applies in the shader
+    pub fn to_physical(self, point: (f32, f32), logical_size: (i32, i32)) -> (f32, f32) {
+        let (w, h) = (logical_size.0 as f32, logical_size.1 as f32);
+        match self {
+            Orientation::Landscape => point,
+            Orientation::Portrait => (h - point.1, point.0),
+            Orientation::LandscapeFlipped => (w - point.0, h - point.1),
+            Orientation::PortraitFlipped => (point.1, w - point.0),
+        }
+    }
+
+    // rotates clip space so content drawn at the logical size lands upright on
+    // the rotated display
+    pub fn transform(self) -> [[f32; 4]; 4] {
+        Matrix4::from_angle_z(Deg(-self.degrees())).into()
+    }
+}
+
+// the uniform every ui vertex is multiplied by in the vertex shader
+pub struct Projection {
+    orientation: Orientation,
+    buffer: wgpu::Buffer,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl Projection {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let orientation = Orientation::default();
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("projection buffer"),
+            contents: bytemuck::cast_slice(&orientation.transform()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("projection bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("projection bind group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+        Self {
+            orientation,
+            buffer,
+            bind_group_layout,
+            bind_group,
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn set_orientation(&mut self, queue: &wgpu::Queue, orientation: Orientation) {
+        if orientation == self.orientation {
+            return;
+        }
+        self.orientation = orientation;
+        queue.write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&orientation.transform()),
+        );
+    }
+}
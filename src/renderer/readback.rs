@@ -0,0 +1,159 @@
+use tinycolors::srgb;
+
+// copies a single pixel out of `texture` and blocks until it's mapped. only
+// meant for occasional reads like the eyedropper, not per-frame use
+pub fn read_pixel(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    point: (u32, u32),
+) -> Option<srgb> {
+    if point.0 >= texture.width() || point.1 >= texture.height() {
+        return None;
+    }
+    if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+        log::warn!("can't read back pixels from a texture without COPY_SRC usage");
+        return None;
+    }
+    let swizzle = match texture.format() {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => [0, 1, 2],
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => [2, 1, 0],
+        format => {
+            log::warn!("pixel readback isn't supported for {format:?}");
+            return None;
+        }
+    };
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("pixel readback buffer"),
+        size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("pixel readback encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d {
+                x: point.0,
+                y: point.1,
+                z: 0,
+            },
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                rows_per_image: Some(1),
+            },
+        },
+        wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..4);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    if let Err(e) = device.poll(wgpu::PollType::Wait) {
+        log::error!("failed waiting for pixel readback: {e}");
+        return None;
+    }
+    rx.recv().ok()?.ok()?;
+
+    let data = slice.get_mapped_range();
+    let color = crate::theme::rgb(
+        data[swizzle[0]] as f32 / 255.0,
+        data[swizzle[1]] as f32 / 255.0,
+        data[swizzle[2]] as f32 / 255.0,
+    );
+    drop(data);
+    buffer.unmap();
+    Some(color)
+}
+
+// copies all of `texture` out as an image and blocks until it's mapped
+pub fn read_image(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Option<::image::RgbaImage> {
+    if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+        log::warn!("can't read back pixels from a texture without COPY_SRC usage");
+        return None;
+    }
+    let swizzle = match texture.format() {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => [0, 1, 2, 3],
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => [2, 1, 0, 3],
+        format => {
+            log::warn!("image readback isn't supported for {format:?}");
+            return None;
+        }
+    };
+
+    let (width, height) = (texture.width(), texture.height());
+    // rows are padded out to the copy alignment
+    let row_bytes = width * 4;
+    let padded_row_bytes = row_bytes.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("image readback buffer"),
+        size: padded_row_bytes as u64 * height as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("image readback encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    if let Err(e) = device.poll(wgpu::PollType::Wait) {
+        log::error!("failed waiting for image readback: {e}");
+        return None;
+    }
+    rx.recv().ok()?.ok()?;
+
+    let data = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+    for row in data.chunks(padded_row_bytes as usize) {
+        for pixel in row[..row_bytes as usize].chunks_exact(4) {
+            pixels.extend(swizzle.map(|i| pixel[i]));
+        }
+    }
+    drop(data);
+    buffer.unmap();
+    ::image::RgbaImage::from_raw(width, height, pixels)
+}
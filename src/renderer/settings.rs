@@ -0,0 +1,217 @@
+use std::{fmt, sync::Arc};
+
+use crate::renderer::color::{HdrSettings, OutputColorSpace};
+
+type AdapterCallback = Arc<dyn Fn(&[wgpu::AdapterInfo]) -> Option<usize> + Send + Sync>;
+
+// picks the gpu teacup renders with out of every adapter that can present to the
+// window. falls back to wgpu's own choice when nothing matches
+#[derive(Clone, Default)]
+pub enum AdapterSelector {
+    #[default]
+    Auto,
+    Discrete,
+    Integrated,
+    // case insensitive substring of the adapter name
+    Name(String),
+    Custom(AdapterCallback),
+}
+
+impl AdapterSelector {
+    pub fn custom(
+        f: impl Fn(&[wgpu::AdapterInfo]) -> Option<usize> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    pub fn select(&self, adapters: &[wgpu::AdapterInfo]) -> Option<usize> {
+        let by_type = |device_type| adapters.iter().position(|a| a.device_type == device_type);
+        match self {
+            AdapterSelector::Auto => None,
+            AdapterSelector::Discrete => by_type(wgpu::DeviceType::DiscreteGpu),
+            AdapterSelector::Integrated => by_type(wgpu::DeviceType::IntegratedGpu),
+            AdapterSelector::Name(name) => {
+                let name = name.to_lowercase();
+                adapters
+                    .iter()
+                    .position(|a| a.name.to_lowercase().contains(&name))
+            }
+            AdapterSelector::Custom(f) => f(adapters).filter(|i| *i < adapters.len()),
+        }
+    }
+}
+
+impl fmt::Debug for AdapterSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdapterSelector::Auto => write!(f, "Auto"),
+            AdapterSelector::Discrete => write!(f, "Discrete"),
+            AdapterSelector::Integrated => write!(f, "Integrated"),
+            AdapterSelector::Name(name) => f.debug_tuple("Name").field(name).finish(),
+            AdapterSelector::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+// how finished frames are handed to the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PresentMode {
+    // vsync. every frame is shown and rendering waits on the display, which
+    // keeps the frame rate at the refresh rate. always supported
+    #[default]
+    Fifo,
+    // vsync without the wait, a newer frame replaces one still queued. lower
+    // latency than fifo without tearing, but renders as fast as it can
+    Mailbox,
+    // no vsync, frames go out as soon as they're done and may tear
+    Immediate,
+}
+
+impl PresentMode {
+    fn to_wgpu(self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+
+    // falls back to fifo when the surface can't do this one
+    pub(crate) fn pick(self, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+        let mode = self.to_wgpu();
+        if supported.contains(&mode) {
+            mode
+        } else {
+            log::warn!("surface doesn't support {self:?} presentation, using fifo");
+            wgpu::PresentMode::Fifo
+        }
+    }
+}
+
+// when the event loop draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedrawMode {
+    // every time around the loop, as fast as the present mode and frame rate
+    // limit allow
+    #[default]
+    Continuous,
+    // right away for input, timers, layout changes and running animations.
+    // otherwise the loop sleeps and only wakes every so often for things like
+    // a blinking caret, so an idle ui barely touches the gpu
+    OnDemand,
+}
+
+// what draws the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RendererKind {
+    // the gpu, or the cpu when no gpu can present to the window and teacup
+    // was built with the software feature
+    #[default]
+    Auto,
+    Gpu,
+    // the cpu even when there's a gpu, needs the software feature
+    Software,
+    // opengl 3.3 on the window's own context, needs the gl feature. lighter
+    // than wgpu, but without custom shaders, post effects or plugins
+    Gl,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    pub backends: wgpu::Backends,
+    // used when the selector doesn't pick an adapter
+    pub power_preference: wgpu::PowerPreference,
+    pub adapter_selector: AdapterSelector,
+    // falls back to srgb when the surface can't do it
+    pub color_space: OutputColorSpace,
+    // only used with OutputColorSpace::Hdr
+    pub hdr: HdrSettings,
+    pub present_mode: PresentMode,
+    // caps frames per second on top of whatever the present mode does
+    pub max_frame_rate: Option<f32>,
+    pub redraw: RedrawMode,
+    pub renderer: RendererKind,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::default(),
+            adapter_selector: AdapterSelector::Auto,
+            color_space: OutputColorSpace::Srgb,
+            hdr: HdrSettings::default(),
+            present_mode: PresentMode::default(),
+            max_frame_rate: None,
+            redraw: RedrawMode::default(),
+            renderer: RendererKind::default(),
+        }
+    }
+}
+
+// every adapter wgpu can see for these backends, for building a gpu picker
+pub fn available_adapters(backends: wgpu::Backends) -> Vec<wgpu::AdapterInfo> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends,
+        ..Default::default()
+    });
+    instance
+        .enumerate_adapters(backends)
+        .iter()
+        .map(wgpu::Adapter::get_info)
+        .collect()
+}
+
+pub async fn select_adapter(
+    instance: &wgpu::Instance,
+    surface: &wgpu::Surface<'_>,
+    settings: &RenderSettings,
+) -> Option<wgpu::Adapter> {
+    let mut adapters: Vec<wgpu::Adapter> = instance
+        .enumerate_adapters(settings.backends)
+        .into_iter()
+        .filter(|adapter| adapter.is_surface_supported(surface))
+        .collect();
+    let infos: Vec<wgpu::AdapterInfo> = adapters.iter().map(wgpu::Adapter::get_info).collect();
+    for info in &infos {
+        log::info!(
+            "found adapter {} ({:?}, {:?})",
+            info.name,
+            info.device_type,
+            info.backend
+        );
+    }
+
+    let adapter = match settings.adapter_selector.select(&infos) {
+        Some(index) => Some(adapters.swap_remove(index)),
+        None => {
+            if !matches!(settings.adapter_selector, AdapterSelector::Auto) {
+                log::warn!(
+                    "no adapter matched {:?}, falling back to the default",
+                    settings.adapter_selector
+                );
+            }
+            instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: settings.power_preference,
+                    force_fallback_adapter: false,
+                    compatible_surface: Some(surface),
+                })
+                .await
+                .ok()
+        }
+    };
+
+    if let Some(adapter) = &adapter {
+        let info = adapter.get_info();
+        log::info!(
+            "rendering with {} ({:?}, {:?}, driver {} {})",
+            info.name,
+            info.device_type,
+            info.backend,
+            info.driver,
+            info.driver_info
+        );
+    }
+    adapter
+}
@@ -0,0 +1,106 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Mutex,
+};
+
+use crate::renderer::{mesh_builder::Vertex, pipeline_builder::PipelineBuilder};
+
+// wgsl for drawing mesh primitives some other way. the shader gets the same
+// verticies as the built in mesh pipeline at location 0 to 3 and the projection
+// matrix at group 0 binding 0
+#[derive(Debug, Clone)]
+pub struct CustomShader {
+    pub source: Cow<'static, str>,
+    pub vertex_entry: String,
+    pub fragment_entry: String,
+    pub blend: wgpu::BlendState,
+}
+
+impl CustomShader {
+    pub fn new(source: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            source: source.into(),
+            vertex_entry: "vs_main".to_string(),
+            fragment_entry: "fs_main".to_string(),
+            blend: wgpu::BlendState::ALPHA_BLENDING,
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(std::fs::read_to_string(path)?))
+    }
+
+    pub fn with_entry_points(mut self, vertex_entry: &str, fragment_entry: &str) -> Self {
+        self.vertex_entry = vertex_entry.to_string();
+        self.fragment_entry = fragment_entry.to_string();
+        self
+    }
+
+    pub fn with_blend(mut self, blend: wgpu::BlendState) -> Self {
+        self.blend = blend;
+        self
+    }
+}
+
+// the custom shaders registered at startup, compiled into pipelines by name
+#[derive(Default)]
+pub struct PipelineRegistry {
+    pipelines: HashMap<String, wgpu::RenderPipeline>,
+    // names already reported as missing, so a bad name logs once
+    missing: Mutex<HashSet<String>>,
+}
+
+impl PipelineRegistry {
+    // shaders that fail to compile are logged and left out, so primitives
+    // asking for them fall back to the mesh pipeline
+    pub async fn build(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        projection_layout: &wgpu::BindGroupLayout,
+        shaders: &[(String, CustomShader)],
+    ) -> Self {
+        let mut pipelines = HashMap::new();
+        for (name, shader) in shaders {
+            let mut pipeline_builder = PipelineBuilder::new();
+            pipeline_builder.set_shader_source(
+                shader.source.clone(),
+                &shader.vertex_entry,
+                &shader.fragment_entry,
+            );
+            pipeline_builder.set_pixel_format(format);
+            pipeline_builder.set_blend_state(shader.blend);
+            pipeline_builder.set_buffer_layout(Vertex::get_layout());
+            pipeline_builder.add_bind_group_layout(projection_layout);
+
+            device.push_error_scope(wgpu::ErrorFilter::Validation);
+            let pipeline = pipeline_builder.build_pipeline(device);
+            match device.pop_error_scope().await {
+                Some(e) => log::error!("custom shader {name:?} failed to compile: {e}"),
+                None => {
+                    pipelines.insert(name.clone(), pipeline);
+                }
+            }
+        }
+        Self {
+            pipelines,
+            missing: Mutex::default(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&wgpu::RenderPipeline> {
+        let pipeline = self.pipelines.get(name);
+        if pipeline.is_none()
+            && let Ok(mut missing) = self.missing.lock()
+            && missing.insert(name.to_string())
+        {
+            log::warn!("no custom shader named {name:?}, drawing with the mesh pipeline");
+        }
+        pipeline
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.pipelines.contains_key(name)
+    }
+}
@@ -0,0 +1,251 @@
+use bytemuck::{Pod, Zeroable};
+use tinycolors::srgb;
+use wgpu::util::DeviceExt;
+
+use crate::renderer::{
+    color::ColorOutput, frame::DynamicBuffer, pipeline_builder::PipelineBuilder,
+};
+
+// seconds for the highlight to cross the window once
+const SHIMMER_PERIOD: f32 = 1.6;
+
+// one rounded placeholder block. the shimmer is computed from the block's
+// position on screen so neighbouring blocks shimmer as one sweep
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct SkeletonInstance {
+    // layout space, y down
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub radius: f32,
+    pub base: [f32; 3],
+    pub highlight: [f32; 3],
+}
+
+impl SkeletonInstance {
+    pub fn new(
+        position: (f32, f32),
+        size: (f32, f32),
+        radius: f32,
+        base: srgb,
+        highlight: srgb,
+    ) -> Self {
+        Self {
+            position: [position.0, position.1],
+            size: [size.0, size.1],
+            radius: radius.min(size.0 / 2.0).min(size.1 / 2.0).max(0.0),
+            base: [base.r, base.g, base.b],
+            highlight: [highlight.r, highlight.g, highlight.b],
+        }
+    }
+
+    pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x2,
+            2 => Float32,
+            3 => Float32x3,
+            4 => Float32x3
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SkeletonInstance>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod, Zeroable)]
+struct SkeletonGlobals {
+    viewport: [f32; 2],
+    time: f32,
+    period: f32,
+}
+
+// draws loading placeholders as instanced rounded rects with an animated
+// shimmer
+pub struct SkeletonRenderer {
+    pipeline: wgpu::RenderPipeline,
+    globals: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instances: DynamicBuffer,
+    color_output: ColorOutput,
+}
+
+impl SkeletonRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        projection_layout: &wgpu::BindGroupLayout,
+        color_output: ColorOutput,
+    ) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("skeleton bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let globals = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("skeleton globals"),
+            contents: bytemuck::bytes_of(&SkeletonGlobals::default()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("skeleton bind group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: globals.as_entire_binding(),
+            }],
+        });
+
+        let mut pipeline_builder = PipelineBuilder::new();
+        pipeline_builder.set_shader_source(skeleton_shader::SOURCE, "vs_skeleton", "fs_skeleton");
+        pipeline_builder.set_pixel_format(format);
+        pipeline_builder.set_buffer_layout(SkeletonInstance::get_layout());
+        pipeline_builder.add_bind_group_layout(projection_layout);
+        pipeline_builder.add_bind_group_layout(&layout);
+        pipeline_builder.set_blend_state(wgpu::BlendState::ALPHA_BLENDING);
+
+        Self {
+            pipeline: pipeline_builder.build_pipeline(device),
+            globals,
+            bind_group,
+            instances: DynamicBuffer::new("skeleton instance buffer", wgpu::BufferUsages::VERTEX),
+            color_output,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.instances.reset();
+    }
+
+    // `time` is the frame clock in seconds. leaves the skeleton pipeline bound
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        blocks: &[SkeletonInstance],
+        time: f32,
+        size: (i32, i32),
+    ) {
+        if blocks.is_empty() {
+            return;
+        }
+        let globals = SkeletonGlobals {
+            viewport: [size.0 as f32, size.1 as f32],
+            time,
+            period: SHIMMER_PERIOD,
+        };
+        queue.write_buffer(&self.globals, 0, bytemuck::bytes_of(&globals));
+
+        let convert = |[r, g, b]: [f32; 3]| self.color_output.convert(srgb { r, g, b }.into());
+        let instances: Vec<SkeletonInstance> = blocks
+            .iter()
+            .map(|block| SkeletonInstance {
+                base: convert(block.base),
+                highlight: convert(block.highlight),
+                ..*block
+            })
+            .collect();
+        let range = self
+            .instances
+            .write(device, queue, bytemuck::cast_slice(&instances));
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instances.slice(range));
+        render_pass.draw(0..6, 0..instances.len() as u32);
+    }
+}
+
+mod skeleton_shader {
+    wgsl_inline::wgsl!(
+    struct SkeletonInstance {
+        @location(0) position: vec2<f32>,
+        @location(1) size: vec2<f32>,
+        @location(2) radius: f32,
+        @location(3) base: vec3<f32>,
+        @location(4) highlight: vec3<f32>,
+    }
+
+    struct SkeletonGlobals {
+        viewport: vec2<f32>,
+        time: f32,
+        period: f32,
+    }
+
+    struct SkeletonPayload {
+        @builtin(position) position: vec4<f32>,
+        // layout space position, and the same relative to the block center
+        @location(0) point: vec2<f32>,
+        @location(1) local: vec2<f32>,
+        @location(2) half_size: vec2<f32>,
+        @location(3) radius: f32,
+        @location(4) base: vec3<f32>,
+        @location(5) highlight: vec3<f32>,
+    };
+
+    @group(0) @binding(0)
+    var<uniform> projection: mat4x4<f32>;
+
+    @group(1) @binding(0)
+    var<uniform> globals: SkeletonGlobals;
+
+    @vertex
+    fn vs_skeleton(@builtin(vertex_index) index: u32, block: SkeletonInstance) -> SkeletonPayload {
+        // two triangles, wound to match the mesh pipeline once y is flipped
+        var corners = array<vec2<f32>, 6>(
+            vec2<f32>(0.0, 0.0),
+            vec2<f32>(0.0, 1.0),
+            vec2<f32>(1.0, 0.0),
+            vec2<f32>(1.0, 1.0),
+            vec2<f32>(1.0, 0.0),
+            vec2<f32>(0.0, 1.0)
+        );
+        let point = block.position + corners[index] * block.size;
+        let ndc = vec2<f32>(point.x / globals.viewport.x - 1.0, 1.0 - point.y / globals.viewport.y);
+
+        var out: SkeletonPayload;
+        out.position = projection * vec4<f32>(ndc, 0.0, 1.0);
+        out.point = point;
+        out.half_size = block.size * 0.5;
+        out.local = point - block.position - out.half_size;
+        out.radius = block.radius;
+        out.base = block.base;
+        out.highlight = block.highlight;
+        return out;
+    }
+
+    @fragment
+    fn fs_skeleton(in: SkeletonPayload) -> @location(0) vec4<f32> {
+        // signed distance to the rounded rect, in layout pixels
+        let q = abs(in.local) - in.half_size + vec2<f32>(in.radius);
+        let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - in.radius;
+        // layout space is twice the logical size, so this is about a pixel of
+        // antialiasing
+        let coverage = clamp(0.5 - distance * 0.5, 0.0, 1.0);
+
+        // a soft diagonal band sweeping left to right across the whole window
+        let width = globals.viewport.x * 2.0;
+        let band = width * 0.15;
+        let progress = fract(globals.time / globals.period);
+        let center = mix(-band, width + band, progress);
+        let offset = (in.point.x + in.point.y * 0.3 - center) / band;
+        let shimmer = exp(-offset * offset);
+
+        return vec4<f32>(mix(in.base, in.highlight, shimmer), coverage);
+    }
+    );
+}
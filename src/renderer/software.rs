@@ -0,0 +1,474 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    num::NonZeroU32,
+};
+
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, RawDisplayHandle,
+    RawWindowHandle, WindowHandle,
+};
+use tiny_skia::{
+    ColorU8, FillRule, FilterQuality, Mask, Paint, PathBuilder, Pixmap, PixmapPaint, Rect,
+    Transform,
+};
+use tinycolors::srgb;
+
+use crate::{
+    layout::UI,
+    renderer::{
+        color::Color,
+        draw_list::{self, DrawCommand, DrawList},
+        image::ImageData,
+        text::{Font, GlyphKey, RasterizedGlyph},
+    },
+};
+
+// glyph bitmaps kept before the cache starts over
+const MAX_GLYPHS: usize = 4096;
+
+// draws a `UI` on the cpu with tiny-skia, from its draw list. for machines
+// where no gpu can present, like vms and ci runners. widgets the draw list
+// leaves out don't show up, see `UI::draw_list`
+pub struct SoftwareRenderer {
+    pixmap: Pixmap,
+    glyphs: HashMap<GlyphKey, RasterizedGlyph>,
+    // premultiplied copies of the images drawn last frame, by image id
+    images: HashMap<usize, Pixmap>,
+    used: HashSet<usize>,
+}
+
+// a clip as (left, top, right, bottom) in target pixels
+type ClipRect = (f32, f32, f32, f32);
+
+impl Default for SoftwareRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SoftwareRenderer {
+    pub fn new() -> Self {
+        Self {
+            pixmap: Pixmap::new(1, 1).expect("a 1x1 pixmap is always valid"),
+            glyphs: HashMap::new(),
+            images: HashMap::new(),
+            used: HashSet::new(),
+        }
+    }
+
+    // draws the laid out `ui` stretched over `target_size` pixels
+    pub fn render(&mut self, ui: &UI, target_size: (u32, u32)) -> &Pixmap {
+        let list = ui.draw_list();
+        self.render_list(
+            &list,
+            ui.background_color.into(),
+            ui.background_alpha,
+            target_size,
+        )
+    }
+
+    // lays `ui` out at `logical_size` points and draws it at `scale_factor`
+    // pixels per point, like `HeadlessRenderer::render` without a gpu
+    pub fn render_image(
+        &mut self,
+        ui: &mut UI,
+        logical_size: (i32, i32),
+        scale_factor: f32,
+    ) -> ::image::RgbaImage {
+        let target_size = (
+            ((logical_size.0 as f32 * scale_factor).round() as u32).max(1),
+            ((logical_size.1 as f32 * scale_factor).round() as u32).max(1),
+        );
+        ui.set_scale_factor(scale_factor);
+        ui.set_size(logical_size);
+        ui.compute_layout();
+        self.render(ui, target_size);
+        self.to_image()
+    }
+
+    pub fn render_list(
+        &mut self,
+        list: &DrawList,
+        background: Color,
+        background_alpha: f32,
+        target_size: (u32, u32),
+    ) -> &Pixmap {
+        let target_size = (target_size.0.max(1), target_size.1.max(1));
+        if (self.pixmap.width(), self.pixmap.height()) != target_size
+            && let Some(pixmap) = Pixmap::new(target_size.0, target_size.1)
+        {
+            self.pixmap = pixmap;
+        }
+        let [r, g, b] = background.to_srgb();
+        let background = tiny_skia::Color::from_rgba(r, g, b, background_alpha.clamp(0.0, 1.0))
+            .unwrap_or(tiny_skia::Color::BLACK);
+        self.pixmap.fill(background);
+
+        // layout pixels to target pixels
+        let scale = (
+            target_size.0 as f32 / list.size.0.max(1) as f32,
+            target_size.1 as f32 / list.size.1.max(1) as f32,
+        );
+        let to_target = |(x, y): (f32, f32)| (x * scale.0, y * scale.1);
+        self.used.clear();
+        if self.glyphs.len() > MAX_GLYPHS {
+            self.glyphs.clear();
+        }
+
+        let mut offsets = vec![(0.0f32, 0.0f32)];
+        let mut clips: Vec<ClipRect> = Vec::new();
+        let mut mask: Option<Mask> = None;
+        for command in &list.lowered() {
+            let offset = offsets.last().copied().unwrap_or_default();
+            let moved = |(x, y): (i32, i32)| (x as f32 + offset.0, y as f32 + offset.1);
+            match command {
+                DrawCommand::Mesh(mesh) => {
+                    self.fill_mesh(mesh, list.mesh_size(), offset, scale, mask.as_ref())
+                }
+                DrawCommand::Text {
+                    font,
+                    text,
+                    px,
+                    origin,
+                    color,
+                } => self.draw_text(
+                    font,
+                    text,
+                    px * scale.0,
+                    to_target((origin.0 + offset.0, origin.1 + offset.1)),
+                    *color,
+                    mask.as_ref(),
+                ),
+                DrawCommand::Image {
+                    image,
+                    position,
+                    extent,
+                    brightness,
+                    opacity,
+                } => {
+                    let (x, y) = to_target(moved(*position));
+                    let (w, h) = to_target((extent.0 as f32, extent.1 as f32));
+                    self.draw_image(image, (x, y, w, h), *brightness, *opacity, mask.as_ref());
+                }
+                DrawCommand::PushClip { position, extent } => {
+                    let (left, top) = to_target(moved(*position));
+                    let (w, h) = to_target((extent.0 as f32, extent.1 as f32));
+                    let mut clip = (left, top, left + w, top + h);
+                    if let Some(parent) = clips.last() {
+                        clip = (
+                            clip.0.max(parent.0),
+                            clip.1.max(parent.1),
+                            clip.2.min(parent.2),
+                            clip.3.min(parent.3),
+                        );
+                    }
+                    clips.push(clip);
+                    mask = clip_mask(clip, target_size);
+                }
+                DrawCommand::PopClip => {
+                    clips.pop();
+                    mask = clips.last().and_then(|clip| clip_mask(*clip, target_size));
+                }
+                DrawCommand::PushTransform { offset: by } => {
+                    offsets.push((offset.0 + by.0, offset.1 + by.1));
+                }
+                DrawCommand::PopTransform => {
+                    if offsets.len() > 1 {
+                        offsets.pop();
+                    }
+                }
+                // gone once the list is lowered
+                DrawCommand::Pipeline(_)
+                | DrawCommand::ImageRegion { .. }
+                | DrawCommand::Skeletons(_)
+                | DrawCommand::Particles(_)
+                | DrawCommand::Plugin { .. } => {}
+            }
+        }
+        self.images.retain(|id, _| self.used.contains(id));
+        &self.pixmap
+    }
+
+    // the last frame's color at a pixel, for the eyedropper
+    pub fn pixel(&self, point: (u32, u32)) -> Option<srgb> {
+        let color = self.pixmap.pixel(point.0, point.1)?.demultiply();
+        Some(crate::theme::rgb(
+            color.red() as f32 / 255.0,
+            color.green() as f32 / 255.0,
+            color.blue() as f32 / 255.0,
+        ))
+    }
+
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.pixmap
+    }
+
+    // the last frame as straight alpha rgba
+    pub fn to_image(&self) -> ::image::RgbaImage {
+        ::image::RgbaImage::from_fn(self.pixmap.width(), self.pixmap.height(), |x, y| {
+            let color = self
+                .pixmap
+                .pixel(x, y)
+                .map(|pixel| pixel.demultiply())
+                .unwrap_or(ColorU8::from_rgba(0, 0, 0, 0));
+            ::image::Rgba([color.red(), color.green(), color.blue(), color.alpha()])
+        })
+    }
+
+    // runs of triangles with the same color are filled as one path, so
+    // antialiasing doesn't leave seams where they meet
+    fn fill_mesh(
+        &mut self,
+        mesh: &crate::renderer::mesh_builder::Mesh,
+        size: (i32, i32),
+        offset: (f32, f32),
+        scale: (f32, f32),
+        mask: Option<&Mask>,
+    ) {
+        let mut run: Option<([u8; 4], PathBuilder)> = None;
+        for triangle in draw_list::triangles(mesh, size) {
+            let rgba = triangle.rgba8();
+            let mut points = triangle
+                .points
+                .map(|(x, y)| ((x + offset.0) * scale.0, (y + offset.1) * scale.1));
+            // all wound the same way, so overlaps in a run don't cancel out
+            let [a, b, c] = points;
+            if (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0) < 0.0 {
+                points.swap(1, 2);
+            }
+
+            if let Some((color, path)) = run.take_if(|(color, _)| *color != rgba) {
+                fill(&mut self.pixmap, path, color, mask);
+            }
+            let (_, path) = run.get_or_insert_with(|| (rgba, PathBuilder::new()));
+            path.move_to(points[0].0, points[0].1);
+            path.line_to(points[1].0, points[1].1);
+            path.line_to(points[2].0, points[2].1);
+            path.close();
+        }
+        if let Some((color, path)) = run {
+            fill(&mut self.pixmap, path, color, mask);
+        }
+    }
+
+    // `px` and `origin` are already in target pixels
+    fn draw_text(
+        &mut self,
+        font: &Font,
+        text: &str,
+        px: f32,
+        origin: (f32, f32),
+        color: Color,
+        mask: Option<&Mask>,
+    ) {
+        let [r, g, b] = color.to_srgb().map(|c| (c * 255.0).round() as u8);
+        for glyph in &font.layout(text, px).glyphs {
+            let entry = self
+                .glyphs
+                .entry(GlyphKey::new(font, glyph.index, px))
+                .or_insert_with(|| font.rasterize_glyph(glyph.index, px));
+            let Some(mut bitmap) = Pixmap::new(entry.size.0, entry.size.1) else {
+                continue;
+            };
+            for (pixel, coverage) in bitmap.pixels_mut().iter_mut().zip(&entry.coverage) {
+                *pixel = ColorU8::from_rgba(r, g, b, *coverage).premultiply();
+            }
+            // snapped to whole pixels like the gpu text
+            let x = (origin.0 + glyph.x + entry.offset.0).round() as i32;
+            let y = (origin.1 + glyph.baseline + entry.offset.1).round() as i32;
+            self.pixmap.draw_pixmap(
+                x,
+                y,
+                bitmap.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                mask,
+            );
+        }
+    }
+
+    // `bounds` is (x, y, width, height) in target pixels
+    fn draw_image(
+        &mut self,
+        image: &ImageData,
+        bounds: (f32, f32, f32, f32),
+        brightness: f32,
+        opacity: f32,
+        mask: Option<&Mask>,
+    ) {
+        let (width, height) = image.size();
+        if width == 0 || height == 0 || bounds.2 <= 0.0 || bounds.3 <= 0.0 {
+            return;
+        }
+        self.used.insert(image.id());
+        let source = match self.images.entry(image.id()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match premultiplied(image, 1.0) {
+                Some(pixmap) => entry.insert(pixmap),
+                None => return,
+            },
+        };
+        let brightened;
+        let source = if (brightness - 1.0).abs() > f32::EPSILON {
+            let Some(pixmap) = premultiplied(image, brightness) else {
+                return;
+            };
+            brightened = pixmap;
+            &brightened
+        } else {
+            source
+        };
+        let transform = Transform::from_row(
+            bounds.2 / width as f32,
+            0.0,
+            0.0,
+            bounds.3 / height as f32,
+            bounds.0,
+            bounds.1,
+        );
+        let paint = PixmapPaint {
+            opacity: opacity.clamp(0.0, 1.0),
+            quality: FilterQuality::Bilinear,
+            ..Default::default()
+        };
+        self.pixmap
+            .draw_pixmap(0, 0, source.as_ref(), &paint, transform, mask);
+    }
+}
+
+fn fill(pixmap: &mut Pixmap, path: PathBuilder, [r, g, b, a]: [u8; 4], mask: Option<&Mask>) {
+    let Some(path) = path.finish() else {
+        return;
+    };
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        mask,
+    );
+}
+
+// a mask letting through only `clip`, snapped to whole pixels like a scissor
+// rect. an empty clip hides everything
+fn clip_mask(clip: ClipRect, size: (u32, u32)) -> Option<Mask> {
+    let mut mask = Mask::new(size.0, size.1)?;
+    if let Some(rect) = Rect::from_ltrb(
+        clip.0.round(),
+        clip.1.round(),
+        clip.2.round(),
+        clip.3.round(),
+    ) {
+        mask.fill_path(
+            &PathBuilder::from_rect(rect),
+            FillRule::Winding,
+            false,
+            Transform::identity(),
+        );
+    }
+    Some(mask)
+}
+
+// `image` as a premultiplied pixmap, its colors scaled by `brightness`
+fn premultiplied(image: &ImageData, brightness: f32) -> Option<Pixmap> {
+    let (width, height) = image.size();
+    let mut pixmap = Pixmap::new(width, height)?;
+    let bright = |c: u8| (c as f32 * brightness).round().clamp(0.0, 255.0) as u8;
+    for (pixel, rgba) in pixmap
+        .pixels_mut()
+        .iter_mut()
+        .zip(image.pixels().chunks_exact(4))
+    {
+        *pixel = ColorU8::from_rgba(bright(rgba[0]), bright(rgba[1]), bright(rgba[2]), rgba[3])
+            .premultiply();
+    }
+    Some(pixmap)
+}
+
+// a window's raw handles, so softbuffer can hold onto them without borrowing
+// the window
+#[derive(Clone)]
+struct RawWindow {
+    window: RawWindowHandle,
+    display: RawDisplayHandle,
+}
+
+impl HasWindowHandle for RawWindow {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        // the window outlives the surface, see `SoftwareSurface::new`
+        Ok(unsafe { WindowHandle::borrow_raw(self.window) })
+    }
+}
+
+impl HasDisplayHandle for RawWindow {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Ok(unsafe { DisplayHandle::borrow_raw(self.display) })
+    }
+}
+
+// frames from a `SoftwareRenderer` copied into a window, for when no gpu can
+// present to it
+pub(crate) struct SoftwareSurface {
+    surface: softbuffer::Surface<RawWindow, RawWindow>,
+    renderer: SoftwareRenderer,
+    size: (u32, u32),
+}
+
+impl SoftwareSurface {
+    // the window has to outlive the surface
+    pub(crate) unsafe fn new(
+        window: &(impl HasWindowHandle + HasDisplayHandle),
+        size: (i32, i32),
+    ) -> anyhow::Result<Self> {
+        let raw = RawWindow {
+            window: window.window_handle()?.as_raw(),
+            display: window.display_handle()?.as_raw(),
+        };
+        let context = softbuffer::Context::new(raw.clone()).map_err(error)?;
+        let mut surface = Self {
+            surface: softbuffer::Surface::new(&context, raw).map_err(error)?,
+            renderer: SoftwareRenderer::new(),
+            size: (0, 0),
+        };
+        surface.resize(size)?;
+        log::info!("rendering on the cpu");
+        Ok(surface)
+    }
+
+    pub(crate) fn resize(&mut self, size: (i32, i32)) -> anyhow::Result<()> {
+        let (Some(width), Some(height)) = (
+            NonZeroU32::new(size.0.max(0) as u32),
+            NonZeroU32::new(size.1.max(0) as u32),
+        ) else {
+            return Ok(());
+        };
+        self.surface.resize(width, height).map_err(error)?;
+        self.size = (width.get(), height.get());
+        Ok(())
+    }
+
+    // draws the laid out `ui` and shows it
+    pub(crate) fn present(&mut self, ui: &mut UI) -> anyhow::Result<()> {
+        let pixmap = self.renderer.render(ui, self.size);
+        let mut buffer = self.surface.buffer_mut().map_err(error)?;
+        // softbuffer takes 0rgb. premultiplied colors are the frame over black,
+        // which is what an opaque window shows
+        for (out, pixel) in buffer.iter_mut().zip(pixmap.pixels()) {
+            *out = (pixel.red() as u32) << 16 | (pixel.green() as u32) << 8 | pixel.blue() as u32;
+        }
+        buffer.present().map_err(error)?;
+
+        if let Some(point) = ui.eyedropper.sample_request() {
+            ui.eyedropper.deliver(self.renderer.pixel(point));
+        }
+        Ok(())
+    }
+}
+
+// softbuffer's errors can hold raw handles, which anyhow won't carry
+fn error(e: softbuffer::SoftBufferError) -> anyhow::Error {
+    anyhow::anyhow!("{e}")
+}
@@ -0,0 +1,525 @@
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::renderer::{
+    color::{Color, ColorOutput},
+    frame::DynamicBuffer,
+    mesh_builder::ss_to_ndc,
+    pipeline_builder::{PipelineBuilder, sampled_texture_layout},
+};
+
+const ATLAS_SIZE: u32 = 1024;
+// empty space around each glyph so linear sampling doesn't bleed into neighbours
+const GLYPH_PADDING: u32 = 1;
+
+static NEXT_FONT_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Font {
+    id: usize,
+    inner: fontdue::Font,
+    // the font file, for embedding in exported documents
+    data: Vec<u8>,
+}
+
+impl Font {
+    pub fn from_bytes(bytes: impl Deref<Target = [u8]>) -> anyhow::Result<Arc<Font>> {
+        let data = bytes.to_vec();
+        let inner = fontdue::Font::from_bytes(data.as_slice(), fontdue::FontSettings::default())
+            .map_err(|e| anyhow::anyhow!("failed to parse font: {e}"))?;
+        Ok(Arc::new(Self {
+            id: NEXT_FONT_ID.fetch_add(1, Ordering::Relaxed),
+            inner,
+            data,
+        }))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Arc<Font>> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    // coverage of one character, a byte per pixel, for building other atlases
+    // like icon sets
+    pub(crate) fn rasterize(&self, c: char, px: f32) -> (fontdue::Metrics, Vec<u8>) {
+        self.inner.rasterize(c, px)
+    }
+
+    pub(crate) fn rasterize_glyph(&self, index: u16, px: f32) -> RasterizedGlyph {
+        let (metrics, coverage) = self.inner.rasterize_indexed(index, px);
+        let (w, h) = (metrics.width as u32, metrics.height as u32);
+        RasterizedGlyph {
+            size: (w, h),
+            offset: (metrics.xmin as f32, -(metrics.ymin as f32 + h as f32)),
+            coverage,
+        }
+    }
+
+    pub(crate) fn has_glyph(&self, c: char) -> bool {
+        self.inner.lookup_glyph_index(c) != 0
+    }
+
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub(crate) fn glyph_index(&self, c: char) -> u16 {
+        self.inner.lookup_glyph_index(c)
+    }
+
+    pub(crate) fn glyph_advance(&self, index: u16, px: f32) -> f32 {
+        self.inner.metrics_indexed(index, px).advance_width
+    }
+
+    // the first font found in a few well known system locations
+    pub fn system_default() -> Option<Arc<Font>> {
+        const PATHS: &[&str] = &[
+            "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+            "/usr/share/fonts/TTF/DejaVuSans.ttf",
+            "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+            "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+            "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+            "/System/Library/Fonts/Supplemental/Arial.ttf",
+            "/Library/Fonts/Arial.ttf",
+            "C:\\Windows\\Fonts\\segoeui.ttf",
+            "C:\\Windows\\Fonts\\arial.ttf",
+        ];
+        PATHS.iter().find_map(|path| Self::load(path).ok())
+    }
+
+    // the same for a fixed width font, for grids of text like a terminal
+    pub fn monospace_default() -> Option<Arc<Font>> {
+        const PATHS: &[&str] = &[
+            "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+            "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+            "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
+            "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+            "/usr/share/fonts/noto/NotoSansMono-Regular.ttf",
+            "/System/Library/Fonts/Menlo.ttc",
+            "C:\\Windows\\Fonts\\consola.ttf",
+            "C:\\Windows\\Fonts\\cour.ttf",
+        ];
+        PATHS.iter().find_map(|path| Self::load(path).ok())
+    }
+
+    // bundled, so tests that measure text run wherever they're built
+    #[cfg(test)]
+    pub(crate) fn for_tests() -> Arc<Font> {
+        Self::from_bytes(&include_bytes!("../../tests/fonts/DejaVuSansMono.ttf")[..])
+            .expect("the bundled test font parses")
+    }
+
+    // how far the pen moves after `c`
+    pub fn advance(&self, c: char, px: f32) -> f32 {
+        self.inner.metrics(c, px).advance_width
+    }
+
+    pub fn line_height(&self, px: f32) -> f32 {
+        self.inner
+            .horizontal_line_metrics(px)
+            .map_or(px * 1.2, |m| m.new_line_size)
+    }
+
+    pub fn ascent(&self, px: f32) -> f32 {
+        self.inner
+            .horizontal_line_metrics(px)
+            .map_or(px, |m| m.ascent)
+    }
+
+    // positions every glyph relative to the top left of the text. newlines
+    // start a new line
+    pub fn layout(&self, text: &str, px: f32) -> TextLayout {
+        let line_height = self.line_height(px);
+        let ascent = self.ascent(px);
+        let mut glyphs = Vec::with_capacity(text.len());
+        let (mut x, mut width, mut line) = (0.0f32, 0.0f32, 0);
+        let mut previous = None;
+
+        for c in text.chars() {
+            if c == '\n' {
+                width = width.max(x);
+                x = 0.0;
+                line += 1;
+                previous = None;
+                continue;
+            }
+            let index = self.inner.lookup_glyph_index(c);
+            if let Some(previous) = previous {
+                x += self
+                    .inner
+                    .horizontal_kern_indexed(previous, index, px)
+                    .unwrap_or(0.0);
+            }
+            glyphs.push(PositionedGlyph {
+                index,
+                x,
+                baseline: ascent + line as f32 * line_height,
+            });
+            x += self.inner.metrics_indexed(index, px).advance_width;
+            previous = Some(index);
+        }
+
+        TextLayout {
+            glyphs,
+            width: width.max(x),
+            height: (line + 1) as f32 * line_height,
+        }
+    }
+
+    pub fn measure(&self, text: &str, px: f32) -> (f32, f32) {
+        let layout = self.layout(text, px);
+        (layout.width, layout.height)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PositionedGlyph {
+    pub index: u16,
+    pub x: f32,
+    pub baseline: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub width: f32,
+    pub height: f32,
+}
+
+// one glyph of one font at one size, what every renderer caches glyphs by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct GlyphKey {
+    font: usize,
+    index: u16,
+    px: u32,
+}
+
+impl GlyphKey {
+    pub(crate) fn new(font: &Font, index: u16, px: f32) -> Self {
+        Self {
+            font: font.id,
+            index,
+            px: px.to_bits(),
+        }
+    }
+}
+
+// a glyph's coverage, a byte per pixel, and where it goes
+pub(crate) struct RasterizedGlyph {
+    pub size: (u32, u32),
+    // from the pen position on the baseline to the bitmap's top left
+    pub offset: (f32, f32),
+    pub coverage: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GlyphEntry {
+    uv: [f32; 4],
+    size: (f32, f32),
+    // from the pen position on the baseline to the bitmap's top left
+    offset: (f32, f32),
+}
+
+// single channel coverage texture packed with shelves of rasterized glyphs
+struct GlyphAtlas {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    glyphs: HashMap<GlyphKey, GlyphEntry>,
+    cursor: (u32, u32),
+    row_height: u32,
+}
+
+impl GlyphAtlas {
+    fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("glyph atlas"),
+            size: wgpu::Extent3d {
+                width: ATLAS_SIZE,
+                height: ATLAS_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("glyph atlas sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("glyph atlas bind group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        Self {
+            texture,
+            bind_group,
+            glyphs: HashMap::new(),
+            cursor: (0, 0),
+            row_height: 0,
+        }
+    }
+
+    fn glyph(
+        &mut self,
+        queue: &wgpu::Queue,
+        font: &Font,
+        index: u16,
+        px: f32,
+    ) -> Option<GlyphEntry> {
+        let key = GlyphKey::new(font, index, px);
+        if let Some(entry) = self.glyphs.get(&key) {
+            return Some(*entry);
+        }
+
+        let glyph = font.rasterize_glyph(index, px);
+        let (w, h) = glyph.size;
+        let mut entry = GlyphEntry {
+            uv: [0.0; 4],
+            size: (w as f32, h as f32),
+            offset: glyph.offset,
+        };
+        if w == 0 || h == 0 {
+            self.glyphs.insert(key, entry);
+            return Some(entry);
+        }
+        if w + GLYPH_PADDING > ATLAS_SIZE || h + GLYPH_PADDING > ATLAS_SIZE {
+            log::warn!("glyph at {px}px is too large for the atlas");
+            return None;
+        }
+
+        if self.cursor.0 + w + GLYPH_PADDING > ATLAS_SIZE {
+            self.cursor = (0, self.cursor.1 + self.row_height);
+            self.row_height = 0;
+        }
+        if self.cursor.1 + h + GLYPH_PADDING > ATLAS_SIZE {
+            // start over. glyphs already drawn this frame may come out wrong
+            // until the next frame rasterizes them again
+            log::warn!("glyph atlas is full, clearing it");
+            self.glyphs.clear();
+            self.cursor = (0, 0);
+            self.row_height = 0;
+        }
+
+        let (x, y) = self.cursor;
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &glyph.coverage,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(w),
+                rows_per_image: Some(h),
+            },
+            wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: 1,
+            },
+        );
+        let size = ATLAS_SIZE as f32;
+        entry.uv = [
+            x as f32 / size,
+            y as f32 / size,
+            (x + w) as f32 / size,
+            (y + h) as f32 / size,
+        ];
+        self.cursor.0 += w + GLYPH_PADDING;
+        self.row_height = self.row_height.max(h + GLYPH_PADDING);
+        self.glyphs.insert(key, entry);
+        Some(entry)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct TextVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 3],
+}
+
+impl TextVertex {
+    pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x3];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<TextVertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+pub struct TextRenderer {
+    pipeline: wgpu::RenderPipeline,
+    atlas: GlyphAtlas,
+    verticies: DynamicBuffer,
+    indices: DynamicBuffer,
+    color_output: ColorOutput,
+}
+
+impl TextRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        projection_layout: &wgpu::BindGroupLayout,
+        color_output: ColorOutput,
+    ) -> Self {
+        let atlas_layout = sampled_texture_layout(device, "glyph atlas bind group layout");
+
+        let mut pipeline_builder = PipelineBuilder::new();
+        pipeline_builder.set_shader_source(text_shader::SOURCE, "vs_text", "fs_text");
+        pipeline_builder.set_pixel_format(format);
+        pipeline_builder.set_buffer_layout(TextVertex::get_layout());
+        pipeline_builder.add_bind_group_layout(projection_layout);
+        pipeline_builder.add_bind_group_layout(&atlas_layout);
+        pipeline_builder.set_blend_state(wgpu::BlendState::ALPHA_BLENDING);
+
+        Self {
+            pipeline: pipeline_builder.build_pipeline(device),
+            atlas: GlyphAtlas::new(device, &atlas_layout),
+            verticies: DynamicBuffer::new("text vertex buffer", wgpu::BufferUsages::VERTEX),
+            indices: DynamicBuffer::new("text index buffer", wgpu::BufferUsages::INDEX),
+            color_output,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.verticies.reset();
+        self.indices.reset();
+    }
+
+    // draws `text` with its top left at `origin` in layout space. leaves the
+    // text pipeline bound
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &mut self,
+        render_pass: &mut wgpu::RenderPass,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        font: &Font,
+        text: &str,
+        px: f32,
+        origin: (f32, f32),
+        color: impl Into<Color>,
+        size: (i32, i32),
+    ) {
+        let layout = font.layout(text, px);
+        // the text shader writes colors as is, so convert once here
+        let color = self.color_output.convert(color.into());
+        let mut verticies = Vec::with_capacity(layout.glyphs.len() * 4);
+        let mut indices: Vec<u32> = Vec::with_capacity(layout.glyphs.len() * 6);
+
+        for glyph in &layout.glyphs {
+            let Some(entry) = self.atlas.glyph(queue, font, glyph.index, px) else {
+                continue;
+            };
+            if entry.size.0 == 0.0 || entry.size.1 == 0.0 {
+                continue;
+            }
+            // snapped to whole pixels so glyphs sample the atlas one to one
+            let x0 = (origin.0 + glyph.x + entry.offset.0).round();
+            let y0 = (origin.1 + glyph.baseline + entry.offset.1).round();
+            let (x1, y1) = (x0 + entry.size.0, y0 + entry.size.1);
+            let [u0, v0, u1, v1] = entry.uv;
+
+            let base = verticies.len() as u32;
+            for (point, uv) in [
+                ((x0, y0), [u0, v0]),
+                ((x1, y0), [u1, v0]),
+                ((x0, y1), [u0, v1]),
+                ((x1, y1), [u1, v1]),
+            ] {
+                let (x, y) = ss_to_ndc(point, size);
+                verticies.push(TextVertex {
+                    position: [x, y],
+                    uv,
+                    color,
+                });
+            }
+            indices.extend([0, 2, 1, 3, 1, 2].map(|i| base + i));
+        }
+        if indices.is_empty() {
+            return;
+        }
+
+        let vertex_range = self
+            .verticies
+            .write(device, queue, bytemuck::cast_slice(&verticies));
+        let index_range = self
+            .indices
+            .write(device, queue, bytemuck::cast_slice(&indices));
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(1, &self.atlas.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.verticies.slice(vertex_range));
+        render_pass.set_index_buffer(self.indices.slice(index_range), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..indices.len() as u32, 0, 0..1);
+    }
+}
+
+mod text_shader {
+    wgsl_inline::wgsl!(
+    struct TextVertex {
+        @location(0) position: vec2<f32>,
+        @location(1) uv: vec2<f32>,
+        @location(2) color: vec3<f32>,
+    }
+
+    struct TextPayload {
+        @builtin(position) position: vec4<f32>,
+        @location(0) uv: vec2<f32>,
+        @location(1) color: vec3<f32>,
+    };
+
+    @group(0) @binding(0)
+    var<uniform> projection: mat4x4<f32>;
+
+    @group(1) @binding(0)
+    var atlas: texture_2d<f32>;
+    @group(1) @binding(1)
+    var atlas_sampler: sampler;
+
+    @vertex
+    fn vs_text(vertex: TextVertex) -> TextPayload {
+        var out: TextPayload;
+        out.position = projection * vec4<f32>(vertex.position, 0.0, 1.0);
+        out.uv = vertex.uv;
+        out.color = vertex.color;
+        return out;
+    }
+
+    @fragment
+    fn fs_text(in: TextPayload) -> @location(0) vec4<f32> {
+        let coverage = textureSample(atlas, atlas_sampler, in.uv).r;
+        return vec4<f32>(in.color, coverage);
+    }
+    );
+}
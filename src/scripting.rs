@@ -0,0 +1,347 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use rhai::{AST, Engine, EvalAltResult, FLOAT, INT};
+use tinycolors::srgb;
+
+use crate::{
+    builder::{self, ElementBuilder},
+    dispatch::Phase,
+    event::{Event, PointerButton},
+    fill::Fill,
+    layout::{AlignCross, AlignMain, LayoutMode, Text, UI},
+    renderer::text::Font,
+    theme::{from_hex, rgb},
+};
+
+type Step = Arc<dyn Fn(ElementBuilder) -> ElementBuilder + Send + Sync>;
+
+// an element as a script sees it. the builder calls are recorded rather than
+// made, so the value can be copied around freely the way rhai expects, and
+// replayed into a real `ElementBuilder` once the script is done
+#[derive(Clone, Default)]
+pub struct ScriptElement {
+    steps: Vec<Step>,
+}
+
+impl ScriptElement {
+    fn then(
+        mut self,
+        step: impl Fn(ElementBuilder) -> ElementBuilder + Send + Sync + 'static,
+    ) -> Self {
+        self.steps.push(Arc::new(step));
+        self
+    }
+
+    pub fn build(&self) -> ElementBuilder {
+        self.steps
+            .iter()
+            .fold(builder::rect(), |builder, step| step(builder))
+    }
+}
+
+#[derive(Clone)]
+pub struct ScriptText {
+    text: String,
+    font: Arc<Font>,
+    font_size: f32,
+    color: srgb,
+}
+
+// where the script came from. files are watched for changes
+enum Source {
+    File(PathBuf, Option<SystemTime>),
+    Inline,
+}
+
+// runs rhai scripts that describe a ui with the builder api, so layouts can
+// be changed without recompiling the host app:
+//
+//     column().grow().padding(16).gap(8).color("#202024")
+//         .child(text("hello", 24.0, "#ffffff"))
+//         .child(row().fixed(120, 40).color("#4285f4").on_click("pressed"))
+//
+// the script's last expression is the root. `on_click` names a function in
+// the script that runs when the element is clicked, after which the ui is
+// built again. hosts expose their own state and actions through
+// `engine_mut`. only available with the `scripting` feature
+pub struct ScriptEngine {
+    engine: Engine,
+    source: Source,
+    ast: Option<AST>,
+    // functions named by `on_click` handlers that have fired since the last
+    // `poll`
+    clicks: Arc<Mutex<Vec<String>>>,
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptEngine {
+    pub fn new() -> Self {
+        let clicks = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = Engine::new();
+        engine.on_print(|text| log::info!("script: {text}"));
+        engine.on_debug(|text, _, position| log::debug!("script {position}: {text}"));
+        register_builder(&mut engine, clicks.clone());
+        Self {
+            engine,
+            source: Source::Inline,
+            ast: None,
+            clicks,
+        }
+    }
+
+    // for registering host functions and types before loading a script
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    pub fn load_str(&mut self, script: &str) -> anyhow::Result<ElementBuilder> {
+        self.ast = Some(self.compile(script)?);
+        self.source = Source::Inline;
+        self.build()
+    }
+
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> anyhow::Result<ElementBuilder> {
+        let path = path.as_ref();
+        let modified = modified(path);
+        self.ast = Some(self.compile(&std::fs::read_to_string(path)?)?);
+        self.source = Source::File(path.to_path_buf(), modified);
+        self.build()
+    }
+
+    // runs the loaded script again for a fresh tree
+    pub fn build(&mut self) -> anyhow::Result<ElementBuilder> {
+        let ast = self
+            .ast
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no script loaded"))?;
+        let root = self
+            .engine
+            .eval_ast::<ScriptElement>(ast)
+            .map_err(|e| anyhow::anyhow!("script failed: {e}"))?;
+        Ok(root.build())
+    }
+
+    // call once a frame. runs the click handlers that fired, reloads the
+    // script file if it changed on disk, and rebuilds `ui` if either happened.
+    // script errors are logged and the current tree is kept
+    pub fn poll(&mut self, ui: &mut UI) {
+        let mut changed = self.run_clicks();
+        if let Source::File(path, modified) = &self.source
+            && let Some(latest) = self::modified(path)
+            && Some(latest) != *modified
+        {
+            let path = path.clone();
+            match std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|script| self.compile(&script))
+            {
+                Ok(ast) => {
+                    log::info!("reloaded {}", path.display());
+                    self.ast = Some(ast);
+                    changed = true;
+                }
+                Err(e) => log::warn!("couldn't reload {}: {e}", path.display()),
+            }
+            self.source = Source::File(path, Some(latest));
+        }
+        if changed {
+            match self.build() {
+                Ok(root) => ui.rebuild(root),
+                Err(e) => log::warn!("{e}"),
+            }
+        }
+    }
+
+    fn run_clicks(&mut self) -> bool {
+        let clicks = match self.clicks.lock() {
+            Ok(mut clicks) => std::mem::take(&mut *clicks),
+            Err(_) => return false,
+        };
+        let Some(ast) = &self.ast else {
+            return false;
+        };
+        for name in &clicks {
+            if let Err(e) = self
+                .engine
+                .call_fn::<()>(&mut rhai::Scope::new(), ast, name, ())
+            {
+                log::warn!("script handler {name} failed: {e}");
+            }
+        }
+        !clicks.is_empty()
+    }
+
+    fn compile(&self, script: &str) -> anyhow::Result<AST> {
+        self.engine
+            .compile(script)
+            .map_err(|e| anyhow::anyhow!("script doesn't parse: {e}"))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+fn color(hex: &str) -> Result<srgb, Box<EvalAltResult>> {
+    from_hex(hex).ok_or_else(|| format!("{hex:?} isn't a #rrggbb color").into())
+}
+
+fn register_builder(engine: &mut Engine, clicks: Arc<Mutex<Vec<String>>>) {
+    engine
+        .register_type_with_name::<ScriptElement>("Element")
+        .register_type_with_name::<ScriptText>("Text");
+
+    engine
+        .register_fn("rect", ScriptElement::default)
+        .register_fn("row", || {
+            ScriptElement::default().then(|b| b.layout(LayoutMode::LeftToRight))
+        })
+        .register_fn("column", || {
+            ScriptElement::default().then(|b| b.layout(LayoutMode::TopToBottom))
+        });
+
+    engine
+        .register_fn("grow", |e: ScriptElement| e.then(|b| b.grow()))
+        .register_fn("fit", |e: ScriptElement| e.then(|b| b.fit()))
+        .register_fn("fixed", |e: ScriptElement, w: INT, h: INT| {
+            e.then(move |b| b.fixed(w as i32, h as i32))
+        })
+        .register_fn("min_width", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.min_width(v as i32))
+        })
+        .register_fn("min_height", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.min_height(v as i32))
+        })
+        .register_fn("max_width", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.max_width(v as i32))
+        })
+        .register_fn("max_height", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.max_height(v as i32))
+        })
+        .register_fn("padding", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.padding(v as i32))
+        })
+        .register_fn("gap", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.gap(v as i32))
+        })
+        .register_fn("wrap", |e: ScriptElement| e.then(|b| b.wrap()))
+        .register_fn("center", |e: ScriptElement| e.then(|b| b.center()))
+        .register_fn("scrollable", |e: ScriptElement| e.then(|b| b.scrollable()))
+        .register_fn("clip_children", |e: ScriptElement| {
+            e.then(|b| b.clip_children())
+        })
+        .register_fn("z_index", |e: ScriptElement, v: INT| {
+            e.then(move |b| b.z_index(v as i32))
+        })
+        .register_fn("id", |e: ScriptElement, key: &str| {
+            let key = key.to_string();
+            e.then(move |b| b.id(&key))
+        });
+
+    engine
+        .register_fn("align_main", |e: ScriptElement, align: &str| {
+            let align = match align {
+                "start" => AlignMain::Start,
+                "center" => AlignMain::Center,
+                "end" => AlignMain::End,
+                "space_between" => AlignMain::SpaceBetween,
+                "space_around" => AlignMain::SpaceAround,
+                align => return Err(format!("unknown alignment {align:?}").into()),
+            };
+            Ok::<_, Box<EvalAltResult>>(e.then(move |b| b.align_main(align)))
+        })
+        .register_fn("align_cross", |e: ScriptElement, align: &str| {
+            let align = match align {
+                "start" => AlignCross::Start,
+                "center" => AlignCross::Center,
+                "end" => AlignCross::End,
+                align => return Err(format!("unknown alignment {align:?}").into()),
+            };
+            Ok::<_, Box<EvalAltResult>>(e.then(move |b| b.align_cross(align)))
+        });
+
+    engine
+        .register_fn("color", |e: ScriptElement, hex: &str| {
+            let color = color(hex)?;
+            Ok::<_, Box<EvalAltResult>>(e.then(move |b| b.color(color)))
+        })
+        .register_fn("color", |e: ScriptElement, r: FLOAT, g: FLOAT, b: FLOAT| {
+            let color = rgb(r as f32, g as f32, b as f32);
+            e.then(move |builder| builder.color(color))
+        })
+        .register_fn(
+            "gradient",
+            |e: ScriptElement, angle: FLOAT, from: &str, to: &str| {
+                let stops = [(0.0, color(from)?), (1.0, color(to)?)];
+                let fill = Fill::linear(angle as f32, stops);
+                Ok::<_, Box<EvalAltResult>>(e.then(move |b| b.fill(fill.clone())))
+            },
+        )
+        .register_fn("corner_radius", |e: ScriptElement, radius: FLOAT| {
+            e.then(move |b| b.corner_radius(radius as f32))
+        })
+        .register_fn("border", |e: ScriptElement, width: INT, hex: &str| {
+            let color = color(hex)?;
+            Ok::<_, Box<EvalAltResult>>(e.then(move |b| b.border(width as i32, color)))
+        })
+        .register_fn("opacity", |e: ScriptElement, opacity: FLOAT| {
+            e.then(move |b| b.opacity(opacity as f32))
+        });
+
+    engine
+        .register_fn("child", |e: ScriptElement, child: ScriptElement| {
+            e.then(move |b| b.child(child.build()))
+        })
+        .register_fn("child", |e: ScriptElement, child: ScriptText| {
+            e.then(move |b| {
+                b.child(Text::new(
+                    &child.text,
+                    child.font.clone(),
+                    child.font_size,
+                    child.color,
+                ))
+            })
+        });
+
+    let font = Font::system_default();
+    engine.register_fn("text", move |text: &str, font_size: FLOAT, hex: &str| {
+        let font = font
+            .clone()
+            .ok_or_else(|| Box::<EvalAltResult>::from("no system font for text"))?;
+        Ok::<_, Box<EvalAltResult>>(ScriptText {
+            text: text.to_string(),
+            font,
+            font_size: font_size as f32,
+            color: color(hex)?,
+        })
+    });
+
+    engine.register_fn("on_click", move |e: ScriptElement, name: &str| {
+        let (clicks, name) = (clicks.clone(), name.to_string());
+        e.then(move |b| {
+            let (clicks, name) = (clicks.clone(), name.clone());
+            b.on_event(move |cx| {
+                if cx.phase() != Phase::Capture
+                    && let Event::PointerReleased {
+                        button: PointerButton::Primary,
+                        ..
+                    } = cx.event
+                    && let Ok(mut clicks) = clicks.lock()
+                {
+                    clicks.push(name.clone());
+                }
+            })
+        })
+    });
+}
@@ -0,0 +1,173 @@
+use std::ops::Range;
+
+use tinycolors::srgb;
+
+#[derive(Debug, Clone)]
+pub struct HighlightSpan {
+    pub range: Range<usize>,
+    pub color: srgb,
+    pub current: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TextSearch {
+    pub query: String,
+    pub case_sensitive: bool,
+    pub highlight_color: srgb,
+    pub current_color: srgb,
+    matches: Vec<Range<usize>>,
+    current: Option<usize>,
+}
+
+impl TextSearch {
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            highlight_color: srgb::AQUA,
+            current_color: srgb::PURPLE,
+            ..Default::default()
+        }
+    }
+
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.matches.clear();
+        self.current = None;
+    }
+
+    pub fn find(&mut self, text: &str) -> &[Range<usize>] {
+        self.matches = find_matches(text, &self.query, self.case_sensitive);
+        self.current = match self.current {
+            Some(i) if i < self.matches.len() => Some(i),
+            _ if self.matches.is_empty() => None,
+            _ => Some(0),
+        };
+        &self.matches
+    }
+
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn current_match(&self) -> Option<Range<usize>> {
+        self.current.map(|i| self.matches[i].clone())
+    }
+
+    pub fn next_match(&mut self) -> Option<Range<usize>> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        self.current = Some(match self.current {
+            Some(i) => (i + 1) % self.matches.len(),
+            None => 0,
+        });
+        self.current_match()
+    }
+
+    pub fn previous_match(&mut self) -> Option<Range<usize>> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        self.current = Some(match self.current {
+            Some(0) | None => self.matches.len() - 1,
+            Some(i) => i - 1,
+        });
+        self.current_match()
+    }
+
+    pub fn jump_to_offset(&mut self, offset: usize) -> Option<Range<usize>> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let i = self
+            .matches
+            .iter()
+            .position(|m| m.end > offset)
+            .unwrap_or(0);
+        self.current = Some(i);
+        self.current_match()
+    }
+
+    pub fn spans(&self) -> Vec<HighlightSpan> {
+        self.matches
+            .iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let current = self.current == Some(i);
+                HighlightSpan {
+                    range: range.clone(),
+                    color: if current {
+                        self.current_color
+                    } else {
+                        self.highlight_color
+                    },
+                    current,
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.query.is_empty() || !find_matches(text, &self.query, self.case_sensitive).is_empty()
+    }
+
+    pub fn filter<T: AsRef<str>>(&self, items: &[T]) -> Vec<usize> {
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| self.is_match(item.as_ref()))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+pub fn find_matches(text: &str, query: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+    if query.is_empty() {
+        return matches;
+    }
+
+    if case_sensitive {
+        let mut start = 0;
+        while let Some(i) = text[start..].find(query) {
+            let begin = start + i;
+            let end = begin + query.len();
+            matches.push(begin..end);
+            start = end;
+        }
+        return matches;
+    }
+
+    let mut next_allowed = 0;
+    for (begin, _) in text.char_indices() {
+        if begin < next_allowed {
+            continue;
+        }
+        if let Some(end) = match_ignore_case(&text[begin..], query) {
+            matches.push(begin..begin + end);
+            next_allowed = begin + end;
+        }
+    }
+    matches
+}
+
+fn match_ignore_case(text: &str, query: &str) -> Option<usize> {
+    let mut text_chars = text.char_indices();
+    let mut end = 0;
+    for q in query.chars() {
+        let (i, t) = text_chars.next()?;
+        if !t.to_lowercase().eq(q.to_lowercase()) {
+            return None;
+        }
+        end = i + t.len_utf8();
+    }
+    Some(end)
+}
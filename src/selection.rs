@@ -0,0 +1,267 @@
+use std::collections::BTreeSet;
+
+use tinycolors::srgb;
+
+use crate::{
+    binding::CollectionChange,
+    input::{Key, Modifiers},
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+    },
+};
+
+// how strongly selected items and the marquee are tinted with the selection color
+pub const SELECTION_ALPHA: f32 = 0.25;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    None,
+    #[default]
+    Single,
+    Multi,
+}
+
+type ChangeCallback = Box<dyn FnMut(&BTreeSet<usize>) + Send>;
+
+#[derive(Default)]
+pub struct SelectionModel {
+    pub mode: SelectionMode,
+    selected: BTreeSet<usize>,
+    anchor: Option<usize>,
+    focus: Option<usize>,
+    on_change: Option<ChangeCallback>,
+}
+
+impl SelectionModel {
+    pub fn new(mode: SelectionMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    pub fn on_change(mut self, f: impl FnMut(&BTreeSet<usize>) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn selected(&self) -> &BTreeSet<usize> {
+        &self.selected
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    // click semantics: plain replaces, ctrl/cmd toggles, shift extends from the anchor
+    pub fn click(&mut self, index: usize, modifiers: Modifiers) {
+        match self.mode {
+            SelectionMode::None => return,
+            SelectionMode::Single => {
+                self.selected.clear();
+                self.selected.insert(index);
+                self.anchor = Some(index);
+            }
+            SelectionMode::Multi => {
+                if modifiers.shift {
+                    let anchor = self.anchor.unwrap_or(index);
+                    if !modifiers.command() {
+                        self.selected.clear();
+                    }
+                    self.selected.extend(anchor.min(index)..=anchor.max(index));
+                } else if modifiers.command() {
+                    if !self.selected.remove(&index) {
+                        self.selected.insert(index);
+                    }
+                    self.anchor = Some(index);
+                } else {
+                    self.selected.clear();
+                    self.selected.insert(index);
+                    self.anchor = Some(index);
+                }
+            }
+        }
+        self.focus = Some(index);
+        self.changed();
+    }
+
+    // arrow key navigation over `count` items, shift extends the selection
+    pub fn handle_key(&mut self, key: Key, modifiers: Modifiers, count: usize) -> bool {
+        if count == 0 {
+            return false;
+        }
+        let last = count - 1;
+        let focus = self.focus.unwrap_or(0);
+        let target = match key {
+            Key::Up | Key::Left => focus.saturating_sub(1),
+            Key::Down | Key::Right => (focus + 1).min(last),
+            Key::Home => 0,
+            Key::End => last,
+            Key::Space => {
+                self.click(
+                    focus,
+                    Modifiers {
+                        control: true,
+                        super_key: true,
+                        ..modifiers
+                    },
+                );
+                return true;
+            }
+            _ => return false,
+        };
+        self.click(target, modifiers);
+        true
+    }
+
+    pub fn select_all(&mut self, count: usize) {
+        if self.mode != SelectionMode::Multi {
+            return;
+        }
+        self.selected = (0..count).collect();
+        self.changed();
+    }
+
+    pub fn set_selection(&mut self, indices: impl IntoIterator<Item = usize>) {
+        self.selected = indices.into_iter().collect();
+        if self.mode == SelectionMode::Single {
+            let first = self.selected.first().copied();
+            self.selected = first.into_iter().collect();
+        }
+        self.changed();
+    }
+
+    pub fn clear(&mut self) {
+        if self.selected.is_empty() {
+            return;
+        }
+        self.selected.clear();
+        self.anchor = None;
+        self.changed();
+    }
+
+    // keeps indices pointing at the same items when the underlying collection changes
+    pub fn apply_change(&mut self, change: CollectionChange) {
+        let remap = |i: usize| -> Option<usize> {
+            match change {
+                CollectionChange::Insert(at) => Some(if i >= at { i + 1 } else { i }),
+                CollectionChange::Remove(at) => match i.cmp(&at) {
+                    std::cmp::Ordering::Less => Some(i),
+                    std::cmp::Ordering::Equal => None,
+                    std::cmp::Ordering::Greater => Some(i - 1),
+                },
+                CollectionChange::Move { from, to } => Some(if i == from {
+                    to
+                } else if from < to && i > from && i <= to {
+                    i - 1
+                } else if to < from && i >= to && i < from {
+                    i + 1
+                } else {
+                    i
+                }),
+                CollectionChange::Update(_) => Some(i),
+                CollectionChange::Reset => None,
+            }
+        };
+        let selected: BTreeSet<usize> = self.selected.iter().filter_map(|i| remap(*i)).collect();
+        self.anchor = self.anchor.and_then(remap);
+        self.focus = self.focus.and_then(remap);
+        if selected != self.selected {
+            self.selected = selected;
+            self.changed();
+        }
+    }
+
+    fn changed(&mut self) {
+        if let Some(on_change) = &mut self.on_change {
+            on_change(&self.selected);
+        }
+    }
+}
+
+pub struct Marquee {
+    pub start: (i32, i32),
+    pub current: (i32, i32),
+    // selection held with ctrl/cmd when the drag started is kept and added to
+    additive: bool,
+    initial: BTreeSet<usize>,
+}
+
+impl Marquee {
+    pub fn begin(start: (i32, i32), modifiers: Modifiers, model: &SelectionModel) -> Self {
+        let additive = modifiers.command() || modifiers.shift;
+        Self {
+            start,
+            current: start,
+            additive,
+            initial: if additive {
+                model.selected().clone()
+            } else {
+                BTreeSet::new()
+            },
+        }
+    }
+
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        let x = self.start.0.min(self.current.0);
+        let y = self.start.1.min(self.current.1);
+        (
+            x,
+            y,
+            (self.start.0 - self.current.0).abs(),
+            (self.start.1 - self.current.1).abs(),
+        )
+    }
+
+    // selects every item whose bounds intersect the marquee
+    pub fn update(
+        &mut self,
+        point: (i32, i32),
+        items: impl IntoIterator<Item = (usize, (i32, i32, i32, i32))>,
+        model: &mut SelectionModel,
+    ) {
+        self.current = point;
+        let (mx, my, mw, mh) = self.bounds();
+        let hits = items.into_iter().filter_map(|(i, (x, y, w, h))| {
+            (x < mx + mw && x + w > mx && y < my + mh && y + h > my).then_some(i)
+        });
+        let selection: BTreeSet<usize> = if self.additive {
+            self.initial.iter().copied().chain(hits).collect()
+        } else {
+            hits.collect()
+        };
+        if &selection != model.selected() {
+            model.set_selection(selection);
+        }
+    }
+
+    pub fn get_mesh(&self, fill: Option<srgb>, outline: srgb, size: (i32, i32)) -> Mesh {
+        let (x, y, w, h) = self.bounds();
+        let mut mesh = Mesh::default();
+        if let Some(fill) = fill {
+            mesh.append(make_ss_rectangle(x, y, w, h, fill, size));
+        }
+        mesh.append(make_ss_rectangle(x, y, w, 1, outline, size));
+        mesh.append(make_ss_rectangle(x, y + h - 1, w, 1, outline, size));
+        mesh.append(make_ss_rectangle(x, y, 1, h, outline, size));
+        mesh.append(make_ss_rectangle(x + w - 1, y, 1, h, outline, size));
+        mesh
+    }
+
+    // a tinted box outlined in `color`, once the drag has gone anywhere
+    pub fn record(&self, list: &mut DrawList, color: srgb, size: (i32, i32)) {
+        let (x, y, w, h) = self.bounds();
+        if w == 0 && h == 0 {
+            return;
+        }
+        let mut fill = make_ss_rectangle(x, y, w, h, color, size);
+        fill.multiply_alpha(SELECTION_ALPHA);
+        list.mesh(fill);
+        list.mesh(self.get_mesh(None, color, size));
+    }
+}
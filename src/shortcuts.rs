@@ -0,0 +1,199 @@
+use std::fmt;
+
+use crate::input::{Key, Modifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl Shortcut {
+    pub fn new(key: Key, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    // parses strings like "Ctrl+Shift+K" or "CmdOrCtrl+Space". CmdOrCtrl means
+    // command on macos and control elsewhere
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = Modifiers::NONE;
+        let mut key = None;
+        for part in text.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.control = true,
+                "shift" => modifiers.shift = true,
+                "alt" | "option" => modifiers.alt = true,
+                "super" | "cmd" | "command" | "meta" => modifiers.super_key = true,
+                "cmdorctrl" | "commandorcontrol" => {
+                    if cfg!(target_os = "macos") {
+                        modifiers.super_key = true;
+                    } else {
+                        modifiers.control = true;
+                    }
+                }
+                name => {
+                    if key.is_some() {
+                        return None;
+                    }
+                    key = Some(parse_key(name)?);
+                }
+            }
+        }
+        Some(Self::new(key?, modifiers))
+    }
+
+    pub fn matches(&self, key: Key, modifiers: Modifiers) -> bool {
+        self.key == key && self.modifiers == modifiers
+    }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    Some(match name {
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "enter" | "return" => Key::Enter,
+        "escape" | "esc" => Key::Escape,
+        "tab" => Key::Tab,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "space" => Key::Space,
+        "f2" => Key::F2,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => Key::Char(c),
+                _ => return None,
+            }
+        }
+    })
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Modifiers {
+            shift,
+            control,
+            alt,
+            super_key,
+        } = self.modifiers;
+        let super_name = if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Super"
+        };
+        for (held, name) in [
+            (control, "Ctrl"),
+            (alt, "Alt"),
+            (shift, "Shift"),
+            (super_key, super_name),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        match self.key {
+            Key::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+type ShortcutCallback = Box<dyn FnMut() + Send>;
+
+struct Entry {
+    name: String,
+    shortcut: Shortcut,
+    callback: ShortcutCallback,
+}
+
+// named actions bound to key combinations. local key events go through
+// dispatch, other sources (menus, global hotkeys) can trigger actions by name
+#[derive(Default)]
+pub struct ShortcutRegistry {
+    entries: Vec<Entry>,
+}
+
+impl ShortcutRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // replaces any action already registered under `name`
+    pub fn register(&mut self, name: &str, shortcut: Shortcut, f: impl FnMut() + Send + 'static) {
+        self.unregister(name);
+        let conflicts = self.conflicts(shortcut);
+        if !conflicts.is_empty() {
+            log::warn!("{shortcut} for {name} is already bound to {conflicts:?}");
+        }
+        self.entries.push(Entry {
+            name: name.to_string(),
+            shortcut,
+            callback: Box::new(f),
+        });
+    }
+
+    pub fn unregister(&mut self, name: &str) -> Option<Shortcut> {
+        let index = self.entries.iter().position(|e| e.name == name)?;
+        Some(self.entries.remove(index).shortcut)
+    }
+
+    pub fn shortcut(&self, name: &str) -> Option<Shortcut> {
+        self.entries
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.shortcut)
+    }
+
+    pub fn rebind(&mut self, name: &str, shortcut: Shortcut) -> bool {
+        match self.entries.iter_mut().find(|e| e.name == name) {
+            Some(entry) => {
+                entry.shortcut = shortcut;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn conflicts(&self, shortcut: Shortcut) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|e| e.shortcut == shortcut)
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Shortcut)> {
+        self.entries.iter().map(|e| (e.name.as_str(), e.shortcut))
+    }
+
+    // runs the first action bound to this key combination
+    pub fn dispatch(&mut self, key: Key, modifiers: Modifiers) -> bool {
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.shortcut.matches(key, modifiers))
+        {
+            Some(entry) => {
+                (entry.callback)();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn trigger(&mut self, name: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.name == name) {
+            Some(entry) => {
+                (entry.callback)();
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+type TransitionFn<S, E> = Box<dyn Fn(&S, &E) -> Option<S> + Send>;
+type HookFn<S> = Box<dyn FnMut(&S) + Send>;
+
+// a finite state machine driven by events. `transition` maps the current
+// state and an event to the next state, or None to ignore the event. exit
+// hooks run with the old state before enter hooks run with the new one
+pub struct StateMachine<S, E> {
+    state: S,
+    transition: TransitionFn<S, E>,
+    on_enter: Vec<HookFn<S>>,
+    on_exit: Vec<HookFn<S>>,
+}
+
+impl<S: PartialEq, E> StateMachine<S, E> {
+    pub fn new(initial: S, transition: impl Fn(&S, &E) -> Option<S> + Send + 'static) -> Self {
+        Self {
+            state: initial,
+            transition: Box::new(transition),
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+
+    pub fn on_enter(mut self, f: impl FnMut(&S) + Send + 'static) -> Self {
+        self.on_enter.push(Box::new(f));
+        self
+    }
+
+    pub fn on_exit(mut self, f: impl FnMut(&S) + Send + 'static) -> Self {
+        self.on_exit.push(Box::new(f));
+        self
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn is(&self, state: &S) -> bool {
+        self.state == *state
+    }
+
+    // returns true when the event changed the state. transitions back into the
+    // current state are ignored and don't run hooks
+    pub fn handle(&mut self, event: &E) -> bool {
+        match (self.transition)(&self.state, event) {
+            Some(next) if next != self.state => {
+                self.set(next);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // jumps straight to `state`, still running hooks
+    pub fn set(&mut self, state: S) {
+        if state == self.state {
+            return;
+        }
+        for hook in &mut self.on_exit {
+            hook(&self.state);
+        }
+        self.state = state;
+        for hook in &mut self.on_enter {
+            hook(&self.state);
+        }
+    }
+}
+
+// the states every pointer driven widget moves through
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interaction {
+    #[default]
+    Idle,
+    Hovered,
+    Pressed,
+    // pressed and moved far enough to count as a drag rather than a click
+    Dragging,
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionEvent {
+    PointerEnter,
+    PointerLeave,
+    Press,
+    DragStart,
+    // `inside` is whether the pointer is still over the widget
+    Release { inside: bool },
+    Disable,
+    Enable,
+}
+
+impl Interaction {
+    pub fn is_active(self) -> bool {
+        matches!(self, Interaction::Pressed | Interaction::Dragging)
+    }
+
+    // a press keeps the widget active even when the pointer leaves it, so
+    // drags and cancelled clicks resolve on release
+    pub fn next(self, event: InteractionEvent) -> Option<Interaction> {
+        use Interaction::*;
+        use InteractionEvent::*;
+        match (self, event) {
+            (Disabled, Enable) => Some(Idle),
+            (Disabled, _) => None,
+            (_, Disable) => Some(Disabled),
+            (Idle, PointerEnter) => Some(Hovered),
+            (Hovered, PointerLeave) => Some(Idle),
+            (Idle | Hovered, Press) => Some(Pressed),
+            (Pressed, DragStart) => Some(Dragging),
+            (Pressed | Dragging, Release { inside: true }) => Some(Hovered),
+            (Pressed | Dragging, Release { inside: false }) => Some(Idle),
+            _ => None,
+        }
+    }
+
+    pub fn machine() -> StateMachine<Interaction, InteractionEvent> {
+        StateMachine::new(Interaction::Idle, |state: &Interaction, event| {
+            state.next(*event)
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// how many frames of input latency are kept, a couple of seconds at 60hz
+const LATENCY_SAMPLES: usize = 120;
+
+// how responsive the window has been lately. input latency is from when the
+// os handed teacup the oldest input event a frame handled to when that frame
+// was presented, on the monotonic clock. frames without input don't count
+#[derive(Debug, Default, Clone)]
+pub struct UiStats {
+    latencies: VecDeque<Duration>,
+    frames: u64,
+    frame_time: Duration,
+    last_present: Option<Instant>,
+}
+
+impl UiStats {
+    // called by the event loop once a frame is presented. `input_at` is when
+    // the oldest input the frame handled came in, if any did
+    pub(crate) fn record_present(&mut self, now: Instant, input_at: Option<Instant>) {
+        self.frames += 1;
+        if let Some(last) = self.last_present.replace(now) {
+            self.frame_time = now.saturating_duration_since(last);
+        }
+        if let Some(input_at) = input_at {
+            if self.latencies.len() == LATENCY_SAMPLES {
+                self.latencies.pop_front();
+            }
+            self.latencies
+                .push_back(now.saturating_duration_since(input_at));
+        }
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    // between the last two presents
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    // of the most recent frame that handled input
+    pub fn input_latency(&self) -> Option<Duration> {
+        self.latencies.back().copied()
+    }
+
+    pub fn input_latency_average(&self) -> Option<Duration> {
+        let count = self.latencies.len() as u32;
+        (count > 0).then(|| self.latencies.iter().sum::<Duration>() / count)
+    }
+
+    // `p` from 0 to 1, so 0.95 is the latency 95% of recent frames beat
+    pub fn input_latency_percentile(&self, p: f32) -> Option<Duration> {
+        let mut sorted: Vec<Duration> = self.latencies.iter().copied().collect();
+        sorted.sort_unstable();
+        let index = ((sorted.len() as f32 - 1.0) * p.clamp(0.0, 1.0)).round() as usize;
+        sorted.get(index).copied()
+    }
+
+    pub fn input_latency_max(&self) -> Option<Duration> {
+        self.latencies.iter().max().copied()
+    }
+
+    // forgets the latency samples, e.g. before measuring one interaction
+    pub fn reset_latency(&mut self) {
+        self.latencies.clear();
+    }
+
+    // one line per figure, for the stats overlay and the console
+    pub fn summary(&self) -> String {
+        let ms = |duration: Option<Duration>| {
+            duration.map_or("-".to_string(), |d| {
+                format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+            })
+        };
+        let frame_ms = self.frame_time.as_secs_f64() * 1000.0;
+        let fps = if frame_ms > 0.0 {
+            1000.0 / frame_ms
+        } else {
+            0.0
+        };
+        [
+            format!("frame {frame_ms:.1}ms ({fps:.0} fps)"),
+            format!("input latency {}", ms(self.input_latency())),
+            format!(
+                "avg {}  p95 {}  max {}",
+                ms(self.input_latency_average()),
+                ms(self.input_latency_percentile(0.95)),
+                ms(self.input_latency_max())
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+// when the oldest input event in `pending` came in. glfw stamps events with
+// its own monotonic clock, `glfw_now` being that clock read just now
+pub(crate) fn oldest_input(glfw_now: f64, pending: &[(f64, glfw::WindowEvent)]) -> Option<Instant> {
+    let oldest = pending
+        .iter()
+        .filter(|(_, event)| {
+            matches!(
+                event,
+                glfw::WindowEvent::CursorPos(..)
+                    | glfw::WindowEvent::MouseButton(..)
+                    | glfw::WindowEvent::Scroll(..)
+                    | glfw::WindowEvent::Key(..)
+                    | glfw::WindowEvent::Char(..)
+            )
+        })
+        .map(|(time, _)| *time)
+        .min_by(f64::total_cmp)?;
+    let waited = Duration::try_from_secs_f64(glfw_now - oldest).unwrap_or_default();
+    Some(Instant::now() - waited)
+}
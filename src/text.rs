@@ -0,0 +1,67 @@
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+pub const ELLIPSIS: &str = "…";
+
+// font size multiplier from the os text size setting, see
+// `UiContext::text_scale`. it lives here rather than on the context so text
+// can be measured during layout, which has no context to read it from
+static TEXT_SCALE: AtomicU32 = AtomicU32::new(1.0f32.to_bits());
+
+pub fn text_scale() -> f32 {
+    f32::from_bits(TEXT_SCALE.load(Ordering::Relaxed))
+}
+
+// returns whether the scale changed
+pub(crate) fn set_text_scale(scale: f32) -> bool {
+    let scale = scale.clamp(0.5, 4.0);
+    f32::from_bits(TEXT_SCALE.swap(scale.to_bits(), Ordering::Relaxed)) != scale
+}
+
+// `font_size` as it should be drawn, with the text scale applied
+pub fn scaled(font_size: f32) -> f32 {
+    font_size * text_scale()
+}
+
+// cuts `text` down so it fits in `max_width` with a trailing ellipsis. returns
+// the original string untouched when it already fits. `measure` gives the
+// width in pixels of some text at `font_size`, usually `Font::measure`
+pub fn ellipsize(
+    text: &str,
+    max_width: f32,
+    font_size: f32,
+    measure: impl Fn(&str, f32) -> f32,
+) -> Cow<'_, str> {
+    if measure(text, font_size) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let available = max_width - measure(ELLIPSIS, font_size);
+    if available <= 0.0 {
+        return Cow::Borrowed("");
+    }
+
+    // widest prefix that still fits, found by binary search over char boundaries
+    let boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let (mut low, mut high) = (0, boundaries.len().saturating_sub(1));
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if measure(&text[..boundaries[mid]], font_size) <= available {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    let prefix = text[..boundaries[low]].trim_end();
+    Cow::Owned(format!("{prefix}{ELLIPSIS}"))
+}
+
+pub fn is_truncated(
+    text: &str,
+    max_width: f32,
+    font_size: f32,
+    measure: impl Fn(&str, f32) -> f32,
+) -> bool {
+    measure(text, font_size) > max_width
+}
@@ -0,0 +1,232 @@
+use tinycolors::srgb;
+
+// colors by role. the `on_*` tokens are for text and icons drawn on top of
+// the matching fill
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub background: srgb,
+    pub surface: srgb,
+    pub on_surface: srgb,
+    pub primary: srgb,
+    pub on_primary: srgb,
+    pub danger: srgb,
+    pub on_danger: srgb,
+    pub accent: srgb,
+    pub hover: srgb,
+    pub pressed: srgb,
+    pub border: srgb,
+    // text on the background
+    pub text: srgb,
+    pub inactive: srgb,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            background: rgb(0.08, 0.08, 0.09),
+            surface: rgb(0.16, 0.16, 0.18),
+            on_surface: rgb(0.93, 0.93, 0.95),
+            primary: rgb(0.26, 0.52, 0.96),
+            on_primary: rgb(0.06, 0.06, 0.08),
+            danger: rgb(0.9, 0.3, 0.3),
+            on_danger: rgb(0.06, 0.06, 0.08),
+            accent: rgb(0.98, 0.75, 0.18),
+            hover: rgb(0.24, 0.24, 0.27),
+            pressed: rgb(0.12, 0.12, 0.14),
+            border: rgb(0.32, 0.32, 0.36),
+            text: rgb(0.93, 0.93, 0.95),
+            inactive: rgb(0.4, 0.4, 0.44),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            background: rgb(0.97, 0.97, 0.98),
+            surface: rgb(1.0, 1.0, 1.0),
+            on_surface: rgb(0.08, 0.08, 0.1),
+            primary: rgb(0.13, 0.4, 0.88),
+            on_primary: rgb(1.0, 1.0, 1.0),
+            danger: rgb(0.78, 0.15, 0.15),
+            on_danger: rgb(1.0, 1.0, 1.0),
+            accent: rgb(0.95, 0.6, 0.05),
+            hover: rgb(0.9, 0.9, 0.93),
+            pressed: rgb(0.82, 0.82, 0.86),
+            border: rgb(0.78, 0.78, 0.82),
+            text: rgb(0.08, 0.08, 0.1),
+            inactive: rgb(0.7, 0.7, 0.74),
+        }
+    }
+}
+
+pub fn rgb(r: f32, g: f32, b: f32) -> srgb {
+    srgb { r, g, b }
+}
+
+// "#rrggbb", as in css
+pub fn from_hex(hex: &str) -> Option<srgb> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(hex.get(i..i + 2)?, 16)
+            .ok()
+            .map(|c| c as f32 / 255.0)
+    };
+    Some(rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+pub fn to_hex(color: srgb) -> String {
+    let [r, g, b] = [color.r, color.g, color.b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+// `t` of the way from `a` to `b`
+pub fn mix(a: srgb, b: srgb, t: f32) -> srgb {
+    let t = t.clamp(0.0, 1.0);
+    srgb {
+        r: a.r + (b.r - a.r) * t,
+        g: a.g + (b.g - a.g) * t,
+        b: a.b + (b.b - a.b) * t,
+    }
+}
+
+// wcag 2 minimum contrast ratios. large text is 18pt, or 14pt bold
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ContrastLevel {
+    #[default]
+    Aa,
+    AaLarge,
+    Aaa,
+    AaaLarge,
+}
+
+impl ContrastLevel {
+    pub fn min_ratio(self) -> f32 {
+        match self {
+            ContrastLevel::Aa | ContrastLevel::AaaLarge => 4.5,
+            ContrastLevel::AaLarge => 3.0,
+            ContrastLevel::Aaa => 7.0,
+        }
+    }
+}
+
+// a foreground/background pair of theme tokens below the required ratio
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    pub foreground: &'static str,
+    pub background: &'static str,
+    pub ratio: f32,
+    pub required: f32,
+}
+
+impl Theme {
+    // the text-on-fill pairs a theme is expected to keep readable
+    fn contrast_pairs(&self) -> [(&'static str, &'static str, srgb, srgb); 4] {
+        [
+            ("text", "background", self.text, self.background),
+            ("on_surface", "surface", self.on_surface, self.surface),
+            ("on_primary", "primary", self.on_primary, self.primary),
+            ("on_danger", "danger", self.on_danger, self.danger),
+        ]
+    }
+
+    // every text/fill pair that falls short of `level`, each logged as a warning
+    pub fn check_contrast(&self, level: ContrastLevel) -> Vec<ContrastIssue> {
+        let required = level.min_ratio();
+        let issues: Vec<ContrastIssue> = self
+            .contrast_pairs()
+            .into_iter()
+            .filter_map(|(foreground, background, fg, bg)| {
+                let ratio = contrast_ratio(fg, bg);
+                (ratio < required).then_some(ContrastIssue {
+                    foreground,
+                    background,
+                    ratio,
+                    required,
+                })
+            })
+            .collect();
+        for issue in &issues {
+            log::warn!(
+                "theme contrast of {} on {} is {:.2}:1, below {:.1}:1",
+                issue.foreground,
+                issue.background,
+                issue.ratio,
+                issue.required
+            );
+        }
+        issues
+    }
+
+    // adjusts the text tokens just enough for every pair to meet `level`.
+    // fills are left alone so brand colors don't drift
+    pub fn with_auto_contrast(mut self, level: ContrastLevel) -> Self {
+        self.text = ensure_contrast(self.text, self.background, level);
+        self.on_surface = ensure_contrast(self.on_surface, self.surface, level);
+        self.on_primary = ensure_contrast(self.on_primary, self.primary, level);
+        self.on_danger = ensure_contrast(self.on_danger, self.danger, level);
+        self
+    }
+}
+
+// wcag relative luminance of an srgb color
+pub fn relative_luminance(color: srgb) -> f32 {
+    let linear = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+// from 1:1 for identical colors up to 21:1 for black on white
+pub fn contrast_ratio(a: srgb, b: srgb) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+// `foreground` moved towards black or white, whichever contrasts more with
+// `background`, by the smallest step that meets `level`. colors that already
+// pass come back unchanged
+pub fn ensure_contrast(foreground: srgb, background: srgb, level: ContrastLevel) -> srgb {
+    let required = level.min_ratio();
+    if contrast_ratio(foreground, background) >= required {
+        return foreground;
+    }
+
+    let black = rgb(0.0, 0.0, 0.0);
+    let white = rgb(1.0, 1.0, 1.0);
+    let target = if contrast_ratio(black, background) > contrast_ratio(white, background) {
+        black
+    } else {
+        white
+    };
+    let mix = |t: f32| {
+        rgb(
+            foreground.r + (target.r - foreground.r) * t,
+            foreground.g + (target.g - foreground.g) * t,
+            foreground.b + (target.b - foreground.b) * t,
+        )
+    };
+
+    // contrast only grows as the color approaches the target, so bisect
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..16 {
+        let mid = (low + high) / 2.0;
+        if contrast_ratio(mix(mid), background) >= required {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    mix(high)
+}
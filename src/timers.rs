@@ -0,0 +1,114 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::context::UiContext;
+
+pub(crate) type TimerCallback = Box<dyn FnMut(&mut UiContext) + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+struct Timer {
+    id: TimerId,
+    deadline: Instant,
+    interval: Option<Duration>,
+    callback: TimerCallback,
+}
+
+// timeouts and intervals that fire on the ui thread as the frame clock
+// passes their deadline, so widgets don't need threads of their own
+#[derive(Default)]
+pub(crate) struct Timers {
+    next_id: u64,
+    timers: Vec<Timer>,
+    // the timer whose callback is running, and whether it cleared itself
+    running: Option<(TimerId, bool)>,
+}
+
+impl fmt::Debug for Timers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timers")
+            .field("pending", &self.timers.len())
+            .finish()
+    }
+}
+
+impl Timers {
+    pub fn add(
+        &mut self,
+        deadline: Instant,
+        interval: Option<Duration>,
+        callback: TimerCallback,
+    ) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            deadline,
+            interval,
+            callback,
+        });
+        id
+    }
+
+    pub fn clear(&mut self, id: TimerId) -> bool {
+        if let Some((running, cleared)) = &mut self.running
+            && *running == id
+        {
+            *cleared = true;
+            return true;
+        }
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != count
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    // removes and returns the earliest timer due at `now`
+    fn pop_due(&mut self, now: Instant) -> Option<Timer> {
+        let index = self
+            .timers
+            .iter()
+            .enumerate()
+            .filter(|(_, timer)| timer.deadline <= now)
+            .min_by_key(|(_, timer)| timer.deadline)
+            .map(|(i, _)| i)?;
+        Some(self.timers.swap_remove(index))
+    }
+}
+
+// fires everything due at `now` in deadline order. an interval that fell
+// behind, e.g. across a long frame, fires once and skips the missed beats
+// rather than running them back to back. returns whether any timer fired
+pub(crate) fn run_due(context: &mut UiContext, now: Instant) -> bool {
+    // timers added by callbacks wait for the next frame
+    let mut rescheduled = Vec::new();
+    let mut fired = false;
+    while let Some(mut timer) = context.timers.pop_due(now) {
+        fired = true;
+        context.timers.running = Some((timer.id, false));
+        (timer.callback)(context);
+        let cleared = context
+            .timers
+            .running
+            .take()
+            .is_some_and(|(_, cleared)| cleared);
+
+        if let Some(interval) = timer.interval
+            && !cleared
+        {
+            timer.deadline += interval.max(Duration::from_millis(1));
+            if timer.deadline <= now {
+                timer.deadline = now + interval;
+            }
+            rescheduled.push(timer);
+        }
+    }
+    context.timers.timers.extend(rescheduled);
+    fired
+}
@@ -0,0 +1,121 @@
+// on linux the tray is backed by gtk, which has to be initialized and pumped on
+// the thread that creates the tray
+use tray_icon::{
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+};
+
+pub const RESTORE_ITEM: &str = "teacup.restore";
+pub const QUIT_ITEM: &str = "teacup.quit";
+
+#[derive(Debug, Clone)]
+pub struct TrayMenuItem {
+    pub id: String,
+    pub label: String,
+    pub enabled: bool,
+}
+
+impl TrayMenuItem {
+    pub fn new(id: &str, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrayOptions {
+    pub tooltip: String,
+    // rgba8 pixels, width, height
+    pub icon: Option<(Vec<u8>, u32, u32)>,
+    pub items: Vec<TrayMenuItem>,
+    // closing the window hides it to the tray instead of quitting
+    pub minimize_to_tray: bool,
+}
+
+impl Default for TrayOptions {
+    fn default() -> Self {
+        Self {
+            tooltip: "teacup".to_string(),
+            icon: None,
+            items: Vec::new(),
+            minimize_to_tray: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrayEvent {
+    // the icon was left clicked, or the restore item picked
+    Restore,
+    Quit,
+    MenuItem(String),
+}
+
+pub struct Tray {
+    icon: TrayIcon,
+    pub minimize_to_tray: bool,
+}
+
+impl Tray {
+    pub fn new(options: &TrayOptions) -> anyhow::Result<Self> {
+        let menu = Menu::new();
+        for item in &options.items {
+            menu.append(&MenuItem::with_id(
+                item.id.as_str(),
+                &item.label,
+                item.enabled,
+                None,
+            ))?;
+        }
+        if !options.items.is_empty() {
+            menu.append(&PredefinedMenuItem::separator())?;
+        }
+        menu.append(&MenuItem::with_id(RESTORE_ITEM, "Show", true, None))?;
+        menu.append(&MenuItem::with_id(QUIT_ITEM, "Quit", true, None))?;
+
+        let mut builder = TrayIconBuilder::new()
+            .with_tooltip(&options.tooltip)
+            .with_menu(Box::new(menu))
+            .with_menu_on_left_click(false);
+        if let Some((rgba, width, height)) = &options.icon {
+            builder = builder.with_icon(Icon::from_rgba(rgba.clone(), *width, *height)?);
+        }
+
+        Ok(Self {
+            icon: builder.build()?,
+            minimize_to_tray: options.minimize_to_tray,
+        })
+    }
+
+    pub fn set_tooltip(&self, tooltip: &str) {
+        if let Err(e) = self.icon.set_tooltip(Some(tooltip)) {
+            log::warn!("failed to set tray tooltip: {e}");
+        }
+    }
+
+    // drains tray and menu events since the last call
+    pub fn poll(&self) -> Vec<TrayEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                events.push(TrayEvent::Restore);
+            }
+        }
+        while let Ok(event) = MenuEvent::receiver().try_recv() {
+            events.push(match event.id.as_ref() {
+                RESTORE_ITEM => TrayEvent::Restore,
+                QUIT_ITEM => TrayEvent::Quit,
+                id => TrayEvent::MenuItem(id.to_string()),
+            });
+        }
+        events
+    }
+}
@@ -0,0 +1,48 @@
+// native translucent materials behind a transparent window. each platform only
+// supports some of them, so `Auto` is usually what you want
+use glfw::PWindow;
+pub use window_vibrancy::NSVisualEffectMaterial;
+use window_vibrancy::{apply_acrylic, apply_blur, apply_mica, apply_vibrancy};
+
+// rgba tint blended over the blurred desktop
+pub type Tint = (u8, u8, u8, u8);
+
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Vibrancy {
+    // the platform's own window material: vibrancy on macos, mica on windows
+    // 11, falling back to acrylic and then plain blur on older windows
+    #[default]
+    Auto,
+    // macos only
+    Material(NSVisualEffectMaterial),
+    // windows 10 and 11
+    Acrylic {
+        tint: Option<Tint>,
+    },
+    // windows 11 only. None follows the system theme
+    Mica {
+        dark: Option<bool>,
+    },
+    // windows 7 to 11
+    Blur {
+        tint: Option<Tint>,
+    },
+}
+
+pub(crate) fn apply(window: &PWindow, vibrancy: Vibrancy) -> Result<(), window_vibrancy::Error> {
+    match vibrancy {
+        Vibrancy::Auto if cfg!(target_os = "macos") => apply_vibrancy(
+            window,
+            NSVisualEffectMaterial::UnderWindowBackground,
+            None,
+            None,
+        ),
+        Vibrancy::Auto => apply_mica(window, None)
+            .or_else(|_| apply_acrylic(window, None))
+            .or_else(|_| apply_blur(window, None)),
+        Vibrancy::Material(material) => apply_vibrancy(window, material, None, None),
+        Vibrancy::Acrylic { tint } => apply_acrylic(window, tint),
+        Vibrancy::Mica { dark } => apply_mica(window, dark),
+        Vibrancy::Blur { tint } => apply_blur(window, tint),
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    builder::{self, IntoNode, Node},
+    event::{Event, PointerButton},
+    input::Modifiers,
+    layout::{AlignCross, Text, UI},
+    renderer::{headless::HeadlessRenderer, text::Font},
+    theme::Theme,
+};
+
+type BuildWidget = Box<dyn Fn(&Theme) -> Node>;
+
+// the states a widget is pictured in. hover and press are real pointer events
+// sent to the middle of the widget, so it reacts the same as it would live
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocState {
+    Idle,
+    Hovered,
+    Pressed,
+}
+
+impl DocState {
+    pub fn name(self) -> &'static str {
+        match self {
+            DocState::Idle => "idle",
+            DocState::Hovered => "hovered",
+            DocState::Pressed => "pressed",
+        }
+    }
+}
+
+// one widget in the gallery. `build` makes a fresh instance for each state
+pub struct WidgetDoc {
+    pub name: String,
+    pub states: Vec<DocState>,
+    // the canvas in points, caption included
+    pub size: (i32, i32),
+    build: BuildWidget,
+}
+
+impl WidgetDoc {
+    pub fn new<T: IntoNode>(name: &str, build: impl Fn(&Theme) -> T + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            states: vec![DocState::Idle, DocState::Hovered, DocState::Pressed],
+            size: (240, 120),
+            build: Box::new(move |theme| build(theme).into_node()),
+        }
+    }
+
+    pub fn states(mut self, states: &[DocState]) -> Self {
+        self.states = states.to_vec();
+        self
+    }
+
+    pub fn size(mut self, width: i32, height: i32) -> Self {
+        self.size = (width, height);
+        self
+    }
+}
+
+// renders every registered widget in each of its states and writes them out
+// as `<widget>-<state>.png`, captioned with the widget and state, so the docs
+// come straight from the code
+pub struct WidgetGallery {
+    pub theme: Theme,
+    pub scale_factor: f32,
+    docs: Vec<WidgetDoc>,
+}
+
+impl Default for WidgetGallery {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            scale_factor: 2.0,
+            docs: Vec::new(),
+        }
+    }
+}
+
+impl WidgetGallery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    pub fn register(&mut self, doc: WidgetDoc) -> &mut Self {
+        self.docs.push(doc);
+        self
+    }
+
+    // returns the files written, in registration order
+    pub async fn generate(&self, dir: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut renderer = HeadlessRenderer::new().await?;
+        let font = Font::system_default();
+        if font.is_none() {
+            log::warn!("no system font found, widget screenshots won't be captioned");
+        }
+
+        let mut written = Vec::new();
+        for doc in &self.docs {
+            for state in &doc.states {
+                let widget = (doc.build)(&self.theme);
+                let mut ui = self.stage(doc, *state, widget.clone(), font.clone());
+                ui.set_size(doc.size);
+                ui.compute_layout();
+                pose(&mut ui, &widget, *state);
+
+                let image = renderer.render(&mut ui, doc.size, self.scale_factor)?;
+                let path = dir.join(format!("{}-{}.png", file_stem(&doc.name), state.name()));
+                image.save(&path)?;
+                log::info!("wrote {}", path.display());
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+
+    // the widget on a plain page, under its caption
+    fn stage(&self, doc: &WidgetDoc, state: DocState, widget: Node, font: Option<Arc<Font>>) -> UI {
+        let mut page = builder::column()
+            .grow()
+            .padding(24)
+            .gap(16)
+            .align_cross(AlignCross::Start)
+            .color(self.theme.background);
+        if let Some(font) = font {
+            let caption = format!("{} ({})", doc.name, state.name());
+            page = page.child(Text::new(&caption, font, 14.0, self.theme.inactive));
+        }
+        let mut ui = UI::default();
+        ui.background_color = self.theme.background;
+        ui.set_root(page.child(widget));
+        ui
+    }
+}
+
+fn pose(ui: &mut UI, widget: &Node, state: DocState) {
+    let center = match widget.lock() {
+        Ok(prim) => {
+            let (x, y) = prim.get_position();
+            (x + prim.get_width() / 2, y + prim.get_height() / 2)
+        }
+        Err(_) => return,
+    };
+    if state == DocState::Idle {
+        return;
+    }
+    ui.dispatch_event(&Event::PointerEntered);
+    ui.dispatch_event(&Event::PointerMoved { position: center });
+    if state == DocState::Pressed {
+        ui.dispatch_event(&Event::PointerPressed {
+            button: PointerButton::Primary,
+            position: center,
+            modifiers: Modifiers::NONE,
+        });
+    }
+}
+
+// lowercase with dashes, safe for any file system
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    stem.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
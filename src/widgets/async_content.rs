@@ -0,0 +1,216 @@
+use std::{
+    future::Future,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use tokio::task::JoinHandle;
+
+use crate::{
+    builder::Node,
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{draw_list::DrawList, mesh_builder::Mesh},
+};
+
+type FallbackFn = Box<dyn FnMut(&anyhow::Error) -> Node + Send>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsyncState {
+    Pending,
+    Ready,
+    // the future returned an error or panicked
+    Failed(String),
+}
+
+// where the background task leaves its result for the ui thread to pick up
+#[derive(Default)]
+struct Slot {
+    ready: AtomicBool,
+    result: Mutex<Option<anyhow::Result<Node>>>,
+}
+
+// content that arrives later. `placeholder` is shown while the future runs on
+// the tokio runtime, then swapped for the subtree it produced during the next
+// layout. errors are shown with the subtree `fallback` builds from them
+pub struct Async {
+    pub rect: Rectangle,
+    placeholder: Node,
+    fallback: FallbackFn,
+    state: AsyncState,
+    slot: Arc<Slot>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl Async {
+    pub fn new(
+        future: impl Future<Output = anyhow::Result<Node>> + Send + 'static,
+        placeholder: Node,
+        fallback: impl FnMut(&anyhow::Error) -> Node + Send + 'static,
+    ) -> Self {
+        let mut content = Self {
+            rect: Rectangle::default(),
+            placeholder,
+            fallback: Box::new(fallback),
+            state: AsyncState::Pending,
+            slot: Arc::new(Slot::default()),
+            task: None,
+        };
+        content.reload(future);
+        content
+    }
+
+    pub fn with_sizing(mut self, sizing: Sizing) -> Self {
+        self.rect.sizing = sizing;
+        self
+    }
+
+    pub fn state(&self) -> &AsyncState {
+        &self.state
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.state == AsyncState::Pending
+    }
+
+    // shows the placeholder again and replaces the content with what `future`
+    // produces. a load still in flight is cancelled
+    pub fn reload(&mut self, future: impl Future<Output = anyhow::Result<Node>> + Send + 'static) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        let slot = Arc::new(Slot::default());
+        let sender = slot.clone();
+        let task = async move {
+            let result = future.await;
+            if let Ok(mut stored) = sender.result.lock() {
+                *stored = Some(result);
+            }
+            sender.ready.store(true, Ordering::Release);
+        };
+        self.task = match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => Some(runtime.spawn(task)),
+            // built outside the event loop, so there's no runtime to borrow
+            Err(_) => {
+                std::thread::spawn(move || block_on(task));
+                None
+            }
+        };
+        self.slot = slot;
+        self.state = AsyncState::Pending;
+        self.rect.children = vec![self.placeholder.clone()];
+        self.rect.mark_dirty();
+    }
+
+    // swaps in the result once the task has finished
+    fn poll(&mut self) {
+        if !self.slot.ready.swap(false, Ordering::Acquire) {
+            if self.is_pending() && self.task.as_ref().is_some_and(|task| task.is_finished()) {
+                // finished without filling the slot, so the future panicked
+                self.fail(anyhow::anyhow!("async content panicked"));
+            }
+            return;
+        }
+        let result = self
+            .slot
+            .result
+            .lock()
+            .ok()
+            .and_then(|mut result| result.take());
+        self.task = None;
+        match result {
+            Some(Ok(content)) => {
+                self.state = AsyncState::Ready;
+                self.rect.children = vec![content];
+                self.rect.mark_dirty();
+            }
+            Some(Err(e)) => self.fail(e),
+            None => {}
+        }
+    }
+
+    fn fail(&mut self, error: anyhow::Error) {
+        log::error!("async content failed: {error:#}");
+        self.task = None;
+        self.state = AsyncState::Failed(format!("{error:#}"));
+        self.rect.children = vec![(self.fallback)(&error)];
+        self.rect.mark_dirty();
+    }
+}
+
+fn block_on(task: impl Future<Output = ()>) {
+    match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime.block_on(task),
+        Err(e) => log::error!("couldn't start a runtime for async content: {e}"),
+    }
+}
+
+impl Drop for Async {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+impl Primative for Async {
+    delegate_primative!(rect, sizing);
+
+    // relaid out once the result lands
+    fn is_dirty(&self) -> bool {
+        self.slot.ready.load(Ordering::Acquire)
+            || self.task.as_ref().is_some_and(|task| task.is_finished())
+            || self.rect.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record(list, size);
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for Async {
+    fn fit_sizing(&mut self) {
+        self.poll();
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
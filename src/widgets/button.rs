@@ -0,0 +1,270 @@
+use std::time::Duration;
+
+use tinycolors::srgb;
+
+use crate::{
+    animation::Fade,
+    builder::IntoNode,
+    context,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    input::Key,
+    layout::{
+        AlignCross, AlignMain, Axis, Container, Primative, Rectangle, Sizing, SizingMode,
+        delegate_primative,
+    },
+    renderer::{draw_list::DrawList, mesh_builder::Mesh},
+    state_machine::{Interaction, InteractionEvent, StateMachine},
+    theme::{Theme, mix},
+};
+
+// how long the fill takes to follow a change of state
+const COLOR_FADE: Duration = Duration::from_millis(120);
+
+type ClickCallback = Box<dyn FnMut() + Send>;
+
+// a clickable container that fits around its label. the fill fades between
+// idle, hovered and pressed as the pointer moves, timed by the frame clock,
+// and `on_click` fires when a press is released over the button
+pub struct Button {
+    pub rect: Rectangle,
+    pub idle_color: srgb,
+    pub hover_color: srgb,
+    pub pressed_color: srgb,
+    pub disabled_color: srgb,
+    interaction: StateMachine<Interaction, InteractionEvent>,
+    // the fill fades from `fade_from` to the color for the current state
+    fade_from: srgb,
+    fade: Fade,
+    on_click: Option<ClickCallback>,
+}
+
+impl Button {
+    pub fn new(label: impl IntoNode, theme: &Theme) -> Self {
+        let mut button = Self {
+            rect: Rectangle {
+                padding: 12,
+                corner_radius: 8.0,
+                align_main: AlignMain::Center,
+                align_cross: AlignCross::Center,
+                children: vec![label.into_node()],
+                ..Default::default()
+            },
+            idle_color: srgb::default(),
+            hover_color: srgb::default(),
+            pressed_color: srgb::default(),
+            disabled_color: srgb::default(),
+            interaction: Interaction::machine(),
+            fade_from: srgb::default(),
+            fade: Fade::new(COLOR_FADE, 1.0),
+            on_click: None,
+        };
+        button.apply_theme(theme);
+        button
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.idle_color = theme.surface;
+        self.hover_color = theme.hover;
+        self.pressed_color = theme.pressed;
+        self.disabled_color = theme.inactive;
+        self.rect.fill = Fill::Solid(self.state_color());
+        self.fade.snap(1.0);
+    }
+
+    pub fn on_click(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.on_click = Some(Box::new(f));
+        self
+    }
+
+    pub fn interaction(&self) -> Interaction {
+        *self.interaction.state()
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.interaction.is(&Interaction::Disabled)
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.handle(if disabled {
+            InteractionEvent::Disable
+        } else {
+            InteractionEvent::Enable
+        });
+    }
+
+    pub fn hover(&mut self, point: (i32, i32)) {
+        if self.contains(point) {
+            self.handle(InteractionEvent::PointerEnter);
+        } else {
+            self.handle(InteractionEvent::PointerLeave);
+        }
+    }
+
+    // returns true when the press landed on the button and should be captured
+    // until release
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+        self.handle(InteractionEvent::PointerEnter);
+        self.handle(InteractionEvent::Press)
+    }
+
+    // releasing outside the button cancels the click
+    pub fn release(&mut self, point: (i32, i32)) {
+        if !self.interaction().is_active() {
+            return;
+        }
+        let inside = self.contains(point);
+        self.handle(InteractionEvent::Release { inside });
+        if inside {
+            self.click();
+        }
+    }
+
+    // fires `on_click` as if the button was pressed, for keyboard activation
+    pub fn click(&mut self) {
+        if self.is_disabled() {
+            return;
+        }
+        if let Some(on_click) = &mut self.on_click {
+            on_click();
+        }
+    }
+
+    fn handle(&mut self, event: InteractionEvent) -> bool {
+        let changed = self.interaction.handle(&event);
+        if changed {
+            self.sync_color();
+        }
+        changed
+    }
+
+    fn state_color(&self) -> srgb {
+        match self.interaction() {
+            Interaction::Idle => self.idle_color,
+            Interaction::Hovered => self.hover_color,
+            Interaction::Pressed | Interaction::Dragging => self.pressed_color,
+            Interaction::Disabled => self.disabled_color,
+        }
+    }
+
+    // starts fading from whatever is showing now
+    fn sync_color(&mut self) {
+        self.fade_from = self.rect.fill.color();
+        self.fade.snap(0.0);
+        self.fade.set_target(1.0, context::frame_now());
+    }
+
+    fn step(&mut self) {
+        let now = context::frame_now();
+        self.rect.fill = Fill::Solid(if self.fade.is_settled(now) {
+            self.state_color()
+        } else {
+            mix(self.fade_from, self.state_color(), self.fade.value(now))
+        });
+    }
+}
+
+impl Primative for Button {
+    delegate_primative!(rect, sizing);
+
+    // laid out every frame while the fill fades
+    fn is_dirty(&self) -> bool {
+        self.rect.fill.color() != self.state_color() || self.rect.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    // the pointer is followed on the way down, so the hover is right even if
+    // the label takes the event. presses and keys are taken on the way up
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let upward = matches!(cx.phase(), Phase::Target | Phase::Bubble);
+        let handled = match cx.event {
+            Event::PointerMoved { position } if cx.phase() != Phase::Bubble => {
+                self.hover(*position);
+                false
+            }
+            Event::PointerLeft => {
+                self.handle(InteractionEvent::PointerLeave);
+                false
+            }
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if upward => self.press(*position),
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if upward && self.interaction().is_active() => {
+                self.release(*position);
+                true
+            }
+            Event::KeyPressed {
+                key: Key::Space | Key::Enter,
+                repeat: false,
+                ..
+            } if upward => {
+                self.click();
+                !self.is_disabled()
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record(list, size);
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for Button {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.step();
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
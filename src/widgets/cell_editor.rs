@@ -0,0 +1,202 @@
+use crate::input::Key;
+
+// edits a single table cell. values go in and out as strings so the table
+// doesn't need to know the column's type
+pub trait CellEditor: Send {
+    fn begin(&mut self, value: &str);
+    fn value(&self) -> String;
+
+    fn insert_char(&mut self, _c: char) {}
+
+    fn handle_key(&mut self, _key: Key) -> bool {
+        false
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TextEditor {
+    pub text: String,
+}
+
+impl CellEditor for TextEditor {
+    fn begin(&mut self, value: &str) {
+        self.text = value.to_string();
+    }
+
+    fn value(&self) -> String {
+        self.text.clone()
+    }
+
+    fn insert_char(&mut self, c: char) {
+        if !c.is_control() {
+            self.text.push(c);
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Backspace => {
+                self.text.pop();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NumberEditor {
+    pub text: String,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl Default for NumberEditor {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            min: f64::MIN,
+            max: f64::MAX,
+            step: 1.0,
+        }
+    }
+}
+
+impl NumberEditor {
+    pub fn new(min: f64, max: f64, step: f64) -> Self {
+        Self {
+            min,
+            max,
+            step,
+            ..Default::default()
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        self.text.trim().parse().ok()
+    }
+}
+
+impl CellEditor for NumberEditor {
+    fn begin(&mut self, value: &str) {
+        self.text = value.to_string();
+    }
+
+    fn value(&self) -> String {
+        self.text.clone()
+    }
+
+    fn insert_char(&mut self, c: char) {
+        let sign = (c == '-' || c == '+') && self.text.is_empty();
+        let point = c == '.' && !self.text.contains('.');
+        if c.is_ascii_digit() || sign || point {
+            self.text.push(c);
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> bool {
+        let delta = match key {
+            Key::Backspace => {
+                self.text.pop();
+                return true;
+            }
+            Key::Up => self.step,
+            Key::Down => -self.step,
+            _ => return false,
+        };
+        let value = (self.number().unwrap_or(0.0) + delta).clamp(self.min, self.max);
+        self.text = value.to_string();
+        true
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.number() {
+            Some(n) if n >= self.min && n <= self.max => Ok(()),
+            Some(_) => Err(format!("must be between {} and {}", self.min, self.max)),
+            None => Err("must be a number".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DropdownEditor {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl DropdownEditor {
+    pub fn new(options: Vec<String>) -> Self {
+        Self {
+            options,
+            selected: 0,
+        }
+    }
+}
+
+impl CellEditor for DropdownEditor {
+    fn begin(&mut self, value: &str) {
+        self.selected = self.options.iter().position(|o| o == value).unwrap_or(0);
+    }
+
+    fn value(&self) -> String {
+        self.options.get(self.selected).cloned().unwrap_or_default()
+    }
+
+    // typing jumps to the next option starting with that letter
+    fn insert_char(&mut self, c: char) {
+        let count = self.options.len();
+        let next = (1..=count)
+            .map(|i| (self.selected + i) % count.max(1))
+            .find(|i| {
+                self.options[*i]
+                    .chars()
+                    .next()
+                    .is_some_and(|first| first.eq_ignore_ascii_case(&c))
+            });
+        if let Some(next) = next {
+            self.selected = next;
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down => {
+                self.selected = (self.selected + 1).min(self.options.len().saturating_sub(1))
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CheckboxEditor {
+    pub checked: bool,
+}
+
+impl CellEditor for CheckboxEditor {
+    fn begin(&mut self, value: &str) {
+        // entering edit mode on a checkbox toggles it straight away
+        self.checked = !matches!(value, "true" | "1" | "yes");
+    }
+
+    fn value(&self) -> String {
+        self.checked.to_string()
+    }
+
+    fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Space => {
+                self.checked = !self.checked;
+                true
+            }
+            _ => false,
+        }
+    }
+}
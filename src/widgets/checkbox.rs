@@ -0,0 +1,237 @@
+use tinycolors::srgb;
+
+use crate::{
+    builder::IntoNode,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    input::Key,
+    layout::{
+        AlignCross, Axis, Container, LayoutMode, Primative, Rectangle, Sizing, SizingMode,
+        delegate_primative,
+    },
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_polygon, make_ss_rounded_rectangle, make_ss_rounded_ring},
+    },
+    theme::Theme,
+};
+
+type ToggleCallback = Box<dyn FnMut(bool) + Send>;
+
+// a box that's ticked or not, with an optional label to its right. clicking
+// anywhere on it or pressing space flips it and fires `on_change`
+pub struct Checkbox {
+    pub rect: Rectangle,
+    pub checked: bool,
+    pub box_size: i32,
+    pub box_color: srgb,
+    pub border_color: srgb,
+    // the box once it's ticked, and the tick on top
+    pub checked_color: srgb,
+    pub mark_color: srgb,
+    on_change: Option<ToggleCallback>,
+}
+
+impl Checkbox {
+    pub fn new(theme: &Theme) -> Self {
+        let box_size = 36;
+        let mut checkbox = Self {
+            rect: Rectangle {
+                layout_mode: LayoutMode::LeftToRight,
+                child_gap: 12,
+                align_cross: AlignCross::Center,
+                // holds the box's place ahead of the label
+                children: vec![spacer(box_size).into_node()],
+                ..Default::default()
+            },
+            checked: false,
+            box_size,
+            box_color: srgb::default(),
+            border_color: srgb::default(),
+            checked_color: srgb::default(),
+            mark_color: srgb::default(),
+            on_change: None,
+        };
+        checkbox.apply_theme(theme);
+        checkbox
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.box_color = theme.surface;
+        self.border_color = theme.border;
+        self.checked_color = theme.primary;
+        self.mark_color = theme.on_primary;
+    }
+
+    pub fn with_label(mut self, label: impl IntoNode) -> Self {
+        self.rect.children.push(label.into_node());
+        self.rect.mark_dirty();
+        self
+    }
+
+    // sets the starting state without firing `on_change`
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    pub fn on_change(mut self, f: impl FnMut(bool) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        if checked == self.checked {
+            return;
+        }
+        self.checked = checked;
+        if let Some(on_change) = &mut self.on_change {
+            on_change(checked);
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_checked(!self.checked);
+    }
+
+    // returns true when the click landed on the checkbox
+    pub fn click(&mut self, point: (i32, i32)) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+        self.toggle();
+        true
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Space => self.toggle(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn box_bounds(&self) -> (i32, i32, i32, i32) {
+        let (x, y) = self.rect.position;
+        let top = y + (self.rect.height - self.box_size) / 2;
+        (x, top, self.box_size, self.box_size)
+    }
+}
+
+fn spacer(size: i32) -> Rectangle {
+    Rectangle {
+        sizing: Sizing {
+            width: SizingMode::Fixed(size),
+            height: SizingMode::Fixed(size),
+        },
+        // the box is drawn by the checkbox
+        opacity: 0.0,
+        ..Default::default()
+    }
+}
+
+// a thick line from `from` to `to`, in screen space
+fn stroke(from: (f32, f32), to: (f32, f32), width: f32, color: srgb, size: (i32, i32)) -> Mesh {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    let (nx, ny) = (-dy / length * width / 2.0, dx / length * width / 2.0);
+    let center = ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0);
+    make_ss_polygon(
+        center,
+        &[
+            (from.0 + nx, from.1 + ny),
+            (to.0 + nx, to.1 + ny),
+            (to.0 - nx, to.1 - ny),
+            (from.0 - nx, from.1 - ny),
+        ],
+        color,
+        size,
+    )
+}
+
+impl Primative for Checkbox {
+    delegate_primative!(rect);
+
+    // clicks and keys are taken on the way up, once the label has had them.
+    // a click counts when the release lands on the checkbox
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || !matches!(cx.phase(), Phase::Target | Phase::Bubble) {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } => self.click(*position),
+            Event::KeyPressed { key, .. } => self.handle_key(*key),
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.get_mesh(size));
+        self.rect.record_children(list, size);
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let (x, y, w, h) = self.box_bounds();
+        let radius = w as f32 * 0.2;
+        if !self.checked {
+            let mut mesh = make_ss_rounded_rectangle(x, y, w, h, radius, self.box_color, size);
+            mesh.append(make_ss_rounded_ring(
+                x,
+                y,
+                w,
+                h,
+                radius,
+                2,
+                self.border_color,
+                size,
+            ));
+            return mesh;
+        }
+
+        let mut mesh = make_ss_rounded_rectangle(x, y, w, h, radius, self.checked_color, size);
+        let point = |u: f32, v: f32| (x as f32 + u * w as f32, y as f32 + v * h as f32);
+        let (start, corner, end) = (point(0.24, 0.52), point(0.42, 0.7), point(0.76, 0.32));
+        let width = w as f32 * 0.12;
+        mesh.append(stroke(start, corner, width, self.mark_color, size));
+        mesh.append(stroke(corner, end, width, self.mark_color, size));
+        mesh
+    }
+}
+
+impl Container for Checkbox {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
@@ -0,0 +1,563 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use tinycolors::srgb;
+use tokio::sync::oneshot;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    floating::{Anchor, AnchorTarget, Positioning},
+    input::Key,
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+        text::Font,
+    },
+    search::TextSearch,
+    text,
+    theme::Theme,
+    widgets::text_input::TextInput,
+};
+
+// space above and below each suggestion's text
+const ROW_PADDING: i32 = 6;
+
+pub type SuggestionFuture = Pin<Box<dyn Future<Output = Vec<String>> + Send>>;
+
+pub trait SuggestionProvider: Send + Sync {
+    fn suggestions(&self, query: &str) -> SuggestionFuture;
+}
+
+impl<F> SuggestionProvider for F
+where
+    F: Fn(&str) -> SuggestionFuture + Send + Sync,
+{
+    fn suggestions(&self, query: &str) -> SuggestionFuture {
+        self(query)
+    }
+}
+
+type SelectCallback = Box<dyn FnMut(&str) + Send>;
+
+// the list of suggestions under a combo box. it's a floating child of the box,
+// so it's drawn above the page, flips above the box when there's no room
+// below, and gets the pointer before anything under it. a row picked with the
+// pointer waits in `picked` for the box to apply
+pub struct SuggestionPopup {
+    pub rect: Rectangle,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub max_visible: usize,
+    pub color: srgb,
+    pub highlight_color: srgb,
+    items: Vec<String>,
+    highlighted: Option<usize>,
+    // the first row showing, once there are more than fit
+    first: usize,
+    open: bool,
+    picked: Option<usize>,
+}
+
+impl SuggestionPopup {
+    fn new(font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                fill: Fill::Solid(theme.surface),
+                border_width: 2,
+                border_color: theme.border,
+                corner_radius: 6.0,
+                z_index: 1,
+                positioning: Positioning::Floating {
+                    anchor: Anchor::below(AnchorTarget::Parent),
+                    offset: (0, 4),
+                },
+                ..Default::default()
+            },
+            font,
+            font_size,
+            max_visible: 8,
+            color: theme.on_surface,
+            highlight_color: theme.hover,
+            items: Vec::new(),
+            highlighted: None,
+            first: 0,
+            open: false,
+            picked: None,
+        }
+    }
+
+    fn px(&self) -> f32 {
+        text::scaled(self.font_size)
+    }
+
+    fn row_height(&self) -> i32 {
+        self.font.line_height(self.px()).ceil() as i32 + ROW_PADDING * 2
+    }
+
+    fn visible_rows(&self) -> usize {
+        if self.open {
+            self.items.len().min(self.max_visible)
+        } else {
+            0
+        }
+    }
+
+    fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.highlighted = self.highlighted.filter(|i| *i < self.items.len());
+        self.first = 0;
+        self.scroll_to_highlight();
+        self.rect.mark_dirty();
+    }
+
+    fn set_open(&mut self, open: bool) {
+        if open != self.open {
+            self.open = open;
+            self.rect.mark_dirty();
+        }
+        if !open {
+            self.highlighted = None;
+            self.first = 0;
+        }
+    }
+
+    fn set_highlighted(&mut self, highlighted: Option<usize>) {
+        self.highlighted = highlighted;
+        self.scroll_to_highlight();
+        self.rect.mark_dirty();
+    }
+
+    fn move_highlight(&mut self, delta: isize) {
+        if self.items.is_empty() {
+            self.set_highlighted(None);
+            return;
+        }
+        let last = self.items.len() as isize - 1;
+        let next = match self.highlighted {
+            Some(i) => (i as isize + delta).clamp(0, last),
+            None if delta < 0 => last,
+            None => 0,
+        };
+        self.set_highlighted(Some(next as usize));
+    }
+
+    fn scroll_to_highlight(&mut self) {
+        let rows = self.max_visible.max(1);
+        if let Some(i) = self.highlighted {
+            if i < self.first {
+                self.first = i;
+            } else if i >= self.first + rows {
+                self.first = i + 1 - rows;
+            }
+        }
+        self.first = self.first.min(self.items.len().saturating_sub(rows));
+    }
+
+    // the suggestion under `point`, in layout pixels
+    fn row_at(&self, point: (i32, i32)) -> Option<usize> {
+        if !self.contains(point) {
+            return None;
+        }
+        let row = ((point.1 - self.rect.position.1) / self.row_height().max(1)) as usize;
+        (row < self.visible_rows()).then_some(self.first + row)
+    }
+
+    // where each visible row's text goes, with its index
+    fn rows(&self) -> impl Iterator<Item = (usize, (i32, i32))> + '_ {
+        let (x, y) = self.rect.position;
+        let row_height = self.row_height();
+        (0..self.visible_rows()).map(move |row| {
+            (
+                self.first + row,
+                (
+                    x + ROW_PADDING * 2,
+                    y + row as i32 * row_height + ROW_PADDING,
+                ),
+            )
+        })
+    }
+}
+
+impl Primative for SuggestionPopup {
+    delegate_primative!(rect);
+
+    fn contains(&self, point: (i32, i32)) -> bool {
+        self.visible_rows() > 0 && self.rect.contains(point)
+    }
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        if !matches!(cx.phase(), Phase::Target | Phase::Bubble) {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerMoved { position } => {
+                if let Some(row) = self.row_at(*position) {
+                    self.set_highlighted(Some(row));
+                }
+                true
+            }
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } => {
+                self.picked = self.row_at(*position);
+                self.rect.mark_dirty();
+                true
+            }
+            Event::Scroll { delta, .. } => {
+                let rows = (-delta.1).round() as isize;
+                let last = self.items.len().saturating_sub(self.max_visible) as isize;
+                self.first = (self.first as isize + rows).clamp(0, last.max(0)) as usize;
+                self.rect.mark_dirty();
+                true
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        if self.visible_rows() == 0 {
+            return Mesh::default();
+        }
+        let mut mesh = self.rect.get_mesh(size);
+        if let Some(i) = self.highlighted
+            && let Some((_, (_, y))) = self.rows().find(|(row, _)| *row == i)
+        {
+            let border = self.rect.border_width;
+            mesh.append(make_ss_rectangle(
+                self.rect.position.0 + border,
+                y - ROW_PADDING,
+                self.rect.width - border * 2,
+                self.row_height(),
+                self.highlight_color,
+                size,
+            ));
+        }
+        mesh
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        if self.visible_rows() == 0 {
+            return;
+        }
+        list.mesh(self.get_mesh(size));
+        list.push_clip(self.rect.position, (self.rect.width, self.rect.height));
+        for (i, (x, y)) in self.rows() {
+            list.text(
+                &self.font,
+                &self.items[i],
+                self.px(),
+                (x as f32, y as f32),
+                self.color,
+            );
+        }
+        list.pop_clip();
+    }
+}
+
+// a text input that suggests values as it's typed in. the suggestions come
+// from filtering `options`, or from an async provider, and show in a popup
+// under the input. the arrow keys move through them and enter or tab picks
+// one, as does clicking it, firing `on_select`
+pub struct ComboBox {
+    // holds the input and the popup
+    pub rect: Rectangle,
+    pub options: Vec<String>,
+    input: Arc<Mutex<TextInput>>,
+    popup: Arc<Mutex<SuggestionPopup>>,
+    search: TextSearch,
+    provider: Option<Arc<dyn SuggestionProvider>>,
+    pending: Option<oneshot::Receiver<Vec<String>>>,
+    on_select: Option<SelectCallback>,
+}
+
+impl ComboBox {
+    pub fn new(options: Vec<String>, font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        let input = Arc::new(Mutex::new(TextInput::new(font.clone(), font_size, theme)));
+        let popup = Arc::new(Mutex::new(SuggestionPopup::new(font, font_size, theme)));
+        Self {
+            rect: Rectangle {
+                children: vec![input.clone(), popup.clone()],
+                ..Default::default()
+            },
+            options,
+            input,
+            popup,
+            search: TextSearch::default(),
+            provider: None,
+            pending: None,
+            on_select: None,
+        }
+    }
+
+    // suggestions come from `provider` instead of `options`
+    pub fn with_provider(mut self, provider: impl SuggestionProvider + 'static) -> Self {
+        self.provider = Some(Arc::new(provider));
+        self
+    }
+
+    pub fn with_placeholder(self, placeholder: &str) -> Self {
+        self.input().placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn on_select(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.input().text.clone()
+    }
+
+    // replaces what's typed without opening the popup or firing `on_select`
+    pub fn set_text(&mut self, text: &str) {
+        self.input().set_text(text);
+        self.refresh_suggestions();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup().open
+    }
+
+    pub fn suggestions(&self) -> Vec<String> {
+        self.popup().items.clone()
+    }
+
+    pub fn highlighted(&self) -> Option<usize> {
+        self.popup().highlighted
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn open(&mut self) {
+        if !self.is_open() {
+            self.refresh_suggestions();
+            self.popup().set_open(true);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.popup().set_open(false);
+    }
+
+    // picks up finished async suggestion requests. runs with layout, which
+    // keeps running while one is pending
+    pub fn poll(&mut self) {
+        let Some(pending) = &mut self.pending else {
+            return;
+        };
+        match pending.try_recv() {
+            Ok(suggestions) => {
+                self.pending = None;
+                self.popup().set_items(suggestions);
+            }
+            Err(oneshot::error::TryRecvError::Empty) => {}
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.pending = None;
+            }
+        }
+    }
+
+    // the keys that move through the suggestions. the rest go to the input
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        let open = self.is_open();
+        let page = self.popup().max_visible as isize;
+        match key {
+            Key::Down => {
+                self.open();
+                self.popup().move_highlight(1);
+            }
+            Key::Up if open => self.popup().move_highlight(-1),
+            Key::PageDown if open => self.popup().move_highlight(page),
+            Key::PageUp if open => self.popup().move_highlight(-page),
+            Key::Enter | Key::Tab if open => {
+                let Some(i) = self.highlighted() else {
+                    return false;
+                };
+                self.select(i);
+            }
+            Key::Escape if open => self.close(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn select(&mut self, index: usize) {
+        let Some(value) = self.popup().items.get(index).cloned() else {
+            return;
+        };
+        self.input().set_text(&value);
+        if let Some(on_select) = &mut self.on_select {
+            on_select(&value);
+        }
+        self.close();
+    }
+
+    fn input(&self) -> MutexGuard<'_, TextInput> {
+        self.input.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn popup(&self) -> MutexGuard<'_, SuggestionPopup> {
+        self.popup.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn refresh_suggestions(&mut self) {
+        let text = self.text();
+        if let Some(provider) = &self.provider {
+            let future = provider.suggestions(&text);
+            let (tx, rx) = oneshot::channel();
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn(async move {
+                        let _ = tx.send(future.await);
+                    });
+                    // replacing the receiver drops any stale request
+                    self.pending = Some(rx);
+                }
+                Err(e) => log::error!("combo box suggestion provider needs a tokio runtime: {e}"),
+            }
+            return;
+        }
+
+        self.search.set_query(&text);
+        let suggestions = self
+            .search
+            .filter(&self.options)
+            .into_iter()
+            .map(|i| self.options[i].clone())
+            .collect();
+        self.popup().set_items(suggestions);
+    }
+
+    // typing goes through here on its way to the input, so the suggestions
+    // follow what's typed
+    fn type_into_input(&mut self, event: &Event) -> bool {
+        let before = self.text();
+        let handled = self.input().handle_event(event);
+        if self.text() != before {
+            self.refresh_suggestions();
+            self.popup().set_open(true);
+        }
+        handled
+    }
+}
+
+impl Primative for ComboBox {
+    delegate_primative!(rect, sizing);
+
+    // laid out every frame while suggestions are loading, so they're picked up
+    fn is_dirty(&self) -> bool {
+        self.pending.is_some() || self.rect.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    // keys are taken on the way down, before the input sees them. a press
+    // anywhere but the input or the popup closes the popup
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let handled = match (cx.phase(), cx.event) {
+            (Phase::Exit, Event::PointerPressed { position, .. }) => {
+                let on_popup = self.popup().contains(*position);
+                if !on_popup {
+                    self.close();
+                }
+                false
+            }
+            (
+                Phase::Capture,
+                Event::PointerPressed {
+                    button: PointerButton::Primary,
+                    ..
+                },
+            ) => {
+                if self.is_open() {
+                    self.close();
+                } else {
+                    self.open();
+                }
+                false
+            }
+            (Phase::Capture, Event::KeyPressed { key, .. }) if self.handle_key(*key) => true,
+            (Phase::Capture, Event::KeyPressed { .. } | Event::Text(_)) => {
+                self.type_into_input(cx.event)
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record_children(list, size);
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    // the input and the popup draw themselves
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
+
+impl Container for ComboBox {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    // the popup is as wide as the box and tall enough for the rows showing
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+        let width = self.rect.width;
+        let mut popup = self.popup();
+        let height = popup.visible_rows() as i32 * popup.row_height();
+        popup.set_width(width);
+        popup.set_height(height);
+    }
+
+    fn set_child_positions(&mut self) {
+        self.poll();
+        let picked = self.popup().picked.take();
+        if let Some(i) = picked {
+            self.select(i);
+        }
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use crate::{
+    builder::Node,
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{draw_list::DrawList, mesh_builder::Mesh},
+};
+
+type BuildFn = Box<dyn FnMut() -> anyhow::Result<Node> + Send>;
+type FallbackFn = Box<dyn FnMut(&BoundaryError, RetryHandle) -> Node + Send>;
+
+#[derive(Debug, Clone)]
+pub struct BoundaryError {
+    pub message: String,
+    // a panic rather than a returned error
+    pub panicked: bool,
+}
+
+impl fmt::Display for BoundaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.panicked {
+            write!(f, "panicked: {}", self.message)
+        } else {
+            write!(f, "{}", self.message)
+        }
+    }
+}
+
+impl BoundaryError {
+    fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        Self {
+            message,
+            panicked: true,
+        }
+    }
+}
+
+// lets a fallback subtree, e.g. a retry button's on_click, ask its boundary to
+// build the content again. the rebuild happens on the next layout
+#[derive(Debug, Clone, Default)]
+pub struct RetryHandle(Arc<AtomicBool>);
+
+impl RetryHandle {
+    pub fn retry(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    fn is_pending(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn take(&self) -> bool {
+        self.0.swap(false, Ordering::Relaxed)
+    }
+}
+
+// isolates a part of the ui that may fail. `build` creates the content; if it
+// returns an error or panics, or the content panics during layout or an
+// `update`, the content is swapped for the subtree `fallback` makes from the
+// error. the rest of the ui keeps running
+pub struct ErrorBoundary {
+    pub rect: Rectangle,
+    build: BuildFn,
+    fallback: FallbackFn,
+    error: Option<BoundaryError>,
+    retry: RetryHandle,
+}
+
+impl ErrorBoundary {
+    pub fn new(
+        build: impl FnMut() -> anyhow::Result<Node> + Send + 'static,
+        fallback: impl FnMut(&BoundaryError, RetryHandle) -> Node + Send + 'static,
+    ) -> Self {
+        let mut boundary = Self {
+            rect: Rectangle::default(),
+            build: Box::new(build),
+            fallback: Box::new(fallback),
+            error: None,
+            retry: RetryHandle::default(),
+        };
+        boundary.retry();
+        boundary
+    }
+
+    pub fn with_sizing(mut self, sizing: Sizing) -> Self {
+        self.rect.sizing = sizing;
+        self
+    }
+
+    pub fn error(&self) -> Option<&BoundaryError> {
+        self.error.as_ref()
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    pub fn retry_handle(&self) -> RetryHandle {
+        self.retry.clone()
+    }
+
+    // builds the content again, replacing the fallback if it succeeds
+    pub fn retry(&mut self) {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| (self.build)()));
+        match result {
+            Ok(Ok(content)) => {
+                self.error = None;
+                self.rect.children = vec![content];
+                self.rect.mark_dirty();
+            }
+            Ok(Err(e)) => self.fail(BoundaryError {
+                message: format!("{e:#}"),
+                panicked: false,
+            }),
+            Err(payload) => self.fail(BoundaryError::from_panic(payload)),
+        }
+    }
+
+    // runs `f` against the live content. failures switch to the fallback the
+    // same way a failed build does
+    pub fn update(&mut self, f: impl FnOnce(&Node) -> anyhow::Result<()>) {
+        if self.has_failed() {
+            return;
+        }
+        let Some(content) = self.rect.children.first().cloned() else {
+            return;
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| f(&content))) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => self.fail(BoundaryError {
+                message: format!("{e:#}"),
+                panicked: false,
+            }),
+            Err(payload) => self.fail(BoundaryError::from_panic(payload)),
+        }
+    }
+
+    fn fail(&mut self, error: BoundaryError) {
+        log::error!("error boundary caught: {error}");
+        let fallback = (self.fallback)(&error, self.retry.clone());
+        self.rect.children = vec![fallback];
+        self.rect.mark_dirty();
+        self.error = Some(error);
+    }
+
+    // runs a layout pass over the content, falling back if it panics. a
+    // panicking fallback is left to propagate
+    fn guard(&mut self, pass: impl Fn(&mut Rectangle)) {
+        if self.has_failed() {
+            pass(&mut self.rect);
+            return;
+        }
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| pass(&mut self.rect))) {
+            self.fail(BoundaryError::from_panic(payload));
+            pass(&mut self.rect);
+        }
+    }
+}
+
+impl Primative for ErrorBoundary {
+    delegate_primative!(rect, sizing);
+
+    // a pending retry rebuilds during layout
+    fn is_dirty(&self) -> bool {
+        self.retry.is_pending() || self.rect.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record(list, size);
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for ErrorBoundary {
+    fn fit_sizing(&mut self) {
+        if self.retry.take() {
+            self.retry();
+        }
+        self.guard(|rect| rect.fit_sizing());
+    }
+
+    fn grow_sizing(&mut self) {
+        self.guard(|rect| rect.grow_sizing());
+    }
+
+    fn set_child_positions(&mut self) {
+        self.guard(|rect| rect.set_child_positions());
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
@@ -0,0 +1,75 @@
+use tinycolors::srgb;
+
+type PickCallback = Box<dyn FnMut(srgb) + Send>;
+
+// samples colors from the rendered frame. while active the renderer reads back
+// the pixel under the pointer every frame for a live preview, and the next click
+// picks it
+#[derive(Default)]
+pub struct Eyedropper {
+    active: bool,
+    pointer: Option<(u32, u32)>,
+    pick_requested: bool,
+    preview: Option<srgb>,
+    on_pick: Option<PickCallback>,
+}
+
+impl Eyedropper {
+    pub fn on_pick(&mut self, f: impl FnMut(srgb) + Send + 'static) {
+        self.on_pick = Some(Box::new(f));
+    }
+
+    pub fn activate(&mut self) {
+        self.active = true;
+        self.preview = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.active = false;
+        self.pick_requested = false;
+        self.preview = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn preview(&self) -> Option<srgb> {
+        self.preview
+    }
+
+    // pointer position in surface pixels
+    pub fn hover(&mut self, point: (u32, u32)) {
+        if self.active {
+            self.pointer = Some(point);
+        }
+    }
+
+    pub fn click(&mut self, point: (u32, u32)) -> bool {
+        if !self.active {
+            return false;
+        }
+        self.pointer = Some(point);
+        self.pick_requested = true;
+        true
+    }
+
+    // the pixel the renderer should read back this frame, if any
+    pub fn sample_request(&self) -> Option<(u32, u32)> {
+        self.active.then_some(self.pointer).flatten()
+    }
+
+    pub fn deliver(&mut self, color: Option<srgb>) {
+        self.preview = color;
+        if !self.pick_requested {
+            return;
+        }
+        self.pick_requested = false;
+        if let Some(color) = color {
+            self.active = false;
+            if let Some(on_pick) = &mut self.on_pick {
+                on_pick(color);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    input::Modifiers,
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::mesh_builder::{Mesh, make_ss_rectangle},
+    theme::Theme,
+    widgets::parameter::Parameter,
+};
+
+pub struct Fader {
+    pub rect: Rectangle,
+    pub parameter: Parameter,
+    pub track_width: i32,
+    pub thumb_height: i32,
+    pub track_color: srgb,
+    pub value_color: srgb,
+    pub thumb_color: srgb,
+    pub hovered: bool,
+    // held while dragging, shift making the drag finer
+    modifiers: Modifiers,
+}
+
+impl Fader {
+    pub fn new(parameter: Parameter, height: i32, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                min_width: 32,
+                min_height: height,
+                ..Default::default()
+            },
+            parameter,
+            track_width: 6,
+            thumb_height: 16,
+            track_color: theme.border,
+            value_color: theme.primary,
+            thumb_color: theme.text,
+            hovered: false,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    // clicking the track jumps the thumb there, grabbing the thumb keeps it in
+    // place. only the height of the pointer moves a fader, so presses and drags
+    // both hand the parameter x = 0
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+        let (_, thumb_y, _, thumb_h) = self.thumb_bounds();
+        if point.1 < thumb_y || point.1 >= thumb_y + thumb_h {
+            self.parameter.set_normalized(self.normalized_at(point.1));
+        }
+        self.parameter.press((0, point.1));
+        true
+    }
+
+    pub fn drag(&mut self, point: (i32, i32), modifiers: Modifiers) {
+        self.parameter.drag((0, point.1), modifiers);
+    }
+
+    pub fn release(&mut self) {
+        self.parameter.release();
+    }
+
+    pub fn hover(&mut self, point: (i32, i32)) {
+        self.hovered = self.contains(point);
+    }
+
+    pub fn contains(&self, point: (i32, i32)) -> bool {
+        let (x, y) = self.rect.position;
+        point.0 >= x
+            && point.0 < x + self.rect.width
+            && point.1 >= y
+            && point.1 < y + self.rect.height
+    }
+
+    fn travel(&self) -> i32 {
+        (self.rect.height - self.thumb_height).max(1)
+    }
+
+    fn normalized_at(&self, y: i32) -> f32 {
+        let top = self.rect.position.1 + self.thumb_height / 2;
+        1.0 - (y - top) as f32 / self.travel() as f32
+    }
+
+    fn thumb_bounds(&self) -> (i32, i32, i32, i32) {
+        let (x, y) = self.rect.position;
+        let offset = ((1.0 - self.parameter.normalized()) * self.travel() as f32) as i32;
+        (x, y + offset, self.rect.width, self.thumb_height)
+    }
+}
+
+impl Primative for Fader {
+    delegate_primative!(rect);
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let (hovered, value) = (self.hovered, self.parameter.normalized());
+        let handled = match (cx.phase(), cx.event) {
+            (Phase::Exit, Event::PointerMoved { .. } | Event::PointerLeft) => {
+                self.hovered = false;
+                false
+            }
+            (Phase::Target | Phase::Bubble, Event::PointerMoved { position }) => {
+                self.hover(*position);
+                self.drag(*position, self.modifiers);
+                self.parameter.is_dragging()
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerPressed {
+                    button: PointerButton::Primary,
+                    position,
+                    modifiers,
+                },
+            ) => {
+                self.modifiers = *modifiers;
+                self.press(*position)
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerReleased {
+                    button: PointerButton::Primary,
+                    ..
+                },
+            ) => {
+                let dragging = self.parameter.is_dragging();
+                self.release();
+                dragging
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::KeyPressed { modifiers, .. } | Event::KeyReleased { modifiers, .. },
+            ) => {
+                self.modifiers = *modifiers;
+                false
+            }
+            _ => false,
+        };
+        if (self.hovered, self.parameter.normalized()) != (hovered, value) {
+            self.rect.mark_dirty();
+        }
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    // the value, while hovering
+    fn tooltip(&self, point: (i32, i32)) -> Option<String> {
+        self.contains(point).then(|| self.parameter.display_value())
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let (x, y) = self.rect.position;
+        let track_x = x + (self.rect.width - self.track_width) / 2;
+        let (thumb_x, thumb_y, thumb_w, thumb_h) = self.thumb_bounds();
+        let track_top = y + self.thumb_height / 2;
+        let track_height = self.travel();
+        let value_top = thumb_y + thumb_h / 2;
+
+        let mut mesh = make_ss_rectangle(
+            track_x,
+            track_top,
+            self.track_width,
+            track_height,
+            self.track_color,
+            size,
+        );
+        mesh.append(make_ss_rectangle(
+            track_x,
+            value_top,
+            self.track_width,
+            track_top + track_height - value_top,
+            self.value_color,
+            size,
+        ));
+        mesh.append(make_ss_rectangle(
+            thumb_x,
+            thumb_y,
+            thumb_w,
+            thumb_h,
+            self.thumb_color,
+            size,
+        ));
+        mesh
+    }
+}
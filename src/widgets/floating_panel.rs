@@ -0,0 +1,351 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    animation::Spring,
+    builder::IntoNode,
+    context,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    input::VelocityTracker,
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{draw_list::DrawList, mesh_builder::Mesh},
+    state_machine::{Interaction, InteractionEvent, StateMachine},
+};
+
+// how far ahead a release is projected along its velocity when picking the
+// corner to settle in
+const FLING_PROJECTION: f32 = 0.25;
+// pointer travel before a press becomes a drag instead of a click
+const DRAG_THRESHOLD: i32 = 8;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+}
+
+struct PanelDrag {
+    origin: (i32, i32),
+    start: (f32, f32),
+    velocity: VelocityTracker,
+}
+
+// a small panel floating above `content`, like a picture-in-picture video
+// or a tool palette. it can be dragged anywhere and springs into the nearest
+// corner on release. minimizing shrinks it to `minimized_size` and hides its
+// children; clicking a minimized panel expands it again
+pub struct FloatingPanel {
+    // holds the page the panel floats over
+    pub rect: Rectangle,
+    pub panel: Rectangle,
+    pub corner: Corner,
+    // gap between the panel and the edges it snaps to
+    pub margin: i32,
+    pub minimized: bool,
+    pub minimized_size: (i32, i32),
+    // offset of the panel from the page origin
+    x: Spring,
+    y: Spring,
+    placed: bool,
+    interaction: StateMachine<Interaction, InteractionEvent>,
+    drag: Option<PanelDrag>,
+    last_step: Option<Instant>,
+}
+
+impl FloatingPanel {
+    pub fn new(content: impl IntoNode, panel: Rectangle) -> Self {
+        Self {
+            rect: Rectangle {
+                sizing: Sizing::GROW,
+                children: vec![content.into_node()],
+                ..Default::default()
+            },
+            panel,
+            corner: Corner::default(),
+            margin: 32,
+            minimized: false,
+            minimized_size: (96, 48),
+            x: Spring::new(0.0),
+            y: Spring::new(0.0),
+            placed: false,
+            interaction: Interaction::machine(),
+            drag: None,
+            last_step: None,
+        }
+    }
+
+    pub fn minimize(&mut self) {
+        self.minimized = true;
+        self.rect.mark_dirty();
+    }
+
+    pub fn expand(&mut self) {
+        self.minimized = false;
+        self.rect.mark_dirty();
+    }
+
+    pub fn toggle(&mut self) {
+        self.minimized = !self.minimized;
+        self.rect.mark_dirty();
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.placed && self.drag.is_none() && self.x.is_settled() && self.y.is_settled()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.interaction.is(&Interaction::Dragging)
+    }
+
+    pub fn panel_contains(&self, point: (i32, i32)) -> bool {
+        let (x, y) = self.panel.position;
+        point.0 >= x
+            && point.0 < x + self.panel.width
+            && point.1 >= y
+            && point.1 < y + self.panel.height
+    }
+
+    // returns true when the press landed on the panel and should be captured
+    // until release
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        if !self.panel_contains(point) {
+            return false;
+        }
+        self.interaction.handle(&InteractionEvent::PointerEnter);
+        self.interaction.handle(&InteractionEvent::Press);
+        let start = (self.x.value(), self.y.value());
+        let mut velocity = VelocityTracker::new();
+        velocity.add(Instant::now(), start);
+        self.drag = Some(PanelDrag {
+            origin: point,
+            start,
+            velocity,
+        });
+        true
+    }
+
+    pub fn drag(&mut self, point: (i32, i32)) {
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+        let delta = (point.0 - drag.origin.0, point.1 - drag.origin.1);
+        if self.interaction.is(&Interaction::Pressed)
+            && delta.0.abs() < DRAG_THRESHOLD
+            && delta.1.abs() < DRAG_THRESHOLD
+        {
+            return;
+        }
+        self.interaction.handle(&InteractionEvent::DragStart);
+
+        let offset = (drag.start.0 + delta.0 as f32, drag.start.1 + delta.1 as f32);
+        self.x.snap(offset.0);
+        self.y.snap(offset.1);
+
+        drag.velocity.add(Instant::now(), offset);
+    }
+
+    // a drag settles in the corner it was thrown towards, a click on a
+    // minimized panel expands it
+    pub fn release(&mut self, point: (i32, i32)) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        let clicked = self.interaction.is(&Interaction::Pressed);
+        let inside = self.panel_contains(point);
+        self.interaction
+            .handle(&InteractionEvent::Release { inside });
+        if !inside {
+            self.interaction.handle(&InteractionEvent::PointerLeave);
+        }
+        if clicked {
+            if self.minimized && inside {
+                self.expand();
+            }
+            return;
+        }
+
+        let velocity = drag.velocity.velocity(Instant::now());
+        let projected = (
+            self.x.value() + velocity.0 * FLING_PROJECTION,
+            self.y.value() + velocity.1 * FLING_PROJECTION,
+        );
+        self.corner = Corner::ALL
+            .into_iter()
+            .min_by(|a, b| {
+                let distance = |corner: &Corner| {
+                    let (x, y) = self.corner_offset(*corner);
+                    (x as f32 - projected.0).powi(2) + (y as f32 - projected.1).powi(2)
+                };
+                distance(a).total_cmp(&distance(b))
+            })
+            .unwrap_or_default();
+        let (x, y) = self.corner_offset(self.corner);
+        self.x.fling(x as f32, velocity.0);
+        self.y.fling(y as f32, velocity.1);
+    }
+
+    // where the panel rests in `corner`, relative to the page origin
+    fn corner_offset(&self, corner: Corner) -> (i32, i32) {
+        let left = self.margin;
+        let top = self.margin;
+        let right = (self.rect.width - self.panel.width - self.margin).max(left);
+        let bottom = (self.rect.height - self.panel.height - self.margin).max(top);
+        match corner {
+            Corner::TopLeft => (left, top),
+            Corner::TopRight => (right, top),
+            Corner::BottomLeft => (left, bottom),
+            Corner::BottomRight => (right, bottom),
+        }
+    }
+
+    fn step(&mut self) {
+        let now = context::frame_now();
+        let dt = self.last_step.map_or(Duration::ZERO, |last| now - last);
+        self.last_step = Some(now);
+        if self.drag.is_some() {
+            return;
+        }
+
+        // follow the corner as the page or the panel resizes
+        let (x, y) = self.corner_offset(self.corner);
+        if self.placed {
+            self.x.set_target(x as f32);
+            self.y.set_target(y as f32);
+            self.x.step(dt);
+            self.y.step(dt);
+            // layout stops running once settled, so the next animation starts
+            // fresh
+            if self.is_settled() {
+                self.last_step = None;
+            }
+        } else {
+            self.x.snap(x as f32);
+            self.y.snap(y as f32);
+            self.placed = true;
+        }
+    }
+}
+
+impl Primative for FloatingPanel {
+    delegate_primative!(rect, sizing);
+
+    // laid out every frame while it moves
+    fn is_dirty(&self) -> bool {
+        !self.is_settled() || self.rect.is_dirty() || self.panel.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+        self.panel.clear_dirty();
+    }
+
+    // the panel sits above the page, so it takes the pointer on the way down
+    // before anything on the page under it can
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if cx.phase() == Phase::Capture => self.press(*position),
+            Event::PointerMoved { position }
+                if cx.phase() == Phase::Capture && self.drag.is_some() =>
+            {
+                self.drag(*position);
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if cx.phase() == Phase::Capture && self.drag.is_some() => {
+                self.release(*position);
+                true
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    // the panel goes last so it's always above the page
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record(list, size);
+        list.push_clip(self.rect.position, (self.rect.width, self.rect.height));
+        if self.minimized {
+            list.mesh(self.panel.get_mesh(size));
+        } else {
+            self.panel.record(list, size);
+        }
+        list.pop_clip();
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for FloatingPanel {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+        self.panel.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+        // the panel has nothing to grow into, so it keeps its fitted size
+        if self.minimized {
+            self.panel.width = self.minimized_size.0;
+            self.panel.height = self.minimized_size.1;
+        }
+        self.panel.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.step();
+        self.rect.set_child_positions();
+        let (x, y) = self.rect.position;
+        self.panel.set_position((
+            x + self.x.value().round() as i32,
+            y + self.y.value().round() as i32,
+        ));
+        self.panel.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
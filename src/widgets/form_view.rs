@@ -0,0 +1,292 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    builder::Node,
+    dispatch::{EventContext, Phase},
+    event::Event,
+    form::Form,
+    input::Key,
+    layout::{
+        Axis, Container, LayoutMode, Primative, Rectangle, Sizing, SizingMode, Text,
+        delegate_primative,
+    },
+    renderer::{draw_list::DrawList, mesh_builder::Mesh, text::Font},
+    theme::Theme,
+    widgets::text_input::TextInput,
+};
+
+struct FieldRow {
+    name: String,
+    input: Arc<Mutex<TextInput>>,
+    error: Arc<Mutex<Text>>,
+}
+
+// a Form's fields as text inputs, each over its error message. what's typed
+// goes into the form on the next layout, enter in a field submits, and a
+// submit that fails focuses the first field that's still invalid
+pub struct FormView {
+    pub rect: Rectangle,
+    pub form: Form,
+    pub error_color: srgb,
+    font: Arc<Font>,
+    font_size: f32,
+    rows: Vec<FieldRow>,
+}
+
+impl FormView {
+    pub fn new(form: Form, font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                layout_mode: LayoutMode::TopToBottom,
+                child_gap: 8,
+                ..Default::default()
+            },
+            form,
+            error_color: theme.danger,
+            font,
+            font_size,
+            rows: Vec::new(),
+        }
+    }
+
+    // the input for the form's field `name`, with room for its error below
+    pub fn input(mut self, name: &str, input: TextInput) -> Self {
+        if self.form.get(name).is_none() {
+            log::warn!("form has no field named {name}");
+        }
+        let input = Arc::new(Mutex::new(input));
+        let error = Arc::new(Mutex::new(Text::new(
+            "",
+            self.font.clone(),
+            self.font_size,
+            self.error_color,
+        )));
+        self.rect.children.push(Arc::new(Mutex::new(Rectangle {
+            layout_mode: LayoutMode::TopToBottom,
+            child_gap: 2,
+            children: vec![input.clone() as Node, error.clone() as Node],
+            ..Default::default()
+        })));
+        self.rows.push(FieldRow {
+            name: name.to_string(),
+            input,
+            error,
+        });
+        self.rect.mark_dirty();
+        self
+    }
+
+    pub fn input_for(&self, name: &str) -> Option<&Arc<Mutex<TextInput>>> {
+        self.rows
+            .iter()
+            .find(|row| row.name == name)
+            .map(|row| &row.input)
+    }
+
+    pub fn submit(&mut self) -> Result<HashMap<String, String>, Vec<(String, String)>> {
+        self.sync();
+        let result = self.form.submit();
+        if result.is_err()
+            && let Some(name) = self.form.first_invalid().map(str::to_string)
+        {
+            self.focus(&name);
+        }
+        self.show_errors();
+        result
+    }
+
+    // focuses the input for `name`, taking focus from the others
+    pub fn focus(&mut self, name: &str) {
+        for row in &self.rows {
+            let Ok(mut input) = row.input.lock() else {
+                continue;
+            };
+            if row.name == name {
+                input.focus();
+                input.select_all();
+            } else if input.is_focused() {
+                input.blur();
+            }
+        }
+        self.rect.mark_dirty();
+    }
+
+    // copies what was typed into the form, which validates it as it changes
+    fn sync(&mut self) {
+        for row in &self.rows {
+            let Ok(input) = row.input.lock() else {
+                continue;
+            };
+            if self.form.value(&row.name) != Some(input.text.as_str()) {
+                self.form.set_value(&row.name, &input.text);
+            }
+        }
+    }
+
+    // a field's error only shows once it's been typed in or submitted
+    fn show_errors(&self) {
+        for row in &self.rows {
+            let message = self
+                .form
+                .get(&row.name)
+                .filter(|field| field.touched)
+                .and_then(|field| field.error.as_deref())
+                .unwrap_or("");
+            if let Ok(mut error) = row.error.lock() {
+                error.set_text(message);
+            }
+        }
+    }
+
+    fn focused_input(&self) -> Option<Arc<Mutex<TextInput>>> {
+        self.rows
+            .iter()
+            .find(|row| row.input.lock().is_ok_and(|input| input.is_focused()))
+            .map(|row| row.input.clone())
+    }
+}
+
+impl Primative for FormView {
+    delegate_primative!(rect);
+
+    // keys go to the focused field wherever the pointer last pressed, so a
+    // field focused by a failed submit can be typed in straight away
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || !matches!(cx.phase(), Phase::Capture | Phase::Target) {
+            return;
+        }
+        if !matches!(cx.event, Event::Text(_) | Event::KeyPressed { .. }) {
+            return;
+        }
+        let Some(input) = self.focused_input() else {
+            return;
+        };
+        let mut handled = input
+            .lock()
+            .is_ok_and(|mut input| input.handle_event(cx.event));
+        if let Event::KeyPressed {
+            key: Key::Enter, ..
+        } = cx.event
+        {
+            let _ = self.submit();
+            handled = true;
+        }
+        if handled {
+            self.rect.mark_dirty();
+            cx.stop_propagation();
+        }
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record(list, size);
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+}
+
+impl Container for FormView {
+    fn fit_sizing(&mut self) {
+        self.sync();
+        self.show_errors();
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::FormView;
+    use crate::{
+        event::{Event, PointerButton},
+        form::{Form, FormField, validators},
+        input::{Key, Modifiers},
+        layout::{Rectangle, Sizing, UI},
+        renderer::text::Font,
+        theme::Theme,
+        widgets::text_input::TextInput,
+    };
+
+    #[test]
+    fn a_failed_submit_shows_errors_and_focuses_the_first_invalid_field() {
+        let font = Font::for_tests();
+        let theme = Theme::default();
+        let form = Form::new()
+            .field(FormField::new("name").validator(validators::required("required")))
+            .field(FormField::new("email").validator(validators::email()));
+        let field = || {
+            let mut input = TextInput::new(font.clone(), 14.0, &theme);
+            input.rect.min_width = 200;
+            input
+        };
+        let view = Arc::new(Mutex::new(
+            FormView::new(form, font.clone(), 14.0, &theme)
+                .input("name", field())
+                .input("email", field()),
+        ));
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![view.clone()],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+
+        let email = view.lock().unwrap().input_for("email").unwrap().clone();
+        let (x, y) = email.lock().unwrap().rect.position;
+        ui.dispatch_event(&Event::PointerPressed {
+            button: PointerButton::Primary,
+            position: (x + 4, y + 4),
+            modifiers: Modifiers::default(),
+        });
+        for c in "a@b.c".chars() {
+            ui.dispatch_event(&Event::Text(c));
+        }
+        ui.dispatch_event(&Event::KeyPressed {
+            key: Key::Enter,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        });
+
+        let view = view.lock().unwrap();
+        assert_eq!(view.form.value("email"), Some("a@b.c"));
+        assert_eq!(view.form.error("name"), Some("required"));
+        assert_eq!(view.rows[0].error.lock().unwrap().text, "required");
+        assert_eq!(view.rows[1].error.lock().unwrap().text, "");
+        assert!(view.rows[0].input.lock().unwrap().is_focused());
+        assert!(!email.lock().unwrap().is_focused());
+    }
+}
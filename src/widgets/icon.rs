@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use tinycolors::srgb;
+
+use crate::{
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::{draw_list::DrawList, icons::IconSet, mesh_builder::Mesh},
+    theme::Theme,
+};
+
+// one icon from an `IconSet`, `size` layout pixels tall and as wide as its
+// aspect needs. names the set doesn't have draw nothing
+pub struct Icon {
+    pub rect: Rectangle,
+    pub set: Arc<IconSet>,
+    pub name: String,
+    pub size: i32,
+    pub tint: srgb,
+    pub opacity: f32,
+}
+
+impl Icon {
+    pub fn new(set: Arc<IconSet>, name: &str, size: i32, theme: &Theme) -> Self {
+        let mut icon = Self {
+            rect: Rectangle::default(),
+            set,
+            name: String::new(),
+            size,
+            tint: theme.text,
+            opacity: 1.0,
+        };
+        icon.set_icon(name);
+        icon
+    }
+
+    pub fn tint(mut self, tint: srgb) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn set_icon(&mut self, name: &str) {
+        if self.name != name && !self.set.contains(name) {
+            log::warn!("no icon named {name:?} in the set");
+        }
+        self.name = name.to_string();
+        self.resize();
+    }
+
+    pub fn set_size(&mut self, size: i32) {
+        self.size = size;
+        self.resize();
+    }
+
+    fn width(&self) -> i32 {
+        let aspect = self.set.get(&self.name).map_or(1.0, |entry| entry.aspect);
+        (self.size as f32 * aspect).round() as i32
+    }
+
+    fn resize(&mut self) {
+        let width = self.width();
+        self.rect.set_min_width(width);
+        self.rect.set_min_height(self.size);
+    }
+}
+
+impl Primative for Icon {
+    delegate_primative!(rect);
+
+    // kept at its own size and centered when the layout gives it more room
+    fn record(&self, list: &mut DrawList, _size: (i32, i32)) {
+        let Some(entry) = self.set.get(&self.name) else {
+            return;
+        };
+        let (width, height) = (self.width(), self.size);
+        let (x, y) = self.rect.position;
+        let position = (
+            (x + (self.rect.width - width) / 2) as f32,
+            (y + (self.rect.height - height) / 2) as f32,
+        );
+        list.image_region(
+            self.set.atlas(),
+            entry.uv,
+            position,
+            (width as f32, height as f32),
+            self.tint,
+            self.opacity,
+        );
+    }
+
+    // drawn from the icon atlas, not as colored geometry
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
@@ -0,0 +1,227 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MaskSlot {
+    Digit,
+    Letter,
+    Alphanumeric,
+    Literal(char),
+}
+
+impl MaskSlot {
+    fn accepts(&self, c: char) -> bool {
+        match self {
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Letter => c.is_alphabetic(),
+            MaskSlot::Alphanumeric => c.is_alphanumeric(),
+            MaskSlot::Literal(_) => false,
+        }
+    }
+}
+
+// `9` matches a digit, `a` a letter and `*` either. everything else is a literal
+// that gets inserted automatically as the user types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMask {
+    pattern: String,
+    slots: Vec<MaskSlot>,
+    pub placeholder: char,
+}
+
+impl InputMask {
+    pub const PHONE: &'static str = "(999) 999-9999";
+    pub const DATE: &'static str = "99/99/9999";
+    pub const TIME: &'static str = "99:99";
+
+    pub fn new(pattern: &str) -> Self {
+        let slots = pattern
+            .chars()
+            .map(|c| match c {
+                '9' => MaskSlot::Digit,
+                'a' => MaskSlot::Letter,
+                '*' => MaskSlot::Alphanumeric,
+                c => MaskSlot::Literal(c),
+            })
+            .collect();
+        Self {
+            pattern: pattern.to_string(),
+            slots,
+            placeholder: '_',
+        }
+    }
+
+    pub fn phone() -> Self {
+        Self::new(Self::PHONE)
+    }
+
+    pub fn date() -> Self {
+        Self::new(Self::DATE)
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|s| !matches!(s, MaskSlot::Literal(_)))
+            .count()
+    }
+
+    // strips literals and anything the mask wouldn't accept from `text`
+    pub fn raw_value(&self, text: &str) -> String {
+        let mut raw = String::new();
+        let mut chars = text.chars().peekable();
+        for slot in &self.slots {
+            match slot {
+                MaskSlot::Literal(l) => {
+                    if chars.peek() == Some(l) {
+                        chars.next();
+                    }
+                }
+                slot => {
+                    let Some(c) = chars.by_ref().find(|c| slot.accepts(*c)) else {
+                        break;
+                    };
+                    raw.push(c);
+                }
+            }
+        }
+        raw
+    }
+
+    // formats raw input into the mask, stopping at the first unfilled slot
+    pub fn apply(&self, raw: &str) -> String {
+        let mut out = String::new();
+        let mut chars = raw.chars().filter(|c| c.is_alphanumeric()).peekable();
+        for slot in &self.slots {
+            match slot {
+                MaskSlot::Literal(l) => {
+                    if chars.peek().is_none() {
+                        break;
+                    }
+                    out.push(*l);
+                }
+                slot => {
+                    let Some(c) = chars.by_ref().find(|c| slot.accepts(*c)) else {
+                        break;
+                    };
+                    out.push(c);
+                }
+            }
+        }
+        out
+    }
+
+    // the masked text padded out with placeholders, for display
+    pub fn display(&self, raw: &str) -> String {
+        let filled = self.apply(raw);
+        let mut out = filled.clone();
+        for slot in self.slots.iter().skip(filled.chars().count()) {
+            match slot {
+                MaskSlot::Literal(l) => out.push(*l),
+                _ => out.push(self.placeholder),
+            }
+        }
+        out
+    }
+
+    pub fn accepts(&self, raw: &str, c: char) -> bool {
+        let filled = raw.chars().count();
+        self.slots
+            .iter()
+            .filter(|s| !matches!(s, MaskSlot::Literal(_)))
+            .nth(filled)
+            .is_some_and(|slot| slot.accepts(c))
+    }
+
+    pub fn is_complete(&self, raw: &str) -> bool {
+        self.raw_value(&self.apply(raw)).chars().count() == self.capacity()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordMode {
+    pub obscure_char: char,
+    pub revealed: bool,
+}
+
+impl Default for PasswordMode {
+    fn default() -> Self {
+        Self {
+            obscure_char: '•',
+            revealed: false,
+        }
+    }
+}
+
+impl PasswordMode {
+    pub fn toggle_reveal(&mut self) {
+        self.revealed = !self.revealed;
+    }
+
+    pub fn display(&self, text: &str) -> String {
+        if self.revealed {
+            text.to_string()
+        } else {
+            text.chars().map(|_| self.obscure_char).collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputMode {
+    #[default]
+    Plain,
+    Masked(InputMask),
+    Password(PasswordMode),
+}
+
+impl InputMode {
+    pub fn display_text(&self, value: &str) -> String {
+        match self {
+            InputMode::Plain => value.to_string(),
+            InputMode::Masked(mask) => mask.display(value),
+            InputMode::Password(password) => password.display(value),
+        }
+    }
+
+    // returns the new value if inserting `c` at the end of `value` is allowed
+    pub fn insert(&self, value: &str, c: char) -> Option<String> {
+        match self {
+            InputMode::Masked(mask) => {
+                let raw = mask.raw_value(value);
+                mask.accepts(&raw, c).then(|| format!("{raw}{c}"))
+            }
+            _ => (!c.is_control()).then(|| format!("{value}{c}")),
+        }
+    }
+
+    // where the char at byte `index` of `value` lands in `display_text`,
+    // counted in chars
+    pub fn display_offset(&self, value: &str, index: usize) -> usize {
+        match self {
+            InputMode::Masked(mask) => mask.apply(&value[..index]).chars().count(),
+            _ => value[..index].chars().count(),
+        }
+    }
+
+    // what `value` holds once `text` is typed or pasted into it whole. masked
+    // values keep only what the mask accepts
+    pub fn normalize(&self, value: &str) -> String {
+        match self {
+            InputMode::Masked(mask) => mask.raw_value(&mask.apply(value)),
+            _ => value.to_string(),
+        }
+    }
+
+    // password fields never hand their contents to the clipboard
+    pub fn can_copy(&self) -> bool {
+        !matches!(self, InputMode::Password(_))
+    }
+
+    pub fn toggle_reveal(&mut self) {
+        if let InputMode::Password(password) = self {
+            password.toggle_reveal();
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::f32::consts::PI;
+
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    input::Modifiers,
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::mesh_builder::{Mesh, make_ss_circle, make_ss_polygon},
+    theme::Theme,
+    widgets::parameter::Parameter,
+};
+
+// the knob sweeps 270 degrees, starting bottom left
+const START_ANGLE: f32 = 0.75 * PI;
+const SWEEP: f32 = 1.5 * PI;
+
+pub struct Knob {
+    pub rect: Rectangle,
+    pub parameter: Parameter,
+    pub body_color: srgb,
+    pub track_color: srgb,
+    pub value_color: srgb,
+    pub indicator_color: srgb,
+    pub hovered: bool,
+    // hide and lock the cursor while dragging so the drag isn't cut short by
+    // the screen edge
+    pub capture_cursor: bool,
+    // held while dragging, shift making the drag finer
+    modifiers: Modifiers,
+}
+
+impl Knob {
+    pub fn new(parameter: Parameter, diameter: i32, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                min_width: diameter,
+                min_height: diameter,
+                ..Default::default()
+            },
+            parameter,
+            body_color: theme.surface,
+            track_color: theme.border,
+            value_color: theme.primary,
+            indicator_color: theme.text,
+            hovered: false,
+            capture_cursor: true,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    // a double click resets the value instead of starting a drag
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+        self.parameter.press(point);
+        true
+    }
+
+    pub fn drag(&mut self, point: (i32, i32), modifiers: Modifiers) {
+        self.parameter.drag(point, modifiers);
+    }
+
+    pub fn release(&mut self) {
+        self.parameter.release();
+    }
+
+    pub fn hover(&mut self, point: (i32, i32)) {
+        self.hovered = self.contains(point);
+    }
+
+    pub fn contains(&self, point: (i32, i32)) -> bool {
+        let (cx, cy, r) = self.geometry();
+        let (dx, dy) = (point.0 as f32 - cx, point.1 as f32 - cy);
+        dx * dx + dy * dy <= r * r
+    }
+
+    fn geometry(&self) -> (f32, f32, f32) {
+        let (x, y) = self.rect.position;
+        let r = self.rect.width.min(self.rect.height) as f32 / 2.0;
+        (
+            x as f32 + self.rect.width as f32 / 2.0,
+            y as f32 + self.rect.height as f32 / 2.0,
+            r,
+        )
+    }
+}
+
+fn arc(
+    center: (f32, f32),
+    inner: f32,
+    outer: f32,
+    from: f32,
+    to: f32,
+    color: srgb,
+    size: (i32, i32),
+) -> Mesh {
+    let mut mesh = Mesh::default();
+    let segments = (((to - from).abs() / SWEEP) * 48.0).ceil().max(1.0) as usize;
+    for i in 0..segments {
+        let a0 = from + (to - from) * i as f32 / segments as f32;
+        let a1 = from + (to - from) * (i + 1) as f32 / segments as f32;
+        let point = |r: f32, a: f32| (center.0 + r * a.cos(), center.1 + r * a.sin());
+        let quad = [
+            point(outer, a0),
+            point(outer, a1),
+            point(inner, a1),
+            point(inner, a0),
+        ];
+        let mid = point((inner + outer) / 2.0, (a0 + a1) / 2.0);
+        mesh.append(make_ss_polygon(mid, &quad, color, size));
+    }
+    mesh
+}
+
+impl Primative for Knob {
+    delegate_primative!(rect);
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let (hovered, value) = (self.hovered, self.parameter.normalized());
+        let handled = match (cx.phase(), cx.event) {
+            (Phase::Exit, Event::PointerMoved { .. } | Event::PointerLeft) => {
+                self.hovered = false;
+                false
+            }
+            (Phase::Target | Phase::Bubble, Event::PointerMoved { position }) => {
+                self.hover(*position);
+                self.drag(*position, self.modifiers);
+                self.parameter.is_dragging()
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerPressed {
+                    button: PointerButton::Primary,
+                    position,
+                    modifiers,
+                },
+            ) => {
+                self.modifiers = *modifiers;
+                let pressed = self.press(*position);
+                if self.capture_cursor && self.parameter.is_dragging() {
+                    cx.capture_cursor(true);
+                }
+                pressed
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerReleased {
+                    button: PointerButton::Primary,
+                    ..
+                },
+            ) => {
+                let dragging = self.parameter.is_dragging();
+                if self.capture_cursor && dragging {
+                    cx.release_cursor();
+                }
+                self.release();
+                dragging
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::KeyPressed { modifiers, .. } | Event::KeyReleased { modifiers, .. },
+            ) => {
+                self.modifiers = *modifiers;
+                false
+            }
+            _ => false,
+        };
+        if (self.hovered, self.parameter.normalized()) != (hovered, value) {
+            self.rect.mark_dirty();
+        }
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    // the value, while hovering
+    fn tooltip(&self, point: (i32, i32)) -> Option<String> {
+        self.contains(point).then(|| self.parameter.display_value())
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let (cx, cy, r) = self.geometry();
+        let center = (cx, cy);
+        let ring = (r * 0.12).max(2.0);
+        let angle = START_ANGLE + SWEEP * self.parameter.normalized();
+
+        let mut mesh = arc(
+            center,
+            r - ring,
+            r,
+            START_ANGLE,
+            START_ANGLE + SWEEP,
+            self.track_color,
+            size,
+        );
+        mesh.append(arc(
+            center,
+            r - ring,
+            r,
+            START_ANGLE,
+            angle,
+            self.value_color,
+            size,
+        ));
+        mesh.append(make_ss_circle(
+            center,
+            r - ring * 1.5,
+            self.body_color,
+            size,
+        ));
+
+        let indicator = (
+            cx + (r - ring * 3.0) * angle.cos(),
+            cy + (r - ring * 3.0) * angle.sin(),
+        );
+        mesh.append(make_ss_circle(indicator, ring, self.indicator_color, size));
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        builder::Node,
+        event::{Event, PointerButton},
+        input::Modifiers,
+        layout::{Rectangle, Sizing, UI},
+        theme::Theme,
+        widgets::parameter::Parameter,
+    };
+
+    use super::Knob;
+
+    #[test]
+    fn dragging_a_knob_up_turns_it_and_captures_the_cursor() {
+        let knob = Arc::new(Mutex::new(Knob::new(
+            Parameter::new(0.0, 1.0, 0.5),
+            64,
+            &Theme::default(),
+        )));
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![knob.clone() as Node],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+
+        assert!(ui.dispatch_event(&Event::PointerPressed {
+            button: PointerButton::Primary,
+            position: (32, 32),
+            modifiers: Modifiers::default(),
+        }));
+        assert!(ui.context.is_cursor_captured());
+        ui.dispatch_event(&Event::PointerMoved { position: (32, -8) });
+        ui.dispatch_event(&Event::PointerReleased {
+            button: PointerButton::Primary,
+            position: (32, -8),
+            modifiers: Modifiers::default(),
+        });
+        assert!(!ui.context.is_cursor_captured());
+        assert!((knob.lock().unwrap().parameter.normalized() - 0.7).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,658 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    animation::Easing,
+    binding::{
+        Bound, BoundList, CollectionChange, ItemTransition, ListBinding, ObservableVec,
+        TransitionKind,
+    },
+    builder::Node,
+    context,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    input::Key,
+    layout::{
+        Axis, Container, LayoutMode, Primative, Rectangle, Sizing, SizingMode, delegate_primative,
+    },
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+    },
+    selection::{Marquee, SELECTION_ALPHA, SelectionMode, SelectionModel},
+    theme::Theme,
+};
+
+struct DragState {
+    from: usize,
+    grab_offset: (i32, i32),
+    pointer: (i32, i32),
+    drop_index: usize,
+}
+
+type ReorderCallback = Box<dyn FnMut(usize, usize) + Send>;
+
+pub struct ListView {
+    pub rect: Rectangle,
+    pub reorderable: bool,
+    // width of the drag handle at the leading edge of each row, the whole row when None
+    pub handle_width: Option<i32>,
+    pub shadow_color: srgb,
+    pub shadow_offset: i32,
+    // which rows are selected. in multi mode a drag that doesn't move a row
+    // draws a marquee
+    pub selection: SelectionModel,
+    pub selection_color: srgb,
+    // how quickly the drop gap opens and closes, higher is snappier
+    pub gap_speed: f32,
+    drag: Option<DragState>,
+    marquee: Option<Marquee>,
+    gaps: Vec<f32>,
+    last_step: Option<Instant>,
+    on_reorder: Option<ReorderCallback>,
+    binding: Option<Box<dyn BoundList>>,
+    // where each row with a running move transition started from
+    origins: Vec<(Node, (i32, i32))>,
+}
+
+impl ListView {
+    pub fn new(theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                layout_mode: LayoutMode::TopToBottom,
+                fill: Fill::Solid(theme.surface),
+                child_gap: 2,
+                ..Default::default()
+            },
+            reorderable: true,
+            handle_width: None,
+            shadow_color: theme.pressed,
+            shadow_offset: 6,
+            selection: SelectionModel::new(SelectionMode::Single),
+            selection_color: theme.primary,
+            gap_speed: 18.0,
+            drag: None,
+            marquee: None,
+            gaps: Vec::new(),
+            last_step: None,
+            on_reorder: None,
+            binding: None,
+            origins: Vec::new(),
+        }
+    }
+
+    // builds the rows from `source` and keeps them in step with it. changes
+    // are picked up on the next layout, inserted rows slide in, removed ones
+    // slide out and moved ones glide to their new place. a drag reorder moves
+    // the item in `source` too
+    pub fn bind<T: Send + 'static>(
+        mut self,
+        source: Arc<Mutex<ObservableVec<T>>>,
+        binding: ListBinding<T>,
+    ) -> Self {
+        self.binding = Some(Box::new(Bound::new(source, binding)));
+        self.rect.mark_dirty();
+        self
+    }
+
+    pub fn on_reorder(mut self, f: impl FnMut(usize, usize) + Send + 'static) -> Self {
+        self.on_reorder = Some(Box::new(f));
+        self
+    }
+
+    pub fn push(&mut self, item: Arc<Mutex<dyn Primative>>) {
+        self.rect.children.push(item);
+        self.rect.mark_dirty();
+    }
+
+    pub fn items(&self) -> &[Arc<Mutex<dyn Primative>>] {
+        &self.rect.children
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    pub fn row_at(&self, point: (i32, i32)) -> Option<usize> {
+        self.rect.children.iter().position(|child| {
+            child.lock().is_ok_and(|prim| {
+                let (x, y) = prim.get_position();
+                point.0 >= x
+                    && point.0 < x + prim.get_width()
+                    && point.1 >= y
+                    && point.1 < y + prim.get_height()
+            })
+        })
+    }
+
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        if !self.reorderable {
+            return false;
+        }
+        let Some(index) = self.row_at(point) else {
+            return false;
+        };
+        let Ok(prim) = self.rect.children[index].lock() else {
+            return false;
+        };
+        let position = prim.get_position();
+        if self
+            .handle_width
+            .is_some_and(|handle| point.0 >= position.0 + handle)
+        {
+            return false;
+        }
+        let grab_offset = (point.0 - position.0, point.1 - position.1);
+        drop(prim);
+
+        self.drag = Some(DragState {
+            from: index,
+            grab_offset,
+            pointer: point,
+            drop_index: index,
+        });
+        true
+    }
+
+    pub fn drag(&mut self, point: (i32, i32)) {
+        let Some(drop_index) = self.drop_index_at(point.1) else {
+            return;
+        };
+        if let Some(drag) = &mut self.drag {
+            drag.pointer = point;
+            drag.drop_index = drop_index;
+        }
+    }
+
+    pub fn release(&mut self) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        // the item left a hole at `from`, so the gap index already accounts for it
+        let to = drag.drop_index.min(self.rect.children.len() - 1);
+        if to != drag.from {
+            match &mut self.binding {
+                Some(binding) => binding.move_item(drag.from, to),
+                None => {
+                    let item = self.rect.children.remove(drag.from);
+                    self.rect.children.insert(to, item);
+                    self.selection.apply_change(CollectionChange::Move {
+                        from: drag.from,
+                        to,
+                    });
+                }
+            }
+            if let Some(on_reorder) = &mut self.on_reorder {
+                on_reorder(drag.from, to);
+            }
+        }
+        self.gaps.clear();
+        self.rect.mark_dirty();
+    }
+
+    pub fn cancel_drag(&mut self) {
+        self.drag = None;
+        self.gaps.clear();
+        self.rect.mark_dirty();
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.drag.is_some()
+            || self.gaps.iter().any(|g| *g > 0.001)
+            || self
+                .binding
+                .as_ref()
+                .is_some_and(|binding| !binding.transitions().is_empty())
+    }
+
+    // eases the drop gap toward the current drop index
+    pub fn tick(&mut self, dt: f32) {
+        let slots = self.rect.children.len();
+        self.gaps.resize(slots, 0.0);
+        let t = 1.0 - (-self.gap_speed * dt).exp();
+        for (i, gap) in self.gaps.iter_mut().enumerate() {
+            let target = match &self.drag {
+                Some(drag) if drag.drop_index == i => 1.0,
+                _ => 0.0,
+            };
+            *gap += (target - *gap) * t;
+        }
+        if let Some(binding) = &mut self.binding {
+            binding.tick(dt);
+        }
+    }
+
+    fn step(&mut self) {
+        let now = context::frame_now();
+        let dt = self.last_step.map_or(Duration::ZERO, |last| now - last);
+        self.last_step = Some(now);
+        self.tick(dt.as_secs_f32());
+        // layout stops running once settled, so the next drag starts fresh
+        if !self.is_animating() {
+            self.last_step = None;
+        }
+    }
+
+    // which slot, among the rows that aren't being dragged, the pointer is over
+    fn drop_index_at(&self, y: i32) -> Option<usize> {
+        let drag = self.drag.as_ref()?;
+        let mut slot = 0;
+        for (i, child) in self.rect.children.iter().enumerate() {
+            if i == drag.from {
+                continue;
+            }
+            if let Ok(prim) = child.lock() {
+                let (_, top) = prim.get_position();
+                if y < top + prim.get_height() / 2 {
+                    return Some(slot);
+                }
+            }
+            slot += 1;
+        }
+        Some(slot)
+    }
+
+    // replays the source's changes on the rows, noting where moved rows were
+    fn sync(&mut self) {
+        let Some(binding) = &mut self.binding else {
+            return;
+        };
+        if !binding.has_changes() {
+            return;
+        }
+        for change in binding.pending_changes() {
+            self.selection.apply_change(change);
+        }
+        let before: Vec<(Node, (i32, i32))> = self
+            .rect
+            .children
+            .iter()
+            .filter_map(|child| Some((child.clone(), child.lock().ok()?.get_position())))
+            .collect();
+        if binding.sync(&mut self.rect.children) {
+            // the dragged row may not be where it was any more
+            self.drag = None;
+            self.gaps.clear();
+        }
+
+        let moving = |element: &Node| {
+            binding
+                .transitions()
+                .iter()
+                .any(|t| t.kind == TransitionKind::Move && Arc::ptr_eq(&t.element, element))
+        };
+        self.origins.retain(|(element, _)| moving(element));
+        for (element, position) in before {
+            if moving(&element) && !self.origins.iter().any(|(e, _)| Arc::ptr_eq(e, &element)) {
+                self.origins.push((element, position));
+            }
+        }
+        self.rect.mark_dirty();
+    }
+
+    fn transition(&self, element: &Node) -> Option<&ItemTransition> {
+        self.binding
+            .as_ref()?
+            .transitions()
+            .iter()
+            .find(|t| Arc::ptr_eq(&t.element, element))
+    }
+
+    // moved rows ease from where they were to where layout just put them
+    fn glide_moved_rows(&self) {
+        for (element, from) in &self.origins {
+            let Some(transition) = self.transition(element) else {
+                continue;
+            };
+            let Ok(mut prim) = element.lock() else {
+                continue;
+            };
+            let t = Easing::EaseOut.apply(transition.progress());
+            let to = prim.get_position();
+            prim.set_position((
+                from.0 + ((to.0 - from.0) as f32 * t) as i32,
+                from.1 + ((to.1 - from.1) as f32 * t) as i32,
+            ));
+            if let Some(container) = prim.as_container() {
+                container.set_child_positions();
+            }
+        }
+    }
+
+    // how far a row sliding in or out sits from its place
+    fn slide_offset(&self, element: &Node, width: i32) -> f32 {
+        let Some(transition) = self.transition(element) else {
+            return 0.0;
+        };
+        let t = Easing::EaseOut.apply(transition.progress());
+        match transition.kind {
+            TransitionKind::Insert => (1.0 - t) * width as f32,
+            TransitionKind::Remove => t * width as f32,
+            TransitionKind::Move => 0.0,
+        }
+    }
+
+    fn record_row(&self, element: &Node, list: &mut DrawList, size: (i32, i32)) {
+        let Ok(prim) = element.lock() else {
+            return;
+        };
+        let offset = self.slide_offset(element, prim.get_width());
+        if offset == 0.0 {
+            prim.record(list, size);
+            return;
+        }
+        list.push_transform((offset, 0.0));
+        prim.record(list, size);
+        list.pop_transform();
+    }
+
+    fn row_bounds(&self) -> Vec<(usize, (i32, i32, i32, i32))> {
+        self.rect
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, child)| {
+                let prim = child.lock().ok()?;
+                let (x, y) = prim.get_position();
+                Some((i, (x, y, prim.get_width(), prim.get_height())))
+            })
+            .collect()
+    }
+
+    fn dragged_height(&self) -> i32 {
+        self.drag
+            .as_ref()
+            .and_then(|drag| self.rect.children[drag.from].lock().ok())
+            .map_or(0, |prim| prim.get_height())
+    }
+}
+
+impl Primative for ListView {
+    delegate_primative!(rect, sizing);
+
+    // rows follow the pointer and the drop gap while dragging
+    fn is_dirty(&self) -> bool {
+        self.is_animating()
+            || self.rect.is_dirty()
+            || self
+                .binding
+                .as_ref()
+                .is_some_and(|binding| binding.has_changes())
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    // rows get the first look, so a row's own controls still work and only
+    // presses they leave alone start a drag
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || !matches!(cx.phase(), Phase::Target | Phase::Bubble) {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                modifiers,
+            } => {
+                let row = self.row_at(*position);
+                if let Some(row) = row {
+                    self.selection.click(row, *modifiers);
+                }
+                if self.press(*position) {
+                    true
+                } else if self.selection.mode == SelectionMode::Multi {
+                    self.marquee = Some(Marquee::begin(*position, *modifiers, &self.selection));
+                    true
+                } else {
+                    row.is_some() && self.selection.mode != SelectionMode::None
+                }
+            }
+            Event::PointerMoved { position } if self.marquee.is_some() => {
+                let rows = self.row_bounds();
+                if let Some(marquee) = &mut self.marquee {
+                    marquee.update(*position, rows, &mut self.selection);
+                }
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                ..
+            } if self.marquee.is_some() => {
+                self.marquee = None;
+                true
+            }
+            Event::PointerMoved { position } if self.is_dragging() => {
+                self.drag(*position);
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                ..
+            } if self.is_dragging() => {
+                self.release();
+                true
+            }
+            Event::KeyPressed {
+                key: Key::Escape, ..
+            } if self.is_dragging() => {
+                self.cancel_drag();
+                true
+            }
+            Event::KeyPressed { key, modifiers, .. }
+                if !self.is_dragging() && self.selection.mode != SelectionMode::None =>
+            {
+                self.selection
+                    .handle_key(*key, *modifiers, self.rect.children.len())
+            }
+            _ => false,
+        };
+        if handled {
+            self.rect.mark_dirty();
+            cx.stop_propagation();
+        }
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.rect.get_mesh(size));
+
+        // rows sliding in or out are cut off at the list's edge
+        let sliding = self.binding.as_ref().is_some_and(|binding| {
+            binding
+                .transitions()
+                .iter()
+                .any(|t| t.kind != TransitionKind::Move)
+        });
+        if sliding {
+            list.push_clip(self.rect.position, (self.rect.width, self.rect.height));
+        }
+        let dragged = self.drag.as_ref().map(|drag| drag.from);
+        for (i, (x, y, w, h)) in self.row_bounds() {
+            if Some(i) != dragged && self.selection.is_selected(i) {
+                let mut highlight = make_ss_rectangle(x, y, w, h, self.selection_color, size);
+                highlight.multiply_alpha(SELECTION_ALPHA);
+                list.mesh(highlight);
+            }
+        }
+        for (i, child) in self.rect.children.iter().enumerate() {
+            if Some(i) != dragged {
+                self.record_row(child, list, size);
+            }
+        }
+        if let Some(binding) = &self.binding {
+            for transition in binding.transitions() {
+                if transition.kind == TransitionKind::Remove {
+                    self.record_row(&transition.element, list, size);
+                }
+            }
+        }
+        if sliding {
+            list.pop_clip();
+        }
+
+        // the lifted item goes last so it sits above its neighbours
+        if let Some(index) = dragged
+            && let Ok(prim) = self.rect.children[index].lock()
+        {
+            let (x, y) = prim.get_position();
+            list.mesh(make_ss_rectangle(
+                x + self.shadow_offset,
+                y + self.shadow_offset,
+                prim.get_width(),
+                prim.get_height(),
+                self.shadow_color,
+                size,
+            ));
+            prim.record(list, size);
+        }
+        if let Some(marquee) = &self.marquee {
+            marquee.record(list, self.selection_color, size);
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+}
+
+impl Container for ListView {
+    fn fit_sizing(&mut self) {
+        self.sync();
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.step();
+        let Some(drag) = &self.drag else {
+            self.rect.set_child_positions();
+            self.glide_moved_rows();
+            return;
+        };
+
+        let gap_height = self.dragged_height() + self.rect.child_gap;
+        let mut position = self.rect.position;
+        position.0 += self.rect.content_padding();
+        position.1 += self.rect.content_padding();
+
+        let mut slot = 0;
+        for (i, child) in self.rect.children.iter().enumerate() {
+            let Ok(mut prim) = child.lock() else {
+                continue;
+            };
+            if i == drag.from {
+                prim.set_position((
+                    drag.pointer.0 - drag.grab_offset.0,
+                    drag.pointer.1 - drag.grab_offset.1,
+                ));
+            } else {
+                position.1 +=
+                    (self.gaps.get(slot).copied().unwrap_or(0.0) * gap_height as f32) as i32;
+                prim.set_position(position);
+                position.1 += prim.get_height() + self.rect.child_gap;
+                slot += 1;
+            }
+            if let Some(container) = prim.as_container() {
+                container.set_child_positions();
+            }
+        }
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::ListView;
+    use crate::{
+        binding::{ListBinding, ObservableVec},
+        builder::Node,
+        input::Modifiers,
+        layout::{Container, Primative, Rectangle, Sizing, SizingMode},
+        theme::Theme,
+    };
+
+    // each row is as tall as its item
+    fn rows() -> ListBinding<i32> {
+        ListBinding::new(|item: &i32| {
+            Arc::new(Mutex::new(Rectangle {
+                sizing: Sizing {
+                    width: SizingMode::Fixed(100),
+                    height: SizingMode::Fixed(*item),
+                },
+                ..Default::default()
+            })) as Node
+        })
+    }
+
+    fn heights(list: &ListView) -> Vec<i32> {
+        list.items()
+            .iter()
+            .map(|row| row.lock().unwrap().get_height())
+            .collect()
+    }
+
+    fn layout(list: &mut ListView) {
+        list.fit_sizing();
+        list.set_child_positions();
+    }
+
+    #[test]
+    fn bound_rows_follow_the_source_and_reorders_move_it() {
+        let source = Arc::new(Mutex::new(ObservableVec::from(vec![10, 20, 30])));
+        let mut list = ListView::new(&Theme::default()).bind(source.clone(), rows());
+        layout(&mut list);
+        assert_eq!(heights(&list), vec![10, 20, 30]);
+        list.selection.click(2, Modifiers::default());
+
+        source.lock().unwrap().remove(0);
+        assert!(list.is_dirty());
+        layout(&mut list);
+        assert_eq!(heights(&list), vec![20, 30]);
+        assert!(list.selection.is_selected(1));
+        // the removed row stays drawn while it slides out
+        assert!(list.is_animating());
+        list.tick(1.0);
+        assert!(!list.is_animating());
+
+        layout(&mut list);
+        let (x, y) = list.items()[0].lock().unwrap().get_position();
+        assert!(list.press((x + 1, y + 1)));
+        list.drag((x + 1, y + 45));
+        list.release();
+        assert_eq!(&source.lock().unwrap()[..], &[30, 20]);
+        layout(&mut list);
+        assert_eq!(heights(&list), vec![30, 20]);
+        assert!(list.selection.is_selected(0));
+    }
+}
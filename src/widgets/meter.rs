@@ -0,0 +1,223 @@
+use std::time::{Duration, Instant};
+
+use tinycolors::srgb;
+
+use crate::{
+    context,
+    fill::Fill,
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::mesh_builder::{Mesh, make_ss_rectangle},
+    theme::{Theme, rgb},
+};
+
+pub fn amplitude_to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        20.0 * amplitude.log10()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MeterOrientation {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+pub struct Meter {
+    pub rect: Rectangle,
+    pub orientation: MeterOrientation,
+    pub floor_db: f32,
+    // how fast the displayed level falls, in db per second
+    pub decay_db_per_second: f32,
+    pub peak_hold_seconds: f32,
+    pub warn_db: f32,
+    pub clip_db: f32,
+    pub level_color: srgb,
+    pub warn_color: srgb,
+    pub clip_color: srgb,
+    pub peak_color: srgb,
+    // levels as of when they were last raised, falling from there on the
+    // frame clock
+    rms: (f32, Instant),
+    peak: (f32, Instant),
+    held_peak: (f32, Instant),
+}
+
+impl Meter {
+    pub fn new(theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                min_width: 12,
+                min_height: 120,
+                fill: Fill::Solid(theme.surface),
+                ..Default::default()
+            },
+            orientation: MeterOrientation::Vertical,
+            floor_db: -60.0,
+            decay_db_per_second: 24.0,
+            peak_hold_seconds: 1.5,
+            warn_db: -12.0,
+            clip_db: -0.5,
+            level_color: rgb(0.2, 0.8, 0.3),
+            warn_color: rgb(0.95, 0.8, 0.15),
+            clip_color: rgb(0.95, 0.2, 0.15),
+            peak_color: theme.text,
+            rms: (f32::NEG_INFINITY, Instant::now()),
+            peak: (f32::NEG_INFINITY, Instant::now()),
+            held_peak: (f32::NEG_INFINITY, Instant::now()),
+        }
+    }
+
+    pub fn rms_db(&self) -> f32 {
+        self.rms_db_at(context::frame_now())
+    }
+
+    pub fn peak_db(&self) -> f32 {
+        self.peak_db_at(context::frame_now())
+    }
+
+    pub fn held_peak_db(&self) -> f32 {
+        self.held_peak_db_at(context::frame_now())
+    }
+
+    pub fn is_clipping(&self) -> bool {
+        self.held_peak_db() >= self.clip_db
+    }
+
+    // feeds a block of samples. levels only ever jump up here, and fall back
+    // on their own as the frame clock moves on
+    pub fn push_samples(&mut self, samples: &[f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        let now = context::frame_now();
+        self.rms = (self.rms_db_at(now).max(amplitude_to_db(rms)), now);
+        self.peak = (self.peak_db_at(now).max(amplitude_to_db(peak)), now);
+        if self.peak.0 >= self.held_peak_db_at(now) {
+            self.held_peak = self.peak;
+        }
+        self.rect.mark_dirty();
+    }
+
+    pub fn reset(&mut self) {
+        let now = context::frame_now();
+        self.rms = (f32::NEG_INFINITY, now);
+        self.peak = (f32::NEG_INFINITY, now);
+        self.held_peak = (f32::NEG_INFINITY, now);
+        self.rect.mark_dirty();
+    }
+
+    // a level after falling for as long as it's been since it was set
+    fn fallen(&self, (db, since): (f32, Instant), now: Instant) -> f32 {
+        let seconds = now.saturating_duration_since(since).as_secs_f32();
+        decay(db, self.decay_db_per_second * seconds, self.floor_db)
+    }
+
+    fn rms_db_at(&self, now: Instant) -> f32 {
+        self.fallen(self.rms, now)
+    }
+
+    fn peak_db_at(&self, now: Instant) -> f32 {
+        self.fallen(self.peak, now)
+    }
+
+    // the held peak stays put for `peak_hold_seconds`, then falls like the
+    // rest without dropping below the live peak
+    fn held_peak_db_at(&self, now: Instant) -> f32 {
+        let (db, since) = self.held_peak;
+        let hold = Duration::try_from_secs_f32(self.peak_hold_seconds).unwrap_or_default();
+        let held = match since.checked_add(hold) {
+            Some(release) => self.fallen((db, release), now),
+            None => db,
+        };
+        held.max(self.peak_db_at(now))
+    }
+
+    fn is_falling(&self, now: Instant) -> bool {
+        self.held_peak_db_at(now).is_finite() || self.rms_db_at(now).is_finite()
+    }
+
+    fn fraction(&self, db: f32) -> f32 {
+        if !db.is_finite() {
+            return 0.0;
+        }
+        ((db - self.floor_db) / -self.floor_db).clamp(0.0, 1.0)
+    }
+
+    // a segment of the meter from `from` to `to` as fractions of its length
+    fn segment(&self, from: f32, to: f32) -> (i32, i32, i32, i32) {
+        let (x, y) = self.rect.position;
+        let (w, h) = (self.rect.width, self.rect.height);
+        match self.orientation {
+            MeterOrientation::Vertical => {
+                let top = y + h - (to * h as f32) as i32;
+                let bottom = y + h - (from * h as f32) as i32;
+                (x, top, w, bottom - top)
+            }
+            MeterOrientation::Horizontal => {
+                let left = x + (from * w as f32) as i32;
+                let right = x + (to * w as f32) as i32;
+                (left, y, right - left, h)
+            }
+        }
+    }
+}
+
+fn decay(db: f32, fall: f32, floor: f32) -> f32 {
+    let db = db - fall;
+    if db < floor { f32::NEG_INFINITY } else { db }
+}
+
+impl Primative for Meter {
+    delegate_primative!(rect, sizing);
+
+    // drawn every frame until the levels have fallen to the floor
+    fn is_dirty(&self) -> bool {
+        self.rect.is_dirty() || self.is_falling(context::frame_now())
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let now = context::frame_now();
+        let mut mesh = self.rect.get_mesh(size);
+        let level = self.fraction(self.rms_db_at(now).max(self.peak_db_at(now)));
+        let warn = self.fraction(self.warn_db);
+        let clip = self.fraction(self.clip_db);
+
+        let zones = [
+            (0.0, warn, self.level_color),
+            (warn, clip, self.warn_color),
+            (clip, 1.0, self.clip_color),
+        ];
+        for (from, to, color) in zones {
+            if level <= from {
+                break;
+            }
+            let (x, y, w, h) = self.segment(from, level.min(to));
+            mesh.append(make_ss_rectangle(x, y, w, h, color, size));
+        }
+
+        let held = self.fraction(self.held_peak_db_at(now));
+        if held > 0.0 {
+            let (x, y, w, h) = self.segment(held, held);
+            let (w, h) = match self.orientation {
+                MeterOrientation::Vertical => (w, 2),
+                MeterOrientation::Horizontal => (2, h),
+            };
+            mesh.append(make_ss_rectangle(x, y, w, h, self.peak_color, size));
+        }
+        mesh
+    }
+}
@@ -0,0 +1,33 @@
+pub mod async_content;
+pub mod button;
+pub mod cell_editor;
+pub mod checkbox;
+pub mod combo_box;
+pub mod error_boundary;
+pub mod eyedropper;
+pub mod fader;
+pub mod floating_panel;
+pub mod form_view;
+pub mod icon;
+pub mod input_mode;
+pub mod knob;
+pub mod list_view;
+pub mod meter;
+pub mod parameter;
+pub mod particle_emitter;
+pub mod plugin_view;
+pub mod radio_group;
+pub mod rating;
+pub mod ruler;
+pub mod scrollbar;
+pub mod segmented_control;
+pub mod sheet;
+pub mod skeleton;
+pub mod table;
+#[cfg(feature = "data")]
+pub mod table_data;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+pub mod text_input;
+pub mod tooltip;
+pub mod waveform;
@@ -0,0 +1,149 @@
+use std::time::Instant;
+
+use crate::input::{self, Modifiers};
+
+type ChangeCallback = Box<dyn FnMut(f32) + Send>;
+
+// a continuous parameter shared by knobs and faders. the canonical value is
+// normalized to 0..=1 so it maps straight onto midi cc / automation lanes
+pub struct Parameter {
+    pub min: f32,
+    pub max: f32,
+    pub default: f32,
+    pub unit: String,
+    pub precision: usize,
+    // normalized change per pixel of drag
+    pub sensitivity: f32,
+    // multiplier applied to sensitivity while the fine-adjust modifier is held
+    pub fine_factor: f32,
+    normalized: f32,
+    drag_origin: Option<((i32, i32), f32)>,
+    last_click: Option<Instant>,
+    on_change: Option<ChangeCallback>,
+}
+
+impl Parameter {
+    pub fn new(min: f32, max: f32, default: f32) -> Self {
+        let mut parameter = Self {
+            min,
+            max,
+            default,
+            unit: String::new(),
+            precision: 2,
+            sensitivity: 1.0 / 200.0,
+            fine_factor: 0.1,
+            normalized: 0.0,
+            drag_origin: None,
+            last_click: None,
+            on_change: None,
+        };
+        parameter.normalized = parameter.normalize(default);
+        parameter
+    }
+
+    pub fn on_change(&mut self, f: impl FnMut(f32) + Send + 'static) {
+        self.on_change = Some(Box::new(f));
+    }
+
+    pub fn normalized(&self) -> f32 {
+        self.normalized
+    }
+
+    pub fn value(&self) -> f32 {
+        self.min + (self.max - self.min) * self.normalized
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.set_normalized(self.normalize(value));
+    }
+
+    pub fn set_normalized(&mut self, normalized: f32) {
+        let normalized = normalized.clamp(0.0, 1.0);
+        if normalized == self.normalized {
+            return;
+        }
+        self.normalized = normalized;
+        if let Some(on_change) = &mut self.on_change {
+            on_change(normalized);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.set_value(self.default);
+    }
+
+    pub fn midi_value(&self) -> u8 {
+        (self.normalized * 127.0).round() as u8
+    }
+
+    pub fn set_midi_value(&mut self, value: u8) {
+        self.set_normalized(value.min(127) as f32 / 127.0);
+    }
+
+    pub fn midi_value_14bit(&self) -> u16 {
+        (self.normalized * 16383.0).round() as u16
+    }
+
+    pub fn set_midi_value_14bit(&mut self, value: u16) {
+        self.set_normalized(value.min(16383) as f32 / 16383.0);
+    }
+
+    pub fn display_value(&self) -> String {
+        format!("{:.*}{}", self.precision, self.value(), self.unit)
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag_origin.is_some()
+    }
+
+    // returns true when this press completed a double click and reset the value
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|last| now.duration_since(last) <= input::double_click_interval());
+        if double {
+            self.last_click = None;
+            self.drag_origin = None;
+            self.reset();
+            return true;
+        }
+        self.last_click = Some(now);
+        self.drag_origin = Some((point, self.normalized));
+        false
+    }
+
+    // dragging up or right increases the value. shift switches to fine adjust
+    pub fn drag(&mut self, point: (i32, i32), modifiers: Modifiers) {
+        let Some((origin, start)) = self.drag_origin else {
+            return;
+        };
+        let delta = (origin.1 - point.1) + (point.0 - origin.0);
+        let mut sensitivity = self.sensitivity;
+        if modifiers.shift {
+            sensitivity *= self.fine_factor;
+            // rebase so switching modes mid-drag doesn't jump the value
+            self.drag_origin = Some((point, self.normalized));
+            self.set_normalized(self.normalized + delta as f32 * sensitivity);
+            return;
+        }
+        self.set_normalized(start + delta as f32 * sensitivity);
+    }
+
+    pub fn release(&mut self) {
+        self.drag_origin = None;
+    }
+
+    pub fn scroll(&mut self, steps: f32, modifiers: Modifiers) {
+        let step = if modifiers.shift { 0.001 } else { 0.01 };
+        self.set_normalized(self.normalized + steps * step);
+    }
+
+    fn normalize(&self, value: f32) -> f32 {
+        if self.max == self.min {
+            0.0
+        } else {
+            ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    f32::consts::PI,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::{draw_list::DrawList, mesh_builder::Mesh, particles::ParticleInstance},
+    theme::rgb,
+};
+
+// how particles leave the emitter. angles are in radians, measured clockwise
+// from straight up; distances are in layout pixels
+#[derive(Debug, Clone)]
+pub struct EmitterConfig {
+    pub lifetime: f32,
+    pub speed: (f32, f32),
+    pub direction: f32,
+    // total width of the cone particles are launched in
+    pub spread: f32,
+    // downward acceleration, negative floats upwards
+    pub gravity: f32,
+    pub size: (f32, f32),
+    // largest angular velocity, in either direction
+    pub spin: f32,
+    // picked from at random per particle
+    pub colors: Vec<srgb>,
+    // particles per second emitted continuously, 0 for bursts only
+    pub rate: f32,
+    // older particles are dropped past this
+    pub max_particles: usize,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> Self {
+        Self::confetti()
+    }
+}
+
+impl EmitterConfig {
+    pub fn confetti() -> Self {
+        Self {
+            lifetime: 2.5,
+            speed: (600.0, 1400.0),
+            direction: 0.0,
+            spread: PI / 2.0,
+            gravity: 1800.0,
+            size: (10.0, 18.0),
+            spin: 4.0 * PI,
+            colors: vec![
+                rgb(0.95, 0.3, 0.35),
+                rgb(1.0, 0.8, 0.25),
+                rgb(0.3, 0.75, 0.95),
+                rgb(0.45, 0.85, 0.45),
+                rgb(0.75, 0.45, 0.95),
+            ],
+            rate: 0.0,
+            max_particles: 2000,
+        }
+    }
+
+    pub fn sparkles() -> Self {
+        Self {
+            lifetime: 0.8,
+            speed: (60.0, 240.0),
+            direction: 0.0,
+            spread: 2.0 * PI,
+            gravity: 0.0,
+            size: (4.0, 8.0),
+            spin: PI,
+            colors: vec![rgb(1.0, 0.95, 0.7), rgb(1.0, 1.0, 1.0)],
+            rate: 40.0,
+            max_particles: 500,
+        }
+    }
+}
+
+// small xorshift generator, particles don't need anything better
+struct Rng(u64);
+
+impl Rng {
+    fn seeded() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self(seed | 1)
+    }
+
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.next()
+    }
+}
+
+struct EmitterState {
+    particles: Vec<ParticleInstance>,
+    pending: usize,
+    // fractional particles owed by the continuous rate
+    carry: f32,
+    last_time: Option<f32>,
+    rng: Rng,
+}
+
+// spawns particles from the center of its rect. drawing does no layout work,
+// so give it a size or let it grow. particles are free to leave the rect
+pub struct ParticleEmitter {
+    pub rect: Rectangle,
+    pub config: EmitterConfig,
+    // where particles spawn, as a fraction of the rect
+    pub origin: (f32, f32),
+    state: Mutex<EmitterState>,
+}
+
+impl ParticleEmitter {
+    pub fn new(config: EmitterConfig) -> Self {
+        Self {
+            rect: Rectangle::default(),
+            config,
+            origin: (0.5, 0.5),
+            state: Mutex::new(EmitterState {
+                particles: Vec::new(),
+                pending: 0,
+                carry: 0.0,
+                last_time: None,
+                rng: Rng::seeded(),
+            }),
+        }
+    }
+
+    // spawns `count` particles on the next frame
+    pub fn burst(&self, count: usize) {
+        self.state.lock().unwrap().pending += count;
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.particles.clear();
+        state.pending = 0;
+        state.carry = 0.0;
+    }
+
+    // whether anything is on screen or about to be
+    pub fn is_active(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        self.config.rate > 0.0 || state.pending > 0 || !state.particles.is_empty()
+    }
+
+    fn spawn(&self, state: &mut EmitterState, time: f32) {
+        let config = &self.config;
+        let rng = &mut state.rng;
+        let origin = [
+            self.rect.position.0 as f32 + self.rect.width as f32 * self.origin.0,
+            self.rect.position.1 as f32 + self.rect.height as f32 * self.origin.1,
+        ];
+        let angle = config.direction + (rng.next() - 0.5) * config.spread;
+        let speed = rng.range(config.speed);
+        let color = match config.colors.len() {
+            0 => srgb {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+            },
+            n => config.colors[((rng.next() * n as f32) as usize).min(n - 1)],
+        };
+        state.particles.push(ParticleInstance {
+            origin,
+            velocity: [speed * angle.sin(), -speed * angle.cos()],
+            color: [color.r, color.g, color.b, 1.0],
+            spawn_time: time,
+            lifetime: config.lifetime,
+            size: rng.range(config.size),
+            gravity: config.gravity,
+            angle: rng.next() * 2.0 * PI,
+            spin: (rng.next() * 2.0 - 1.0) * config.spin,
+        });
+    }
+
+    // advances the emitter to the frame clock's `time`
+    fn update(&self, state: &mut EmitterState, time: f32) {
+        let dt = state.last_time.map_or(0.0, |last| (time - last).max(0.0));
+        state.last_time = Some(time);
+
+        state.carry += self.config.rate * dt;
+        let count = std::mem::take(&mut state.pending) + state.carry as usize;
+        state.carry = state.carry.fract();
+        for _ in 0..count {
+            self.spawn(state, time);
+        }
+
+        state.particles.retain(|particle| particle.is_alive(time));
+        let excess = state
+            .particles
+            .len()
+            .saturating_sub(self.config.max_particles);
+        state.particles.drain(..excess);
+    }
+}
+
+impl Primative for ParticleEmitter {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, _size: (i32, i32)) {
+        let time = list.clock.elapsed().as_secs_f32();
+        let mut state = self.state.lock().unwrap();
+        self.update(&mut state, time);
+        list.particles(state.particles.clone());
+    }
+
+    // drawn by the particle pipeline, not as colored geometry
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    layout::{Primative, Rectangle, Sizing, delegate_primative},
+    renderer::{draw_list::DrawList, mesh_builder::Mesh},
+};
+
+// a spot in the tree drawn by a `RenderPlugin`, the one registered under
+// `plugin`. it grows to fill its parent unless sized otherwise
+pub struct PluginView {
+    pub rect: Rectangle,
+    pub plugin: String,
+}
+
+impl PluginView {
+    pub fn new(plugin: &str) -> Self {
+        Self {
+            rect: Rectangle {
+                sizing: Sizing::GROW,
+                ..Default::default()
+            },
+            plugin: plugin.to_string(),
+        }
+    }
+
+    pub fn sizing(mut self, sizing: Sizing) -> Self {
+        self.rect.sizing = sizing;
+        self
+    }
+}
+
+impl Primative for PluginView {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, _size: (i32, i32)) {
+        list.plugin(
+            &self.plugin,
+            self.rect.position,
+            (self.rect.width, self.rect.height),
+        );
+    }
+
+    // all of it is up to the plugin
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
@@ -0,0 +1,229 @@
+use tinycolors::srgb;
+
+use crate::{
+    builder::{IntoNode, Node},
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    input::Key,
+    layout::{
+        AlignCross, Axis, Container, LayoutMode, Primative, Rectangle, Sizing, SizingMode,
+        delegate_primative,
+    },
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_circle},
+    },
+    theme::Theme,
+};
+
+type ChangeCallback = Box<dyn FnMut(usize) + Send>;
+
+// a column of options where only one can be picked, each a dot with its label
+// to the right. clicking an option or the arrow keys pick one, space picks the
+// first when nothing is yet, and `on_change` fires with its index
+pub struct RadioGroup {
+    pub rect: Rectangle,
+    pub selected: Option<usize>,
+    pub dot_size: i32,
+    pub dot_color: srgb,
+    pub border_color: srgb,
+    // the picked option's dot, and the smaller one inside it
+    pub selected_color: srgb,
+    pub mark_color: srgb,
+    on_change: Option<ChangeCallback>,
+}
+
+impl RadioGroup {
+    pub fn new<T: IntoNode>(options: impl IntoIterator<Item = T>, theme: &Theme) -> Self {
+        let dot_size = 36;
+        let children = options
+            .into_iter()
+            .map(|label| option(dot_size, label.into_node()).into_node())
+            .collect();
+        let mut group = Self {
+            rect: Rectangle {
+                layout_mode: LayoutMode::TopToBottom,
+                child_gap: 8,
+                children,
+                ..Default::default()
+            },
+            selected: None,
+            dot_size,
+            dot_color: srgb::default(),
+            border_color: srgb::default(),
+            selected_color: srgb::default(),
+            mark_color: srgb::default(),
+            on_change: None,
+        };
+        group.apply_theme(theme);
+        group
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.dot_color = theme.surface;
+        self.border_color = theme.border;
+        self.selected_color = theme.primary;
+        self.mark_color = theme.on_primary;
+    }
+
+    // sets the starting option without firing `on_change`
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = (index < self.len()).then_some(index);
+        self
+    }
+
+    pub fn on_change(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rect.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rect.children.is_empty()
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index >= self.len() || self.selected == Some(index) {
+            return;
+        }
+        self.selected = Some(index);
+        if let Some(on_change) = &mut self.on_change {
+            on_change(index);
+        }
+    }
+
+    pub fn option_at(&self, point: (i32, i32)) -> Option<usize> {
+        self.rect
+            .children
+            .iter()
+            .position(|child| child.lock().is_ok_and(|prim| prim.contains(point)))
+    }
+
+    // returns true when the click landed on an option
+    pub fn click(&mut self, point: (i32, i32)) -> bool {
+        match self.option_at(point) {
+            Some(index) => {
+                self.select(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        let last = self.len().saturating_sub(1);
+        match (key, self.selected) {
+            (Key::Up | Key::Left, Some(index)) => self.select(index.saturating_sub(1)),
+            (Key::Down | Key::Right, Some(index)) => self.select((index + 1).min(last)),
+            (Key::Up | Key::Left | Key::Down | Key::Right | Key::Space, None) => self.select(0),
+            (Key::Home, _) => self.select(0),
+            (Key::End, _) => self.select(last),
+            _ => return false,
+        }
+        true
+    }
+}
+
+// a row with room for the dot ahead of the label. the row and the room are
+// see through, the group draws the dots
+fn option(dot_size: i32, label: Node) -> Rectangle {
+    let dot = Rectangle {
+        sizing: Sizing {
+            width: SizingMode::Fixed(dot_size),
+            height: SizingMode::Fixed(dot_size),
+        },
+        opacity: 0.0,
+        ..Default::default()
+    };
+    Rectangle {
+        layout_mode: LayoutMode::LeftToRight,
+        child_gap: 12,
+        align_cross: AlignCross::Center,
+        children: vec![dot.into_node(), label],
+        opacity: 0.0,
+        ..Default::default()
+    }
+}
+
+impl Primative for RadioGroup {
+    delegate_primative!(rect);
+
+    // clicks and keys are taken on the way up, once the label has had them.
+    // a click counts when the release lands on the option
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || !matches!(cx.phase(), Phase::Target | Phase::Bubble) {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } => self.click(*position),
+            Event::KeyPressed { key, .. } => self.handle_key(*key),
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.get_mesh(size));
+        self.rect.record_children(list, size);
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let mut mesh = Mesh::default();
+        let radius = self.dot_size as f32 / 2.0;
+        for (i, child) in self.rect.children.iter().enumerate() {
+            let Ok(row) = child.lock() else {
+                continue;
+            };
+            let (x, y) = row.get_position();
+            let center = (x as f32 + radius, y as f32 + row.get_height() as f32 / 2.0);
+            if self.selected == Some(i) {
+                mesh.append(make_ss_circle(center, radius, self.selected_color, size));
+                mesh.append(make_ss_circle(center, radius * 0.4, self.mark_color, size));
+            } else {
+                mesh.append(make_ss_circle(center, radius, self.border_color, size));
+                mesh.append(make_ss_circle(center, radius - 2.0, self.dot_color, size));
+            }
+        }
+        mesh
+    }
+}
+
+impl Container for RadioGroup {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
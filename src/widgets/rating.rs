@@ -0,0 +1,173 @@
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    input::Key,
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::mesh_builder::{Mesh, make_ss_star},
+    theme::Theme,
+};
+
+type ChangeCallback = Box<dyn FnMut(u8) + Send>;
+
+pub struct Rating {
+    pub rect: Rectangle,
+    pub value: u8,
+    pub max: u8,
+    pub hover_value: Option<u8>,
+    pub star_size: i32,
+    pub gap: i32,
+    pub filled_color: srgb,
+    pub preview_color: srgb,
+    pub empty_color: srgb,
+    on_change: Option<ChangeCallback>,
+}
+
+impl Rating {
+    pub fn new(max: u8, theme: &Theme) -> Self {
+        let star_size = 24;
+        let gap = 4;
+        let width = max as i32 * star_size + (max as i32 - 1).max(0) * gap;
+        let mut rating = Self {
+            rect: Rectangle {
+                min_width: width,
+                min_height: star_size,
+                ..Default::default()
+            },
+            value: 0,
+            max,
+            hover_value: None,
+            star_size,
+            gap,
+            filled_color: srgb::default(),
+            preview_color: srgb::default(),
+            empty_color: srgb::default(),
+            on_change: None,
+        };
+        rating.apply_theme(theme);
+        rating
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.rect.fill = Fill::Solid(theme.background);
+        self.filled_color = theme.accent;
+        self.preview_color = theme.hover;
+        self.empty_color = theme.inactive;
+    }
+
+    pub fn on_change(mut self, f: impl FnMut(u8) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn set_value(&mut self, value: u8) {
+        let value = value.min(self.max);
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+        if let Some(on_change) = &mut self.on_change {
+            on_change(value);
+        }
+    }
+
+    // the rating a click at `point` would produce, if it lands on a star
+    pub fn value_at(&self, point: (i32, i32)) -> Option<u8> {
+        let (x, y) = self.rect.position;
+        if point.1 < y || point.1 >= y + self.star_size || point.0 < x {
+            return None;
+        }
+        let star = (point.0 - x) / (self.star_size + self.gap);
+        (star < self.max as i32).then_some(star as u8 + 1)
+    }
+
+    pub fn hover(&mut self, point: (i32, i32)) {
+        self.hover_value = self.value_at(point);
+    }
+
+    pub fn leave(&mut self) {
+        self.hover_value = None;
+    }
+
+    pub fn click(&mut self, point: (i32, i32)) {
+        if let Some(value) = self.value_at(point) {
+            // clicking the current rating again clears it
+            self.set_value(if value == self.value { 0 } else { value });
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Left | Key::Down => self.set_value(self.value.saturating_sub(1)),
+            Key::Right | Key::Up => self.set_value(self.value.saturating_add(1)),
+            Key::Home => self.set_value(0),
+            Key::End => self.set_value(self.max),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Primative for Rating {
+    delegate_primative!(rect);
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let (hover_value, value) = (self.hover_value, self.value);
+        let handled = match (cx.phase(), cx.event) {
+            (Phase::Exit, Event::PointerMoved { .. } | Event::PointerLeft) => {
+                self.leave();
+                false
+            }
+            (Phase::Target | Phase::Bubble, Event::PointerMoved { position }) => {
+                self.hover(*position);
+                false
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerReleased {
+                    button: PointerButton::Primary,
+                    position,
+                    ..
+                },
+            ) => {
+                self.click(*position);
+                self.value_at(*position).is_some()
+            }
+            (Phase::Target | Phase::Bubble, Event::KeyPressed { key, .. }) => self.handle_key(*key),
+            _ => false,
+        };
+        if (self.hover_value, self.value) != (hover_value, value) {
+            self.rect.mark_dirty();
+        }
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let mut mesh = Mesh::default();
+        let (x, y) = self.rect.position;
+        let radius = self.star_size as f32 / 2.0;
+        let shown = self.hover_value.unwrap_or(self.value);
+
+        for i in 0..self.max {
+            let color = match self.hover_value {
+                Some(_) if i < shown => self.preview_color,
+                None if i < shown => self.filled_color,
+                _ => self.empty_color,
+            };
+            let center = (
+                (x + i as i32 * (self.star_size + self.gap)) as f32 + radius,
+                y as f32 + radius,
+            );
+            mesh.append(make_ss_star(center, radius, radius * 0.45, color, size));
+        }
+        mesh
+    }
+}
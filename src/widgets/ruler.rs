@@ -0,0 +1,552 @@
+use std::sync::{Arc, Mutex};
+
+use tinycolors::srgb;
+
+use crate::{
+    builder::IntoNode,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+    },
+    theme::Theme,
+};
+
+// maps between canvas units and screen pixels along one axis
+#[derive(Debug, Clone, Copy)]
+pub struct CanvasTransform {
+    // canvas coordinate shown at the canvas' leading edge
+    pub offset: f32,
+    // pixels per canvas unit
+    pub scale: f32,
+}
+
+impl Default for CanvasTransform {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl CanvasTransform {
+    pub fn to_screen(self, value: f32, origin: i32) -> f32 {
+        origin as f32 + (value - self.offset) * self.scale
+    }
+
+    pub fn to_canvas(self, screen: i32, origin: i32) -> f32 {
+        (screen - origin) as f32 / self.scale + self.offset
+    }
+}
+
+pub struct Ruler {
+    pub rect: Rectangle,
+    pub axis: Axis,
+    pub transform: CanvasTransform,
+    pub tick_color: srgb,
+    // minimum on-screen distance between major ticks
+    pub min_major_spacing: f32,
+    pub subdivisions: u32,
+    // where guides dragged out of the ruler go
+    guides: Option<Arc<Mutex<GuideOverlay>>>,
+}
+
+impl Ruler {
+    pub fn new(axis: Axis, thickness: i32, theme: &Theme) -> Self {
+        let (min_width, min_height) = match axis {
+            Axis::Horizontal => (0, thickness),
+            Axis::Vertical => (thickness, 0),
+        };
+        Self {
+            rect: Rectangle {
+                min_width,
+                min_height,
+                fill: Fill::Solid(theme.surface),
+                ..Default::default()
+            },
+            axis,
+            transform: CanvasTransform::default(),
+            tick_color: theme.inactive,
+            min_major_spacing: 64.0,
+            subdivisions: 10,
+            guides: None,
+        }
+    }
+
+    // pressing the ruler starts a guide across it on `overlay`, which follows
+    // the pointer until it's let go
+    pub fn with_guides(mut self, overlay: Arc<Mutex<GuideOverlay>>) -> Self {
+        self.guides = Some(overlay);
+        self
+    }
+
+    // picks a 1/2/5 * 10^n step so major ticks stay at least `min_major_spacing` apart
+    pub fn major_step(&self) -> f32 {
+        let raw = self.min_major_spacing / self.transform.scale;
+        let magnitude = 10f32.powf(raw.log10().floor());
+        [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|m| m * magnitude)
+            .find(|step| *step >= raw)
+            .unwrap_or(10.0 * magnitude)
+    }
+
+    fn origin(&self) -> i32 {
+        match self.axis {
+            Axis::Horizontal => self.rect.position.0,
+            Axis::Vertical => self.rect.position.1,
+        }
+    }
+
+    fn length(&self) -> i32 {
+        match self.axis {
+            Axis::Horizontal => self.rect.width,
+            Axis::Vertical => self.rect.height,
+        }
+    }
+
+    fn thickness(&self) -> i32 {
+        match self.axis {
+            Axis::Horizontal => self.rect.height,
+            Axis::Vertical => self.rect.width,
+        }
+    }
+}
+
+impl Primative for Ruler {
+    delegate_primative!(rect);
+
+    // the pointer stays with the ruler while it's held, so the drag arrives
+    // here even once it's out over the canvas
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || !matches!(cx.phase(), Phase::Target | Phase::Bubble) {
+            return;
+        }
+        let Some(overlay) = &self.guides else {
+            return;
+        };
+        let Ok(mut overlay) = overlay.lock() else {
+            return;
+        };
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } => {
+                overlay.start_guide(self.axis, *position);
+                true
+            }
+            Event::PointerMoved { position } if overlay.dragging().is_some() => {
+                overlay.drag(*position);
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if overlay.dragging().is_some() => {
+                overlay.release(*position);
+                true
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let mut mesh = self.rect.get_mesh(size);
+        let major = self.major_step();
+        let minor = major / self.subdivisions.max(1) as f32;
+        // a zero or broken scale has no step to walk the ruler with
+        if !(minor.is_finite() && minor > 0.0) {
+            return mesh;
+        }
+        let origin = self.origin();
+        let end = self.transform.to_canvas(origin + self.length(), origin);
+        let thickness = self.thickness();
+        let (x, y) = self.rect.position;
+
+        // ticks closer than a pixel can't be told apart, so there are never
+        // more than the ruler is long
+        let first = (self.transform.offset / minor).floor() as i64;
+        let max_ticks = self.length().max(0) as i64 + 1;
+        for i in first..first.saturating_add(max_ticks) {
+            let value = i as f32 * minor;
+            if value > end {
+                break;
+            }
+            let is_major = i % self.subdivisions.max(1) as i64 == 0;
+            let tick = if is_major { thickness } else { thickness / 3 };
+            let screen = self.transform.to_screen(value, origin) as i32;
+            if screen >= origin {
+                let (tx, ty, tw, th) = match self.axis {
+                    Axis::Horizontal => (screen, y + thickness - tick, 1, tick),
+                    Axis::Vertical => (x + thickness - tick, screen, tick, 1),
+                };
+                mesh.append(make_ss_rectangle(tx, ty, tw, th, self.tick_color, size));
+            }
+        }
+        mesh
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guide {
+    // a horizontal guide is a line of constant y, dragged out of the horizontal ruler
+    pub axis: Axis,
+    pub position: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    // screen-space distance within which values snap
+    pub threshold: f32,
+    pub grid: Option<f32>,
+    pub to_guides: bool,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 6.0,
+            grid: None,
+            to_guides: true,
+        }
+    }
+}
+
+// returns the closest candidate within `threshold`, or `value` if none is close enough
+pub fn snap(value: f32, candidates: impl IntoIterator<Item = f32>, threshold: f32) -> f32 {
+    candidates
+        .into_iter()
+        .map(|c| (c, (c - value).abs()))
+        .filter(|(_, d)| *d <= threshold)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(value, |(c, _)| c)
+}
+
+// the guide lines drawn over a canvas, plus the drag state for creating and
+// moving them. it wraps the canvas and draws the lines above it. presses near
+// a guide are taken on the way down, before the canvas sees them, and rulers
+// given the overlay with `Ruler::with_guides` drag new guides out of
+// themselves
+pub struct GuideOverlay {
+    pub rect: Rectangle,
+    pub guides: Vec<Guide>,
+    pub snap: SnapSettings,
+    pub color: srgb,
+    pub active_color: srgb,
+    pub horizontal: CanvasTransform,
+    pub vertical: CanvasTransform,
+    dragging: Option<usize>,
+}
+
+impl GuideOverlay {
+    pub fn new(canvas: impl IntoNode, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                sizing: Sizing::GROW,
+                children: vec![canvas.into_node()],
+                ..Default::default()
+            },
+            guides: Vec::new(),
+            snap: SnapSettings::default(),
+            color: theme.primary,
+            active_color: theme.accent,
+            horizontal: CanvasTransform::default(),
+            vertical: CanvasTransform::default(),
+            dragging: None,
+        }
+    }
+
+    pub fn dragging(&self) -> Option<&Guide> {
+        self.dragging.map(|i| &self.guides[i])
+    }
+
+    // a new guide along `axis` under `point`, held until the release
+    pub fn start_guide(&mut self, axis: Axis, point: (i32, i32)) {
+        self.guides.push(Guide {
+            axis,
+            position: self.canvas_position(axis, point),
+        });
+        self.dragging = Some(self.guides.len() - 1);
+        self.rect.mark_dirty();
+    }
+
+    // grabs the guide under `point`, if there is one
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        self.dragging = self.guide_at(point);
+        if self.dragging.is_some() {
+            self.rect.mark_dirty();
+        }
+        self.dragging.is_some()
+    }
+
+    pub fn drag(&mut self, point: (i32, i32)) {
+        let Some(i) = self.dragging else {
+            return;
+        };
+        let axis = self.guides[i].axis;
+        let position = self.canvas_position(axis, point);
+        self.guides[i].position = self.snap_value(axis, position, Some(i));
+        self.rect.mark_dirty();
+    }
+
+    // dropping a guide back onto a ruler (or outside the canvas) removes it
+    pub fn release(&mut self, point: (i32, i32)) {
+        let Some(i) = self.dragging.take() else {
+            return;
+        };
+        if !self.in_canvas(point) {
+            self.guides.remove(i);
+        }
+        self.rect.mark_dirty();
+    }
+
+    pub fn guide_at(&self, point: (i32, i32)) -> Option<usize> {
+        if !self.in_canvas(point) {
+            return None;
+        }
+        self.guides.iter().position(|g| {
+            let screen = self.screen_position(g);
+            let pointer = match g.axis {
+                Axis::Horizontal => point.1,
+                Axis::Vertical => point.0,
+            };
+            (screen - pointer as f32).abs() <= self.snap.threshold
+        })
+    }
+
+    // snaps a canvas point to nearby guides and the grid
+    pub fn snap_point(&self, point: (f32, f32)) -> (f32, f32) {
+        (
+            self.snap_value(Axis::Vertical, point.0, None),
+            self.snap_value(Axis::Horizontal, point.1, None),
+        )
+    }
+
+    fn snap_value(&self, axis: Axis, value: f32, skip: Option<usize>) -> f32 {
+        if !self.snap.enabled {
+            return value;
+        }
+        let scale = self.transform(axis).scale;
+        let threshold = self.snap.threshold / scale;
+        let guides = self
+            .guides
+            .iter()
+            .enumerate()
+            .filter(|(i, g)| self.snap.to_guides && Some(*i) != skip && g.axis == axis)
+            .map(|(_, g)| g.position);
+        let grid = self.snap.grid.map(|step| (value / step).round() * step);
+        snap(value, guides.chain(grid), threshold)
+    }
+
+    fn transform(&self, axis: Axis) -> &CanvasTransform {
+        match axis {
+            Axis::Horizontal => &self.vertical,
+            Axis::Vertical => &self.horizontal,
+        }
+    }
+
+    fn canvas_position(&self, axis: Axis, point: (i32, i32)) -> f32 {
+        let (x, y) = self.rect.position;
+        match axis {
+            Axis::Horizontal => self.vertical.to_canvas(point.1, y),
+            Axis::Vertical => self.horizontal.to_canvas(point.0, x),
+        }
+    }
+
+    fn screen_position(&self, guide: &Guide) -> f32 {
+        let (x, y) = self.rect.position;
+        match guide.axis {
+            Axis::Horizontal => self.vertical.to_screen(guide.position, y),
+            Axis::Vertical => self.horizontal.to_screen(guide.position, x),
+        }
+    }
+
+    fn in_canvas(&self, point: (i32, i32)) -> bool {
+        let (x, y) = self.rect.position;
+        point.0 >= x
+            && point.0 < x + self.rect.width
+            && point.1 >= y
+            && point.1 < y + self.rect.height
+    }
+}
+
+impl Primative for GuideOverlay {
+    delegate_primative!(rect);
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || cx.phase() != Phase::Capture {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } => self.press(*position),
+            Event::PointerMoved { position } if self.dragging.is_some() => {
+                self.drag(*position);
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if self.dragging.is_some() => {
+                self.release(*position);
+                true
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    // the canvas, then the guides over it
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record_children(list, size);
+        list.mesh(self.get_mesh(size));
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    // just the lines, the canvas shows through
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let mut mesh = Mesh::default();
+        let (x, y) = self.rect.position;
+        let (w, h) = (self.rect.width, self.rect.height);
+        for (i, guide) in self.guides.iter().enumerate() {
+            let color = if self.dragging == Some(i) {
+                self.active_color
+            } else {
+                self.color
+            };
+            let screen = self.screen_position(guide) as i32;
+            let line = match guide.axis {
+                Axis::Horizontal if screen >= y && screen < y + h => Some((x, screen, w, 1)),
+                Axis::Vertical if screen >= x && screen < x + w => Some((screen, y, 1, h)),
+                _ => None,
+            };
+            if let Some((lx, ly, lw, lh)) = line {
+                mesh.append(make_ss_rectangle(lx, ly, lw, lh, color, size));
+            }
+        }
+        mesh
+    }
+}
+
+impl Container for GuideOverlay {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        builder::Node,
+        event::{Event, PointerButton},
+        input::Modifiers,
+        layout::{Axis, LayoutMode, Primative, Rectangle, Sizing, UI},
+        theme::Theme,
+    };
+
+    use super::{GuideOverlay, Ruler};
+
+    fn pointer(ui: &mut UI, press: (i32, i32), release: (i32, i32)) {
+        ui.dispatch_event(&Event::PointerPressed {
+            button: PointerButton::Primary,
+            position: press,
+            modifiers: Modifiers::default(),
+        });
+        ui.dispatch_event(&Event::PointerMoved { position: release });
+        ui.dispatch_event(&Event::PointerReleased {
+            button: PointerButton::Primary,
+            position: release,
+            modifiers: Modifiers::default(),
+        });
+    }
+
+    #[test]
+    fn guides_drag_out_of_the_ruler_and_back_into_it() {
+        let theme = Theme::default();
+        let overlay = Arc::new(Mutex::new(GuideOverlay::new(
+            Rectangle {
+                sizing: Sizing::GROW,
+                ..Default::default()
+            },
+            &theme,
+        )));
+        let mut ruler = Ruler::new(Axis::Horizontal, 20, &theme).with_guides(overlay.clone());
+        ruler.set_min_width(800);
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            layout_mode: LayoutMode::TopToBottom,
+            children: vec![Arc::new(Mutex::new(ruler)) as Node, overlay.clone() as Node],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+
+        // the canvas starts under the 20 pixel ruler
+        pointer(&mut ui, (100, 10), (100, 150));
+        let guides = overlay.lock().unwrap().guides.clone();
+        assert_eq!(guides.len(), 1);
+        assert_eq!(guides[0].axis, Axis::Horizontal);
+        assert_eq!(guides[0].position, 130.0);
+
+        pointer(&mut ui, (200, 151), (200, 5));
+        assert!(overlay.lock().unwrap().guides.is_empty());
+    }
+
+    #[test]
+    fn a_ruler_with_no_scale_draws_without_ticks() {
+        let mut ruler = Ruler::new(Axis::Horizontal, 20, &Theme::default());
+        ruler.set_width(400);
+        ruler.set_height(20);
+        let plain = ruler.rect.get_mesh((200, 200)).indices.len();
+        for scale in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            ruler.transform.scale = scale;
+            assert_eq!(ruler.get_mesh((200, 200)).indices.len(), plain);
+        }
+    }
+}
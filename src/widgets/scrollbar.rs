@@ -0,0 +1,204 @@
+use std::time::{Duration, Instant};
+
+use tinycolors::srgb;
+
+use crate::{
+    layout::Axis,
+    platform::{Conventions, ScrollbarVisibility},
+    renderer::mesh_builder::{Mesh, make_ss_rounded_rectangle},
+    theme::Theme,
+};
+
+// how long overlay scrollbars stay up after the last scroll or hover, then how
+// long they take to fade out
+pub const AUTO_HIDE_DELAY: Duration = Duration::from_millis(800);
+pub const AUTO_HIDE_FADE: Duration = Duration::from_millis(250);
+
+// the thumb never gets shorter than this, however long the content
+const MIN_THUMB: i32 = 40;
+
+// the strip one scrollbar runs along and what it scrolls, in layout pixels.
+// worked out by the container from its bounds whenever it's needed
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollTrack {
+    // where the strip starts across the axis, e.g. its left edge for a
+    // vertical bar
+    pub across: i32,
+    // where it starts and how long it is along the axis
+    pub start: i32,
+    pub length: i32,
+    pub viewport: i32,
+    pub content: i32,
+    pub offset: i32,
+}
+
+impl ScrollTrack {
+    pub fn max_scroll(&self) -> i32 {
+        (self.content - self.viewport).max(0)
+    }
+}
+
+// the draggable thumb for one axis of a `ScrollContainer`. it's sized by how
+// much of the content fits in the viewport and, for overlay scrollbars, fades
+// out once scrolling stops
+pub struct Scrollbar {
+    pub axis: Axis,
+    pub thickness: i32,
+    // gap between the bar and the container's edge
+    pub margin: i32,
+    pub color: srgb,
+    pub drag_color: srgb,
+    pub visibility: ScrollbarVisibility,
+    last_activity: Option<Instant>,
+    // distance from the start of the thumb to where it was grabbed
+    grab: Option<i32>,
+}
+
+impl Scrollbar {
+    pub fn new(axis: Axis, theme: &Theme) -> Self {
+        Self {
+            axis,
+            thickness: 12,
+            margin: 4,
+            color: theme.inactive,
+            drag_color: theme.text,
+            visibility: Conventions::default().scrollbars,
+            last_activity: None,
+            grab: None,
+        }
+    }
+
+    // shows the bar again and restarts the hide delay
+    pub fn touch(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+    }
+
+    // keeps showing and dragging where `previous` left off, when the tree is
+    // rebuilt
+    pub(crate) fn adopt_state(&mut self, previous: &Scrollbar) {
+        self.last_activity = previous.last_activity;
+        self.grab = previous.grab;
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    // start and length of the thumb along the axis
+    pub fn thumb(&self, track: &ScrollTrack) -> (i32, i32) {
+        let length = if track.content > 0 {
+            (track.length as i64 * track.viewport as i64 / track.content as i64) as i32
+        } else {
+            track.length
+        };
+        let length = length.clamp(MIN_THUMB.min(track.length), track.length.max(0));
+        let travel = track.length - length;
+        let max = track.max_scroll();
+        let start = if max > 0 {
+            track.start + (travel as i64 * track.offset as i64 / max as i64) as i32
+        } else {
+            track.start
+        };
+        (start, length)
+    }
+
+    pub fn thumb_bounds(&self, track: &ScrollTrack) -> (i32, i32, i32, i32) {
+        let (start, length) = self.thumb(track);
+        match self.axis {
+            Axis::Vertical => (track.across, start, self.thickness, length),
+            Axis::Horizontal => (start, track.across, length, self.thickness),
+        }
+    }
+
+    // the whole strip, margin included, so the thin bar is easy to hit
+    pub fn in_track(&self, track: &ScrollTrack, point: (i32, i32)) -> bool {
+        let (along, across) = self.split(point);
+        along >= track.start
+            && along < track.start + track.length
+            && across >= track.across - self.margin
+            && across < track.across + self.thickness + self.margin
+    }
+
+    // grabbing the thumb keeps it under the pointer, pressing elsewhere on the
+    // track centers it there first. returns the new scroll offset, or `None`
+    // when the press missed
+    pub fn press(&mut self, track: &ScrollTrack, point: (i32, i32), now: Instant) -> Option<i32> {
+        if track.max_scroll() == 0 || !self.in_track(track, point) {
+            return None;
+        }
+        self.touch(now);
+        let (along, _) = self.split(point);
+        let (start, length) = self.thumb(track);
+        if along >= start && along < start + length {
+            self.grab = Some(along - start);
+            Some(track.offset)
+        } else {
+            self.grab = Some(length / 2);
+            self.drag(track, point, now)
+        }
+    }
+
+    // the scroll offset that puts the grabbed spot under `point`
+    pub fn drag(&mut self, track: &ScrollTrack, point: (i32, i32), now: Instant) -> Option<i32> {
+        let grab = self.grab?;
+        self.touch(now);
+        let (along, _) = self.split(point);
+        let (_, length) = self.thumb(track);
+        let travel = (track.length - length).max(1);
+        let moved = (along - grab - track.start).clamp(0, travel);
+        Some((moved as i64 * track.max_scroll() as i64 / travel as i64) as i32)
+    }
+
+    pub fn release(&mut self, now: Instant) {
+        if self.grab.take().is_some() {
+            self.touch(now);
+        }
+    }
+
+    // hovering the track keeps an overlay bar from hiding
+    pub fn hover(&mut self, track: &ScrollTrack, point: (i32, i32), now: Instant) {
+        if track.max_scroll() > 0 && self.in_track(track, point) {
+            self.touch(now);
+        }
+    }
+
+    pub fn opacity(&self, now: Instant) -> f32 {
+        if self.visibility == ScrollbarVisibility::Always || self.is_dragging() {
+            return 1.0;
+        }
+        let Some(last_activity) = self.last_activity else {
+            return 0.0;
+        };
+        let idle = now.saturating_duration_since(last_activity);
+        let fading = idle.saturating_sub(AUTO_HIDE_DELAY);
+        1.0 - (fading.as_secs_f32() / AUTO_HIDE_FADE.as_secs_f32()).min(1.0)
+    }
+
+    // nothing when the content fits or the bar has faded out
+    pub fn get_mesh(&self, track: &ScrollTrack, now: Instant, size: (i32, i32)) -> Option<Mesh> {
+        let opacity = self.opacity(now);
+        if track.max_scroll() == 0 || opacity <= 0.0 {
+            return None;
+        }
+        let color = if self.is_dragging() {
+            self.drag_color
+        } else {
+            self.color
+        };
+        let (x, y, w, h) = self.thumb_bounds(track);
+        let mut mesh =
+            make_ss_rounded_rectangle(x, y, w, h, self.thickness as f32 / 2.0, color, size);
+        if opacity < 1.0 {
+            mesh.multiply_alpha(opacity);
+        }
+        Some(mesh)
+    }
+
+    // a point as (along the axis, across it)
+    fn split(&self, point: (i32, i32)) -> (i32, i32) {
+        match self.axis {
+            Axis::Vertical => (point.1, point.0),
+            Axis::Horizontal => (point.0, point.1),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    input::Key,
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+        text::Font,
+    },
+    text,
+    theme::Theme,
+};
+
+type ChangeCallback = Box<dyn FnMut(usize) + Send>;
+
+// space kept between a label and the edges of its segment
+const LABEL_PADDING: i32 = 8;
+
+pub struct SegmentedControl {
+    pub rect: Rectangle,
+    pub segments: Vec<String>,
+    pub selected: usize,
+    pub hovered: Option<usize>,
+    pub selected_color: srgb,
+    pub hover_color: srgb,
+    pub separator_color: srgb,
+    pub text_color: srgb,
+    pub selected_text_color: srgb,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub inset: i32,
+    on_change: Option<ChangeCallback>,
+}
+
+impl SegmentedControl {
+    pub fn new(segments: Vec<String>, font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        let mut control = Self {
+            rect: Rectangle {
+                min_width: 80 * segments.len() as i32,
+                min_height: 32,
+                ..Default::default()
+            },
+            segments,
+            selected: 0,
+            hovered: None,
+            selected_color: srgb::default(),
+            hover_color: srgb::default(),
+            separator_color: srgb::default(),
+            text_color: srgb::default(),
+            selected_text_color: srgb::default(),
+            font,
+            font_size,
+            inset: 2,
+            on_change: None,
+        };
+        control.apply_theme(theme);
+        control.text_scale_changed();
+        control
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.rect.fill = Fill::Solid(theme.surface);
+        self.selected_color = theme.primary;
+        self.hover_color = theme.hover;
+        self.separator_color = theme.border;
+        self.text_color = theme.text;
+        self.selected_text_color = theme.on_primary;
+    }
+
+    fn px(&self) -> f32 {
+        text::scaled(self.font_size)
+    }
+
+    pub fn on_change(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index >= self.segments.len() || index == self.selected {
+            return;
+        }
+        self.selected = index;
+        if let Some(on_change) = &mut self.on_change {
+            on_change(index);
+        }
+    }
+
+    pub fn segment_at(&self, point: (i32, i32)) -> Option<usize> {
+        let (x, y) = self.rect.position;
+        if self.segments.is_empty()
+            || point.0 < x
+            || point.0 >= x + self.rect.width
+            || point.1 < y
+            || point.1 >= y + self.rect.height
+        {
+            return None;
+        }
+        let index = ((point.0 - x) * self.segments.len() as i32 / self.rect.width.max(1)) as usize;
+        Some(index.min(self.segments.len() - 1))
+    }
+
+    pub fn hover(&mut self, point: (i32, i32)) {
+        self.hovered = self.segment_at(point);
+    }
+
+    pub fn click(&mut self, point: (i32, i32)) {
+        if let Some(i) = self.segment_at(point) {
+            self.select(i);
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Left if self.selected > 0 => self.select(self.selected - 1),
+            Key::Right => self.select(self.selected + 1),
+            Key::Home => self.select(0),
+            Key::End => self.select(self.segments.len().saturating_sub(1)),
+            _ => return false,
+        }
+        true
+    }
+
+    fn segment_bounds(&self, index: usize) -> (i32, i32, i32, i32) {
+        let count = self.segments.len().max(1) as i32;
+        let (x, y) = self.rect.position;
+        let start = x + self.rect.width * index as i32 / count;
+        let end = x + self.rect.width * (index as i32 + 1) / count;
+        (start, y, end - start, self.rect.height)
+    }
+}
+
+impl Primative for SegmentedControl {
+    delegate_primative!(rect);
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let (hovered, selected) = (self.hovered, self.selected);
+        let handled = match (cx.phase(), cx.event) {
+            (Phase::Exit, Event::PointerMoved { .. } | Event::PointerLeft) => {
+                self.hovered = None;
+                false
+            }
+            (Phase::Target | Phase::Bubble, Event::PointerMoved { position }) => {
+                self.hover(*position);
+                false
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerReleased {
+                    button: PointerButton::Primary,
+                    position,
+                    ..
+                },
+            ) => {
+                self.click(*position);
+                self.segment_at(*position).is_some()
+            }
+            (Phase::Target | Phase::Bubble, Event::KeyPressed { key, .. }) => self.handle_key(*key),
+            _ => false,
+        };
+        if (self.hovered, self.selected) != (hovered, selected) {
+            self.rect.mark_dirty();
+        }
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    // tall enough for a line of text, and never shorter than the default 32
+    fn text_scale_changed(&mut self) {
+        let line = self.font.line_height(self.px()).ceil() as i32;
+        self.rect.set_min_height((line + LABEL_PADDING * 2).max(32));
+    }
+
+    // the segments, then their labels centered on them. a label too wide for
+    // its segment is ellipsized
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.get_mesh(size));
+        let px = self.px();
+        let line = self.font.line_height(px);
+        let measure = |text: &str, px| self.font.measure(text, px).0;
+        for (i, label) in self.segments.iter().enumerate() {
+            let (x, y, w, h) = self.segment_bounds(i);
+            let label = text::ellipsize(label, (w - LABEL_PADDING * 2) as f32, px, measure);
+            let width = measure(&label, px);
+            let color = if i == self.selected {
+                self.selected_text_color
+            } else {
+                self.text_color
+            };
+            list.text(
+                &self.font,
+                &label,
+                px,
+                (
+                    x as f32 + ((w as f32 - width) / 2.0).floor(),
+                    y as f32 + ((h as f32 - line) / 2.0).floor(),
+                ),
+                color,
+            );
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let mut mesh = self.rect.get_mesh(size);
+        let inset = self.inset;
+
+        for i in 1..self.segments.len() {
+            if i == self.selected || i == self.selected + 1 {
+                continue;
+            }
+            let (x, y, _, h) = self.segment_bounds(i);
+            mesh.append(make_ss_rectangle(
+                x,
+                y + inset * 2,
+                1,
+                h - inset * 4,
+                self.separator_color,
+                size,
+            ));
+        }
+
+        if let Some(hovered) = self.hovered.filter(|h| *h != self.selected) {
+            let (x, y, w, h) = self.segment_bounds(hovered);
+            mesh.append(make_ss_rectangle(
+                x + inset,
+                y + inset,
+                w - inset * 2,
+                h - inset * 2,
+                self.hover_color,
+                size,
+            ));
+        }
+
+        if self.selected < self.segments.len() {
+            let (x, y, w, h) = self.segment_bounds(self.selected);
+            mesh.append(make_ss_rectangle(
+                x + inset,
+                y + inset,
+                w - inset * 2,
+                h - inset * 2,
+                self.selected_color,
+                size,
+            ));
+        }
+        mesh
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    animation::Spring,
+    builder::IntoNode,
+    context,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    input::{Key, VelocityTracker},
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{draw_list::DrawList, image::ImageData, mesh_builder::Mesh},
+    state_machine::{Interaction, InteractionEvent, StateMachine},
+};
+
+// how far ahead a release is projected along its velocity when picking the
+// detent to settle on
+const FLING_PROJECTION: f32 = 0.2;
+// pointer travel before a press on the panel becomes a drag
+const DRAG_THRESHOLD: i32 = 8;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SheetEdge {
+    #[default]
+    Bottom,
+    Left,
+    Right,
+}
+
+impl SheetEdge {
+    fn axis(self) -> Axis {
+        match self {
+            SheetEdge::Bottom => Axis::Vertical,
+            SheetEdge::Left | SheetEdge::Right => Axis::Horizontal,
+        }
+    }
+
+    // pointer movement along the axis that opens the sheet further
+    fn opening_delta(self, from: (i32, i32), to: (i32, i32)) -> i32 {
+        match self {
+            SheetEdge::Bottom => from.1 - to.1,
+            SheetEdge::Left => to.0 - from.0,
+            SheetEdge::Right => from.0 - to.0,
+        }
+    }
+}
+
+struct SheetDrag {
+    origin: (i32, i32),
+    start: f32,
+    velocity: VelocityTracker,
+}
+
+// a panel that slides in from an edge over `content`. it is dragged between
+// `detents`, fractions of the panel's size where 0 is closed and 1 fully
+// open, and settles on one with a spring after a release or fling. a scrim
+// darkens the content as the sheet opens
+pub struct Sheet {
+    // holds the page behind the sheet
+    pub rect: Rectangle,
+    pub panel: Rectangle,
+    pub edge: SheetEdge,
+    // sorted ascending
+    pub detents: Vec<f32>,
+    // close when the scrim is pressed
+    pub dismissible: bool,
+    pub scrim_opacity: f32,
+    scrim: Arc<ImageData>,
+    spring: Spring,
+    interaction: StateMachine<Interaction, InteractionEvent>,
+    drag: Option<SheetDrag>,
+    last_step: Option<Instant>,
+}
+
+impl Sheet {
+    pub fn new(content: impl IntoNode, panel: Rectangle) -> Self {
+        Self {
+            rect: Rectangle {
+                sizing: Sizing::GROW,
+                children: vec![content.into_node()],
+                ..Default::default()
+            },
+            panel,
+            edge: SheetEdge::Bottom,
+            detents: vec![0.0, 0.5, 1.0],
+            dismissible: true,
+            scrim_opacity: 0.5,
+            scrim: scrim_image(srgb {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            }),
+            spring: Spring::new(0.0),
+            interaction: Interaction::machine(),
+            drag: None,
+            last_step: None,
+        }
+    }
+
+    pub fn set_scrim_color(&mut self, color: srgb) {
+        self.scrim = scrim_image(color);
+    }
+
+    // how far open the sheet is right now, 0 closed to 1 fully open
+    pub fn openness(&self) -> f32 {
+        self.spring.value()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.openness() > 0.0 || self.spring.target() > 0.0
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.drag.is_none() && self.spring.is_settled()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.interaction.is(&Interaction::Dragging)
+    }
+
+    // the detent the sheet is resting at or heading to
+    pub fn detent(&self) -> Option<usize> {
+        self.detents
+            .iter()
+            .position(|detent| *detent == self.spring.target())
+    }
+
+    pub fn snap_to(&mut self, index: usize) {
+        if let Some(detent) = self.detents.get(index) {
+            self.spring.set_target(*detent);
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.spring.set_target(self.max_detent());
+    }
+
+    pub fn close(&mut self) {
+        self.spring.set_target(0.0);
+    }
+
+    // returns true when the press landed on the sheet or its scrim and should
+    // be captured until release
+    pub fn press(&mut self, point: (i32, i32)) -> bool {
+        if self.panel_contains(point) {
+            self.interaction.handle(&InteractionEvent::PointerEnter);
+            self.interaction.handle(&InteractionEvent::Press);
+            let mut velocity = VelocityTracker::new();
+            velocity.add(Instant::now(), (self.openness(), 0.0));
+            self.drag = Some(SheetDrag {
+                origin: point,
+                start: self.openness(),
+                velocity,
+            });
+            return true;
+        }
+        if self.is_open() && self.dismissible && self.contains(point) {
+            self.close();
+            return true;
+        }
+        false
+    }
+
+    pub fn drag(&mut self, point: (i32, i32)) {
+        let max = self.max_detent();
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+        let delta = self.edge.opening_delta(drag.origin, point);
+        if self.interaction.is(&Interaction::Pressed) && delta.abs() < DRAG_THRESHOLD {
+            return;
+        }
+        self.interaction.handle(&InteractionEvent::DragStart);
+
+        let extent = panel_extent(&self.panel, self.edge).max(1) as f32;
+        let openness = (drag.start + delta as f32 / extent).clamp(0.0, max);
+        self.spring.snap(openness);
+
+        drag.velocity.add(Instant::now(), (openness, 0.0));
+    }
+
+    // settles on the detent nearest to where the release was heading
+    pub fn release(&mut self, point: (i32, i32)) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        let inside = self.panel_contains(point);
+        self.interaction
+            .handle(&InteractionEvent::Release { inside });
+        if !inside {
+            self.interaction.handle(&InteractionEvent::PointerLeave);
+        }
+
+        let (velocity, _) = drag.velocity.velocity(Instant::now());
+        let projected = self.openness() + velocity * FLING_PROJECTION;
+        let target = self
+            .detents
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - projected).abs().total_cmp(&(b - projected).abs()))
+            .unwrap_or(0.0);
+        self.spring.fling(target, velocity);
+    }
+
+    pub fn contains(&self, point: (i32, i32)) -> bool {
+        rect_contains(&self.rect, point)
+    }
+
+    pub fn panel_contains(&self, point: (i32, i32)) -> bool {
+        self.openness() > 0.0 && rect_contains(&self.panel, point)
+    }
+
+    fn max_detent(&self) -> f32 {
+        self.detents.last().copied().unwrap_or(1.0)
+    }
+
+    fn step(&mut self) {
+        let now = context::frame_now();
+        let dt = self.last_step.map_or(Duration::ZERO, |last| now - last);
+        self.last_step = Some(now);
+        if self.drag.is_none() {
+            self.spring.step(dt);
+        }
+        // layout stops running once settled, so the next animation starts fresh
+        if self.is_settled() {
+            self.last_step = None;
+        }
+    }
+
+    fn panel_position(&self) -> (i32, i32) {
+        let (x, y) = self.rect.position;
+        let extent = panel_extent(&self.panel, self.edge);
+        let shown = (extent as f32 * self.openness()).round() as i32;
+        match self.edge {
+            SheetEdge::Bottom => (x, y + self.rect.height - shown),
+            SheetEdge::Left => (x - extent + shown, y),
+            SheetEdge::Right => (x + self.rect.width - shown, y),
+        }
+    }
+}
+
+fn panel_extent(panel: &Rectangle, edge: SheetEdge) -> i32 {
+    panel.get_size_along_axis(edge.axis())
+}
+
+fn rect_contains(rect: &Rectangle, point: (i32, i32)) -> bool {
+    let (x, y) = rect.position;
+    point.0 >= x && point.0 < x + rect.width && point.1 >= y && point.1 < y + rect.height
+}
+
+fn scrim_image(color: srgb) -> Arc<ImageData> {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    ImageData::from_rgba(
+        1,
+        1,
+        vec![channel(color.r), channel(color.g), channel(color.b), 255],
+    )
+    .expect("one pixel of rgba")
+}
+
+impl Primative for Sheet {
+    delegate_primative!(rect, sizing);
+
+    // laid out every frame while it moves
+    fn is_dirty(&self) -> bool {
+        !self.is_settled() || self.rect.is_dirty() || self.panel.is_dirty()
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+        self.panel.clear_dirty();
+    }
+
+    // the sheet and its scrim cover the page, so they take the pointer on the
+    // way down. escape closes a dismissible sheet if nothing else used it
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if cx.phase() == Phase::Capture => self.press(*position),
+            Event::PointerMoved { position }
+                if cx.phase() == Phase::Capture && self.drag.is_some() =>
+            {
+                self.drag(*position);
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                position,
+                ..
+            } if cx.phase() == Phase::Capture && self.drag.is_some() => {
+                self.release(*position);
+                true
+            }
+            Event::KeyPressed {
+                key: Key::Escape, ..
+            } if matches!(cx.phase(), Phase::Target | Phase::Bubble)
+                && self.dismissible
+                && self.is_open() =>
+            {
+                self.close();
+                true
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        self.rect.record(list, size);
+        let openness = self.openness();
+        if openness <= 0.0 {
+            return;
+        }
+        let max = self.max_detent().max(f32::EPSILON);
+        list.image(
+            &self.scrim,
+            self.rect.position,
+            (self.rect.width, self.rect.height),
+            1.0,
+            self.scrim_opacity * (openness / max).min(1.0),
+        );
+        list.push_clip(self.rect.position, (self.rect.width, self.rect.height));
+        self.panel.record(list, size);
+        list.pop_clip();
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for Sheet {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+        self.panel.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+
+        // the panel spans the edge it's attached to and keeps its own depth,
+        // up to the size of the page
+        let axis = self.edge.axis();
+        let depth = match self.panel.get_sizing_along_axis(axis) {
+            SizingMode::Fixed(depth) => *depth,
+            SizingMode::Grow => self.rect.get_size_along_axis(axis),
+            SizingMode::Fit => self.panel.get_size_along_axis(axis),
+        };
+        self.panel
+            .set_size_along_axis(axis, depth.min(self.rect.get_size_along_axis(axis)));
+        self.panel
+            .set_size_along_axis(!axis, self.rect.get_size_along_axis(!axis));
+        self.panel.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.step();
+        self.rect.set_child_positions();
+        self.panel.set_position(self.panel_position());
+        self.panel.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
@@ -0,0 +1,240 @@
+use tinycolors::srgb;
+
+use crate::{
+    builder::IntoNode,
+    layout::{Axis, Container, Primative, Rectangle, Sizing, SizingMode, delegate_primative},
+    renderer::{draw_list::DrawList, mesh_builder::Mesh, skeleton::SkeletonInstance},
+    theme::Theme,
+};
+
+// the outline a placeholder takes, in layout pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SkeletonShape {
+    Rect {
+        radius: f32,
+    },
+    // the largest circle that fits, centered
+    Circle,
+    // stacked bars with a shorter last line, like a paragraph
+    TextLines {
+        lines: usize,
+        line_height: i32,
+        gap: i32,
+    },
+}
+
+impl Default for SkeletonShape {
+    fn default() -> Self {
+        SkeletonShape::Rect { radius: 8.0 }
+    }
+}
+
+impl SkeletonShape {
+    pub fn blocks(
+        &self,
+        position: (i32, i32),
+        extent: (i32, i32),
+        base: srgb,
+        highlight: srgb,
+    ) -> Vec<SkeletonInstance> {
+        let (x, y) = (position.0 as f32, position.1 as f32);
+        let (width, height) = (extent.0 as f32, extent.1 as f32);
+        match *self {
+            SkeletonShape::Rect { radius } => {
+                vec![SkeletonInstance::new(
+                    (x, y),
+                    (width, height),
+                    radius,
+                    base,
+                    highlight,
+                )]
+            }
+            SkeletonShape::Circle => {
+                let diameter = width.min(height);
+                vec![SkeletonInstance::new(
+                    (x + (width - diameter) / 2.0, y + (height - diameter) / 2.0),
+                    (diameter, diameter),
+                    diameter / 2.0,
+                    base,
+                    highlight,
+                )]
+            }
+            SkeletonShape::TextLines {
+                lines,
+                line_height,
+                gap,
+            } => (0..lines)
+                .map(|line| {
+                    let top = y + (line_height + gap) as f32 * line as f32;
+                    let width = if line + 1 == lines && lines > 1 {
+                        width * 0.6
+                    } else {
+                        width
+                    };
+                    SkeletonInstance::new(
+                        (x, top),
+                        (width, line_height as f32),
+                        line_height as f32 / 4.0,
+                        base,
+                        highlight,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    // the height text lines need, 0 for shapes that take any size
+    fn min_height(&self) -> i32 {
+        match *self {
+            SkeletonShape::TextLines {
+                lines,
+                line_height,
+                gap,
+            } => lines as i32 * line_height + (lines as i32 - 1).max(0) * gap,
+            _ => 0,
+        }
+    }
+}
+
+// a shimmering placeholder standing in for content that hasn't loaded yet
+pub struct Skeleton {
+    pub rect: Rectangle,
+    pub shape: SkeletonShape,
+    pub base_color: srgb,
+    pub highlight_color: srgb,
+}
+
+impl Skeleton {
+    pub fn new(shape: SkeletonShape, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                min_height: shape.min_height(),
+                ..Default::default()
+            },
+            shape,
+            base_color: theme.surface,
+            highlight_color: theme.hover,
+        }
+    }
+
+    pub fn text_lines(lines: usize, line_height: i32, theme: &Theme) -> Self {
+        Self::new(
+            SkeletonShape::TextLines {
+                lines,
+                line_height,
+                gap: line_height / 2,
+            },
+            theme,
+        )
+    }
+}
+
+impl Primative for Skeleton {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, _size: (i32, i32)) {
+        list.skeletons(self.shape.blocks(
+            self.rect.position,
+            (self.rect.width, self.rect.height),
+            self.base_color,
+            self.highlight_color,
+        ));
+    }
+
+    // drawn by the skeleton pipeline, not as colored geometry
+    fn get_mesh(&self, _size: (i32, i32)) -> Mesh {
+        Mesh::default()
+    }
+}
+
+// wraps a subtree that may still be loading. the subtree is always laid out,
+// so while `loading` each child is replaced by a placeholder of its own size
+// and nothing jumps once the content arrives
+pub struct Loading {
+    pub rect: Rectangle,
+    pub loading: bool,
+    pub shape: SkeletonShape,
+    pub base_color: srgb,
+    pub highlight_color: srgb,
+}
+
+impl Loading {
+    pub fn new(content: impl IntoNode, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                children: vec![content.into_node()],
+                ..Default::default()
+            },
+            loading: true,
+            shape: SkeletonShape::default(),
+            base_color: theme.surface,
+            highlight_color: theme.hover,
+        }
+    }
+
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading;
+    }
+}
+
+impl Primative for Loading {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        if !self.loading {
+            self.rect.record(list, size);
+            return;
+        }
+
+        list.mesh(self.rect.get_mesh(size));
+        let blocks: Vec<SkeletonInstance> = self
+            .rect
+            .children
+            .iter()
+            .filter_map(|child| child.lock().ok())
+            .flat_map(|prim| {
+                self.shape.blocks(
+                    prim.get_position(),
+                    (prim.get_width(), prim.get_height()),
+                    self.base_color,
+                    self.highlight_color,
+                )
+            })
+            .collect();
+        list.skeletons(blocks);
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
+
+impl Container for Loading {
+    fn fit_sizing(&mut self) {
+        self.rect.fit_sizing();
+    }
+
+    fn grow_sizing(&mut self) {
+        self.rect.grow_sizing();
+    }
+
+    fn set_child_positions(&mut self) {
+        self.rect.set_child_positions();
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
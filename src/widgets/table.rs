@@ -0,0 +1,1085 @@
+use std::{
+    borrow::Cow,
+    ops::Range,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    binding::CollectionChange,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    input::{self, Key, Modifiers},
+    layout::{
+        Axis, Container, Primative, Rectangle, SCROLL_LINE, Sizing, SizingMode, delegate_primative,
+    },
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+        text::Font,
+    },
+    selection::{Marquee, SELECTION_ALPHA, SelectionMode, SelectionModel},
+    text,
+    theme::{Theme, rgb},
+    widgets::cell_editor::CellEditor,
+};
+
+const RESIZE_GRAB_DISTANCE: i32 = 4;
+
+pub struct Column {
+    pub title: String,
+    pub width: i32,
+    pub editor: Option<Box<dyn CellEditor>>,
+}
+
+impl Column {
+    pub fn new(title: &str, width: i32) -> Self {
+        Self {
+            title: title.to_string(),
+            width,
+            editor: None,
+        }
+    }
+
+    pub fn with_editor(mut self, editor: impl CellEditor + 'static) -> Self {
+        self.editor = Some(Box::new(editor));
+        self
+    }
+}
+
+#[derive(Default)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Text(String),
+    Element(Arc<Mutex<dyn Primative>>),
+}
+
+type EditCallback = Box<dyn FnMut(usize, usize, &str) + Send>;
+
+// a line of cell or header text as it's drawn, with its top left corner
+type TextRun<'a> = (Cow<'a, str>, (f32, f32));
+
+// layout pixels, position then extent
+type Clip = ((i32, i32), (i32, i32));
+
+struct RowResize {
+    row: usize,
+    start_y: i32,
+    start_height: i32,
+}
+
+pub struct Table {
+    pub rect: Rectangle,
+    pub columns: Vec<Column>,
+    pub header_height: i32,
+    pub default_row_height: i32,
+    pub min_row_height: i32,
+    pub resizable_rows: bool,
+    // leading columns that stay put while the rest scroll horizontally
+    pub frozen_columns: usize,
+    pub header_color: srgb,
+    pub frozen_color: srgb,
+    pub grid_color: srgb,
+    pub resize_color: srgb,
+    pub selection_color: srgb,
+    pub editing_color: srgb,
+    pub error_color: srgb,
+    pub text_color: srgb,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub cell_padding: i32,
+    // show the full text of ellipsized cells as a tooltip
+    pub truncation_tooltips: bool,
+    // the selected rows. the focused one holds the selected cell, in multi
+    // mode dragging across rows selects them with a marquee
+    pub selection: SelectionModel,
+    row_heights: Vec<i32>,
+    cells: Vec<Vec<Cell>>,
+    scroll: (i32, i32),
+    resizing: Option<RowResize>,
+    // the column of the selected cell
+    column: usize,
+    marquee: Option<Marquee>,
+    editing: Option<(usize, usize)>,
+    edit_error: Option<String>,
+    last_click: Option<(Instant, (usize, usize))>,
+    on_edit: Option<EditCallback>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>, font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                sizing: Sizing::GROW,
+                fill: Fill::Solid(theme.surface),
+                ..Default::default()
+            },
+            columns,
+            header_height: 28,
+            default_row_height: 24,
+            min_row_height: 12,
+            resizable_rows: true,
+            frozen_columns: 0,
+            header_color: theme.hover,
+            frozen_color: theme.background,
+            grid_color: theme.border,
+            resize_color: theme.primary,
+            selection_color: theme.primary,
+            editing_color: theme.pressed,
+            error_color: rgb(0.9, 0.2, 0.2),
+            text_color: theme.text,
+            font,
+            font_size,
+            cell_padding: 6,
+            truncation_tooltips: true,
+            selection: SelectionModel::new(SelectionMode::Multi),
+            row_heights: Vec::new(),
+            cells: Vec::new(),
+            scroll: (0, 0),
+            resizing: None,
+            column: 0,
+            marquee: None,
+            editing: None,
+            edit_error: None,
+            last_click: None,
+            on_edit: None,
+        }
+    }
+
+    // called with (row, column, new value) whenever an edit is committed
+    pub fn on_edit(mut self, f: impl FnMut(usize, usize, &str) + Send + 'static) -> Self {
+        self.on_edit = Some(Box::new(f));
+        self
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn push_row(&mut self, cells: Vec<Cell>) {
+        self.cells.push(cells);
+        self.row_heights.push(self.default_row_height);
+        self.rect.mark_dirty();
+    }
+
+    pub fn insert_row(&mut self, index: usize, cells: Vec<Cell>) {
+        self.cells.insert(index, cells);
+        self.row_heights.insert(index, self.default_row_height);
+        self.selection.apply_change(CollectionChange::Insert(index));
+        self.rect.mark_dirty();
+    }
+
+    pub fn remove_row(&mut self, index: usize) -> Vec<Cell> {
+        self.row_heights.remove(index);
+        self.selection.apply_change(CollectionChange::Remove(index));
+        self.rect.mark_dirty();
+        self.cells.remove(index)
+    }
+
+    pub fn clear_rows(&mut self) {
+        self.cells.clear();
+        self.row_heights.clear();
+        self.selection.apply_change(CollectionChange::Reset);
+        self.rect.mark_dirty();
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Option<&Cell> {
+        self.cells.get(row)?.get(column)
+    }
+
+    pub fn set_cell(&mut self, row: usize, column: usize, cell: Cell) {
+        let Some(cells) = self.cells.get_mut(row) else {
+            return;
+        };
+        if cells.len() <= column {
+            cells.resize_with(column + 1, Cell::default);
+        }
+        cells[column] = cell;
+        self.rect.mark_dirty();
+    }
+
+    pub fn row_height(&self, row: usize) -> i32 {
+        self.row_heights[row]
+    }
+
+    pub fn set_row_height(&mut self, row: usize, height: i32) {
+        self.row_heights[row] = height.max(self.min_row_height);
+        self.rect.mark_dirty();
+    }
+
+    pub fn scroll(&self) -> (i32, i32) {
+        self.scroll
+    }
+
+    pub fn scroll_by(&mut self, dx: i32, dy: i32) {
+        let (max_x, max_y) = self.max_scroll();
+        self.scroll.0 = (self.scroll.0 + dx).clamp(0, max_x);
+        self.scroll.1 = (self.scroll.1 + dy).clamp(0, max_y);
+        self.rect.mark_dirty();
+    }
+
+    pub fn content_size(&self) -> (i32, i32) {
+        (
+            self.columns.iter().map(|c| c.width).sum(),
+            self.row_heights.iter().sum(),
+        )
+    }
+
+    fn max_scroll(&self) -> (i32, i32) {
+        let (w, h) = self.content_size();
+        (
+            (w - self.rect.width).max(0),
+            (h - (self.rect.height - self.header_height)).max(0),
+        )
+    }
+
+    fn frozen_width(&self) -> i32 {
+        self.columns
+            .iter()
+            .take(self.frozen_columns)
+            .map(|c| c.width)
+            .sum()
+    }
+
+    fn column_x(&self, column: usize) -> i32 {
+        let offset: i32 = self.columns.iter().take(column).map(|c| c.width).sum();
+        let scroll = if column < self.frozen_columns {
+            0
+        } else {
+            self.scroll.0
+        };
+        self.rect.position.0 + offset - scroll
+    }
+
+    fn row_y(&self, row: usize) -> i32 {
+        let offset: i32 = self.row_heights.iter().take(row).sum();
+        self.rect.position.1 + self.header_height + offset - self.scroll.1
+    }
+
+    pub fn cell_bounds(&self, row: usize, column: usize) -> Option<(i32, i32, i32, i32)> {
+        let width = self.columns.get(column)?.width;
+        let height = *self.row_heights.get(row)?;
+        Some((self.column_x(column), self.row_y(row), width, height))
+    }
+
+    pub fn cell_at(&self, point: (i32, i32)) -> Option<(usize, usize)> {
+        let (x, y) = self.rect.position;
+        if point.1 < y + self.header_height || point.0 < x || point.0 >= x + self.rect.width {
+            return None;
+        }
+        let frozen_edge = x + self.frozen_width();
+        let column = (0..self.columns.len()).find(|c| {
+            let left = self.column_x(*c);
+            let scrolled_under = *c >= self.frozen_columns && point.0 < frozen_edge;
+            !scrolled_under && point.0 >= left && point.0 < left + self.columns[*c].width
+        })?;
+        let row = (0..self.row_heights.len()).find(|r| {
+            let top = self.row_y(*r);
+            point.1 >= top && point.1 < top + self.row_heights[*r]
+        })?;
+        Some((row, column))
+    }
+
+    fn px(&self) -> f32 {
+        text::scaled(self.font_size)
+    }
+
+    fn text_width(&self, column: usize) -> f32 {
+        (self.columns[column].width - self.cell_padding * 2) as f32
+    }
+
+    fn measure(&self) -> impl Fn(&str, f32) -> f32 + '_ {
+        |text, px| self.font.measure(text, px).0
+    }
+
+    // `text` as it fits in `column`, ellipsized when too wide
+    fn fit<'a>(&self, text: &'a str, column: usize) -> Cow<'a, str> {
+        text::ellipsize(text, self.text_width(column), self.px(), self.measure())
+    }
+
+    // cell text as it fits in the column, ellipsized when too wide
+    pub fn display_text(&self, row: usize, column: usize) -> Option<Cow<'_, str>> {
+        let Some(Cell::Text(text)) = self.cell(row, column) else {
+            return None;
+        };
+        Some(self.fit(text, column))
+    }
+
+    // where text starts in a box `height` tall with its top left at (x, y),
+    // centered vertically
+    fn text_origin(&self, (x, y): (i32, i32), height: i32) -> (f32, f32) {
+        let line = self.font.line_height(self.px());
+        (
+            (x + self.cell_padding) as f32,
+            y as f32 + ((height as f32 - line) / 2.0).floor(),
+        )
+    }
+
+    fn frozen_range(&self, frozen: bool) -> Range<usize> {
+        let split = self.frozen_columns.min(self.columns.len());
+        if frozen {
+            0..split
+        } else {
+            split..self.columns.len()
+        }
+    }
+
+    // the part of the body the frozen or scrolling columns show in
+    fn body_clip(&self, frozen: bool) -> Clip {
+        let (x, y) = self.rect.position;
+        let frozen_width = self.frozen_width();
+        let top = y + self.header_height;
+        let height = self.rect.height - self.header_height;
+        if frozen {
+            ((x, top), (frozen_width, height))
+        } else {
+            (
+                (x + frozen_width, top),
+                (self.rect.width - frozen_width, height),
+            )
+        }
+    }
+
+    fn header_clip(&self, frozen: bool) -> Clip {
+        let ((x, _), (w, _)) = self.body_clip(frozen);
+        ((x, self.rect.position.1), (w, self.header_height))
+    }
+
+    // the text of the visible cells in the frozen or scrolling columns. the
+    // cell being edited shows the editor's value
+    fn cell_text(&self, frozen: bool) -> Vec<TextRun<'_>> {
+        let mut runs = Vec::new();
+        for row in (0..self.row_heights.len()).filter(|row| self.is_visible(*row)) {
+            for column in self.frozen_range(frozen) {
+                let text = if self.editing == Some((row, column)) {
+                    let Some(editor) = &self.columns[column].editor else {
+                        continue;
+                    };
+                    Cow::Owned(self.fit(&editor.value(), column).into_owned())
+                } else {
+                    match self.display_text(row, column) {
+                        Some(text) => text,
+                        None => continue,
+                    }
+                };
+                let origin = (self.column_x(column), self.row_y(row));
+                runs.push((text, self.text_origin(origin, self.row_heights[row])));
+            }
+        }
+        runs
+    }
+
+    fn header_text(&self, frozen: bool) -> Vec<TextRun<'_>> {
+        self.frozen_range(frozen)
+            .map(|column| {
+                let origin = (self.column_x(column), self.rect.position.1);
+                (
+                    self.fit(&self.columns[column].title, column),
+                    self.text_origin(origin, self.header_height),
+                )
+            })
+            .collect()
+    }
+
+    // what's drawn under the cell text: the frozen columns' background, which
+    // paints over whatever scrolled underneath them, the selected rows and the
+    // cell being edited
+    fn cell_background(&self, frozen: bool, size: (i32, i32)) -> Mesh {
+        let mut mesh = Mesh::default();
+        let ((x, y), (w, h)) = self.body_clip(true);
+        if frozen && w > 0 {
+            mesh.append(make_ss_rectangle(x, y, w, h, self.frozen_color, size));
+        }
+        let ((x, _), (w, _)) = self.body_clip(frozen);
+        for &row in self.selection.selected() {
+            if row < self.row_heights.len() && self.is_visible(row) {
+                let h = self.row_heights[row];
+                let mut highlight =
+                    make_ss_rectangle(x, self.row_y(row), w, h, self.selection_color, size);
+                highlight.multiply_alpha(SELECTION_ALPHA);
+                mesh.append(highlight);
+            }
+        }
+        if let Some((row, column)) = self.editing
+            && self.frozen_range(frozen).contains(&column)
+            && let Some((x, y, w, h)) = self.cell_bounds(row, column)
+        {
+            let color = match self.edit_error {
+                Some(_) => self.error_color,
+                None => self.editing_color,
+            };
+            mesh.append(make_ss_rectangle(x, y, w, h, color, size));
+        }
+        mesh
+    }
+
+    // row and column lines, the selected cell's outline and the header
+    fn grid(&self, size: (i32, i32)) -> Mesh {
+        let (x, y) = self.rect.position;
+        let (w, h) = (self.rect.width, self.rect.height);
+        let frozen_width = self.frozen_width();
+        let mut mesh = Mesh::default();
+        for row in 0..self.row_heights.len() {
+            if !self.is_visible(row) {
+                continue;
+            }
+            let bottom = self.row_y(row) + self.row_heights[row];
+            let color = match &self.resizing {
+                Some(resize) if resize.row == row => self.resize_color,
+                _ => self.grid_color,
+            };
+            mesh.append(make_ss_rectangle(x, bottom, w, 1, color, size));
+        }
+        for column in 0..self.columns.len() {
+            let right = self.column_x(column) + self.columns[column].width;
+            if right > x
+                && right < x + w
+                && (column < self.frozen_columns || right > x + frozen_width)
+            {
+                mesh.append(make_ss_rectangle(right, y, 1, h, self.grid_color, size));
+            }
+        }
+        if let Some((row, column)) = self.selected_cell()
+            && self.is_visible(row)
+            && let Some((cx, cy, cw, ch)) = self.cell_bounds(row, column)
+        {
+            let color = self.selection_color;
+            mesh.append(make_ss_rectangle(cx, cy, cw, 2, color, size));
+            mesh.append(make_ss_rectangle(cx, cy + ch - 2, cw, 2, color, size));
+            mesh.append(make_ss_rectangle(cx, cy, 2, ch, color, size));
+            mesh.append(make_ss_rectangle(cx + cw - 2, cy, 2, ch, color, size));
+        }
+        mesh.append(make_ss_rectangle(
+            x,
+            y,
+            w,
+            self.header_height,
+            self.header_color,
+            size,
+        ));
+        mesh
+    }
+
+    fn record_text(&self, list: &mut DrawList, (position, extent): Clip, runs: Vec<TextRun>) {
+        if runs.is_empty() {
+            return;
+        }
+        list.push_clip(position, extent);
+        for (text, origin) in runs {
+            list.text(&self.font, &text, self.px(), origin, self.text_color);
+        }
+        list.pop_clip();
+    }
+
+    // the row whose bottom border is under the pointer
+    fn resize_handle_at(&self, point: (i32, i32)) -> Option<usize> {
+        let (x, y) = self.rect.position;
+        if point.0 < x || point.0 >= x + self.rect.width || point.1 < y + self.header_height {
+            return None;
+        }
+        (0..self.row_heights.len()).find(|r| {
+            let bottom = self.row_y(*r) + self.row_heights[*r];
+            (point.1 - bottom).abs() <= RESIZE_GRAB_DISTANCE
+        })
+    }
+
+    pub fn press(&mut self, point: (i32, i32), modifiers: Modifiers) -> bool {
+        if self.resizable_rows
+            && let Some(row) = self.resize_handle_at(point)
+        {
+            self.resizing = Some(RowResize {
+                row,
+                start_y: point.1,
+                start_height: self.row_heights[row],
+            });
+            return true;
+        }
+
+        let Some(cell) = self.cell_at(point) else {
+            return false;
+        };
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(time, last)| {
+            last == cell && now.duration_since(time) <= input::double_click_interval()
+        });
+        self.last_click = Some((now, cell));
+
+        if self.editing == Some(cell) {
+            return true;
+        }
+        if self.editing.is_some() && !self.commit_edit() {
+            return true;
+        }
+        self.selection.click(cell.0, modifiers);
+        self.column = cell.1;
+        if self.selection.mode == SelectionMode::Multi {
+            self.marquee = Some(Marquee::begin(point, modifiers, &self.selection));
+        }
+        if double {
+            self.last_click = None;
+            self.begin_edit(cell.0, cell.1);
+        }
+        true
+    }
+
+    // the focused row's cell in the selected column
+    pub fn selected_cell(&self) -> Option<(usize, usize)> {
+        self.selection.focus().map(|row| (row, self.column))
+    }
+
+    pub fn editing(&self) -> Option<(usize, usize)> {
+        self.editing
+    }
+
+    pub fn edit_error(&self) -> Option<&str> {
+        self.edit_error.as_deref()
+    }
+
+    pub fn select_cell(&mut self, row: usize, column: usize) {
+        if row < self.cells.len() && column < self.columns.len() {
+            self.selection.click(row, Modifiers::default());
+            self.column = column;
+        }
+    }
+
+    pub fn begin_edit(&mut self, row: usize, column: usize) -> bool {
+        let value = match self.cell(row, column) {
+            Some(Cell::Text(text)) => text.clone(),
+            Some(Cell::Empty) | None => String::new(),
+            Some(Cell::Element(_)) => return false,
+        };
+        let Some(editor) = self.columns.get_mut(column).and_then(|c| c.editor.as_mut()) else {
+            return false;
+        };
+        editor.begin(&value);
+        // editing a cell in the selection keeps the rest of it
+        if self.selection.focus() != Some(row) {
+            self.selection.click(row, Modifiers::default());
+        }
+        self.column = column;
+        self.editing = Some((row, column));
+        self.edit_error = None;
+        true
+    }
+
+    // writes the editor's value back into the cell. fails and stays in edit
+    // mode if the editor rejects its value
+    pub fn commit_edit(&mut self) -> bool {
+        let Some((row, column)) = self.editing else {
+            return true;
+        };
+        let Some(editor) = self.columns[column].editor.as_ref() else {
+            self.editing = None;
+            return true;
+        };
+        if let Err(e) = editor.validate() {
+            self.edit_error = Some(e);
+            return false;
+        }
+        let value = editor.value();
+        self.set_cell(row, column, Cell::Text(value.clone()));
+        self.editing = None;
+        self.edit_error = None;
+        if let Some(on_edit) = &mut self.on_edit {
+            on_edit(row, column, &value);
+        }
+        true
+    }
+
+    pub fn cancel_edit(&mut self) {
+        self.editing = None;
+        self.edit_error = None;
+    }
+
+    // typing on a selected cell starts an edit that replaces its contents
+    pub fn insert_char(&mut self, c: char) {
+        if self.editing.is_none() {
+            let Some((row, column)) = self.selected_cell() else {
+                return;
+            };
+            if !self.begin_edit(row, column) {
+                return;
+            }
+            if let Some(editor) = self.columns[column].editor.as_mut() {
+                editor.begin("");
+            }
+        }
+        if let Some((_, column)) = self.editing
+            && let Some(editor) = self.columns[column].editor.as_mut()
+        {
+            editor.insert_char(c);
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key, modifiers: Modifiers) -> bool {
+        if let Some((_, column)) = self.editing {
+            match key {
+                Key::Escape => self.cancel_edit(),
+                Key::Enter | Key::Tab => {
+                    if self.commit_edit() {
+                        self.move_selection(key, modifiers);
+                    }
+                }
+                key => {
+                    return self.columns[column]
+                        .editor
+                        .as_mut()
+                        .is_some_and(|editor| editor.handle_key(key));
+                }
+            }
+            return true;
+        }
+
+        let Some((row, column)) = self.selected_cell() else {
+            return false;
+        };
+        match key {
+            Key::F2 | Key::Enter => {
+                self.begin_edit(row, column);
+            }
+            Key::Space if self.columns[column].editor.is_some() => {
+                // checkboxes toggle on space without a separate commit
+                if self.begin_edit(row, column) {
+                    self.commit_edit();
+                }
+            }
+            Key::Up | Key::Down | Key::Left | Key::Right | Key::Tab => {
+                self.move_selection(key, modifiers);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // arrows with shift extend the selected rows, tab and enter just move
+    fn move_selection(&mut self, key: Key, modifiers: Modifiers) {
+        let Some((row, column)) = self.selected_cell() else {
+            return;
+        };
+        let rows = self.cells.len();
+        let columns = self.columns.len();
+        if rows == 0 || columns == 0 {
+            return;
+        }
+        let index = row * columns + column;
+        let (next_row, column) = match (key, modifiers.shift) {
+            (Key::Tab, false) => {
+                let next = (index + 1).min(rows * columns - 1);
+                (next / columns, next % columns)
+            }
+            (Key::Tab, true) => {
+                let previous = index.saturating_sub(1);
+                (previous / columns, previous % columns)
+            }
+            (Key::Enter, false) | (Key::Down, _) => ((row + 1).min(rows - 1), column),
+            (Key::Enter, true) | (Key::Up, _) => (row.saturating_sub(1), column),
+            (Key::Left, _) => (row, column.saturating_sub(1)),
+            (Key::Right, _) => (row, (column + 1).min(columns - 1)),
+            _ => (row, column),
+        };
+        if next_row != row {
+            let modifiers = match key {
+                Key::Up | Key::Down => modifiers,
+                _ => Modifiers::default(),
+            };
+            self.selection.click(next_row, modifiers);
+        }
+        self.column = column;
+    }
+
+    pub fn drag(&mut self, point: (i32, i32)) {
+        if let Some(resize) = &self.resizing {
+            let (row, height) = (resize.row, resize.start_height + point.1 - resize.start_y);
+            self.set_row_height(row, height);
+            return;
+        }
+        let (x, width) = (self.rect.position.0, self.rect.width);
+        let rows: Vec<_> = (0..self.row_heights.len())
+            .map(|row| (row, (x, self.row_y(row), width, self.row_heights[row])))
+            .collect();
+        if let Some(marquee) = &mut self.marquee {
+            marquee.update(point, rows, &mut self.selection);
+        }
+    }
+
+    pub fn release(&mut self) {
+        self.resizing = None;
+        self.marquee = None;
+    }
+
+    fn elements(&self) -> impl Iterator<Item = (usize, usize, &Arc<Mutex<dyn Primative>>)> {
+        self.cells.iter().enumerate().flat_map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(c, cell)| match cell {
+                    Cell::Element(element) => Some((r, c, element)),
+                    _ => None,
+                })
+        })
+    }
+
+    fn is_visible(&self, row: usize) -> bool {
+        let top = self.row_y(row);
+        let (_, y) = self.rect.position;
+        top + self.row_heights[row] > y + self.header_height && top < y + self.rect.height
+    }
+}
+
+impl Primative for Table {
+    delegate_primative!(rect, sizing);
+
+    // cell elements live outside `rect.children`
+    fn is_dirty(&self) -> bool {
+        self.rect.is_dirty()
+            || self
+                .elements()
+                .any(|(_, _, element)| element.lock().map_or(true, |prim| prim.is_dirty()))
+    }
+
+    fn mark_dirty(&mut self) {
+        self.rect.mark_dirty();
+    }
+
+    fn clear_dirty(&mut self) {
+        self.rect.clear_dirty();
+        for (_, _, element) in self.elements() {
+            if let Ok(mut prim) = element.lock() {
+                prim.clear_dirty();
+            }
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || !matches!(cx.phase(), Phase::Target | Phase::Bubble) {
+            return;
+        }
+        let handled = match cx.event {
+            Event::PointerPressed {
+                button: PointerButton::Primary,
+                position,
+                modifiers,
+            } => self.press(*position, *modifiers),
+            Event::PointerMoved { position }
+                if self.resizing.is_some() || self.marquee.is_some() =>
+            {
+                self.drag(*position);
+                true
+            }
+            Event::PointerReleased {
+                button: PointerButton::Primary,
+                ..
+            } if self.resizing.is_some() || self.marquee.is_some() => {
+                self.release();
+                true
+            }
+            Event::KeyPressed { key, modifiers, .. } => self.handle_key(*key, *modifiers),
+            Event::Text(c) if self.selected_cell().is_some() => {
+                self.insert_char(*c);
+                true
+            }
+            Event::Scroll { delta, precise } => {
+                let line = if *precise {
+                    SCROLL_LINE as f64
+                } else {
+                    SCROLL_LINE as f64 * input::wheel_lines() as f64
+                };
+                let previous = self.scroll;
+                self.scroll_by((-delta.0 * line) as i32, (-delta.1 * line) as i32);
+                self.scroll != previous
+            }
+            _ => false,
+        };
+        if handled {
+            self.rect.mark_dirty();
+            cx.stop_propagation();
+        }
+    }
+
+    // full text of the cell under the pointer if it's been cut off
+    fn tooltip(&self, point: (i32, i32)) -> Option<String> {
+        if !self.truncation_tooltips || self.resizing.is_some() {
+            return None;
+        }
+        let (row, column) = self.cell_at(point)?;
+        if self.editing == Some((row, column)) {
+            return None;
+        }
+        let Some(Cell::Text(text)) = self.cell(row, column) else {
+            return None;
+        };
+        text::is_truncated(text, self.text_width(column), self.px(), self.measure())
+            .then(|| text.clone())
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        list.mesh(self.rect.get_mesh(size));
+        for frozen in [false, true] {
+            list.mesh(self.cell_background(frozen, size));
+            for (row, column, element) in self.elements() {
+                if self.frozen_range(frozen).contains(&column)
+                    && self.is_visible(row)
+                    && let Ok(prim) = element.lock()
+                {
+                    prim.record(list, size);
+                }
+            }
+            self.record_text(list, self.body_clip(frozen), self.cell_text(frozen));
+        }
+        list.mesh(self.grid(size));
+        for frozen in [false, true] {
+            self.record_text(list, self.header_clip(frozen), self.header_text(frozen));
+        }
+        if let Some(marquee) = &self.marquee {
+            list.push_clip(self.rect.position, (self.rect.width, self.rect.height));
+            marquee.record(list, self.selection_color, size);
+            list.pop_clip();
+        }
+    }
+
+    fn as_container(&mut self) -> Option<&mut dyn Container> {
+        Some(self as &mut dyn Container)
+    }
+}
+
+impl Container for Table {
+    fn fit_sizing(&mut self) {
+        let (w, h) = self.content_size();
+        let fit = |mode: &SizingMode, content: i32, min: i32, max: Option<i32>| match mode {
+            SizingMode::Fixed(size) => *size,
+            SizingMode::Fit | SizingMode::Grow => {
+                let size = content.max(min);
+                max.map_or(size, |max| size.min(max))
+            }
+        };
+        self.rect.width = fit(
+            &self.rect.sizing.width,
+            w,
+            self.rect.min_width,
+            self.rect.max_width,
+        );
+        self.rect.height = fit(
+            &self.rect.sizing.height,
+            h + self.header_height,
+            self.rect.min_height,
+            self.rect.max_height,
+        );
+    }
+
+    fn grow_sizing(&mut self) {
+        let (max_x, max_y) = self.max_scroll();
+        self.scroll = (self.scroll.0.min(max_x), self.scroll.1.min(max_y));
+        for (_, _, element) in self.elements() {
+            if let Ok(mut prim) = element.lock()
+                && let Some(container) = prim.as_container()
+            {
+                container.grow_sizing();
+            }
+        }
+    }
+
+    fn set_child_positions(&mut self) {
+        for (row, column, element) in self.elements() {
+            let Some((x, y, w, h)) = self.cell_bounds(row, column) else {
+                continue;
+            };
+            if let Ok(mut prim) = element.lock() {
+                prim.set_width(w);
+                prim.set_height(h);
+                prim.set_position((x, y));
+                if let Some(container) = prim.as_container() {
+                    container.set_child_positions();
+                }
+            }
+        }
+    }
+
+    fn get_sizing(&self) -> &Sizing {
+        &self.rect.sizing
+    }
+
+    fn get_sizing_along_axis(&self, axis: Axis) -> &SizingMode {
+        self.rect.get_sizing_along_axis(axis)
+    }
+
+    fn as_primative(&mut self) -> Option<&mut dyn Primative> {
+        Some(self as &mut dyn Primative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{
+        builder::IntoNode,
+        event::{Event, PointerButton},
+        input::{Key, Modifiers},
+        layout::{Rectangle, Sizing, UI},
+        renderer::text::Font,
+        theme::Theme,
+        widgets::{cell_editor::TextEditor, tooltip::Tooltip},
+    };
+
+    use super::{Cell, Column, Table};
+
+    #[test]
+    fn cut_off_cells_show_in_the_tooltip() {
+        let font = Font::for_tests();
+        let theme = Theme::default();
+        let mut table = Table::new(
+            vec![Column::new("name", 60), Column::new("notes", 400)],
+            font.clone(),
+            14.0,
+            &theme,
+        );
+        let long = "a name far too long for its column".to_string();
+        table.push_row(vec![Cell::Text(long.clone()), Cell::Text("short".into())]);
+        assert_ne!(table.display_text(0, 0).as_deref(), Some(long.as_str()));
+
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![table.into_node()],
+            ..Default::default()
+        }));
+        let mut tooltip = Tooltip::new(font, 14.0, &theme);
+        tooltip.delay = Duration::ZERO;
+        ui.tooltip = Some(tooltip);
+        ui.set_size((400, 400));
+        ui.compute_layout();
+        let shown = |ui: &UI| {
+            let tooltip = ui.tooltip.as_ref().unwrap();
+            tooltip.text(ui.context.clock().now()).map(str::to_string)
+        };
+
+        ui.dispatch_event(&Event::PointerMoved { position: (20, 40) });
+        assert_eq!(shown(&ui), Some(long));
+        ui.dispatch_event(&Event::PointerMoved {
+            position: (100, 40),
+        });
+        assert_eq!(shown(&ui), None);
+    }
+
+    #[test]
+    fn double_clicking_a_cell_edits_it() {
+        let font = Font::for_tests();
+        let mut table = Table::new(
+            vec![Column::new("name", 100).with_editor(TextEditor::default())],
+            font,
+            14.0,
+            &Theme::default(),
+        );
+        table.push_row(vec![Cell::Text("old".into())]);
+        let table = Arc::new(Mutex::new(table));
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![table.clone()],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+
+        let press = Event::PointerPressed {
+            button: PointerButton::Primary,
+            position: (20, 40),
+            modifiers: Modifiers::default(),
+        };
+        assert!(ui.dispatch_event(&press));
+        assert_eq!(table.lock().unwrap().selected_cell(), Some((0, 0)));
+        assert!(ui.dispatch_event(&press));
+        assert_eq!(table.lock().unwrap().editing(), Some((0, 0)));
+
+        for c in "new".chars() {
+            ui.dispatch_event(&Event::Text(c));
+        }
+        ui.dispatch_event(&Event::KeyPressed {
+            key: Key::Enter,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        });
+        let table = table.lock().unwrap();
+        assert_eq!(table.editing(), None);
+        assert_eq!(table.display_text(0, 0).as_deref(), Some("oldnew"));
+    }
+
+    #[test]
+    fn shift_clicks_and_marquees_select_rows() {
+        let font = Font::for_tests();
+        let mut table = Table::new(
+            vec![Column::new("name", 100)],
+            font,
+            14.0,
+            &Theme::default(),
+        );
+        for name in ["a", "b", "c", "d"] {
+            table.push_row(vec![Cell::Text(name.into())]);
+        }
+        let table = Arc::new(Mutex::new(table));
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![table.clone()],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+        let selected = || -> Vec<usize> {
+            let table = table.lock().unwrap();
+            table.selection.selected().iter().copied().collect()
+        };
+        // rows are 24 tall under a 28 tall header
+        let click = |ui: &mut UI, y: i32, modifiers: Modifiers| {
+            ui.dispatch_event(&Event::PointerPressed {
+                button: PointerButton::Primary,
+                position: (20, y),
+                modifiers,
+            });
+            ui.dispatch_event(&Event::PointerReleased {
+                button: PointerButton::Primary,
+                position: (20, y),
+                modifiers,
+            });
+        };
+
+        click(&mut ui, 40, Modifiers::default());
+        let shift = Modifiers {
+            shift: true,
+            ..Default::default()
+        };
+        click(&mut ui, 90, shift);
+        assert_eq!(selected(), vec![0, 1, 2]);
+        assert_eq!(table.lock().unwrap().selected_cell(), Some((2, 0)));
+
+        table.lock().unwrap().remove_row(0);
+        assert_eq!(selected(), vec![0, 1]);
+
+        ui.compute_layout();
+        ui.dispatch_event(&Event::PointerPressed {
+            button: PointerButton::Primary,
+            position: (20, 60),
+            modifiers: Modifiers::default(),
+        });
+        assert_eq!(selected(), vec![1]);
+        ui.dispatch_event(&Event::PointerMoved { position: (30, 90) });
+        assert_eq!(selected(), vec![1, 2]);
+        ui.dispatch_event(&Event::PointerReleased {
+            button: PointerButton::Primary,
+            position: (30, 90),
+            modifiers: Modifiers::default(),
+        });
+        assert!(table.lock().unwrap().marquee.is_none());
+    }
+}
@@ -0,0 +1,363 @@
+use std::{cmp::Ordering, io::Read, sync::Arc};
+
+use crate::{
+    renderer::text::Font,
+    search::TextSearch,
+    theme::Theme,
+    widgets::{
+        cell_editor::{CheckboxEditor, NumberEditor, TextEditor},
+        table::{Cell, Column, Table},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl Value {
+    fn parse(text: &str, column_type: ColumnType) -> Value {
+        let text = text.trim();
+        if text.is_empty() {
+            return Value::Null;
+        }
+        match column_type {
+            ColumnType::Bool => text.parse().map_or(Value::Null, Value::Bool),
+            ColumnType::Integer => text.parse().map_or(Value::Null, Value::Integer),
+            ColumnType::Float => text.parse().map_or(Value::Null, Value::Float),
+            ColumnType::Text => Value::Text(text.to_string()),
+        }
+    }
+
+    fn from_json(value: &serde_json::Value) -> Value {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(*b),
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(Value::Integer)
+                .or_else(|| n.as_f64().map(Value::Float))
+                .unwrap_or(Value::Null),
+            serde_json::Value::String(s) => Value::Text(s.clone()),
+            other => Value::Text(other.to_string()),
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Bool(b) => b.to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Text(s) => s.clone(),
+        }
+    }
+
+    fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Null, _) => Ordering::Greater,
+            (_, Value::Null) => Ordering::Less,
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+            (Value::Integer(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+            (Value::Float(a), Value::Integer(b)) => a.total_cmp(&(*b as f64)),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (a, b) => a.display().cmp(&b.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Bool,
+    Integer,
+    Float,
+    Text,
+}
+
+impl ColumnType {
+    // the narrowest type every non-empty sample parses as
+    fn infer<'a>(samples: impl IntoIterator<Item = &'a str>) -> ColumnType {
+        let mut column_type = ColumnType::Bool;
+        for sample in samples.into_iter().map(str::trim).filter(|s| !s.is_empty()) {
+            if column_type == ColumnType::Bool && sample.parse::<bool>().is_err() {
+                column_type = ColumnType::Integer;
+            }
+            if column_type == ColumnType::Integer && sample.parse::<i64>().is_err() {
+                column_type = ColumnType::Float;
+            }
+            if column_type == ColumnType::Float && sample.parse::<f64>().is_err() {
+                return ColumnType::Text;
+            }
+        }
+        column_type
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+// tabular data loaded from csv or json, with sorting and filtering applied as a
+// view over the original rows so the source order is never lost
+pub struct DataSource {
+    columns: Vec<(String, ColumnType)>,
+    rows: Vec<Vec<Value>>,
+    view: Vec<usize>,
+    sort: Option<(usize, SortOrder)>,
+    filter: TextSearch,
+    filter_column: Option<usize>,
+}
+
+impl DataSource {
+    pub fn new(columns: Vec<(String, ColumnType)>, rows: Vec<Vec<Value>>) -> Self {
+        let mut source = Self {
+            columns,
+            rows,
+            view: Vec::new(),
+            sort: None,
+            filter: TextSearch::default(),
+            filter_column: None,
+        };
+        source.refresh_view();
+        source
+    }
+
+    pub fn from_csv(reader: impl Read) -> anyhow::Result<Self> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+        let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>()?;
+
+        let columns: Vec<(String, ColumnType)> = headers
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let column_type = ColumnType::infer(records.iter().filter_map(|r| r.get(i)));
+                (name, column_type)
+            })
+            .collect();
+        let rows = records
+            .iter()
+            .map(|record| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, t))| Value::parse(record.get(i).unwrap_or(""), *t))
+                    .collect()
+            })
+            .collect();
+        Ok(Self::new(columns, rows))
+    }
+
+    pub fn from_csv_str(text: &str) -> anyhow::Result<Self> {
+        Self::from_csv(text.as_bytes())
+    }
+
+    // accepts an array of objects (keys become columns, in first-seen order)
+    // or an array of arrays whose first entry is the header row
+    pub fn from_json_str(text: &str) -> anyhow::Result<Self> {
+        let json: serde_json::Value = serde_json::from_str(text)?;
+        let Some(items) = json.as_array() else {
+            anyhow::bail!("expected a json array of rows");
+        };
+
+        let (names, rows): (Vec<String>, Vec<Vec<Value>>) = match items.first() {
+            Some(serde_json::Value::Array(header)) => {
+                let names = header
+                    .iter()
+                    .map(|h| Value::from_json(h).display())
+                    .collect();
+                let rows = items[1..]
+                    .iter()
+                    .map(|row| {
+                        row.as_array()
+                            .map(|cells| cells.iter().map(Value::from_json).collect())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                (names, rows)
+            }
+            _ => {
+                let mut names: Vec<String> = Vec::new();
+                for item in items {
+                    for key in item.as_object().into_iter().flat_map(|o| o.keys()) {
+                        if !names.contains(key) {
+                            names.push(key.clone());
+                        }
+                    }
+                }
+                let rows = items
+                    .iter()
+                    .map(|item| {
+                        names
+                            .iter()
+                            .map(|name| item.get(name).map_or(Value::Null, Value::from_json))
+                            .collect()
+                    })
+                    .collect();
+                (names, rows)
+            }
+        };
+
+        let columns = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let samples: Vec<String> = rows
+                    .iter()
+                    .filter_map(|row| row.get(i).map(Value::display))
+                    .collect();
+                (name, ColumnType::infer(samples.iter().map(String::as_str)))
+            })
+            .collect::<Vec<_>>();
+        // json numbers/bools already carry a type, but strings like "42" get the
+        // same inference csv does
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, t))| match row.get(i) {
+                        Some(Value::Text(s)) => Value::parse(s, *t),
+                        Some(value) => value.clone(),
+                        None => Value::Null,
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self::new(columns, rows))
+    }
+
+    pub fn columns(&self) -> &[(String, ColumnType)] {
+        &self.columns
+    }
+
+    pub fn len(&self) -> usize {
+        self.view.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.view.is_empty()
+    }
+
+    pub fn total_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn row(&self, view_row: usize) -> Option<&[Value]> {
+        self.view.get(view_row).map(|i| self.rows[*i].as_slice())
+    }
+
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    pub fn sort_by(&mut self, column: usize, order: SortOrder) {
+        self.sort = Some((column, order));
+        self.refresh_view();
+    }
+
+    // cycles ascending -> descending -> unsorted, like clicking a column header
+    pub fn toggle_sort(&mut self, column: usize) {
+        self.sort = match self.sort {
+            Some((c, SortOrder::Ascending)) if c == column => Some((c, SortOrder::Descending)),
+            Some((c, SortOrder::Descending)) if c == column => None,
+            _ => Some((column, SortOrder::Ascending)),
+        };
+        self.refresh_view();
+    }
+
+    // filters rows containing `query`, in one column or any of them
+    pub fn set_filter(&mut self, query: &str, column: Option<usize>) {
+        self.filter.set_query(query);
+        self.filter_column = column;
+        self.refresh_view();
+    }
+
+    // parses an edited cell back into the column's type
+    pub fn set_value(&mut self, view_row: usize, column: usize, text: &str) {
+        let (Some(row), Some((_, column_type))) =
+            (self.view.get(view_row), self.columns.get(column))
+        else {
+            return;
+        };
+        self.rows[*row][column] = Value::parse(text, *column_type);
+    }
+
+    pub fn table_columns(&self, width: i32) -> Vec<Column> {
+        self.columns
+            .iter()
+            .map(|(name, column_type)| {
+                let column = Column::new(name, width);
+                match column_type {
+                    ColumnType::Bool => column.with_editor(CheckboxEditor::default()),
+                    ColumnType::Integer => {
+                        column.with_editor(NumberEditor::new(i64::MIN as f64, i64::MAX as f64, 1.0))
+                    }
+                    ColumnType::Float => column.with_editor(NumberEditor::default()),
+                    ColumnType::Text => column.with_editor(TextEditor::default()),
+                }
+            })
+            .collect()
+    }
+
+    pub fn build_table(
+        &self,
+        column_width: i32,
+        font: Arc<Font>,
+        font_size: f32,
+        theme: &Theme,
+    ) -> Table {
+        let mut table = Table::new(self.table_columns(column_width), font, font_size, theme);
+        self.populate(&mut table);
+        table
+    }
+
+    // replaces the table's rows with the current view
+    pub fn populate(&self, table: &mut Table) {
+        table.clear_rows();
+        for row in &self.view {
+            table.push_row(
+                self.rows[*row]
+                    .iter()
+                    .map(|value| Cell::Text(value.display()))
+                    .collect(),
+            );
+        }
+    }
+
+    fn refresh_view(&mut self) {
+        let filter = &self.filter;
+        let filter_column = self.filter_column;
+        self.view = (0..self.rows.len())
+            .filter(|i| {
+                let row = &self.rows[*i];
+                match filter_column {
+                    Some(c) => row.get(c).is_some_and(|v| filter.is_match(&v.display())),
+                    None => {
+                        filter.query.is_empty() || row.iter().any(|v| filter.is_match(&v.display()))
+                    }
+                }
+            })
+            .collect();
+
+        if let Some((column, order)) = self.sort {
+            let rows = &self.rows;
+            self.view.sort_by(|a, b| {
+                let ordering = rows[*a][column].compare(&rows[*b][column]);
+                match order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use std::sync::Arc;
+
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::Event,
+    fill::Fill,
+    input::{self, Key, Modifiers},
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+        text::Font,
+    },
+    text,
+    theme::{Theme, mix, rgb},
+};
+
+const DEFAULT_ROWS: u16 = 24;
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_SCROLLBACK: usize = 1000;
+
+type InputCallback = Box<dyn FnMut(&[u8]) + Send>;
+
+// the 16 standard colors, the rest of the 256 color palette is worked out
+const ANSI: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x31, 0x31),
+    (0x0d, 0xbc, 0x79),
+    (0xe5, 0xe5, 0x10),
+    (0x24, 0x72, 0xc8),
+    (0xbc, 0x3f, 0xbc),
+    (0x11, 0xa8, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x66, 0x66, 0x66),
+    (0xf1, 0x4c, 0x4c),
+    (0x23, 0xd1, 0x8b),
+    (0xf5, 0xf5, 0x43),
+    (0x3b, 0x8e, 0xea),
+    (0xd6, 0x70, 0xd6),
+    (0x29, 0xb8, 0xdb),
+    (0xff, 0xff, 0xff),
+];
+
+// a vt100 screen, for embedding a shell or any other terminal program. output
+// from the program goes in through `process` and what the user types comes
+// out of `on_input`, ready to write to a pty. key, text, pointer and scroll
+// events are forwarded by the app through `handle_event`. dragging selects,
+// the wheel and shift+page up/down move through the scrollback. only
+// available with the `terminal` feature
+pub struct Terminal {
+    pub rect: Rectangle,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub foreground: srgb,
+    pub cursor_color: srgb,
+    pub selection_color: srgb,
+    parser: vt100::Parser,
+    focused: bool,
+    // grid cells as (row, col) on screen, from where the drag started to where
+    // it is now
+    selection: Option<((u16, u16), (u16, u16))>,
+    selecting: bool,
+    pointer: Option<(i32, i32)>,
+    // lines scrolled by a trackpad that haven't added up to a whole one yet
+    scroll_remainder: f64,
+    on_input: Option<InputCallback>,
+}
+
+impl Terminal {
+    pub fn new(font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        let mut terminal = Self {
+            rect: Rectangle {
+                padding: 8,
+                ..Default::default()
+            },
+            font,
+            font_size,
+            foreground: srgb::default(),
+            cursor_color: srgb::default(),
+            selection_color: srgb::default(),
+            parser: vt100::Parser::new(DEFAULT_ROWS, DEFAULT_COLS, DEFAULT_SCROLLBACK),
+            focused: false,
+            selection: None,
+            selecting: false,
+            pointer: None,
+            scroll_remainder: 0.0,
+            on_input: None,
+        };
+        terminal.apply_theme(theme);
+        terminal.fit_grid();
+        terminal
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.rect.fill = Fill::Solid(theme.background);
+        self.foreground = theme.text;
+        self.cursor_color = theme.on_surface;
+        self.selection_color = theme.primary;
+    }
+
+    pub fn with_grid_size(mut self, rows: u16, cols: u16) -> Self {
+        self.set_grid_size(rows, cols);
+        self
+    }
+
+    // how many lines that scrolled off the top are kept
+    pub fn with_scrollback(mut self, lines: usize) -> Self {
+        let (rows, cols) = self.grid_size();
+        let contents = self.parser.screen().contents_formatted();
+        self.parser = vt100::Parser::new(rows, cols, lines);
+        self.parser.process(&contents);
+        self
+    }
+
+    // bytes to send to the program, for keys typed and text pasted
+    pub fn on_input(mut self, f: impl FnMut(&[u8]) + Send + 'static) -> Self {
+        self.on_input = Some(Box::new(f));
+        self
+    }
+
+    // the program's output
+    pub fn process(&mut self, bytes: &[u8]) {
+        self.parser.process(bytes);
+    }
+
+    pub fn screen(&self) -> &vt100::Screen {
+        self.parser.screen()
+    }
+
+    pub fn grid_size(&self) -> (u16, u16) {
+        self.parser.screen().size()
+    }
+
+    // the program should be told too, e.g. by resizing its pty
+    pub fn set_grid_size(&mut self, rows: u16, cols: u16) {
+        self.parser.screen_mut().set_size(rows.max(1), cols.max(1));
+        self.selection = None;
+        self.fit_grid();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn blur(&mut self) {
+        self.focused = false;
+        self.selecting = false;
+    }
+
+    // lines scrolled back from the live screen
+    pub fn scrollback(&self) -> usize {
+        self.parser.screen().scrollback()
+    }
+
+    // positive goes back in time. clamped to what's been kept
+    pub fn scroll_lines(&mut self, lines: i32) {
+        let current = self.scrollback() as i64;
+        let target = (current + lines as i64).max(0) as usize;
+        self.parser.screen_mut().set_scrollback(target);
+        self.selection = None;
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.ordered_selection()?;
+        let text = self
+            .parser
+            .screen()
+            .contents_between(start.0, start.1, end.0, end.1 + 1);
+        (!text.is_empty()).then_some(text)
+    }
+
+    // sends text as if it was typed, marked as a paste when the program asked
+    // for bracketed paste
+    pub fn paste(&mut self, text: &str) {
+        if self.parser.screen().bracketed_paste() {
+            self.send(b"\x1b[200~");
+            self.send(text.as_bytes());
+            self.send(b"\x1b[201~");
+        } else {
+            self.send(text.as_bytes());
+        }
+    }
+
+    // returns true when the event was used
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::PointerPressed { position, .. } => {
+                if !self.contains(*position) {
+                    self.blur();
+                    return false;
+                }
+                self.focus();
+                let cell = self.cell_at(*position);
+                self.selection = Some((cell, cell));
+                self.selecting = true;
+                true
+            }
+            Event::PointerMoved { position } => {
+                self.pointer = Some(*position);
+                if self.selecting
+                    && let Some((start, _)) = self.selection
+                {
+                    self.selection = Some((start, self.cell_at(*position)));
+                    return true;
+                }
+                false
+            }
+            Event::PointerReleased { .. } => {
+                // a click without a drag selects nothing
+                if std::mem::take(&mut self.selecting)
+                    && let Some((start, end)) = self.selection
+                    && start == end
+                {
+                    self.selection = None;
+                }
+                false
+            }
+            Event::Scroll { delta, .. } => {
+                if !self.pointer.is_some_and(|pointer| self.contains(pointer)) {
+                    return false;
+                }
+                let lines = delta.1 * input::wheel_lines() as f64 + self.scroll_remainder;
+                self.scroll_remainder = lines.fract();
+                self.scroll_lines(lines.trunc() as i32);
+                true
+            }
+            Event::Text(c) if self.focused => {
+                self.send(c.encode_utf8(&mut [0; 4]).as_bytes());
+                true
+            }
+            Event::KeyPressed { key, modifiers, .. } if self.focused => {
+                self.handle_key(*key, *modifiers)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key, modifiers: Modifiers) -> bool {
+        let page = self.grid_size().0 as i32;
+        match key {
+            Key::PageUp if modifiers.shift => self.scroll_lines(page),
+            Key::PageDown if modifiers.shift => self.scroll_lines(-page),
+            key => match self.key_bytes(key, modifiers) {
+                Some(bytes) => self.send(&bytes),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    // the escape sequence a key sends. typed text comes through `Event::Text`
+    // instead
+    fn key_bytes(&self, key: Key, modifiers: Modifiers) -> Option<Vec<u8>> {
+        let application = self.parser.screen().application_cursor();
+        let cursor = |c: u8| {
+            if application {
+                vec![0x1b, b'O', c]
+            } else {
+                vec![0x1b, b'[', c]
+            }
+        };
+        Some(match key {
+            Key::Up => cursor(b'A'),
+            Key::Down => cursor(b'B'),
+            Key::Right => cursor(b'C'),
+            Key::Left => cursor(b'D'),
+            Key::Home => cursor(b'H'),
+            Key::End => cursor(b'F'),
+            Key::Enter => b"\r".to_vec(),
+            Key::Tab if modifiers.shift => b"\x1b[Z".to_vec(),
+            Key::Tab => b"\t".to_vec(),
+            Key::Backspace => b"\x7f".to_vec(),
+            Key::Escape => b"\x1b".to_vec(),
+            Key::Delete => b"\x1b[3~".to_vec(),
+            Key::PageUp => b"\x1b[5~".to_vec(),
+            Key::PageDown => b"\x1b[6~".to_vec(),
+            Key::Space if modifiers.control => vec![0],
+            Key::Char(c) if modifiers.control && c.is_ascii_lowercase() => vec![c as u8 & 0x1f],
+            _ => return None,
+        })
+    }
+
+    fn send(&mut self, bytes: &[u8]) {
+        // typing jumps back to the live screen
+        if self.scrollback() > 0 {
+            self.parser.screen_mut().set_scrollback(0);
+        }
+        self.selection = None;
+        if let Some(on_input) = &mut self.on_input {
+            on_input(bytes);
+        }
+    }
+
+    // the font size with the text scale applied
+    fn px(&self) -> f32 {
+        text::scaled(self.font_size)
+    }
+
+    fn cell_size(&self) -> (f32, f32) {
+        let px = self.px();
+        (self.font.advance('M', px), self.font.line_height(px).ceil())
+    }
+
+    // the widget is exactly as big as the grid
+    fn fit_grid(&mut self) {
+        let (rows, cols) = self.grid_size();
+        let (width, height) = self.cell_size();
+        let padding = self.rect.content_padding() * 2;
+        self.rect.min_width = (width * cols as f32).ceil() as i32 + padding;
+        self.rect.min_height = (height * rows as f32).ceil() as i32 + padding;
+        self.rect.mark_dirty();
+    }
+
+    fn origin(&self) -> (f32, f32) {
+        let padding = self.rect.content_padding();
+        (
+            (self.rect.position.0 + padding) as f32,
+            (self.rect.position.1 + padding) as f32,
+        )
+    }
+
+    fn cell_at(&self, point: (i32, i32)) -> (u16, u16) {
+        let (rows, cols) = self.grid_size();
+        let (x, y) = self.origin();
+        let (width, height) = self.cell_size();
+        let col = ((point.0 as f32 - x) / width).floor().max(0.0) as u16;
+        let row = ((point.1 as f32 - y) / height).floor().max(0.0) as u16;
+        (row.min(rows - 1), col.min(cols - 1))
+    }
+
+    fn ordered_selection(&self) -> Option<((u16, u16), (u16, u16))> {
+        let (a, b) = self.selection?;
+        Some(if a <= b { (a, b) } else { (b, a) })
+    }
+
+    fn is_selected(&self, row: u16, col: u16) -> bool {
+        self.ordered_selection()
+            .is_some_and(|(start, end)| (row, col) >= start && (row, col) <= end)
+    }
+
+    fn color(&self, color: vt100::Color, bold: bool, default: srgb) -> srgb {
+        match color {
+            vt100::Color::Default => default,
+            // bold makes the first eight colors bright, like most terminals
+            vt100::Color::Idx(index) if bold && index < 8 => palette(index + 8),
+            vt100::Color::Idx(index) => palette(index),
+            vt100::Color::Rgb(r, g, b) => byte_rgb(r, g, b),
+        }
+    }
+
+    // foreground and background of a cell, with inverse applied
+    fn cell_colors(&self, cell: &vt100::Cell) -> (srgb, Option<srgb>) {
+        let mut foreground = self.color(cell.fgcolor(), cell.bold(), self.foreground);
+        if cell.dim() {
+            foreground = mix(foreground, self.rect.fill.color(), 0.5);
+        }
+        let background = match cell.bgcolor() {
+            vt100::Color::Default => None,
+            color => Some(self.color(color, false, self.rect.fill.color())),
+        };
+        if cell.inverse() {
+            (
+                background.unwrap_or(self.rect.fill.color()),
+                Some(foreground),
+            )
+        } else {
+            (foreground, background)
+        }
+    }
+}
+
+fn byte_rgb(r: u8, g: u8, b: u8) -> srgb {
+    rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+// xterm's 256 colors: the 16 standard ones, a 6x6x6 cube, then 24 grays
+fn palette(index: u8) -> srgb {
+    match index {
+        0..16 => {
+            let (r, g, b) = ANSI[index as usize];
+            byte_rgb(r, g, b)
+        }
+        16..232 => {
+            let index = index - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            byte_rgb(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            byte_rgb(gray, gray, gray)
+        }
+    }
+}
+
+impl Primative for Terminal {
+    delegate_primative!(rect);
+
+    // in the exit phase it only sees that the pointer is elsewhere, which
+    // takes focus away after a press
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() || cx.phase() == Phase::Capture {
+            return;
+        }
+        if self.handle_event(cx.event) && cx.phase() != Phase::Exit {
+            cx.stop_propagation();
+        }
+    }
+
+    fn text_scale_changed(&mut self) {
+        self.fit_grid();
+    }
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        let screen = self.parser.screen();
+        let (rows, cols) = screen.size();
+        let (x, y) = self.origin();
+        let (width, height) = self.cell_size();
+        let cell_rect = |row: u16, col: u16, span: u16| {
+            let left = (x + col as f32 * width).round() as i32;
+            let right = (x + (col + span) as f32 * width).round() as i32;
+            let top = (y + row as f32 * height).round() as i32;
+            (left, top, right - left, height as i32)
+        };
+
+        let mut mesh = self.get_mesh(size);
+        for row in 0..rows {
+            for col in 0..cols {
+                let Some(cell) = screen.cell(row, col) else {
+                    continue;
+                };
+                let fill = if self.is_selected(row, col) {
+                    Some(self.selection_color)
+                } else {
+                    self.cell_colors(cell).1
+                };
+                if let Some(fill) = fill {
+                    let (left, top, w, h) = cell_rect(row, col, 1);
+                    mesh.append(make_ss_rectangle(left, top, w, h, fill, size));
+                }
+            }
+        }
+        // only on the live screen, the cursor isn't in the scrollback
+        if self.focused && !screen.hide_cursor() && screen.scrollback() == 0 {
+            let (row, col) = screen.cursor_position();
+            let (left, top, w, h) = cell_rect(row, col, 1);
+            let color = if list.window_focused {
+                self.cursor_color
+            } else {
+                mix(self.cursor_color, self.rect.fill.color(), 0.6)
+            };
+            mesh.append(make_ss_rectangle(left, top, w, h, color, size));
+        }
+        list.mesh(mesh);
+
+        // runs of cells in one color are drawn together. wide characters end a
+        // run, so the cells after them stay on the grid
+        let px = self.px();
+        for row in 0..rows {
+            let mut run = String::new();
+            let mut run_start = 0;
+            let mut run_color = self.foreground;
+            let flush = |run: &mut String, start: u16, color: srgb, list: &mut DrawList| {
+                if !run.trim().is_empty() {
+                    let (left, top, _, _) = cell_rect(row, start, 1);
+                    list.text(&self.font, run, px, (left as f32, top as f32), color);
+                }
+                run.clear();
+            };
+            for col in 0..cols {
+                let Some(cell) = screen.cell(row, col) else {
+                    continue;
+                };
+                if cell.is_wide_continuation() {
+                    continue;
+                }
+                let color = self.cell_colors(cell).0;
+                if color != run_color && !run.is_empty() {
+                    flush(&mut run, run_start, run_color, list);
+                }
+                if run.is_empty() {
+                    run_start = col;
+                    run_color = color;
+                }
+                if cell.has_contents() {
+                    run.push_str(cell.contents());
+                } else {
+                    run.push(' ');
+                }
+                if cell.is_wide() {
+                    flush(&mut run, run_start, run_color, list);
+                }
+            }
+            flush(&mut run, run_start, run_color, list);
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+}
@@ -0,0 +1,707 @@
+use std::{any::Any, ops::Range, sync::Arc, time::Duration};
+
+use tinycolors::srgb;
+
+use crate::{
+    animation::{Blink, pulse},
+    context,
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    input::{Key, Modifiers},
+    layout::{Primative, Rectangle, delegate_primative},
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+        text::Font,
+    },
+    text,
+    theme::{Theme, mix},
+    widgets::input_mode::InputMode,
+};
+
+// one full on/off cycle of the caret
+const BLINK_PERIOD: Duration = Duration::from_millis(1060);
+// the selection breathes towards the background while the field is focused
+const SELECTION_PULSE: Duration = Duration::from_secs(2);
+const SELECTION_PULSE_DEPTH: f32 = 0.3;
+const CARET_WIDTH: i32 = 2;
+
+type TextCallback = Box<dyn FnMut(&str) + Send>;
+
+// a single line of editable text. clicking it focuses it, and while focused
+// it takes the keys and text that are dispatched to it. they can also be fed
+// in through `handle_event`, or `insert_char` and `handle_key`. text wider
+// than the field scrolls horizontally to keep the caret in view. `mode` makes
+// it a password or masked field, see `InputMode`
+pub struct TextInput {
+    pub rect: Rectangle,
+    // for masked fields, only what was typed into the mask's slots
+    pub text: String,
+    pub mode: InputMode,
+    pub placeholder: String,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub color: srgb,
+    pub placeholder_color: srgb,
+    pub selection_color: srgb,
+    // the selection while the window is in the background
+    pub inactive_selection_color: srgb,
+    pub caret_color: srgb,
+    pub border_color: srgb,
+    pub focus_color: srgb,
+    focused: bool,
+    // byte offsets into `text`. the selection runs between them
+    cursor: usize,
+    anchor: usize,
+    // the pointer was pressed in the field and is still held
+    selecting: bool,
+    // how far the text is scrolled left, in layout pixels
+    scroll: f32,
+    // restarts the blink so the caret stays solid while typing
+    blink: Blink,
+    on_change: Option<TextCallback>,
+    on_submit: Option<TextCallback>,
+}
+
+impl TextInput {
+    pub fn new(font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        let padding = 8;
+        let mut input = Self {
+            rect: Rectangle {
+                min_width: 200,
+                min_height: font.line_height(text::scaled(font_size)).ceil() as i32 + padding * 2,
+                padding,
+                corner_radius: 6.0,
+                border_width: 2,
+                ..Default::default()
+            },
+            text: String::new(),
+            mode: InputMode::Plain,
+            placeholder: String::new(),
+            font,
+            font_size,
+            color: srgb::default(),
+            placeholder_color: srgb::default(),
+            selection_color: srgb::default(),
+            inactive_selection_color: srgb::default(),
+            caret_color: srgb::default(),
+            border_color: srgb::default(),
+            focus_color: srgb::default(),
+            focused: false,
+            cursor: 0,
+            anchor: 0,
+            selecting: false,
+            scroll: 0.0,
+            blink: Blink::new(BLINK_PERIOD),
+            on_change: None,
+            on_submit: None,
+        };
+        input.apply_theme(theme);
+        input
+    }
+
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.rect.fill = Fill::Solid(theme.surface);
+        self.border_color = theme.border;
+        self.focus_color = theme.primary;
+        self.color = theme.on_surface;
+        self.placeholder_color = theme.inactive;
+        self.selection_color = theme.primary;
+        self.inactive_selection_color = theme.inactive;
+        self.caret_color = theme.on_surface;
+        self.sync_border();
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: InputMode) -> Self {
+        self.mode = mode;
+        let text = std::mem::take(&mut self.text);
+        self.set_text(&text);
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn on_change(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    // enter was pressed
+    pub fn on_submit(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+
+    // replaces the contents without firing `on_change`, leaving the caret at
+    // the end
+    pub fn set_text(&mut self, text: &str) {
+        self.text = self.mode.normalize(&single_line(text));
+        self.cursor = self.text.len();
+        self.anchor = self.cursor;
+        self.touch();
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.anchor)..self.cursor.max(self.anchor)
+    }
+
+    pub fn selected_text(&self) -> &str {
+        &self.text[self.selection()]
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.cursor != self.anchor
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.cursor = self.text.len();
+        self.touch();
+    }
+
+    // shows or hides a password field's text
+    pub fn toggle_reveal(&mut self) {
+        self.mode.toggle_reveal();
+        self.scroll_to_caret();
+        self.touch();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+        self.sync_border();
+        self.touch();
+    }
+
+    pub fn blur(&mut self) {
+        self.focused = false;
+        self.selecting = false;
+        self.anchor = self.cursor;
+        self.sync_border();
+    }
+
+    // takes text and key presses while focused, returns true when the event
+    // was used
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        if !self.focused {
+            return false;
+        }
+        match event {
+            Event::Text(c) => {
+                self.insert_char(*c);
+                true
+            }
+            Event::KeyPressed { key, modifiers, .. } => self.handle_key(*key, *modifiers),
+            _ => false,
+        }
+    }
+
+    // replaces the selection with `c`, if the mode takes it there
+    pub fn insert_char(&mut self, c: char) {
+        let selection = self.selection();
+        let Some(head) = self.mode.insert(&self.text[..selection.start], c) else {
+            return;
+        };
+        self.cursor = head.len();
+        self.anchor = self.cursor;
+        self.text = head + &self.text[selection.end..];
+        self.changed();
+    }
+
+    // replaces the selection with `text`, e.g. for pasting
+    pub fn insert(&mut self, text: &str) {
+        let text = single_line(text);
+        let selection = self.selection();
+        let head = self
+            .mode
+            .normalize(&format!("{}{text}", &self.text[..selection.start]));
+        let value = self
+            .mode
+            .normalize(&format!("{head}{}", &self.text[selection.end..]));
+        self.text = value;
+        self.cursor = head.len().min(self.text.len());
+        self.anchor = self.cursor;
+        self.changed();
+    }
+
+    // nothing for password fields, see `InputMode::can_copy`
+    pub fn copy(&self) -> Option<String> {
+        (self.mode.can_copy() && self.has_selection()).then(|| self.selected_text().to_string())
+    }
+
+    pub fn cut(&mut self) -> Option<String> {
+        let copied = self.copy()?;
+        self.delete_selection();
+        self.changed();
+        Some(copied)
+    }
+
+    // letter keys only count with the command modifier held, typed letters
+    // arrive through `insert_char`
+    pub fn handle_key(&mut self, key: Key, modifiers: Modifiers) -> bool {
+        let word = modifiers.alt || modifiers.control;
+        match key {
+            Key::Left => {
+                let to = if !modifiers.shift && self.has_selection() {
+                    self.selection().start
+                } else if word {
+                    self.previous_word(self.cursor)
+                } else {
+                    self.previous_char(self.cursor)
+                };
+                self.move_to(to, modifiers.shift);
+            }
+            Key::Right => {
+                let to = if !modifiers.shift && self.has_selection() {
+                    self.selection().end
+                } else if word {
+                    self.next_word(self.cursor)
+                } else {
+                    self.next_char(self.cursor)
+                };
+                self.move_to(to, modifiers.shift);
+            }
+            Key::Home | Key::Up => self.move_to(0, modifiers.shift),
+            Key::End | Key::Down => self.move_to(self.text.len(), modifiers.shift),
+            Key::Backspace => {
+                if !self.has_selection() {
+                    self.anchor = if word {
+                        self.previous_word(self.cursor)
+                    } else {
+                        self.previous_char(self.cursor)
+                    };
+                }
+                if self.has_selection() {
+                    self.delete_selection();
+                    self.changed();
+                }
+            }
+            Key::Delete => {
+                if !self.has_selection() {
+                    self.anchor = if word {
+                        self.next_word(self.cursor)
+                    } else {
+                        self.next_char(self.cursor)
+                    };
+                }
+                if self.has_selection() {
+                    self.delete_selection();
+                    self.changed();
+                }
+            }
+            Key::Enter => {
+                if let Some(on_submit) = &mut self.on_submit {
+                    on_submit(&self.text);
+                }
+            }
+            Key::Char('a') if modifiers.command() => self.select_all(),
+            _ => return false,
+        }
+        true
+    }
+
+    // places the caret under the pointer, shift extends the selection.
+    // clicking anywhere else takes focus away
+    pub fn click(&mut self, point: (i32, i32), modifiers: Modifiers) -> bool {
+        if !self.contains(point) {
+            self.blur();
+            return false;
+        }
+        self.focus();
+        let index = self.index_at(point.0);
+        self.move_to(index, modifiers.shift);
+        true
+    }
+
+    // extends the selection while the pointer is held after a click
+    pub fn drag(&mut self, point: (i32, i32)) {
+        if self.focused {
+            let index = self.index_at(point.0);
+            self.move_to(index, true);
+        }
+    }
+
+    // masked fields are typed into from the end, so they never select
+    fn move_to(&mut self, index: usize, extend: bool) {
+        self.cursor = match self.mode {
+            InputMode::Masked(_) => self.text.len(),
+            _ => index,
+        };
+        if !extend || matches!(self.mode, InputMode::Masked(_)) {
+            self.anchor = self.cursor;
+        }
+        self.scroll_to_caret();
+        self.touch();
+    }
+
+    fn delete_selection(&mut self) {
+        let selection = self.selection();
+        self.text.replace_range(selection.clone(), "");
+        self.cursor = selection.start;
+        self.anchor = self.cursor;
+    }
+
+    fn changed(&mut self) {
+        self.scroll_to_caret();
+        self.touch();
+        if let Some(on_change) = &mut self.on_change {
+            on_change(&self.text);
+        }
+    }
+
+    fn sync_border(&mut self) {
+        self.rect.border_color = if self.focused {
+            self.focus_color
+        } else {
+            self.border_color
+        };
+    }
+
+    fn touch(&mut self) {
+        self.blink.restart(context::frame_now());
+    }
+
+    fn previous_char(&self, index: usize) -> usize {
+        self.text[..index]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_char(&self, index: usize) -> usize {
+        self.text[index..]
+            .chars()
+            .next()
+            .map_or(index, |c| index + c.len_utf8())
+    }
+
+    // start of the word before `index`, skipping any whitespace first. a
+    // password is one word, so its spaces don't give it away
+    fn previous_word(&self, index: usize) -> usize {
+        if matches!(self.mode, InputMode::Password(_)) {
+            return 0;
+        }
+        let before = self.text[..index].trim_end();
+        before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8())
+    }
+
+    // end of the word after `index`, skipping any whitespace first
+    fn next_word(&self, index: usize) -> usize {
+        if matches!(self.mode, InputMode::Password(_)) {
+            return self.text.len();
+        }
+        let after = &self.text[index..];
+        let start = after.len() - after.trim_start().len();
+        after[start..]
+            .char_indices()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(self.text.len(), |(i, _)| index + start + i)
+    }
+
+    // what's drawn, which only differs from `text` for masked and password
+    // fields
+    fn display_text(&self) -> String {
+        self.mode.display_text(&self.text)
+    }
+
+    // x offset of every char boundary in `text`, from the start of what's
+    // drawn
+    fn boundaries(&self) -> Vec<(usize, f32)> {
+        let layout = self.font.layout(&self.display_text(), self.px());
+        let x = |index: usize| {
+            let offset = self.mode.display_offset(&self.text, index);
+            layout
+                .glyphs
+                .get(offset)
+                .map_or(layout.width, |glyph| glyph.x)
+        };
+        let mut boundaries: Vec<(usize, f32)> =
+            self.text.char_indices().map(|(i, _)| (i, x(i))).collect();
+        boundaries.push((self.text.len(), x(self.text.len())));
+        boundaries
+    }
+
+    // the text to draw and its color, the placeholder while it's empty
+    fn shown(&self) -> (String, srgb) {
+        if self.text.is_empty() && !self.placeholder.is_empty() {
+            (self.placeholder.clone(), self.placeholder_color)
+        } else {
+            (self.display_text(), self.color)
+        }
+    }
+
+    fn offset_of(&self, index: usize) -> f32 {
+        self.boundaries()
+            .into_iter()
+            .find(|(i, _)| *i >= index)
+            .map_or(0.0, |(_, x)| x)
+    }
+
+    fn index_at(&self, x: i32) -> usize {
+        let x = (x - self.text_origin().0) as f32 + self.scroll;
+        self.boundaries()
+            .into_iter()
+            .min_by(|a, b| (a.1 - x).abs().total_cmp(&(b.1 - x).abs()))
+            .map_or(0, |(i, _)| i)
+    }
+
+    // the font size with the text scale applied
+    fn px(&self) -> f32 {
+        text::scaled(self.font_size)
+    }
+
+    fn line_height(&self) -> f32 {
+        self.font.line_height(self.px())
+    }
+
+    fn inner_width(&self) -> f32 {
+        (self.rect.width - self.rect.content_padding() * 2).max(0) as f32
+    }
+
+    fn scroll_to_caret(&mut self) {
+        let caret = self.offset_of(self.cursor);
+        let width = self.inner_width();
+        if caret - self.scroll > width {
+            self.scroll = caret - width;
+        } else if caret < self.scroll {
+            self.scroll = caret;
+        }
+        let overflow = (self.font.measure(&self.display_text(), self.px()).0 - width).max(0.0);
+        self.scroll = self.scroll.clamp(0.0, overflow);
+    }
+
+    fn text_origin(&self) -> (i32, i32) {
+        let (x, y) = self.rect.position;
+        let padding = self.rect.content_padding();
+        let line_height = self.line_height();
+        let top = (self.rect.height as f32 - line_height) / 2.0;
+        (x + padding, y + top.round() as i32)
+    }
+
+    // the caret is hidden and the selection dimmed while the window is in the
+    // background, so only the focused window looks ready for typing
+    fn caret_visible(&self, list: &DrawList) -> bool {
+        self.focused && list.window_focused && self.blink.is_on(list.clock.now())
+    }
+
+    fn selection_fill(&self, list: &DrawList) -> srgb {
+        if !list.window_focused {
+            return self.inactive_selection_color;
+        }
+        let t = pulse(list.clock.elapsed(), SELECTION_PULSE) * SELECTION_PULSE_DEPTH;
+        mix(self.selection_color, self.rect.fill.color(), t)
+    }
+}
+
+// newlines and tabs become spaces
+fn single_line(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+impl Primative for TextInput {
+    delegate_primative!(rect);
+
+    fn record(&self, list: &mut DrawList, size: (i32, i32)) {
+        let (x, y) = self.text_origin();
+        let line_height = self.line_height().ceil() as i32;
+        let scroll = self.scroll.round() as i32;
+
+        let mut mesh = self.get_mesh(size);
+        if self.focused && self.has_selection() {
+            let selection = self.selection();
+            let start = self.offset_of(selection.start).round() as i32;
+            let end = self.offset_of(selection.end).round() as i32;
+            mesh.append(make_ss_rectangle(
+                x + start - scroll,
+                y,
+                end - start,
+                line_height,
+                self.selection_fill(list),
+                size,
+            ));
+        }
+        list.mesh(mesh);
+
+        let padding = self.rect.content_padding();
+        list.push_clip(
+            (self.rect.position.0 + padding, self.rect.position.1),
+            (self.inner_width() as i32, self.rect.height),
+        );
+        let (text, color) = self.shown();
+        list.text(
+            &self.font,
+            &text,
+            self.px(),
+            ((x - scroll) as f32, y as f32),
+            color,
+        );
+        if self.caret_visible(list) {
+            let caret = self.offset_of(self.cursor).round() as i32;
+            list.mesh(make_ss_rectangle(
+                x + caret - scroll - CARET_WIDTH / 2,
+                y,
+                CARET_WIDTH,
+                line_height,
+                self.caret_color,
+                size,
+            ));
+        }
+        list.pop_clip();
+    }
+
+    // a press anywhere else, which arrives in the exit phase, takes focus away
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let handled = match (cx.phase(), cx.event) {
+            (
+                Phase::Exit,
+                Event::PointerPressed {
+                    position,
+                    modifiers,
+                    ..
+                },
+            ) => self.click(*position, *modifiers),
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerPressed {
+                    button: PointerButton::Primary,
+                    position,
+                    modifiers,
+                },
+            ) => {
+                self.selecting = self.click(*position, *modifiers);
+                self.selecting
+            }
+            (Phase::Target | Phase::Bubble, Event::PointerMoved { position }) if self.selecting => {
+                self.drag(*position);
+                true
+            }
+            (_, Event::PointerReleased { .. }) => std::mem::take(&mut self.selecting),
+            (Phase::Target | Phase::Bubble, event) => self.handle_event(event),
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+
+    // the text belongs to the field once it's been typed in, so a rebuilt input
+    // keeps it unless the description sets some of its own
+    fn adopt_state(&mut self, previous: &mut dyn Primative) {
+        let Some(previous) = previous
+            .as_any_mut()
+            .and_then(|any| any.downcast_mut::<TextInput>())
+        else {
+            return;
+        };
+        if self.text.is_empty() {
+            self.text = std::mem::take(&mut previous.text);
+        }
+        let clamp = |mut offset: usize| {
+            offset = offset.min(self.text.len());
+            while !self.text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        self.cursor = clamp(previous.cursor);
+        self.anchor = clamp(previous.anchor);
+        self.focused = previous.focused;
+        self.scroll = previous.scroll;
+        self.blink = previous.blink;
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        self.rect.get_mesh(size)
+    }
+
+    fn text_scale_changed(&mut self) {
+        let padding = self.rect.content_padding();
+        self.rect.min_height = self.line_height().ceil() as i32 + padding * 2;
+        self.rect.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextInput;
+    use crate::{
+        input::{Key, Modifiers},
+        renderer::text::Font,
+        theme::Theme,
+        widgets::input_mode::{InputMask, InputMode, PasswordMode},
+    };
+
+    fn input(mode: InputMode) -> TextInput {
+        TextInput::new(Font::for_tests(), 16.0, &Theme::default()).with_mode(mode)
+    }
+
+    #[test]
+    fn copying_from_a_password_field_is_refused() {
+        let mut field = input(InputMode::Password(PasswordMode::default()));
+        field.set_text("hunter2");
+        field.select_all();
+        assert_eq!(field.copy(), None);
+        assert_eq!(field.cut(), None);
+        assert_eq!(field.text, "hunter2");
+        assert_eq!(field.display_text(), "•••••••");
+    }
+
+    #[test]
+    fn plain_fields_copy_the_selection() {
+        let mut field = input(InputMode::Plain);
+        field.set_text("hunter2");
+        field.select_all();
+        assert_eq!(field.copy().as_deref(), Some("hunter2"));
+    }
+
+    #[test]
+    fn masked_fields_keep_what_the_mask_takes() {
+        let mut field = input(InputMode::Masked(InputMask::phone()));
+        for c in "55a5-1234".chars() {
+            field.insert_char(c);
+        }
+        assert_eq!(field.text, "5551234");
+        assert_eq!(field.display_text(), "(555) 123-4___");
+    }
+
+    #[test]
+    fn typing_after_home_in_a_masked_field_appends() {
+        let mut field = input(InputMode::Masked(InputMask::phone()));
+        field.set_text("555");
+        field.focus();
+        assert!(field.handle_key(Key::Home, Modifiers::default()));
+        assert!(!field.has_selection());
+        field.insert_char('1');
+        assert_eq!(field.text, "5551");
+    }
+}
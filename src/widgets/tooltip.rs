@@ -0,0 +1,138 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tinycolors::srgb;
+
+use crate::{
+    renderer::{
+        draw_list::DrawList,
+        mesh_builder::{Mesh, make_ss_rectangle},
+        text::Font,
+    },
+    text,
+    theme::Theme,
+};
+
+// offset from the pointer so the tooltip doesn't sit under the cursor
+const POINTER_OFFSET: (i32, i32) = (12, 20);
+
+// a line of text that shows next to the pointer once it's rested on something
+// for `delay`. the ui keeps one and fills it from `Primative::tooltip` for
+// whatever is under the pointer, drawing it above the overlays. times come
+// from the frame clock
+pub struct Tooltip {
+    pub delay: Duration,
+    pub background_color: srgb,
+    pub border_color: srgb,
+    pub color: srgb,
+    pub font: Arc<Font>,
+    pub font_size: f32,
+    pub padding: i32,
+    text: Option<String>,
+    anchor: (i32, i32),
+    hover_start: Option<Instant>,
+}
+
+impl Tooltip {
+    pub fn new(font: Arc<Font>, font_size: f32, theme: &Theme) -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            background_color: theme.surface,
+            border_color: theme.border,
+            color: theme.text,
+            font,
+            font_size,
+            padding: 6,
+            text: None,
+            anchor: (0, 0),
+            hover_start: None,
+        }
+    }
+
+    // call on every pointer move with whatever is under the pointer. the delay
+    // restarts and the tooltip moves to `point` whenever the text changes,
+    // which is what this returns
+    pub fn hover(&mut self, text: Option<String>, point: (i32, i32), now: Instant) -> bool {
+        if text == self.text {
+            return false;
+        }
+        self.anchor = point;
+        self.hover_start = text.as_ref().map(|_| now);
+        self.text = text;
+        true
+    }
+
+    pub fn hide(&mut self) {
+        self.text = None;
+        self.hover_start = None;
+    }
+
+    // has text, whether or not its delay is up
+    pub fn is_active(&self) -> bool {
+        self.text.is_some()
+    }
+
+    pub fn is_visible(&self, now: Instant) -> bool {
+        self.hover_start
+            .is_some_and(|start| now.saturating_duration_since(start) >= self.delay)
+    }
+
+    // the text once the delay is up
+    pub fn text(&self, now: Instant) -> Option<&str> {
+        self.is_visible(now)
+            .then_some(self.text.as_deref())
+            .flatten()
+    }
+
+    fn px(&self) -> f32 {
+        text::scaled(self.font_size)
+    }
+
+    // kept inside `window` (width, height) in layout pixels, flipping to the
+    // other side of the pointer when it would run off the edge
+    pub fn bounds(&self, window: (i32, i32), now: Instant) -> Option<(i32, i32, i32, i32)> {
+        let text = self.text(now)?;
+        let (text_w, text_h) = self.font.measure(text, self.px());
+        let w = text_w.ceil() as i32 + self.padding * 2;
+        let h = text_h.ceil() as i32 + self.padding * 2;
+        let mut x = self.anchor.0 + POINTER_OFFSET.0;
+        let mut y = self.anchor.1 + POINTER_OFFSET.1;
+        if x + w > window.0 {
+            x = (self.anchor.0 - w).max(0);
+        }
+        if y + h > window.1 {
+            y = (self.anchor.1 - h).max(0);
+        }
+        Some((x, y, w, h))
+    }
+
+    fn mesh(&self, (x, y, w, h): (i32, i32, i32, i32), size: (i32, i32)) -> Mesh {
+        let mut mesh = make_ss_rectangle(x, y, w, h, self.border_color, size);
+        mesh.append(make_ss_rectangle(
+            x + 1,
+            y + 1,
+            w - 2,
+            h - 2,
+            self.background_color,
+            size,
+        ));
+        mesh
+    }
+
+    pub fn record(&self, list: &mut DrawList, size: (i32, i32), now: Instant) {
+        let (Some(text), Some(bounds)) = (self.text(now), self.bounds(list.size, now)) else {
+            return;
+        };
+        list.mesh(self.mesh(bounds, size));
+        let (x, y, _, _) = bounds;
+        list.text(
+            &self.font,
+            text,
+            self.px(),
+            ((x + self.padding) as f32, (y + self.padding) as f32),
+            self.color,
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use tinycolors::srgb;
+
+use crate::{
+    dispatch::{EventContext, Phase},
+    event::{Event, PointerButton},
+    fill::Fill,
+    layout::{Primative, Rectangle, SCROLL_LINE, delegate_primative},
+    renderer::mesh_builder::{Mesh, make_ss_bars, make_ss_rectangle},
+    theme::Theme,
+};
+
+// how much one line of the wheel zooms in or out
+const ZOOM_PER_LINE: f64 = 1.25;
+
+// the closest a waveform zooms in, in samples per pixel
+const MIN_SAMPLES_PER_PIXEL: f64 = 1.0 / 16.0;
+
+// min/max pairs at successively halved resolutions so zoomed out views don't
+// have to scan every sample each frame
+struct EnvelopePyramid {
+    levels: Vec<Vec<(f32, f32)>>,
+}
+
+impl EnvelopePyramid {
+    fn new(samples: &[f32]) -> Self {
+        let mut levels = vec![samples.iter().map(|s| (*s, *s)).collect::<Vec<_>>()];
+        while levels.last().is_some_and(|l| l.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| {
+                    pair.iter()
+                        .fold((f32::MAX, f32::MIN), |(lo, hi), (min, max)| {
+                            (lo.min(*min), hi.max(*max))
+                        })
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    // min/max over samples[start..end], using the coarsest level that still
+    // has at least one entry per requested column
+    fn range(&self, start: f64, end: f64) -> Option<(f32, f32)> {
+        let span = (end - start).max(1.0);
+        let level = (span.log2().floor() as usize).min(self.levels.len().saturating_sub(1));
+        let data = self.levels.get(level)?;
+        let scale = (1usize << level) as f64;
+        let from = (start / scale).floor().max(0.0) as usize;
+        let to = ((end / scale).ceil() as usize).min(data.len());
+        if from >= to {
+            return None;
+        }
+        Some(
+            data[from..to]
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(lo, hi), (min, max)| {
+                    (lo.min(*min), hi.max(*max))
+                }),
+        )
+    }
+}
+
+pub struct Waveform {
+    pub rect: Rectangle,
+    pub wave_color: srgb,
+    pub center_line_color: srgb,
+    sample_count: usize,
+    envelope: EnvelopePyramid,
+    // samples per pixel column, once zoomed
+    samples_per_pixel: f64,
+    // first visible sample
+    offset: f64,
+    // showing the whole clip, however wide the waveform is laid out. a zoom
+    // or scroll ends it, and `zoom_to_fit` starts it again
+    fitted: bool,
+    // where the pointer is, to zoom around, and where a drag last was
+    pointer_x: Option<i32>,
+    drag_x: Option<i32>,
+}
+
+impl Waveform {
+    pub fn new(theme: &Theme) -> Self {
+        Self {
+            rect: Rectangle {
+                min_width: 200,
+                min_height: 80,
+                fill: Fill::Solid(theme.surface),
+                ..Default::default()
+            },
+            wave_color: theme.primary,
+            center_line_color: theme.border,
+            sample_count: 0,
+            envelope: EnvelopePyramid::new(&[]),
+            samples_per_pixel: 1.0,
+            offset: 0.0,
+            fitted: true,
+            pointer_x: None,
+            drag_x: None,
+        }
+    }
+
+    pub fn set_samples(&mut self, samples: &[f32]) {
+        self.sample_count = samples.len();
+        self.envelope = EnvelopePyramid::new(samples);
+        self.zoom_to_fit();
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.sample_count
+    }
+
+    pub fn samples_per_pixel(&self) -> f64 {
+        if self.fitted {
+            self.fit_samples_per_pixel()
+        } else {
+            self.samples_per_pixel
+        }
+    }
+
+    pub fn visible_range(&self) -> (f64, f64) {
+        let offset = self.clamped_offset();
+        (
+            offset,
+            offset + self.samples_per_pixel() * self.rect.width as f64,
+        )
+    }
+
+    // fits the clip to the width it's laid out at, now and after any resize
+    pub fn zoom_to_fit(&mut self) {
+        self.offset = 0.0;
+        self.fitted = true;
+        self.rect.mark_dirty();
+    }
+
+    // zooms by `factor` (> 1 zooms in) keeping the sample under `anchor_x` fixed
+    pub fn zoom(&mut self, factor: f64, anchor_x: i32) {
+        let column = (anchor_x - self.rect.position.0) as f64;
+        let anchor_sample = self.clamped_offset() + column * self.samples_per_pixel();
+        let fit = self.fit_samples_per_pixel();
+        self.samples_per_pixel =
+            (self.samples_per_pixel() / factor).clamp(MIN_SAMPLES_PER_PIXEL, fit);
+        // zoomed all the way out is the same as fitting
+        self.fitted = self.samples_per_pixel >= fit;
+        self.offset = anchor_sample - column * self.samples_per_pixel;
+        self.offset = self.clamped_offset();
+        self.rect.mark_dirty();
+    }
+
+    pub fn scroll(&mut self, pixels: i32) {
+        self.offset = self.clamped_offset() + pixels as f64 * self.samples_per_pixel();
+        self.offset = self.clamped_offset();
+        self.rect.mark_dirty();
+    }
+
+    fn fit_samples_per_pixel(&self) -> f64 {
+        (self.sample_count as f64 / self.rect.width.max(1) as f64).max(MIN_SAMPLES_PER_PIXEL)
+    }
+
+    // the first visible sample, kept in the clip for the width it's at now
+    fn clamped_offset(&self) -> f64 {
+        if self.fitted {
+            return 0.0;
+        }
+        let visible = self.samples_per_pixel * self.rect.width as f64;
+        self.offset
+            .clamp(0.0, (self.sample_count as f64 - visible).max(0.0))
+    }
+}
+
+impl Primative for Waveform {
+    delegate_primative!(rect);
+
+    // the wheel zooms around the pointer and scrolls sideways, and dragging
+    // pans
+    fn on_event(&mut self, cx: &mut EventContext) {
+        self.rect.on_event(cx);
+        if cx.is_propagation_stopped() {
+            return;
+        }
+        let handled = match (cx.phase(), cx.event) {
+            (Phase::Exit, Event::PointerMoved { .. } | Event::PointerLeft) => {
+                self.pointer_x = None;
+                false
+            }
+            (Phase::Target | Phase::Bubble, Event::PointerMoved { position }) => {
+                self.pointer_x = Some(position.0);
+                match self.drag_x.replace(position.0) {
+                    Some(last) => {
+                        self.scroll(last - position.0);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerPressed {
+                    button: PointerButton::Primary,
+                    position,
+                    ..
+                },
+            ) => {
+                self.drag_x = Some(position.0);
+                true
+            }
+            (
+                Phase::Target | Phase::Bubble,
+                Event::PointerReleased {
+                    button: PointerButton::Primary,
+                    ..
+                },
+            ) => self.drag_x.take().is_some(),
+            (Phase::Target | Phase::Bubble, Event::Scroll { delta, .. }) => {
+                if delta.1 != 0.0 {
+                    let anchor = self.pointer_x.unwrap_or(self.rect.position.0);
+                    self.zoom(ZOOM_PER_LINE.powf(delta.1), anchor);
+                }
+                if delta.0 != 0.0 {
+                    self.scroll((-delta.0 * SCROLL_LINE as f64).round() as i32);
+                }
+                true
+            }
+            _ => false,
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn get_mesh(&self, size: (i32, i32)) -> Mesh {
+        let mut mesh = self.rect.get_mesh(size);
+        let (x, y) = self.rect.position;
+        let half = self.rect.height as f32 / 2.0;
+        let center = y as f32 + half;
+
+        mesh.append(make_ss_rectangle(
+            x,
+            center as i32,
+            self.rect.width,
+            1,
+            self.center_line_color,
+            size,
+        ));
+
+        let (offset, samples_per_pixel) = (self.clamped_offset(), self.samples_per_pixel());
+        let bars: Vec<(f32, f32, f32)> = (0..self.rect.width)
+            .filter_map(|column| {
+                let start = offset + column as f64 * samples_per_pixel;
+                let end = start + samples_per_pixel;
+                let (min, max) = self.envelope.range(start, end)?;
+                let top = center - max.clamp(-1.0, 1.0) * half;
+                // always draw at least a pixel so silence stays visible
+                let bottom = (center - min.clamp(-1.0, 1.0) * half).max(top + 1.0);
+                Some(((x + column) as f32, top, bottom))
+            })
+            .collect();
+        mesh.append(make_ss_bars(&bars, 1.0, self.wave_color, size));
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        builder::Node,
+        event::Event,
+        layout::{Rectangle, Sizing, UI},
+        theme::Theme,
+    };
+
+    use super::Waveform;
+
+    #[test]
+    fn samples_set_before_layout_fit_the_laid_out_width() {
+        let mut waveform = Waveform::new(&Theme::default());
+        waveform.set_samples(&vec![0.5; 4000]);
+        let waveform = Arc::new(Mutex::new(waveform));
+        let mut ui = UI::default();
+        ui.root_item = Arc::new(Mutex::new(Rectangle {
+            sizing: Sizing::GROW,
+            children: vec![waveform.clone() as Node],
+            ..Default::default()
+        }));
+        ui.set_size((400, 400));
+        ui.compute_layout();
+        let width = waveform.lock().unwrap().rect.width;
+        assert_eq!(width, 200);
+        assert_eq!(waveform.lock().unwrap().visible_range(), (0.0, 4000.0));
+
+        // a line of the wheel zooms in around the pointer
+        ui.dispatch_event(&Event::PointerMoved {
+            position: (100, 20),
+        });
+        ui.dispatch_event(&Event::Scroll {
+            delta: (0.0, 1.0),
+            precise: false,
+        });
+        let (start, end) = waveform.lock().unwrap().visible_range();
+        assert!((end - start - 3200.0).abs() < 1e-6);
+        assert!((start - 400.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,67 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{input::InputSettings, renderer::settings::RenderSettings};
+
+#[cfg(feature = "tray")]
+use crate::tray::TrayOptions;
+#[cfg(feature = "vibrancy")]
+use crate::vibrancy::Vibrancy;
+
+#[derive(Debug, Clone)]
+pub struct WindowOptions {
+    pub title: String,
+    pub size: (u32, u32),
+    // limits on how far the window can be resized, in window pixels
+    pub min_size: Option<(u32, u32)>,
+    pub max_size: Option<(u32, u32)>,
+    // keeps width to height at this ratio while resizing, e.g. (16, 9)
+    pub aspect_ratio: Option<(u32, u32)>,
+    // waits for the size to hold still this long before laying the tree out
+    // again, for uis too expensive to relayout every frame of a drag. until
+    // then the old layout is drawn as is
+    pub resize_debounce: Option<Duration>,
+    // where user preferences like the zoom level are kept between runs. without
+    // one they only last until the window closes
+    pub preferences_path: Option<PathBuf>,
+    // asks for a framebuffer with an alpha channel that the compositor blends
+    // with the desktop. combine with a translucent UI background
+    pub transparent: bool,
+    pub render: RenderSettings,
+    pub input: InputSettings,
+    #[cfg(feature = "tray")]
+    pub tray: Option<TrayOptions>,
+    // a native blurred material behind the window. implies `transparent`
+    #[cfg(feature = "vibrancy")]
+    pub vibrancy: Option<Vibrancy>,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: "teacup".to_string(),
+            size: (800, 600),
+            min_size: None,
+            max_size: None,
+            aspect_ratio: None,
+            resize_debounce: None,
+            preferences_path: None,
+            transparent: false,
+            render: RenderSettings::default(),
+            input: InputSettings::default(),
+            #[cfg(feature = "tray")]
+            tray: None,
+            #[cfg(feature = "vibrancy")]
+            vibrancy: None,
+        }
+    }
+}
+
+impl WindowOptions {
+    pub fn is_transparent(&self) -> bool {
+        #[cfg(feature = "vibrancy")]
+        if self.vibrancy.is_some() {
+            return true;
+        }
+        self.transparent
+    }
+}